@@ -0,0 +1,66 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+use fsm::bench_support::{dictionary_dawg, nth_from_end, random_dfa, random_nfa, random_words};
+use fsm::dawg::Dawg;
+
+const ALPHABET: [char; 4] = ['a', 'b', 'c', 'd'];
+
+fn matching(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let dfa = random_dfa(&mut rng, 1000, &ALPHABET, 0.5);
+    let word = random_words(&mut rng, 1, &ALPHABET, 100_000).remove(0);
+    c.bench_function("dfa/accepts", |b| {
+        b.iter(|| dfa.accepts(black_box(&word).iter().copied()))
+    });
+
+    let nfa = nth_from_end(12);
+    let word = random_words(&mut rng, 1, &['a', 'b'], 10_000).remove(0);
+    c.bench_function("nfa/accepts", |b| {
+        b.iter(|| nfa.accepts(black_box(&word).iter().copied()))
+    });
+}
+
+fn determinization(c: &mut Criterion) {
+    let nfa = nth_from_end(12);
+    c.bench_function("determinize/nth_from_end", |b| b.iter(|| nfa.determinize()));
+
+    let nfa = random_nfa(&mut StdRng::seed_from_u64(42), 12, &ALPHABET, 1.5, 0.3);
+    c.bench_function("determinize/random", |b| b.iter(|| nfa.determinize()));
+}
+
+fn minimization(c: &mut Criterion) {
+    let dfa = random_dfa(&mut StdRng::seed_from_u64(42), 1000, &ALPHABET, 0.5);
+    c.bench_function("minimize/hopcroft", |b| b.iter(|| dfa.minimize()));
+
+    // Reversing a random DFA blows up, so use one with a small reverse:
+    let dfa = nth_from_end(10).determinize();
+    c.bench_function("minimize/brzozowski", |b| {
+        b.iter(|| dfa.minimize_brzozowski())
+    });
+}
+
+fn dawg(c: &mut Criterion) {
+    let words = random_words(&mut StdRng::seed_from_u64(42), 1000, &ALPHABET, 12);
+    c.bench_function("dawg/insert", |b| {
+        b.iter_batched(
+            Dawg::new,
+            |mut dawg| {
+                for word in &words {
+                    dawg.insert(word);
+                }
+                dawg
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    let dawg = dictionary_dawg(&mut StdRng::seed_from_u64(42), 1000, &ALPHABET, 12);
+    c.bench_function("dawg/contains", |b| {
+        b.iter(|| words.iter().filter(|word| dawg.contains(word)).count())
+    });
+}
+
+criterion_group!(benches, matching, determinization, minimization, dawg);
+criterion_main!(benches);
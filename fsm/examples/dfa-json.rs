@@ -26,7 +26,7 @@ fn main() -> color_eyre::Result<()> {
     ]}";
     println!("json3 = {}", json3);
 
-    let dfa3: Dfa<char> = serde_json::from_str(&json3)?;
+    let dfa3: Dfa<char> = serde_json::from_str(json3)?;
     println!("dfa3 = {:?}", dfa3);
     println!("{}", dfa3.render_graphviz());
 
@@ -0,0 +1,32 @@
+use fsm::alphabet::presets::{parse_fasta, Dna};
+use fsm::dfa::Dfa;
+
+const FASTA: &str = ">chr1 fragment
+GGATCCTTAGGATCCAA
+TTGGATCC
+>chr2 fragment
+ACGTACGTACGT
+";
+
+fn main() -> color_eyre::Result<()> {
+    color_eyre::install()?;
+
+    // Recognition site "GGATCC" of the BamHI restriction enzyme:
+    let motif = Dna::parse("GGATCC")?;
+    let mut dfa = Dfa::new();
+    let states = (0..=motif.len())
+        .map(|i| dfa.add_state(i == motif.len()))
+        .collect::<Vec<_>>();
+    for (i, &base) in motif.iter().enumerate() {
+        dfa.add_transition(states[i], base, states[i + 1]);
+    }
+
+    for record in parse_fasta(FASTA)? {
+        let sites = dfa
+            .scan(record.sequence.iter().copied())
+            .collect::<Vec<_>>();
+        println!("{}: {} site(s) at {:?}", record.header, sites.len(), sites);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,190 @@
+use std::collections::BTreeSet;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+
+pub mod presets;
+
+pub trait Alphabet: Hash + Eq + Debug + Clone + Copy + Ord {
+    /// Collects all symbols occurring in a corpus of words.
+    fn collect_from<W, I>(words: I) -> BTreeSet<Self>
+    where
+        I: IntoIterator<Item = W>,
+        W: IntoIterator<Item = Self>,
+    {
+        words.into_iter().flatten().collect()
+    }
+}
+
+impl<T> Alphabet for T where T: Hash + Eq + Debug + Clone + Copy + Ord {}
+
+/// Alphabet with exactly `N` symbols known at compile time, typically a fieldless enum.
+///
+/// Enables [`ArrayDfa`](crate::dfa::array::ArrayDfa), which stores transitions in
+/// fixed-size arrays indexed by [`index`](FiniteAlphabet::index) instead of hash maps.
+pub trait FiniteAlphabet<const N: usize>: Alphabet {
+    /// All symbols, in the order of their indices.
+    const SYMBOLS: [Self; N];
+
+    /// Position of this symbol in [`SYMBOLS`](FiniteAlphabet::SYMBOLS), in `0..N`.
+    fn index(self) -> usize;
+}
+
+impl FiniteAlphabet<2> for bool {
+    const SYMBOLS: [bool; 2] = [false, true];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Differences between the symbols of a corpus and the symbols of an automaton.
+///
+/// Symbols in `unknown` make every word containing them rejected, which usually means
+/// the automaton was built for a different symbol set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlphabetMismatch<A: Alphabet> {
+    /// Symbols occurring in the corpus, but on no transition of the automaton.
+    pub unknown: BTreeSet<A>,
+    /// Symbols of the automaton not occurring in the corpus.
+    pub unused: BTreeSet<A>,
+}
+
+impl<A: Alphabet> AlphabetMismatch<A> {
+    pub fn new(automaton: &BTreeSet<A>, corpus: &BTreeSet<A>) -> Self {
+        Self {
+            unknown: corpus.difference(automaton).copied().collect(),
+            unused: automaton.difference(corpus).copied().collect(),
+        }
+    }
+
+    /// Whether both symbol sets coincide.
+    pub fn is_empty(&self) -> bool {
+        self.unknown.is_empty() && self.unused.is_empty()
+    }
+}
+
+/// Named groups of symbols (e.g. `digit` for `'0'..='9'`), keeping large alphabets readable.
+///
+/// Groups are kept in declaration order, which is also the order in which
+/// [`label`](SymbolGroups::label) tries them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SymbolGroups<A: Alphabet> {
+    groups: Vec<(String, BTreeSet<A>)>,
+}
+
+impl<A: Alphabet> SymbolGroups<A> {
+    pub fn new() -> Self {
+        Self { groups: Vec::new() }
+    }
+
+    /// Declares the group `name`, replacing its previous definition (if any).
+    pub fn define(&mut self, name: impl Into<String>, symbols: impl IntoIterator<Item = A>) {
+        let name = name.into();
+        let symbols = symbols.into_iter().collect();
+        match self.groups.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, group)) => *group = symbols,
+            None => self.groups.push((name, symbols)),
+        }
+    }
+
+    /// Same as [`define`](SymbolGroups::define), in builder style.
+    pub fn with(mut self, name: impl Into<String>, symbols: impl IntoIterator<Item = A>) -> Self {
+        self.define(name, symbols);
+        self
+    }
+
+    pub fn get(&self, name: &str) -> Option<&BTreeSet<A>> {
+        self.groups
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, group)| group)
+    }
+
+    /// Symbols of the group `name` in sorted order, or `None` if there is no such group.
+    pub fn expand(&self, name: &str) -> Option<impl Iterator<Item = A> + '_> {
+        self.get(name).map(|group| group.iter().copied())
+    }
+
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Renders a set of symbols compactly, e.g. `"digit, ."`.
+    ///
+    /// Every group (in declaration order) whose symbols are all still uncovered is
+    /// replaced by its name; remaining symbols follow in sorted order.
+    pub fn label<'a>(&self, symbols: impl IntoIterator<Item = &'a A>) -> String
+    where
+        A: Display + 'a,
+    {
+        let mut rest = symbols.into_iter().copied().collect::<BTreeSet<_>>();
+        let mut parts = Vec::new();
+        for (name, group) in &self.groups {
+            if !group.is_empty() && group.is_subset(&rest) {
+                rest.retain(|symbol| !group.contains(symbol));
+                parts.push(name.clone());
+            }
+        }
+        parts.extend(rest.iter().map(|symbol| symbol.to_string()));
+        parts.join(", ")
+    }
+}
+
+impl<A: Alphabet> Default for SymbolGroups<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups() -> SymbolGroups<char> {
+        SymbolGroups::new()
+            .with("digit", '0'..='9')
+            .with("sign", ['+', '-'])
+    }
+
+    #[test]
+    fn test_collect_from() {
+        let symbols = char::collect_from(["abc", "cd", ""].iter().map(|word| word.chars()));
+        assert_eq!(symbols, BTreeSet::from(['a', 'b', 'c', 'd']));
+
+        let mismatch = AlphabetMismatch::new(&BTreeSet::from(['a', 'b', 'x']), &symbols);
+        assert_eq!(mismatch.unknown, BTreeSet::from(['c', 'd']));
+        assert_eq!(mismatch.unused, BTreeSet::from(['x']));
+        assert!(!mismatch.is_empty());
+    }
+
+    #[test]
+    fn test_symbol_groups() {
+        let groups = groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(
+            groups.expand("sign").unwrap().collect::<Vec<_>>(),
+            ['+', '-']
+        );
+        assert!(groups.expand("letter").is_none());
+
+        let mut symbols = ('0'..='9').collect::<Vec<_>>();
+        symbols.push('.');
+        assert_eq!(groups.label(&symbols), "digit, .");
+        // Partially covered groups are not used:
+        assert_eq!(groups.label(&['1', '+', '-']), "sign, 1");
+        assert_eq!(groups.label(&[]), "");
+    }
+
+    #[test]
+    fn test_symbol_groups_redefine() {
+        let mut groups = groups();
+        groups.define("digit", ['0', '1']);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups.label(&['0', '1']), "digit");
+    }
+}
@@ -0,0 +1,303 @@
+//! Ready-made alphabets for common inputs: nucleotides, ASCII text and raw bytes.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::alphabet::FiniteAlphabet;
+
+/// A char that does not belong to the alphabet it was parsed into.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InvalidSymbol {
+    pub symbol: char,
+    /// Byte offset of the symbol in the parsed text.
+    pub offset: usize,
+}
+
+impl Display for InvalidSymbol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "invalid symbol {:?} at offset {}",
+            self.symbol, self.offset
+        )
+    }
+}
+
+impl Error for InvalidSymbol {}
+
+/// Parses every char of `text` with `parse`, failing on the first one it rejects.
+fn parse_all<A>(text: &str, parse: impl Fn(char) -> Option<A>) -> Result<Vec<A>, InvalidSymbol> {
+    text.char_indices()
+        .map(|(offset, symbol)| parse(symbol).ok_or(InvalidSymbol { symbol, offset }))
+        .collect()
+}
+
+/// Nucleotide of a DNA sequence.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Dna {
+    A,
+    C,
+    G,
+    T,
+}
+
+impl Dna {
+    /// Parses a nucleotide letter, in either case.
+    pub fn from_char(c: char) -> Option<Dna> {
+        match c.to_ascii_uppercase() {
+            'A' => Some(Dna::A),
+            'C' => Some(Dna::C),
+            'G' => Some(Dna::G),
+            'T' => Some(Dna::T),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Dna::A => 'A',
+            Dna::C => 'C',
+            Dna::G => 'G',
+            Dna::T => 'T',
+        }
+    }
+
+    /// The paired nucleotide (A-T, C-G).
+    pub fn complement(self) -> Dna {
+        match self {
+            Dna::A => Dna::T,
+            Dna::C => Dna::G,
+            Dna::G => Dna::C,
+            Dna::T => Dna::A,
+        }
+    }
+
+    /// Parses a sequence such as `"GATTACA"`.
+    pub fn parse(text: &str) -> Result<Vec<Dna>, InvalidSymbol> {
+        parse_all(text, Dna::from_char)
+    }
+
+    /// Packs a sequence into 2 bits per nucleotide, the first one in the highest bits.
+    ///
+    /// The length is not stored, so pass it to [`unpack`](Dna::unpack) separately.
+    pub fn pack(sequence: &[Dna]) -> Vec<u8> {
+        sequence
+            .chunks(4)
+            .map(|chunk| {
+                chunk.iter().enumerate().fold(0, |byte, (i, &base)| {
+                    byte | (base.index() as u8) << (6 - 2 * i)
+                })
+            })
+            .collect()
+    }
+
+    /// Unpacks the first `len` nucleotides from the output of [`pack`](Dna::pack).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` holds fewer than `len` nucleotides.
+    pub fn unpack(bytes: &[u8], len: usize) -> Vec<Dna> {
+        assert!(
+            len <= 4 * bytes.len(),
+            "{} bytes hold at most {} nucleotides",
+            bytes.len(),
+            4 * bytes.len()
+        );
+        (0..len)
+            .map(|i| Dna::SYMBOLS[(bytes[i / 4] >> (6 - 2 * (i % 4)) & 0b11) as usize])
+            .collect()
+    }
+}
+
+impl FiniteAlphabet<4> for Dna {
+    const SYMBOLS: [Dna; 4] = [Dna::A, Dna::C, Dna::G, Dna::T];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl Display for Dna {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// A 7-bit ASCII char.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Ascii(u8);
+
+impl Ascii {
+    pub fn new(c: char) -> Option<Ascii> {
+        c.is_ascii().then_some(Ascii(c as u8))
+    }
+
+    pub fn to_char(self) -> char {
+        self.0 as char
+    }
+
+    pub fn to_byte(self) -> u8 {
+        self.0
+    }
+
+    pub fn parse(text: &str) -> Result<Vec<Ascii>, InvalidSymbol> {
+        parse_all(text, Ascii::new)
+    }
+}
+
+impl FiniteAlphabet<128> for Ascii {
+    const SYMBOLS: [Ascii; 128] = {
+        let mut symbols = [Ascii(0); 128];
+        let mut i = 0;
+        while i < 128 {
+            symbols[i] = Ascii(i as u8);
+            i += 1;
+        }
+        symbols
+    };
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Display for Ascii {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+/// A raw byte, for automata over binary data.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Byte(pub u8);
+
+impl Byte {
+    pub fn from_bytes(bytes: &[u8]) -> Vec<Byte> {
+        bytes.iter().copied().map(Byte).collect()
+    }
+
+    pub fn to_bytes(symbols: &[Byte]) -> Vec<u8> {
+        symbols.iter().map(|byte| byte.0).collect()
+    }
+}
+
+impl FiniteAlphabet<256> for Byte {
+    const SYMBOLS: [Byte; 256] = {
+        let mut symbols = [Byte(0); 256];
+        let mut i = 0;
+        while i < 256 {
+            symbols[i] = Byte(i as u8);
+            i += 1;
+        }
+        symbols
+    };
+
+    fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+impl Display for Byte {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02x}", self.0)
+    }
+}
+
+/// Record of a FASTA file: a `>` header line followed by sequence lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FastaRecord {
+    /// Header line without the leading `>`.
+    pub header: String,
+    pub sequence: Vec<Dna>,
+}
+
+/// Parses DNA records in the FASTA format.
+///
+/// Blank lines and `;` comment lines are skipped. Sequence lines before the first header
+/// form a record with an empty header. The offset of an [`InvalidSymbol`] is within `text`.
+pub fn parse_fasta(text: &str) -> Result<Vec<FastaRecord>, InvalidSymbol> {
+    let mut records: Vec<FastaRecord> = Vec::new();
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let line = line.trim_end();
+        if let Some(header) = line.strip_prefix('>') {
+            records.push(FastaRecord {
+                header: header.trim().to_string(),
+                sequence: Vec::new(),
+            });
+        } else if !line.is_empty() && !line.starts_with(';') {
+            let sequence = Dna::parse(line).map_err(|error| InvalidSymbol {
+                offset: start + error.offset,
+                ..error
+            })?;
+            if records.is_empty() {
+                records.push(FastaRecord {
+                    header: String::new(),
+                    sequence: Vec::new(),
+                });
+            }
+            records.last_mut().unwrap().sequence.extend(sequence);
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dna() {
+        let sequence = Dna::parse("GATTACAg").unwrap();
+        assert_eq!(sequence.len(), 8);
+        assert_eq!(
+            sequence
+                .iter()
+                .map(|base| base.to_string())
+                .collect::<String>(),
+            "GATTACAG"
+        );
+        assert_eq!(Dna::G.complement(), Dna::C);
+        assert_eq!(
+            Dna::parse("GAXT"),
+            Err(InvalidSymbol {
+                symbol: 'X',
+                offset: 2
+            })
+        );
+
+        let packed = Dna::pack(&sequence[..5]);
+        assert_eq!(packed, [0b10_00_11_11, 0b00_00_00_00]);
+        assert_eq!(Dna::unpack(&packed, 5), sequence[..5]);
+        assert!(Dna::pack(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_ascii_and_bytes() {
+        let text = Ascii::parse("Hi!").unwrap();
+        assert_eq!(text.iter().map(|c| c.to_byte()).collect::<Vec<_>>(), b"Hi!");
+        assert_eq!(Ascii::new('é'), None);
+        assert_eq!(Ascii::SYMBOLS[b'H' as usize].index(), b'H' as usize);
+
+        let bytes = Byte::from_bytes(&[0, 255]);
+        assert_eq!(Byte::to_bytes(&bytes), [0, 255]);
+        assert_eq!(Byte(0xab).to_string(), "ab");
+        assert_eq!(Byte::SYMBOLS[200], Byte(200));
+    }
+
+    #[test]
+    fn test_parse_fasta() {
+        let text = ">seq1 first\nGATT\nACA\n\n;comment\n>seq2\nggcc\n";
+        let records = parse_fasta(text).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].header, "seq1 first");
+        assert_eq!(records[0].sequence, Dna::parse("GATTACA").unwrap());
+        assert_eq!(records[1].sequence, Dna::parse("GGCC").unwrap());
+
+        let error = parse_fasta(">s\nGA\nGNT\n").unwrap_err();
+        assert_eq!(error.symbol, 'N');
+        assert_eq!(error.offset, 7);
+    }
+}
@@ -0,0 +1,417 @@
+//! Import and export of automata in the AT&T text format used by OpenFst (`fstcompile`/`fstprint`),
+//! see [`Dfa::from_att`] and [`Dfa::to_att`] (and the same for [`Nfa`] and [`Mealy`]).
+//!
+//! Every line is either an arc `from to input [output] [weight]` or a final state
+//! `state [weight]`, with fields separated by tabs or spaces. The source of the first line
+//! is the initial state. States are numbered in the order of appearance, so the initial
+//! state becomes state 0. Labels are symbols parsed with [`FromStr`], and the label `<eps>`
+//! is ε. Weights are ignored, so a weighted automaton is read as the set of words it
+//! accepts with any weight.
+//!
+//! For acceptors ([`Dfa`] and [`Nfa`]), the output label may be omitted, and otherwise
+//! has to be the same as the input label. A fourth field that is not the input label is
+//! taken as a weight. For a [`Mealy`] machine, the output label is required and final
+//! lines are ignored. When written, all states of a Mealy machine are final.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::conflict::ConflictPolicy;
+use crate::dfa::state::DfaStateId;
+use crate::dfa::Dfa;
+use crate::mealy::state::MealyStateId;
+use crate::mealy::Mealy;
+use crate::nfa::state::NfaStateId;
+use crate::nfa::Nfa;
+
+/// The label of ε-transitions.
+pub const EPSILON: &str = "<eps>";
+
+/// Error of reading an automaton in the AT&T format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttError {
+    /// Line number, starting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for AttError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for AttError {}
+
+fn error(line: usize, message: impl Into<String>) -> AttError {
+    AttError {
+        line,
+        message: message.into(),
+    }
+}
+
+struct Arc<'a> {
+    line: usize,
+    from: usize,
+    to: usize,
+    input: &'a str,
+    output: Option<&'a str>,
+}
+
+/// Lines of an AT&T file, with states numbered from the initial one.
+#[derive(Default)]
+struct Lines<'a> {
+    num_states: usize,
+    arcs: Vec<Arc<'a>>,
+    finals: Vec<usize>,
+}
+
+fn is_weight(field: &str) -> bool {
+    field.parse::<f64>().is_ok()
+}
+
+/// Splits the lines, `transducer` telling how to read a fourth field, see the [module docs](self).
+fn parse_lines(text: &str, transducer: bool) -> Result<Lines<'_>, AttError> {
+    let mut lines = Lines::default();
+    let mut ids = HashMap::new();
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        let mut state = |field: &str| {
+            if field.parse::<u64>().is_err() {
+                return Err(error(line_number, format!("invalid state {:?}", field)));
+            }
+            let next = ids.len();
+            Ok(*ids.entry(field.to_string()).or_insert(next))
+        };
+        match fields.as_slice() {
+            [] => {}
+            [state_field] | [state_field, _] => {
+                if let [_, weight] = fields.as_slice() {
+                    if !is_weight(weight) {
+                        return Err(error(line_number, format!("invalid weight {:?}", weight)));
+                    }
+                }
+                lines.finals.push(state(state_field)?);
+            }
+            [from, to, input, rest @ ..] => {
+                let (output, weight) = match rest {
+                    [] => (None, None),
+                    [field] if transducer || *field == *input || !is_weight(field) => {
+                        (Some(*field), None)
+                    }
+                    [weight] => (None, Some(*weight)),
+                    [output, weight] => (Some(*output), Some(*weight)),
+                    _ => return Err(error(line_number, "too many fields")),
+                };
+                if weight.is_some_and(|weight| !is_weight(weight)) {
+                    return Err(error(
+                        line_number,
+                        format!("invalid weight {:?}", weight.unwrap()),
+                    ));
+                }
+                lines.arcs.push(Arc {
+                    line: line_number,
+                    from: state(from)?,
+                    to: state(to)?,
+                    input,
+                    output,
+                });
+            }
+        }
+    }
+    lines.num_states = ids.len();
+    Ok(lines)
+}
+
+/// Input label of an acceptor arc, `None` for ε.
+fn acceptor_label<'a>(arc: &Arc<'a>) -> Result<Option<&'a str>, AttError> {
+    if arc.output.is_some_and(|output| output != arc.input) {
+        return Err(error(
+            arc.line,
+            "different input and output labels, not an acceptor",
+        ));
+    }
+    Ok((arc.input != EPSILON).then_some(arc.input))
+}
+
+fn symbol<A: FromStr>(label: &str, line: usize) -> Result<A, AttError> {
+    label
+        .parse()
+        .map_err(|_| error(line, format!("invalid symbol {:?}", label)))
+}
+
+/// Writes the arcs sorted by their source (so that the first line starts in state 0),
+/// followed by the final states.
+///
+/// An initial state without arcs that is not final accepts nothing and cannot be the source
+/// of the first line, so then the empty machine is written instead.
+fn write_lines(arcs: Vec<(usize, usize, String)>, finals: impl Iterator<Item = usize>) -> String {
+    let mut arcs = arcs;
+    arcs.sort();
+    let mut out = String::new();
+    let mut finals = finals.collect::<Vec<_>>();
+    // Without arcs from the initial state, the first line has to be its final line:
+    if arcs.first().is_none_or(|&(from, _, _)| from != 0) {
+        if finals.first() != Some(&0) {
+            return out;
+        }
+        writeln!(out, "0").unwrap();
+        finals.remove(0);
+    }
+    for (from, to, labels) in arcs {
+        writeln!(out, "{}\t{}\t{}", from, to, labels).unwrap();
+    }
+    for state in finals {
+        writeln!(out, "{}", state).unwrap();
+    }
+    out
+}
+
+impl<A: Alphabet + FromStr> Dfa<A> {
+    /// Reads a DFA in the AT&T format, see the [module docs](crate::att) for the details.
+    ///
+    /// Fails on malformed lines, ε-arcs, and arcs on the same symbol leaving a state
+    /// to different targets.
+    pub fn from_att(text: &str) -> Result<Self, AttError> {
+        let lines = parse_lines(text, false)?;
+        let mut dfa = Dfa::new().with_conflict_policy(ConflictPolicy::Error);
+        for _ in 0..lines.num_states {
+            dfa.add_state(false);
+        }
+        for state in lines.finals {
+            dfa.state_mut(DfaStateId::new(state)).accepting = true;
+        }
+        for arc in &lines.arcs {
+            let Some(label) = acceptor_label(arc)? else {
+                return Err(error(arc.line, "ε-arc in a DFA"));
+            };
+            let (from, to) = (DfaStateId::new(arc.from), DfaStateId::new(arc.to));
+            dfa.try_add_transition(from, symbol(label, arc.line)?, to)
+                .map_err(|conflict| error(arc.line, conflict.to_string()))?;
+        }
+        dfa.set_conflict_policy(ConflictPolicy::default());
+        Ok(dfa)
+    }
+}
+
+impl<A: Alphabet + Display, S, T> Dfa<A, S, T> {
+    /// Writes the automaton as an acceptor in the AT&T format, with symbols written by [`Display`].
+    ///
+    /// Symbols are expected to contain no whitespace, and none to be written as `<eps>`.
+    pub fn to_att(&self) -> String {
+        let arcs = self
+            .transitions()
+            .map(|(from, symbol, to)| (from.id.index(), to.id.index(), symbol.to_string()))
+            .collect();
+        let finals = self.states().filter(|state| state.accepting);
+        write_lines(arcs, finals.map(|state| state.id.index()))
+    }
+}
+
+impl<A: Alphabet + FromStr> Nfa<A> {
+    /// Reads an NFA in the AT&T format, see the [module docs](crate::att) for the details.
+    pub fn from_att(text: &str) -> Result<Self, AttError> {
+        let lines = parse_lines(text, false)?;
+        let mut nfa = Nfa::new();
+        for _ in 0..lines.num_states {
+            nfa.add_state(false);
+        }
+        for state in lines.finals {
+            nfa.state_mut(NfaStateId::new(state)).accepting = true;
+        }
+        for arc in &lines.arcs {
+            let (from, to) = (NfaStateId::new(arc.from), NfaStateId::new(arc.to));
+            match acceptor_label(arc)? {
+                Some(label) => nfa.add_transition(from, symbol(label, arc.line)?, to),
+                None => nfa.add_epsilon_transition(from, to),
+            }
+        }
+        Ok(nfa)
+    }
+}
+
+impl<A: Alphabet + Display> Nfa<A> {
+    /// Writes the automaton as an acceptor in the AT&T format, with symbols written by [`Display`]
+    /// and ε-transitions labeled `<eps>`.
+    pub fn to_att(&self) -> String {
+        let transitions = self
+            .transitions()
+            .map(|(from, symbol, to)| (from.id.index(), to.id.index(), symbol.to_string()));
+        let epsilon_transitions = self
+            .epsilon_transitions()
+            .map(|(from, to)| (from.id.index(), to.id.index(), EPSILON.to_string()));
+        let finals = self.states().filter(|state| state.accepting);
+        write_lines(
+            transitions.chain(epsilon_transitions).collect(),
+            finals.map(|state| state.id.index()),
+        )
+    }
+}
+
+impl<I: Alphabet + FromStr, O: Alphabet + FromStr> Mealy<I, O> {
+    /// Reads a Mealy machine from a transducer in the AT&T format, see the [module docs](crate::att).
+    ///
+    /// Fails on malformed lines, arcs without an output label, ε-labels,
+    /// and arcs on the same input leaving a state twice.
+    pub fn from_att(text: &str) -> Result<Self, AttError> {
+        let lines = parse_lines(text, true)?;
+        let mut mealy = Mealy::new();
+        for _ in 0..lines.num_states {
+            mealy.add_state();
+        }
+        for arc in &lines.arcs {
+            let Some(output) = arc.output else {
+                return Err(error(arc.line, "arc without an output label"));
+            };
+            if arc.input == EPSILON || output == EPSILON {
+                return Err(error(arc.line, "ε-label in a Mealy machine"));
+            }
+            let input = symbol(arc.input, arc.line)?;
+            let (from, to) = (MealyStateId::new(arc.from), MealyStateId::new(arc.to));
+            if mealy.state(from).next(input).is_some() {
+                return Err(error(
+                    arc.line,
+                    format!("state {} already has an arc on {:?}", arc.from, arc.input),
+                ));
+            }
+            mealy.add_transition(from, input, to, symbol(output, arc.line)?);
+        }
+        Ok(mealy)
+    }
+}
+
+impl<I: Alphabet + Display, O: Alphabet + Display> Mealy<I, O> {
+    /// Writes the machine as a transducer in the AT&T format, with all states final.
+    pub fn to_att(&self) -> String {
+        let arcs = self
+            .transitions()
+            .map(|(from, input, to, output)| {
+                let labels = format!("{}\t{}", input, output);
+                (from.id.index(), to.id.index(), labels)
+            })
+            .collect();
+        write_lines(arcs, self.states().map(|state| state.id.index()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dfa_from_att() {
+        // Words over {a, b} ending with "ab", with weights and sparse state numbers:
+        let text = "7\t3\ta\ta\t0.5\n7\t7\tb\n3 3 a 1\n3 12 b b\n12 3 a\n12 7 b\n\n12\t0\n";
+        let dfa = Dfa::<char>::from_att(text).unwrap();
+        assert_eq!(dfa.num_states(), 3);
+        assert_eq!(dfa.num_transitions(), 6);
+        assert!(dfa.accepts("bbaab".chars()));
+        assert!(!dfa.accepts("aba".chars()));
+        assert!(dfa.accepting(DfaStateId::new(2)));
+
+        let written = dfa.to_att();
+        assert!(written.starts_with("0\t0\tb\n0\t1\ta\n"));
+        let read = Dfa::<char>::from_att(&written).unwrap();
+        assert!(read.equivalent(&dfa));
+
+        // Only ε is accepted:
+        let mut dfa = Dfa::<char>::new();
+        dfa.add_state(true);
+        dfa.add_state(true);
+        dfa.add_transition(DfaStateId::new(1), 'x', DfaStateId::new(0));
+        assert_eq!(dfa.to_att(), "0\n1\t0\tx\n1\n");
+        let read = Dfa::<char>::from_att(&dfa.to_att()).unwrap();
+        assert!(read.accepts("".chars()));
+        assert!(!read.accepts("x".chars()));
+    }
+
+    #[test]
+    fn test_nfa_from_att() {
+        let text = "0 1 <eps>\n0 0 1 1\n1 2 2 2\n2\n";
+        let nfa = Nfa::<u8>::from_att(text).unwrap();
+        assert_eq!(nfa.num_epsilon_transitions(), 1);
+        assert!(nfa.accepts([2]));
+        assert!(nfa.accepts([1, 1, 2]));
+        assert!(!nfa.accepts([1]));
+
+        let read = Nfa::<u8>::from_att(&nfa.to_att()).unwrap();
+        assert_eq!(read.num_transitions(), 2);
+        assert_eq!(read.num_epsilon_transitions(), 1);
+        assert!(read.determinize().equivalent(&nfa.determinize()));
+    }
+
+    #[test]
+    fn test_mealy_from_att() {
+        let text = "0 1 a x\n0 0 b y 1.5\n1 0 a y\n1 1 b x\n0\n";
+        let mealy = Mealy::<char, char>::from_att(text).unwrap();
+        assert_eq!(mealy.num_states(), 2);
+        assert_eq!(
+            mealy.state(MealyStateId::new(0)).next('a'),
+            Some((MealyStateId::new(1), 'x'))
+        );
+        assert_eq!(
+            mealy.state(MealyStateId::new(1)).next('a'),
+            Some((MealyStateId::new(0), 'y'))
+        );
+
+        let read = Mealy::<char, char>::from_att(&mealy.to_att()).unwrap();
+        assert_eq!(read.num_transitions(), 4);
+        assert_eq!(
+            read.state(MealyStateId::new(0)).next('b'),
+            Some((MealyStateId::new(0), 'y'))
+        );
+    }
+
+    #[test]
+    fn test_from_att_errors() {
+        let check = |text: &str, line: usize, message: &str| {
+            let error = Dfa::<char>::from_att(text).unwrap_err();
+            assert_eq!(error.line, line, "{}", error);
+            assert!(error.message.contains(message), "{}", error);
+        };
+        check("0 1 a\n1 2 a b\n", 2, "not an acceptor");
+        check("0 1 a\n1 1 <eps>\n", 2, "ε-arc");
+        check("0 1 a\n0 0 a\n", 2, "already has");
+        check("0 1 ab\n", 1, "invalid symbol");
+        check("q0 q1 a\n", 1, "invalid state");
+        check("0 1 a\n1 x\n", 2, "invalid weight");
+        check("0 1 a a 0 0\n", 1, "too many fields");
+
+        let error = Mealy::<char, char>::from_att("0 1 a\n").unwrap_err();
+        assert!(error.message.contains("without an output"), "{}", error);
+    }
+
+    #[test]
+    fn test_att_initial_state_without_arcs() {
+        // The initial state is not final and has no arcs, so nothing is accepted:
+        let mut dfa = Dfa::<char>::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q1, 'a', q1);
+        assert!(!dfa.accepts("".chars()));
+        assert_eq!(dfa.to_att(), "");
+        let read = Dfa::<char>::from_att(&dfa.to_att()).unwrap();
+        assert!(!read.accepts("".chars()));
+        assert!(!read.accepts("a".chars()));
+        assert!(read.equivalent(&dfa));
+
+        let mut nfa = Nfa::<char>::new();
+        nfa.add_state(false);
+        let p1 = nfa.add_state(true);
+        nfa.add_transition(p1, 'a', p1);
+        nfa.add_epsilon_transition(p1, p1);
+        assert_eq!(nfa.to_att(), "");
+        let read = Nfa::<char>::from_att(&nfa.to_att()).unwrap();
+        assert!(!read.accepts("".chars()));
+        assert!(!read.accepts("a".chars()));
+
+        // An arc into the initial state does not make it the source of the first line:
+        dfa.add_transition(q1, 'b', q0);
+        let read = Dfa::<char>::from_att(&dfa.to_att()).unwrap();
+        assert!(read.equivalent(&dfa));
+    }
+}
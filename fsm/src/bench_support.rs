@@ -0,0 +1,139 @@
+use rand::Rng;
+
+use crate::alphabet::Alphabet;
+use crate::dawg::Dawg;
+use crate::dfa::state::DfaStateId;
+use crate::dfa::Dfa;
+use crate::nfa::state::NfaStateId;
+use crate::nfa::Nfa;
+
+/// Random complete DFA with `num_states` states over `alphabet`.
+///
+/// Every transition target is uniform, and every state is accepting with probability
+/// `accepting_ratio`. Such automata are mostly reachable and far from minimal,
+/// which makes them a fair workload for minimization.
+pub fn random_dfa<A: Alphabet, R: Rng + ?Sized>(
+    rng: &mut R,
+    num_states: usize,
+    alphabet: &[A],
+    accepting_ratio: f64,
+) -> Dfa<A> {
+    let mut dfa = Dfa::new();
+    for _ in 0..num_states {
+        dfa.add_state(rng.gen_bool(accepting_ratio));
+    }
+    for from in 0..num_states {
+        for &symbol in alphabet {
+            let to = DfaStateId::new(rng.gen_range(0..num_states));
+            dfa.add_transition(DfaStateId::new(from), symbol, to);
+        }
+    }
+    dfa
+}
+
+/// Random NFA with `num_states` states over `alphabet`, with `density` expected targets
+/// per `(state, symbol)` pair and `density / 4` expected ε-transitions per state.
+///
+/// Determinizing such automata tends to produce many subsets, so keep `num_states` small.
+pub fn random_nfa<A: Alphabet, R: Rng + ?Sized>(
+    rng: &mut R,
+    num_states: usize,
+    alphabet: &[A],
+    density: f64,
+    accepting_ratio: f64,
+) -> Nfa<A> {
+    let mut nfa = Nfa::new();
+    for _ in 0..num_states {
+        nfa.add_state(rng.gen_bool(accepting_ratio));
+    }
+    let p = (density / num_states as f64).min(1.0);
+    for from in (0..num_states).map(NfaStateId::new) {
+        for to in (0..num_states).map(NfaStateId::new) {
+            for &symbol in alphabet {
+                if rng.gen_bool(p) {
+                    nfa.add_transition(from, symbol, to);
+                }
+            }
+            if from != to && rng.gen_bool(p / 4.0) {
+                nfa.add_epsilon_transition(from, to);
+            }
+        }
+    }
+    nfa
+}
+
+/// `num_words` random words over `alphabet`, with lengths uniform in `1..=max_length`.
+///
+/// Words may repeat.
+pub fn random_words<A: Alphabet, R: Rng + ?Sized>(
+    rng: &mut R,
+    num_words: usize,
+    alphabet: &[A],
+    max_length: usize,
+) -> Vec<Vec<A>> {
+    (0..num_words)
+        .map(|_| {
+            let length = rng.gen_range(1..=max_length);
+            (0..length)
+                .map(|_| alphabet[rng.gen_range(0..alphabet.len())])
+                .collect()
+        })
+        .collect()
+}
+
+/// Dictionary of [random words](random_words), stored in a [`Dawg`].
+pub fn dictionary_dawg<A: Alphabet, R: Rng + ?Sized>(
+    rng: &mut R,
+    num_words: usize,
+    alphabet: &[A],
+    max_length: usize,
+) -> Dawg<A> {
+    let mut dawg = Dawg::new();
+    for word in random_words(rng, num_words, alphabet, max_length) {
+        dawg.insert(&word);
+    }
+    dawg
+}
+
+/// Words over {a, b} whose `n`-th symbol from the end is `a`.
+///
+/// The NFA has `n + 1` states, while the minimal DFA has `2^n` states,
+/// so this is the standard worst case for determinization.
+pub fn nth_from_end(n: usize) -> Nfa<char> {
+    let mut nfa = Nfa::new();
+    let states = (0..=n).map(|i| nfa.add_state(i == n)).collect::<Vec<_>>();
+    nfa.add_transition(states[0], 'a', states[0]);
+    nfa.add_transition(states[0], 'b', states[0]);
+    nfa.add_transition(states[0], 'a', states[1]);
+    for i in 1..n {
+        nfa.add_transition(states[i], 'a', states[i + 1]);
+        nfa.add_transition(states[i], 'b', states[i + 1]);
+    }
+    nfa
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn test_workloads() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let dfa = random_dfa(&mut rng, 50, &['a', 'b'], 0.5);
+        assert_eq!(dfa.num_states(), 50);
+        assert_eq!(dfa.num_transitions(), 100);
+
+        let nfa = random_nfa(&mut rng, 8, &['a', 'b'], 1.5, 0.3);
+        assert_eq!(nfa.num_states(), 8);
+
+        let words = random_words(&mut rng, 100, &['x', 'y', 'z'], 6);
+        let dawg = dictionary_dawg(&mut StdRng::seed_from_u64(7), 100, &['x', 'y', 'z'], 6);
+        assert!(words.iter().all(|word| (1..=6).contains(&word.len())));
+        assert!(!dawg.is_empty());
+
+        assert_eq!(nth_from_end(4).determinize().minimize().num_states(), 16);
+    }
+}
@@ -0,0 +1,487 @@
+//! Embedding of precompiled automata into Rust code, intended for build scripts.
+//!
+//! A [`Codegen`] collects named DFAs over chars, from memory or from files. It minimizes
+//! them and emits a Rust module per automaton. Each module holds static tables and
+//! accessor functions, so nothing is built at runtime:
+//!
+//! ```no_run
+//! // build.rs
+//! let out = std::path::Path::new(&std::env::var("OUT_DIR").unwrap()).join("automata.rs");
+//! fsm::codegen::Codegen::new()
+//!     .spec_file("identifier", "automata/identifier.fsm")
+//!     .unwrap()
+//!     .write(&out)
+//!     .unwrap();
+//! ```
+//!
+//! The crate then includes the result and calls `identifier::accepts(s)`:
+//!
+//! ```text
+//! include!(concat!(env!("OUT_DIR"), "/automata.rs"));
+//! ```
+//!
+//! Patterns are embedded by compiling them with `Regex::to_dfa` from `regex-thompson`
+//! (with its `fsm` feature) and adding the result with [`Codegen::dfa`].
+//!
+//! [`Codegen::write`] tells cargo to rerun the build script only when an input file changes,
+//! and leaves the output file untouched when its content is the same, so dependent code
+//! is not recompiled needlessly.
+//!
+//! # Spec format
+//!
+//! One transition `from symbol to` per line, and `accept` lines listing accepting states.
+//! States are numbered from 0, which is the initial state. A symbol is a single char or one
+//! of the escapes `\s` (space), `\t`, `\n` and `\\`. Blank lines and `#` comments are skipped:
+//!
+//! ```text
+//! # Even number of 'a's
+//! accept 0
+//! 0 a 1
+//! 1 a 0
+//! 0 b 0
+//! 1 b 1
+//! ```
+
+use std::error::Error;
+use std::fmt::{Display, Formatter, Write};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::dfa::conflict::ConflictPolicy;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// A line of a spec that could not be parsed, see [`parse_spec`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecError {
+    /// Line number, starting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for SpecError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for SpecError {}
+
+/// Error of adding an automaton to a [`Codegen`].
+#[derive(Debug)]
+pub enum CodegenError {
+    /// The input file could not be read.
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    Spec {
+        path: PathBuf,
+        error: SpecError,
+    },
+    #[cfg(feature = "json")]
+    Json {
+        path: PathBuf,
+        error: serde_json::Error,
+    },
+    /// The name is not a Rust identifier, or is a keyword.
+    InvalidName(String),
+    DuplicateName(String),
+}
+
+impl Display for CodegenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CodegenError::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            CodegenError::Spec { path, error } => write!(f, "{}: {}", path.display(), error),
+            #[cfg(feature = "json")]
+            CodegenError::Json { path, error } => write!(f, "{}: {}", path.display(), error),
+            CodegenError::InvalidName(name) => write!(f, "invalid automaton name {:?}", name),
+            CodegenError::DuplicateName(name) => write!(f, "duplicate automaton name {:?}", name),
+        }
+    }
+}
+
+impl Error for CodegenError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CodegenError::Io { error, .. } => Some(error),
+            CodegenError::Spec { error, .. } => Some(error),
+            #[cfg(feature = "json")]
+            CodegenError::Json { error, .. } => Some(error),
+            CodegenError::InvalidName(_) | CodegenError::DuplicateName(_) => None,
+        }
+    }
+}
+
+/// Parses a DFA in the [spec format](self#spec-format).
+///
+/// Two transitions from a state on the same symbol are an error.
+pub fn parse_spec(text: &str) -> Result<Dfa<char>, SpecError> {
+    let mut accepting = Vec::new();
+    let mut transitions = Vec::new();
+    let mut num_states = 0;
+    for (i, line) in text.lines().enumerate() {
+        let error = |message: String| SpecError {
+            line: i + 1,
+            message,
+        };
+        let state = |token: &str| {
+            token
+                .parse::<usize>()
+                .map_err(|_| error(format!("invalid state {:?}", token)))
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        if tokens[0] == "accept" {
+            for token in &tokens[1..] {
+                let state = state(token)?;
+                num_states = num_states.max(state + 1);
+                accepting.push(state);
+            }
+            continue;
+        }
+        let &[from, symbol, to] = tokens.as_slice() else {
+            return Err(error(format!(
+                "expected `from symbol to`, found {} tokens",
+                tokens.len()
+            )));
+        };
+        let symbol = match symbol {
+            "\\s" => ' ',
+            "\\t" => '\t',
+            "\\n" => '\n',
+            "\\\\" => '\\',
+            _ => {
+                let mut chars = symbol.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => return Err(error(format!("invalid symbol {:?}", symbol))),
+                }
+            }
+        };
+        let (from, to) = (state(from)?, state(to)?);
+        num_states = num_states.max(from.max(to) + 1);
+        transitions.push((i, from, symbol, to));
+    }
+
+    let mut dfa = Dfa::new().with_conflict_policy(ConflictPolicy::Error);
+    for _ in 0..num_states {
+        dfa.add_state(false);
+    }
+    for state in accepting {
+        dfa.state_mut(StateId::new(state)).accepting = true;
+    }
+    for (i, from, symbol, to) in transitions {
+        dfa.try_add_transition(StateId::new(from), symbol, StateId::new(to))
+            .map_err(|conflict| SpecError {
+                line: i + 1,
+                message: conflict.to_string(),
+            })?;
+    }
+    dfa.set_conflict_policy(ConflictPolicy::default());
+    Ok(dfa)
+}
+
+/// Strict and reserved keywords of Rust, which cannot name a module.
+const KEYWORDS: &[&str] = &[
+    "_", "abstract", "as", "async", "await", "become", "box", "break", "const", "continue",
+    "crate", "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+fn read(path: &Path) -> Result<String, CodegenError> {
+    std::fs::read_to_string(path).map_err(|error| CodegenError::Io {
+        path: path.to_path_buf(),
+        error,
+    })
+}
+
+/// Generator of a Rust module embedding minimized DFAs, see the [module docs](self).
+#[derive(Debug, Default)]
+pub struct Codegen {
+    automata: Vec<(String, Dfa<char>)>,
+    inputs: Vec<PathBuf>,
+}
+
+impl Codegen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the minimization of `dfa`, emitted as the module `name`, which must be
+    /// an identifier and not a keyword.
+    pub fn dfa(mut self, name: &str, dfa: &Dfa<char>) -> Result<Self, CodegenError> {
+        let mut chars = name.chars();
+        if !chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            || !chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+            || KEYWORDS.contains(&name)
+        {
+            return Err(CodegenError::InvalidName(name.to_string()));
+        }
+        if self.automata.iter().any(|(other, _)| other == name) {
+            return Err(CodegenError::DuplicateName(name.to_string()));
+        }
+        self.automata.push((name.to_string(), dfa.minimize()));
+        Ok(self)
+    }
+
+    /// Adds the DFA read from a file in the [spec format](self#spec-format).
+    pub fn spec_file(self, name: &str, path: impl AsRef<Path>) -> Result<Self, CodegenError> {
+        let path = path.as_ref().to_path_buf();
+        let text = read(&path)?;
+        let dfa = parse_spec(&text).map_err(|error| CodegenError::Spec {
+            path: path.clone(),
+            error,
+        })?;
+        let mut this = self.dfa(name, &dfa)?;
+        this.inputs.push(path);
+        Ok(this)
+    }
+
+    /// Adds the DFA read from a file with its serde JSON serialization.
+    #[cfg(feature = "json")]
+    pub fn json_file(self, name: &str, path: impl AsRef<Path>) -> Result<Self, CodegenError> {
+        let path = path.as_ref().to_path_buf();
+        let text = read(&path)?;
+        let dfa: Dfa<char> = serde_json::from_str(&text).map_err(|error| CodegenError::Json {
+            path: path.clone(),
+            error,
+        })?;
+        let mut this = self.dfa(name, &dfa)?;
+        this.inputs.push(path);
+        Ok(this)
+    }
+
+    /// Files the automata were read from.
+    pub fn inputs(&self) -> &[PathBuf] {
+        &self.inputs
+    }
+
+    /// Renders the generated code.
+    ///
+    /// Every automaton `name` becomes a module `name` with the tables `SYMBOLS` (sorted),
+    /// `ACCEPTING` and `TRANSITIONS` (a row per state, a column per symbol), and the
+    /// functions `next(state, symbol)`, `accepting(state)` and `accepts(word)`.
+    /// The initial state is 0.
+    pub fn generate(&self) -> String {
+        let mut out = String::from("// @generated by fsm::codegen, do not edit.\n");
+        for (name, dfa) in &self.automata {
+            let dense = dfa.to_dense();
+            let symbols = dense.symbols();
+            let num_states = dense.num_states();
+            let list = |items: Vec<String>| items.join(", ");
+
+            writeln!(out).unwrap();
+            writeln!(
+                out,
+                "/// Minimal DFA with {} states over {} symbols.",
+                num_states,
+                symbols.len()
+            )
+            .unwrap();
+            writeln!(out, "#[allow(dead_code)]\npub mod {} {{", name).unwrap();
+            writeln!(out, "    pub const NUM_STATES: usize = {};", num_states).unwrap();
+            writeln!(
+                out,
+                "    pub static SYMBOLS: [char; {}] = [{}];",
+                symbols.len(),
+                list(symbols.iter().map(|c| format!("{:?}", c)).collect())
+            )
+            .unwrap();
+            writeln!(
+                out,
+                "    pub static ACCEPTING: [bool; {}] = [{}];",
+                num_states,
+                list(
+                    (0..num_states)
+                        .map(|q| dense.accepting(StateId::new(q)).to_string())
+                        .collect()
+                )
+            )
+            .unwrap();
+            let rows = (0..num_states)
+                .map(StateId::new)
+                .map(|q| {
+                    let row = symbols.iter().map(|&c| match dense.next(q, c) {
+                        Some(to) => format!("Some({})", to),
+                        None => "None".to_string(),
+                    });
+                    format!("[{}]", list(row.collect()))
+                })
+                .collect();
+            writeln!(
+                out,
+                "    pub static TRANSITIONS: [[Option<usize>; {}]; {}] = [{}];",
+                symbols.len(),
+                num_states,
+                list(rows)
+            )
+            .unwrap();
+            out.push_str(
+                "
+    pub fn next(state: usize, symbol: char) -> Option<usize> {
+        let column = SYMBOLS.binary_search(&symbol).ok()?;
+        TRANSITIONS[state][column]
+    }
+
+    pub fn accepting(state: usize) -> bool {
+        ACCEPTING[state]
+    }
+",
+            );
+            if num_states == 0 {
+                out.push_str(
+                    "
+    pub fn accepts(_word: &str) -> bool {
+        false
+    }
+}
+",
+                );
+            } else {
+                out.push_str(
+                    "
+    pub fn accepts(word: &str) -> bool {
+        let mut state = 0;
+        for symbol in word.chars() {
+            match next(state, symbol) {
+                Some(to) => state = to,
+                None => return false,
+            }
+        }
+        accepting(state)
+    }
+}
+",
+                );
+            }
+        }
+        out
+    }
+
+    /// Writes the [generated](Codegen::generate) code to `path`, unless the file already
+    /// has this content, returning whether it was written.
+    ///
+    /// Also prints the `cargo:rerun-if-changed` directives for the [inputs](Codegen::inputs),
+    /// so it is meant to be called from a build script.
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<bool> {
+        for input in &self.inputs {
+            println!("cargo:rerun-if-changed={}", input.display());
+        }
+        let path = path.as_ref();
+        let code = self.generate();
+        if std::fs::read_to_string(path).is_ok_and(|existing| existing == code) {
+            return Ok(false);
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, code)?;
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec() {
+        let dfa = parse_spec("# even a\naccept 0\n0 a 1\n1 a 0\n0 b 0\n\n1 \\s 1\n").unwrap();
+        assert_eq!(dfa.num_states(), 2);
+        assert!(dfa.accepts("baa".chars()));
+        assert!(dfa.accepts("a  a".chars()));
+        assert!(!dfa.accepts("a b".chars()));
+
+        let error = parse_spec("0 a 1\n0 ab 1").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: invalid symbol \"ab\"");
+        assert_eq!(parse_spec("accept x").unwrap_err().line, 1);
+        assert!(parse_spec("0 a 1\n0 a 2").is_err());
+    }
+
+    #[test]
+    fn test_generate() {
+        // Redundant states are merged:
+        let dfa = parse_spec("accept 1 2\n0 x 1\n0 y 2\n1 ' 1\n2 ' 2").unwrap();
+        let code = Codegen::new().dfa("quoted", &dfa).unwrap().generate();
+        assert!(code.contains("pub mod quoted {"));
+        assert!(code.contains("pub const NUM_STATES: usize = 2;"));
+        assert!(code.contains("pub static SYMBOLS: [char; 3] = ['\\'', 'x', 'y'];"));
+        assert!(code.contains("pub static ACCEPTING: [bool; 2] = [false, true];"));
+        assert!(code.contains(
+            "pub static TRANSITIONS: [[Option<usize>; 3]; 2] = \
+             [[None, Some(1), Some(1)], [Some(1), None, None]];"
+        ));
+
+        let empty = Codegen::new().dfa("empty", &Dfa::new()).unwrap().generate();
+        assert!(empty.contains("pub fn accepts(_word: &str) -> bool {\n        false"));
+
+        for name in ["1st", "type", "fn", "self", "Self", "crate", "super", "_"] {
+            assert!(
+                matches!(
+                    Codegen::new().dfa(name, &dfa),
+                    Err(CodegenError::InvalidName(_))
+                ),
+                "{}",
+                name
+            );
+        }
+        assert!(Codegen::new().dfa("types", &dfa).is_ok());
+        assert!(matches!(
+            Codegen::new().dfa("a", &dfa).unwrap().dfa("a", &dfa),
+            Err(CodegenError::DuplicateName(_))
+        ));
+    }
+
+    #[test]
+    fn test_write() {
+        let dir = std::env::temp_dir().join(format!("fsm-codegen-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let spec = dir.join("even.fsm");
+        std::fs::write(&spec, "accept 0\n0 a 1\n1 a 0\n").unwrap();
+        let out = dir.join("out/automata.rs");
+
+        let codegen = Codegen::new().spec_file("even", &spec).unwrap();
+        assert_eq!(codegen.inputs(), std::slice::from_ref(&spec));
+        assert!(codegen.write(&out).unwrap());
+        // Unchanged output is not rewritten:
+        assert!(!codegen.write(&out).unwrap());
+
+        std::fs::write(&spec, "accept 0\n0 a 1\n1 a 2\n2 a 0\n").unwrap();
+        let codegen = Codegen::new().spec_file("even", &spec).unwrap();
+        assert!(codegen.write(&out).unwrap());
+        assert!(std::fs::read_to_string(&out)
+            .unwrap()
+            .contains("NUM_STATES: usize = 3;"));
+
+        let missing = Codegen::new().spec_file("missing", dir.join("missing.fsm"));
+        assert!(matches!(missing, Err(CodegenError::Io { .. })));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_file() {
+        let dir = std::env::temp_dir().join(format!("fsm-codegen-json-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("ab.json");
+        let dfa = parse_spec("accept 2\n0 a 1\n1 b 2").unwrap();
+        std::fs::write(&path, serde_json::to_string(&dfa).unwrap()).unwrap();
+
+        let code = Codegen::new().json_file("ab", &path).unwrap().generate();
+        assert!(code.contains("pub static SYMBOLS: [char; 2] = ['a', 'b'];"));
+
+        std::fs::write(&path, "{").unwrap();
+        let error = Codegen::new().json_file("ab", &path);
+        assert!(matches!(error, Err(CodegenError::Json { .. })));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
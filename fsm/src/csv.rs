@@ -0,0 +1,353 @@
+//! Import and export of DFAs as transition tables in CSV or TSV, so that they can be
+//! edited in spreadsheets and diffed as plain text, see [`Dfa::from_csv`] and [`Dfa::to_csv`].
+//!
+//! Every row is a transition with the columns `state`, `symbol`, `target` and `accepting`,
+//! the latter telling whether the state of the row is accepting. A state without transitions
+//! is listed in a row with empty `symbol` and `target`. A first row with the column names
+//! is a header and is skipped, and empty rows are ignored.
+//!
+//! States are named by any non-empty text. They are numbered in the order of their first
+//! row, and then states appearing only as targets in the order of appearance, so the state
+//! of the first row becomes state 0. A state appearing only as a target is not accepting.
+//! When written, states are named by their ids and all states have rows, so the ids
+//! survive a round-trip.
+//!
+//! Fields containing the delimiter, quotes or line breaks are enclosed in double quotes,
+//! with quotes doubled, as in [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180).
+//! TSV uses the same rules with tabs as the delimiter.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::conflict::ConflictPolicy;
+use crate::dfa::state::DfaStateId;
+use crate::dfa::Dfa;
+
+/// Names of the columns, written in the header.
+pub const HEADER: [&str; 4] = ["state", "symbol", "target", "accepting"];
+
+/// Error of reading a transition table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvError {
+    /// Line number, starting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for CsvError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for CsvError {}
+
+fn error(line: usize, message: impl Into<String>) -> CsvError {
+    CsvError {
+        line,
+        message: message.into(),
+    }
+}
+
+/// A row of fields, with the line it starts on.
+struct Row {
+    line: usize,
+    fields: Vec<String>,
+}
+
+/// Splits the text into rows, skipping empty ones.
+fn parse_rows(text: &str, delimiter: char) -> Result<Vec<Row>, CsvError> {
+    let mut rows = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut line = 1;
+    let mut start = 1;
+    let mut chars = text.chars().peekable();
+    let mut quoted = false;
+    let mut end_row = |fields: &mut Vec<String>, field: &mut String, start: usize| {
+        fields.push(std::mem::take(field));
+        if fields.len() > 1 || !fields[0].is_empty() {
+            rows.push(Row {
+                line: start,
+                fields: std::mem::take(fields),
+            });
+        }
+        fields.clear();
+    };
+    while let Some(c) = chars.next() {
+        if c == '\n' {
+            line += 1;
+        }
+        if quoted {
+            match c {
+                '"' if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                '"' => quoted = false,
+                _ => field.push(c),
+            }
+        } else if c == '"' && field.is_empty() {
+            quoted = true;
+        } else if c == delimiter {
+            fields.push(std::mem::take(&mut field));
+        } else if c == '\n' || (c == '\r' && chars.peek() == Some(&'\n')) {
+            if c == '\r' {
+                chars.next();
+                line += 1;
+            }
+            end_row(&mut fields, &mut field, start);
+            start = line;
+        } else if c == '"' {
+            return Err(error(line, "quote inside an unquoted field"));
+        } else {
+            field.push(c);
+        }
+    }
+    if quoted {
+        return Err(error(start, "unterminated quoted field"));
+    }
+    end_row(&mut fields, &mut field, start);
+    Ok(rows)
+}
+
+fn is_header(row: &Row) -> bool {
+    row.fields.len() == HEADER.len()
+        && row
+            .fields
+            .iter()
+            .zip(HEADER)
+            .all(|(field, name)| field.trim().eq_ignore_ascii_case(name))
+}
+
+fn parse_accepting(field: &str, line: usize) -> Result<bool, CsvError> {
+    match field.trim() {
+        "true" | "1" => Ok(true),
+        "false" | "0" => Ok(false),
+        _ => Err(error(line, format!("invalid accepting flag {:?}", field))),
+    }
+}
+
+fn write_field(out: &mut String, field: &str, delimiter: char) {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        write!(out, "\"{}\"", field.replace('"', "\"\"")).unwrap();
+    } else {
+        out.push_str(field);
+    }
+}
+
+impl<A: Alphabet + FromStr> Dfa<A> {
+    /// Reads a DFA from a comma-separated transition table, see the [module docs](crate::csv).
+    ///
+    /// Fails on malformed rows, states with conflicting accepting flags, and transitions
+    /// on the same symbol leaving a state to different targets.
+    pub fn from_csv(text: &str) -> Result<Self, CsvError> {
+        Self::from_table(text, ',')
+    }
+
+    /// Reads a DFA from a tab-separated transition table, see [`Dfa::from_csv`].
+    pub fn from_tsv(text: &str) -> Result<Self, CsvError> {
+        Self::from_table(text, '\t')
+    }
+
+    fn from_table(text: &str, delimiter: char) -> Result<Self, CsvError> {
+        let mut rows = parse_rows(text, delimiter)?;
+        if rows.first().is_some_and(is_header) {
+            rows.remove(0);
+        }
+        for row in &rows {
+            if row.fields.len() != HEADER.len() {
+                return Err(error(
+                    row.line,
+                    format!(
+                        "expected {} fields, found {}",
+                        HEADER.len(),
+                        row.fields.len()
+                    ),
+                ));
+            }
+            if row.fields[0].is_empty() {
+                return Err(error(row.line, "empty state"));
+            }
+        }
+
+        // Sources are numbered first, then the states appearing only as targets:
+        let mut ids = HashMap::<&str, DfaStateId>::new();
+        for name in rows
+            .iter()
+            .map(|row| &row.fields[0])
+            .chain(rows.iter().map(|row| &row.fields[2]))
+            .filter(|name| !name.is_empty())
+        {
+            let next = DfaStateId::new(ids.len());
+            ids.entry(name).or_insert(next);
+        }
+
+        let mut dfa = Dfa::new().with_conflict_policy(ConflictPolicy::Error);
+        let mut accepting = vec![None; ids.len()];
+        for _ in 0..ids.len() {
+            dfa.add_state(false);
+        }
+        for row in &rows {
+            let [state, symbol, target, flag] = &row.fields[..] else {
+                unreachable!()
+            };
+            let from = ids[state.as_str()];
+            let flag = parse_accepting(flag, row.line)?;
+            if accepting[from.index()]
+                .replace(flag)
+                .is_some_and(|old| old != flag)
+            {
+                return Err(error(
+                    row.line,
+                    format!("conflicting accepting flags of state {:?}", state),
+                ));
+            }
+            dfa.state_mut(from).accepting = flag;
+            match (symbol.is_empty(), target.is_empty()) {
+                (true, true) => {}
+                (false, false) => {
+                    let symbol = symbol
+                        .parse()
+                        .map_err(|_| error(row.line, format!("invalid symbol {:?}", symbol)))?;
+                    dfa.try_add_transition(from, symbol, ids[target.as_str()])
+                        .map_err(|conflict| error(row.line, conflict.to_string()))?;
+                }
+                (true, false) => return Err(error(row.line, "target without a symbol")),
+                (false, true) => return Err(error(row.line, "symbol without a target")),
+            }
+        }
+        dfa.set_conflict_policy(ConflictPolicy::default());
+        Ok(dfa)
+    }
+}
+
+impl<A: Alphabet + Display, S, T> Dfa<A, S, T> {
+    /// Writes the automaton as a comma-separated transition table with a header,
+    /// with symbols written by [`Display`], see the [module docs](crate::csv).
+    ///
+    /// Rows are sorted by state and symbol, so the output is stable.
+    pub fn to_csv(&self) -> String {
+        self.to_table(',')
+    }
+
+    /// Writes the automaton as a tab-separated transition table, see [`Dfa::to_csv`].
+    pub fn to_tsv(&self) -> String {
+        self.to_table('\t')
+    }
+
+    fn to_table(&self, delimiter: char) -> String {
+        let mut out = String::new();
+        let mut write_row = |fields: [&str; 4]| {
+            for (i, field) in fields.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(delimiter);
+                }
+                write_field(&mut out, field, delimiter);
+            }
+            out.push('\n');
+        };
+        write_row(HEADER);
+        for state in self.states() {
+            let id = state.id.to_string();
+            let accepting = state.accepting.to_string();
+            let mut transitions = state.transitions().collect::<Vec<_>>();
+            transitions.sort_unstable();
+            if transitions.is_empty() {
+                write_row([&id, "", "", &accepting]);
+            }
+            for (symbol, to) in transitions {
+                write_row([&id, &symbol.to_string(), &to.to_string(), &accepting]);
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dfa_csv() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let q2 = dfa.add_state(false);
+        dfa.add_transition(q0, ',', q1);
+        dfa.add_transition(q0, '"', q2);
+        dfa.add_transition(q1, 'a', q1);
+        assert_eq!(
+            dfa.to_csv(),
+            "state,symbol,target,accepting\n\
+             0,\"\"\"\",2,false\n\
+             0,\",\",1,false\n\
+             1,a,1,true\n\
+             2,,,false\n"
+        );
+        assert_eq!(
+            dfa.to_tsv(),
+            "state\tsymbol\ttarget\taccepting\n\
+             0\t\"\"\"\"\t2\tfalse\n\
+             0\t,\t1\tfalse\n\
+             1\ta\t1\ttrue\n\
+             2\t\t\tfalse\n"
+        );
+
+        for read in [
+            Dfa::<char>::from_csv(&dfa.to_csv()).unwrap(),
+            Dfa::<char>::from_tsv(&dfa.to_tsv()).unwrap(),
+        ] {
+            assert_eq!(read.num_states(), 3);
+            assert_eq!(read.state(q0).next(','), Some(q1));
+            assert_eq!(read.state(q0).next('"'), Some(q2));
+            assert!(read.accepting(q1));
+            assert_eq!(read.to_csv(), dfa.to_csv());
+        }
+    }
+
+    #[test]
+    fn test_dfa_from_csv_names() {
+        // A protocol with named states, edited by hand:
+        let text = "State,Symbol,Target,Accepting\r\n\
+                    idle,o,open,1\r\n\
+                    \r\n\
+                    open,c,idle,0\r\n\
+                    open,x,\"broken, for good\",0\r\n\
+                    idle,x,\"broken, for good\",1\r\n";
+        let dfa = Dfa::<char>::from_csv(text).unwrap();
+        assert_eq!(dfa.num_states(), 3);
+        assert!(dfa.accepting(DfaStateId::new(0)));
+        assert!(!dfa.accepting(DfaStateId::new(1)));
+        assert!(!dfa.accepting(DfaStateId::new(2)));
+        assert!(dfa.accepts("ococ".chars()));
+        assert!(!dfa.accepts("ox".chars()));
+        assert_eq!(
+            dfa.state(DfaStateId::new(1)).next('x'),
+            Some(DfaStateId::new(2))
+        );
+    }
+
+    #[test]
+    fn test_dfa_from_csv_errors() {
+        let check = |text: &str, line: usize, message: &str| {
+            let error = Dfa::<char>::from_csv(text).unwrap_err();
+            assert_eq!(error.line, line, "{}", error);
+            assert!(error.message.contains(message), "{}", error);
+        };
+        check("0,a,1,false\n1,a,0\n", 2, "expected 4 fields, found 3");
+        check("0,a,1,false\n,a,0,false\n", 2, "empty state");
+        check("0,a,1,yes\n", 1, "invalid accepting flag");
+        check(
+            "0,a,1,false\n0,b,1,true\n",
+            2,
+            "conflicting accepting flags",
+        );
+        check("0,ab,1,false\n", 1, "invalid symbol");
+        check("0,a,1,false\n0,a,0,false\n", 2, "already has");
+        check("0,,1,false\n", 1, "target without a symbol");
+        check("0,a,,false\n", 1, "symbol without a target");
+        check("0,a\"b,1,false\n", 1, "quote inside");
+        check("0,a,1,false\n1,\"a\n,0,false\n", 2, "unterminated");
+    }
+}
@@ -0,0 +1,334 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::Dfa;
+
+#[derive(Debug, Clone)]
+struct Node<A: Alphabet> {
+    accepting: bool,
+    transitions: BTreeMap<A, usize>,
+    /// Number of incoming transitions (plus one for the root).
+    refs: usize,
+}
+
+impl<A: Alphabet> Node<A> {
+    fn new() -> Self {
+        Self {
+            accepting: false,
+            transitions: BTreeMap::new(),
+            refs: 0,
+        }
+    }
+
+    fn is_useless(&self) -> bool {
+        !self.accepting && self.transitions.is_empty()
+    }
+}
+
+/// Right language of a node, given that all its children are unique.
+type Signature<A> = (bool, Vec<(A, usize)>);
+
+/// Minimal acyclic DFA for a finite set of words, maintained incrementally.
+///
+/// Every update copies the states along the path of the word, changes the last one,
+/// and then merges the copies bottom-up with equivalent registered states (Carrasco & Forcada,
+/// Daciuk et al.). States that become unreferenced are freed, so the automaton stays minimal
+/// after every [`insert`](Dawg::insert) and [`remove`](Dawg::remove), in any order of words.
+#[derive(Debug)]
+pub struct Dawg<A: Alphabet> {
+    nodes: Vec<Option<Node<A>>>,
+    free: Vec<usize>,
+    register: HashMap<Signature<A>, usize>,
+    root: usize,
+    num_words: usize,
+}
+
+impl<A: Alphabet> Dawg<A> {
+    pub fn new() -> Self {
+        let mut dawg = Self {
+            nodes: Vec::new(),
+            free: Vec::new(),
+            register: HashMap::new(),
+            root: 0,
+            num_words: 0,
+        };
+        let root = dawg.alloc(Node::new());
+        dawg.root = dawg.canonicalize(root, true).unwrap();
+        dawg.node_mut(dawg.root).refs += 1;
+        dawg
+    }
+
+    /// Number of stored words.
+    pub fn len(&self) -> usize {
+        self.num_words
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.num_words == 0
+    }
+
+    /// Number of states, including the initial one (even when no words are stored).
+    pub fn num_states(&self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    pub fn contains(&self, word: &[A]) -> bool {
+        let mut current = self.root;
+        for symbol in word {
+            match self.node(current).transitions.get(symbol) {
+                Some(&next) => current = next,
+                None => return false,
+            }
+        }
+        self.node(current).accepting
+    }
+
+    /// Adds `word`, returning `false` if it was already present.
+    pub fn insert(&mut self, word: &[A]) -> bool {
+        if self.contains(word) {
+            return false;
+        }
+        self.update(word, true);
+        self.num_words += 1;
+        true
+    }
+
+    /// Removes `word`, returning `false` if it was not present.
+    pub fn remove(&mut self, word: &[A]) -> bool {
+        if !self.contains(word) {
+            return false;
+        }
+        self.update(word, false);
+        self.num_words -= 1;
+        true
+    }
+
+    /// Converts into a [`Dfa`], numbering states in BFS order following transitions in symbol order.
+    pub fn to_dfa(&self) -> Dfa<A> {
+        let mut dfa = Dfa::new();
+        let mut node2state = HashMap::new();
+        let mut queue = VecDeque::new();
+        node2state.insert(self.root, dfa.add_state(self.node(self.root).accepting));
+        queue.push_back(self.root);
+        while let Some(node) = queue.pop_front() {
+            let from = node2state[&node];
+            for (&symbol, &next) in &self.node(node).transitions {
+                let to = *node2state.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    dfa.add_state(self.node(next).accepting)
+                });
+                dfa.add_transition(from, symbol, to);
+            }
+        }
+        dfa
+    }
+
+    fn node(&self, id: usize) -> &Node<A> {
+        self.nodes[id].as_ref().unwrap()
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Node<A> {
+        self.nodes[id].as_mut().unwrap()
+    }
+
+    fn alloc(&mut self, node: Node<A>) -> usize {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn signature(&self, id: usize) -> Signature<A> {
+        let node = self.node(id);
+        let transitions = node
+            .transitions
+            .iter()
+            .map(|(&symbol, &to)| (symbol, to))
+            .collect();
+        (node.accepting, transitions)
+    }
+
+    /// Makes the path of `word` end in a state with the given acceptance.
+    fn update(&mut self, word: &[A], accepting: bool) {
+        // Copy the path, sharing all children of the copies:
+        let mut path = Vec::with_capacity(word.len() + 1);
+        let mut original = Some(self.root);
+        for i in 0..=word.len() {
+            let node = match original {
+                Some(id) => {
+                    let mut node = self.node(id).clone();
+                    node.refs = 0;
+                    for &child in node.transitions.values() {
+                        self.node_mut(child).refs += 1;
+                    }
+                    node
+                }
+                None => Node::new(),
+            };
+            path.push(self.alloc(node));
+            if i < word.len() {
+                original = original.and_then(|id| self.node(id).transitions.get(&word[i]).copied());
+            }
+        }
+        self.node_mut(path[word.len()]).accepting = accepting;
+
+        // Link the copies bottom-up, replacing each one by its registered equivalent:
+        let mut child = self.canonicalize(path[word.len()], word.is_empty());
+        for i in (0..word.len()).rev() {
+            let parent = path[i];
+            if let Some(old) = self.node_mut(parent).transitions.remove(&word[i]) {
+                self.release(old);
+            }
+            if let Some(child) = child {
+                self.node_mut(parent).transitions.insert(word[i], child);
+                self.node_mut(child).refs += 1;
+            }
+            child = self.canonicalize(parent, i == 0);
+        }
+
+        let root = child.unwrap();
+        self.node_mut(root).refs += 1;
+        let old_root = std::mem::replace(&mut self.root, root);
+        self.release(old_root);
+    }
+
+    /// Registers a fresh (unreferenced) node or replaces it by its registered equivalent.
+    ///
+    /// Useless nodes are dropped (returning `None`), unless `is_root`.
+    fn canonicalize(&mut self, id: usize, is_root: bool) -> Option<usize> {
+        if !is_root && self.node(id).is_useless() {
+            self.nodes[id] = None;
+            self.free.push(id);
+            return None;
+        }
+        let signature = self.signature(id);
+        match self.register.get(&signature) {
+            Some(&existing) => {
+                // Free the unreferenced copy, dropping its references to the shared children:
+                self.node_mut(id).refs += 1;
+                self.release(id);
+                Some(existing)
+            }
+            None => {
+                self.register.insert(signature, id);
+                Some(id)
+            }
+        }
+    }
+
+    /// Drops a reference to `id`, freeing all nodes that become unreferenced.
+    fn release(&mut self, id: usize) {
+        let mut stack = vec![id];
+        while let Some(id) = stack.pop() {
+            let node = self.node_mut(id);
+            node.refs -= 1;
+            if node.refs > 0 {
+                continue;
+            }
+            let signature = self.signature(id);
+            if self.register.get(&signature) == Some(&id) {
+                self.register.remove(&signature);
+            }
+            let node = self.nodes[id].take().unwrap();
+            self.free.push(id);
+            stack.extend(node.transitions.into_values());
+        }
+    }
+}
+
+impl<A: Alphabet> Default for Dawg<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use super::*;
+
+    fn chars(word: &str) -> Vec<char> {
+        word.chars().collect()
+    }
+
+    /// Checks the DAWG against the set of words it should contain.
+    fn check(dawg: &Dawg<char>, words: &BTreeSet<Vec<char>>) {
+        assert_eq!(dawg.len(), words.len());
+        let dfa = dawg.to_dfa();
+        assert_eq!(dfa.num_states(), dawg.num_states());
+        let stored = dfa.words_up_to(8).collect::<BTreeSet<_>>();
+        assert_eq!(&stored, words);
+        // Minimal (the empty set is represented by a lone non-accepting state):
+        if !words.is_empty() {
+            assert_eq!(dfa.minimize().num_states(), dawg.num_states());
+        } else {
+            assert_eq!(dawg.num_states(), 1);
+        }
+    }
+
+    #[test]
+    fn test_dawg_insert() {
+        let mut dawg = Dawg::new();
+        let mut words = BTreeSet::new();
+        for word in ["tap", "taps", "top", "tops", "", "t"] {
+            assert!(dawg.insert(&chars(word)));
+            words.insert(chars(word));
+            check(&dawg, &words);
+        }
+        assert!(!dawg.insert(&chars("top")));
+        assert!(dawg.contains(&chars("taps")));
+        assert!(!dawg.contains(&chars("ta")));
+        // {"", t, tap, taps, top, tops}: root, t, ta|to, tap|top, taps|tops
+        assert_eq!(dawg.num_states(), 5);
+    }
+
+    #[test]
+    fn test_dawg_remove() {
+        let mut dawg = Dawg::new();
+        let mut words = BTreeSet::new();
+        for word in ["cat", "cats", "bat", "bats", "ca"] {
+            dawg.insert(&chars(word));
+            words.insert(chars(word));
+        }
+        for word in ["cats", "ca", "bat", "cat", "bats"] {
+            assert!(dawg.remove(&chars(word)));
+            assert!(!dawg.remove(&chars(word)));
+            words.remove(&chars(word));
+            check(&dawg, &words);
+        }
+        assert!(dawg.is_empty());
+    }
+
+    #[test]
+    fn test_dawg_random_updates() {
+        // Simple LCG, to keep the test deterministic:
+        let mut seed = 42u64;
+        let mut next = move |n: u64| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) % n
+        };
+        let mut dawg = Dawg::new();
+        let mut words = BTreeSet::new();
+        for _ in 0..300 {
+            let len = next(6) as usize;
+            let word = (0..len)
+                .map(|_| ['a', 'b', 'c'][next(3) as usize])
+                .collect::<Vec<_>>();
+            if next(3) == 0 {
+                assert_eq!(dawg.remove(&word), words.remove(&word));
+            } else {
+                assert_eq!(dawg.insert(&word), words.insert(word));
+            }
+            check(&dawg, &words);
+        }
+    }
+}
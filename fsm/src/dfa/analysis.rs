@@ -0,0 +1,252 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::alphabet::{Alphabet, AlphabetMismatch};
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+use crate::util::dfs::{dfs, multi_dfs};
+
+/// Problematic states found by [`Dfa::deadlock_report`].
+///
+/// Every entry is a reachable state together with its access word
+/// (a shortest word leading to it from the initial state).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeadlockReport<A: Alphabet> {
+    /// Non-accepting states without outgoing transitions.
+    pub deadlocks: Vec<(StateId, Vec<A>)>,
+    /// Non-accepting states with outgoing transitions, all of them self-loops.
+    pub sinks: Vec<(StateId, Vec<A>)>,
+    /// Accepting states that can't be left, i.e. all outgoing transitions (if any) are self-loops.
+    pub accepting_traps: Vec<(StateId, Vec<A>)>,
+}
+
+impl<A: Alphabet> DeadlockReport<A> {
+    /// Whether nothing was found.
+    pub fn is_empty(&self) -> bool {
+        self.deadlocks.is_empty() && self.sinks.is_empty() && self.accepting_traps.is_empty()
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// States reachable from the initial state.
+    ///
+    /// Unlike [`Dfa::trim`], this leaves the automaton untouched.
+    pub fn accessible_states(&self) -> BTreeSet<StateId> {
+        if self.states.is_empty() {
+            return BTreeSet::new();
+        }
+        dfs(StateId::new(0), |state| {
+            self.state(state)
+                .transitions()
+                .map(|(_, to)| to)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+    }
+
+    /// States from which some accepting state is reachable.
+    ///
+    /// Unlike [`Dfa::trim`], this leaves the automaton untouched.
+    pub fn coaccessible_states(&self) -> BTreeSet<StateId> {
+        let mut predecessors: HashMap<StateId, Vec<StateId>> = HashMap::new();
+        for (from, _, to) in self.transitions() {
+            predecessors.entry(to.id).or_default().push(from.id);
+        }
+        let accepting = self
+            .states()
+            .filter(|state| state.accepting)
+            .map(|state| state.id)
+            .collect();
+        multi_dfs(accepting, |state| {
+            predecessors.get(&state).cloned().unwrap_or_default()
+        })
+        .collect()
+    }
+
+    /// Lists transitions that are never taken while reading an accepted word.
+    ///
+    /// A transition is dead when its source is unreachable from the initial state,
+    /// or when no accepting state is reachable from its target.
+    /// The result is sorted by `(from, symbol)`.
+    pub fn dead_transitions(&self) -> Vec<(StateId, A, StateId)> {
+        let accessible = self.accessible_states();
+        let coaccessible = self.coaccessible_states();
+        let mut dead = self
+            .transitions()
+            .filter(|(from, _, to)| {
+                !accessible.contains(&from.id) || !coaccessible.contains(&to.id)
+            })
+            .map(|(from, symbol, to)| (from.id, symbol, to.id))
+            .collect::<Vec<_>>();
+        dead.sort_unstable();
+        dead
+    }
+
+    /// Removes all [dead transitions](Dfa::dead_transitions), returning the number of removed ones.
+    ///
+    /// States are kept as is (so all `StateId`s stay valid), and the language is unchanged.
+    /// Payloads of the removed transitions are dropped.
+    pub fn prune_useless(&mut self) -> usize {
+        let dead = self.dead_transitions();
+        for &(from, symbol, _) in &dead {
+            self.remove_transition(from, symbol);
+        }
+        dead.len()
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Finds reachable deadlocks, non-accepting sinks and accepting traps.
+    ///
+    /// Useful for sanity-checking composed protocol models. Entries are sorted by state.
+    pub fn deadlock_report(&self) -> DeadlockReport<A> {
+        let access_words = self.access_words();
+        let mut report = DeadlockReport {
+            deadlocks: Vec::new(),
+            sinks: Vec::new(),
+            accepting_traps: Vec::new(),
+        };
+        let mut reachable = access_words.keys().copied().collect::<Vec<_>>();
+        reachable.sort_unstable();
+        for state in reachable {
+            let entry = (state, access_words[&state].clone());
+            let num_transitions = self.state(state).num_transitions();
+            let only_self_loops = self.state(state).transitions().all(|(_, to)| to == state);
+            if self.accepting(state) {
+                if only_self_loops {
+                    report.accepting_traps.push(entry);
+                }
+            } else if num_transitions == 0 {
+                report.deadlocks.push(entry);
+            } else if only_self_loops {
+                report.sinks.push(entry);
+            }
+        }
+        report
+    }
+
+    /// Compares the symbols used in `corpus` with the [`symbols`](Dfa::symbols) of this automaton.
+    pub fn alphabet_mismatch<W, I>(&self, corpus: I) -> AlphabetMismatch<A>
+    where
+        I: IntoIterator<Item = W>,
+        W: IntoIterator<Item = A>,
+    {
+        let symbols = self.symbols().into_iter().collect();
+        AlphabetMismatch::new(&symbols, &A::collect_from(corpus))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    #[test]
+    fn test_deadlock_report() {
+        let mut dfa = Dfa::new();
+        let idle = dfa.add_state(true);
+        let waiting = dfa.add_state(false);
+        let stuck = dfa.add_state(false);
+        let spinning = dfa.add_state(false);
+        let done = dfa.add_state(true);
+        let _unreachable = dfa.add_state(false);
+        dfa.add_transition(idle, 'r', waiting);
+        dfa.add_transition(waiting, 'a', idle);
+        dfa.add_transition(waiting, 'x', stuck);
+        dfa.add_transition(waiting, 'y', spinning);
+        dfa.add_transition(spinning, 'y', spinning);
+        dfa.add_transition(idle, 'q', done);
+        dfa.add_transition(done, 'q', done);
+
+        let report = dfa.deadlock_report();
+        assert_eq!(report.deadlocks, vec![(stuck, vec!['r', 'x'])]);
+        assert_eq!(report.sinks, vec![(spinning, vec!['r', 'y'])]);
+        assert_eq!(report.accepting_traps, vec![(done, vec!['q'])]);
+        assert!(!report.is_empty());
+    }
+
+    #[test]
+    fn test_accessible_states() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let trap = dfa.add_state(false);
+        let orphan = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'b', trap);
+        dfa.add_transition(orphan, 'a', q0);
+        assert_eq!(dfa.accessible_states(), BTreeSet::from([q0, q1, trap]));
+        assert_eq!(dfa.coaccessible_states(), BTreeSet::from([q0, q1, orphan]));
+        assert_eq!(dfa.num_states(), 4);
+        assert!(Dfa::<char>::new().accessible_states().is_empty());
+    }
+
+    #[test]
+    fn test_dead_transitions() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let trap = dfa.add_state(false);
+        let orphan = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', trap);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, 'b', trap);
+        dfa.add_transition(trap, 'a', trap);
+        dfa.add_transition(orphan, 'a', q1);
+
+        assert_eq!(
+            dfa.dead_transitions(),
+            vec![
+                (q0, 'b', trap),
+                (q1, 'b', trap),
+                (trap, 'a', trap),
+                (orphan, 'a', q1),
+            ]
+        );
+
+        let words = generate_strings(&['a', 'b'], 6);
+        let expected = words
+            .iter()
+            .map(|word| dfa.accepts(word.chars()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(dfa.prune_useless(), 4);
+        assert_eq!(dfa.num_states(), 4);
+        assert_eq!(dfa.num_transitions(), 2);
+        assert!(dfa.dead_transitions().is_empty());
+        for (word, expected) in words.iter().zip(expected) {
+            assert_eq!(dfa.accepts(word.chars()), expected);
+        }
+    }
+
+    #[test]
+    fn test_prune_useless_drops_payloads() {
+        let mut dfa = Dfa::<char, (), &str>::default();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let trap = dfa.add_state(false);
+        dfa.add_transition_with(q0, 'a', q1, "good");
+        dfa.add_transition_with(q0, 'b', trap, "dead");
+
+        assert_eq!(dfa.prune_useless(), 1);
+        assert_eq!(dfa.transition_data(q0, 'a'), Some(&"good"));
+        assert_eq!(dfa.transition_data(q0, 'b'), None);
+        // A new edge on the same symbol starts with the default payload:
+        dfa.add_transition(q0, 'b', q1);
+        assert_eq!(dfa.transition_data(q0, 'b'), Some(&""));
+    }
+
+    #[test]
+    fn test_alphabet_mismatch() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q0);
+        dfa.add_transition(q0, 'b', q0);
+        let corpus = ["ab", "AB"];
+        let mismatch = dfa.alphabet_mismatch(corpus.iter().map(|word| word.chars()));
+        assert_eq!(mismatch.unknown, ['A', 'B'].into());
+        assert!(mismatch.unused.is_empty());
+    }
+}
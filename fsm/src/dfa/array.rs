@@ -0,0 +1,169 @@
+use std::marker::PhantomData;
+
+use crate::alphabet::{Alphabet, FiniteAlphabet};
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// DFA over a [`FiniteAlphabet`] of `N` symbols, with a fixed-size row of transitions
+/// per state, see [`Dfa::to_array`].
+///
+/// Looking up a transition is a single array access, with neither hashing nor searching.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArrayDfa<A: FiniteAlphabet<N>, const N: usize> {
+    table: Vec<[Option<StateId>; N]>,
+    accepting: Vec<bool>,
+    symbols: PhantomData<A>,
+}
+
+impl<A: FiniteAlphabet<N>, const N: usize> ArrayDfa<A, N> {
+    pub fn num_states(&self) -> usize {
+        self.accepting.len()
+    }
+
+    pub fn num_transitions(&self) -> usize {
+        self.table
+            .iter()
+            .flatten()
+            .filter(|to| to.is_some())
+            .count()
+    }
+
+    pub fn accepting(&self, state: StateId) -> bool {
+        self.accepting[state.index()]
+    }
+
+    pub fn next(&self, state: StateId, symbol: A) -> Option<StateId> {
+        self.table[state.index()][symbol.index()]
+    }
+
+    pub fn accepts(&self, word: impl IntoIterator<Item = A>) -> bool {
+        if self.accepting.is_empty() {
+            return false;
+        }
+        let mut current = StateId::new(0);
+        for symbol in word {
+            match self.next(current, symbol) {
+                Some(next) => current = next,
+                None => return false,
+            }
+        }
+        self.accepting(current)
+    }
+
+    /// Converts back into a [`Dfa`] with the same states.
+    pub fn to_dfa(&self) -> Dfa<A> {
+        let mut dfa = Dfa::new();
+        for &accepting in &self.accepting {
+            dfa.add_state(accepting);
+        }
+        for (from, row) in self.table.iter().enumerate() {
+            for (&symbol, &to) in A::SYMBOLS.iter().zip(row) {
+                if let Some(to) = to {
+                    dfa.add_transition(StateId::new(from), symbol, to);
+                }
+            }
+        }
+        dfa
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Compiles into an [`ArrayDfa`] with the same states.
+    pub fn to_array<const N: usize>(&self) -> ArrayDfa<A, N>
+    where
+        A: FiniteAlphabet<N>,
+    {
+        let table = self
+            .states()
+            .map(|state| A::SYMBOLS.map(|symbol| state.next(symbol)))
+            .collect();
+        let accepting = self.states().map(|state| state.accepting).collect();
+        ArrayDfa {
+            table,
+            accepting,
+            symbols: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+    enum Base {
+        A,
+        C,
+        G,
+        T,
+    }
+
+    impl FiniteAlphabet<4> for Base {
+        const SYMBOLS: [Base; 4] = [Base::A, Base::C, Base::G, Base::T];
+
+        fn index(self) -> usize {
+            self as usize
+        }
+    }
+
+    fn all_words<const N: usize, A: FiniteAlphabet<N>>(max_length: usize) -> Vec<Vec<A>> {
+        let mut words = vec![Vec::new()];
+        let mut last = vec![Vec::new()];
+        for _ in 0..max_length {
+            last = last
+                .iter()
+                .flat_map(|word: &Vec<A>| {
+                    A::SYMBOLS.into_iter().map(move |symbol| {
+                        let mut word = word.clone();
+                        word.push(symbol);
+                        word
+                    })
+                })
+                .collect();
+            words.extend(last.iter().cloned());
+        }
+        words
+    }
+
+    #[test]
+    fn test_to_array() {
+        use Base::*;
+
+        // Words containing the motif "GA":
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        for base in [A, C, T] {
+            dfa.add_transition(q0, base, q0);
+        }
+        dfa.add_transition(q0, G, q1);
+        dfa.add_transition(q1, G, q1);
+        dfa.add_transition(q1, A, q2);
+        dfa.add_transition(q1, C, q0);
+        for base in Base::SYMBOLS {
+            dfa.add_transition(q2, base, q2);
+        }
+
+        let array = dfa.to_array();
+        assert_eq!(array.num_states(), 3);
+        assert_eq!(array.num_transitions(), dfa.num_transitions());
+        assert_eq!(array.next(q1, A), Some(q2));
+        assert_eq!(array.next(q1, T), None);
+        for word in all_words::<4, Base>(5) {
+            assert_eq!(array.accepts(word.clone()), dfa.accepts(word));
+        }
+        assert!(array.to_dfa().equivalent(&dfa));
+
+        let mut parity = Dfa::new();
+        let even = parity.add_state(true);
+        let odd = parity.add_state(false);
+        parity.add_transition(even, true, odd);
+        parity.add_transition(odd, true, even);
+        parity.add_transition(even, false, even);
+        parity.add_transition(odd, false, odd);
+        let parity: ArrayDfa<bool, 2> = parity.to_array();
+        assert!(parity.accepts([true, false, true]));
+        assert!(!parity.accepts([true, true, true]));
+    }
+}
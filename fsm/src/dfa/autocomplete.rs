@@ -0,0 +1,96 @@
+use std::collections::BTreeSet;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::words::Words;
+use crate::dfa::Dfa;
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// State reached after reading `prefix`, or `None` if some symbol has no transition.
+    fn state_after(&self, prefix: &[A]) -> Option<StateId> {
+        let run = self.run(prefix.iter().copied());
+        if run.len() == prefix.len() + 1 {
+            run.last().copied()
+        } else {
+            None
+        }
+    }
+
+    /// Symbols that can follow `after` in some accepted word, in sorted order.
+    ///
+    /// Symbols leading only to dead ends are left out, so every suggestion can be completed.
+    pub fn valid_next_symbols(&self, after: &[A]) -> BTreeSet<A> {
+        match self.state_after(after) {
+            Some(state) => self.first_symbols(state),
+            None => BTreeSet::new(),
+        }
+    }
+
+    /// Finds up to `limit` shortest completions of `prefix`, i.e. suffixes `s`
+    /// such that `prefix` followed by `s` is accepted.
+    ///
+    /// Completions are ordered by length, then lexicographically; the empty completion
+    /// comes first when `prefix` itself is accepted.
+    pub fn complete_prefix(&self, prefix: &[A], limit: usize) -> Vec<Vec<A>> {
+        match self.state_after(prefix) {
+            Some(state) => Words::new(self, state, usize::MAX).take(limit).collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Commands "get", "git", "go" and "go" followed by any number of "!".
+    fn commands() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let start = dfa.add_state(false);
+        let g = dfa.add_state(false);
+        let ge = dfa.add_state(false);
+        let gi = dfa.add_state(false);
+        let done = dfa.add_state(true);
+        let go = dfa.add_state(true);
+        let trap = dfa.add_state(false);
+        dfa.add_transition(start, 'g', g);
+        dfa.add_transition(g, 'e', ge);
+        dfa.add_transition(g, 'i', gi);
+        dfa.add_transition(g, 'o', go);
+        dfa.add_transition(g, 'x', trap);
+        dfa.add_transition(ge, 't', done);
+        dfa.add_transition(gi, 't', done);
+        dfa.add_transition(go, '!', go);
+        dfa
+    }
+
+    fn strings(words: Vec<Vec<char>>) -> Vec<String> {
+        words.into_iter().map(String::from_iter).collect()
+    }
+
+    #[test]
+    fn test_valid_next_symbols() {
+        let dfa = commands();
+        assert_eq!(dfa.valid_next_symbols(&[]), BTreeSet::from(['g']));
+        assert_eq!(
+            dfa.valid_next_symbols(&['g']),
+            BTreeSet::from(['e', 'i', 'o'])
+        );
+        assert_eq!(dfa.valid_next_symbols(&['g', 'o']), BTreeSet::from(['!']));
+        assert!(dfa.valid_next_symbols(&['g', 'e', 't']).is_empty());
+        assert!(dfa.valid_next_symbols(&['x']).is_empty());
+    }
+
+    #[test]
+    fn test_complete_prefix() {
+        let dfa = commands();
+        assert_eq!(strings(dfa.complete_prefix(&['g'], 3)), ["o", "et", "it"]);
+        assert_eq!(
+            strings(dfa.complete_prefix(&['g', 'o'], 3)),
+            ["", "!", "!!"]
+        );
+        assert!(dfa.complete_prefix(&['g', 'x'], 3).is_empty());
+        assert!(dfa.complete_prefix(&['a'], 3).is_empty());
+        assert!(dfa.complete_prefix(&[], 0).is_empty());
+    }
+}
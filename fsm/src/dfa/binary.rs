@@ -0,0 +1,392 @@
+//! Compact binary format for DFAs, independent of serde, see [`Dfa::to_bytes`].
+//!
+//! Layout (all integers are unsigned LEB128 varints unless noted):
+//!
+//! - Magic `b"FSMD"` and the format version byte ([`VERSION`]).
+//! - Number of states `n`, number of distinct symbols `k`, and the `k` symbols in sorted order,
+//!   each encoded with [`BinarySymbol`].
+//! - Accepting states as a bitset of `⌈n / 8⌉` bytes, least significant bit first.
+//! - For every state in order: its number of transitions, then for each transition
+//!   (sorted by symbol) the index of its symbol in the symbol table and its target state.
+//!
+//! Symbols are stored once in the table, so a transition usually takes 2–4 bytes.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+const MAGIC: &[u8; 4] = b"FSMD";
+
+/// Current version of the format, written by [`Dfa::to_bytes`].
+pub const VERSION: u8 = 1;
+
+/// Error of decoding a DFA with [`Dfa::from_bytes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryError {
+    /// The input does not start with the magic bytes of the format.
+    BadMagic,
+    /// The input was written by a newer version of the format.
+    UnsupportedVersion(u8),
+    /// The input ends in the middle of the automaton.
+    UnexpectedEnd,
+    /// The input is well-formed up to `offset`, where it has an invalid value.
+    Invalid { offset: usize, message: String },
+}
+
+impl Display for BinaryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BinaryError::BadMagic => write!(f, "not a binary DFA"),
+            BinaryError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {}", version)
+            }
+            BinaryError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            BinaryError::Invalid { offset, message } => {
+                write!(f, "at byte {}: {}", offset, message)
+            }
+        }
+    }
+}
+
+impl Error for BinaryError {}
+
+/// Reader over the encoded bytes, tracking the offset for errors.
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Decoder<'_> {
+    pub fn byte(&mut self) -> Result<u8, BinaryError> {
+        let &byte = self
+            .bytes
+            .get(self.offset)
+            .ok_or(BinaryError::UnexpectedEnd)?;
+        self.offset += 1;
+        Ok(byte)
+    }
+
+    pub fn varint(&mut self) -> Result<u64, BinaryError> {
+        let start = self.offset;
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                if shift == 63 && byte > 1 {
+                    break;
+                }
+                return Ok(value);
+            }
+        }
+        Err(self.invalid_at(start, "varint overflow"))
+    }
+
+    fn usize(&mut self) -> Result<usize, BinaryError> {
+        let start = self.offset;
+        let value = self.varint()?;
+        usize::try_from(value).map_err(|_| self.invalid_at(start, "value too large"))
+    }
+
+    /// Error about the value starting at `offset`.
+    pub fn invalid_at(&self, offset: usize, message: impl Into<String>) -> BinaryError {
+        BinaryError::Invalid {
+            offset,
+            message: message.into(),
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Symbol type that can be stored in the binary format.
+pub trait BinarySymbol: Sized {
+    fn encode(&self, out: &mut Vec<u8>);
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, BinaryError>;
+}
+
+macro_rules! impl_binary_symbol_for_uint {
+    ($($t:ty),*) => {$(
+        impl BinarySymbol for $t {
+            fn encode(&self, out: &mut Vec<u8>) {
+                write_varint(out, *self as u64);
+            }
+
+            fn decode(decoder: &mut Decoder<'_>) -> Result<Self, BinaryError> {
+                let start = decoder.offset();
+                let value = decoder.varint()?;
+                <$t>::try_from(value).map_err(|_| {
+                    decoder.invalid_at(start, format!("{} out of range", stringify!($t)))
+                })
+            }
+        }
+    )*};
+}
+
+impl_binary_symbol_for_uint!(u8, u16, u32, u64, usize);
+
+impl BinarySymbol for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        write_varint(out, u64::from(*self));
+    }
+
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, BinaryError> {
+        let start = decoder.offset();
+        let value = decoder.varint()?;
+        u32::try_from(value)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| decoder.invalid_at(start, "invalid char"))
+    }
+}
+
+impl BinarySymbol for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(u8::from(*self));
+    }
+
+    fn decode(decoder: &mut Decoder<'_>) -> Result<Self, BinaryError> {
+        let start = decoder.offset();
+        match decoder.byte()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(decoder.invalid_at(start, "invalid bool")),
+        }
+    }
+}
+
+impl<A: Alphabet + BinarySymbol> Dfa<A> {
+    /// Encodes the automaton in the binary format, see the [module docs](crate::dfa::binary).
+    ///
+    /// State ids are kept, so decoding gives the same automaton.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(VERSION);
+        write_varint(&mut out, self.num_states() as u64);
+        let symbols = self.symbols();
+        write_varint(&mut out, symbols.len() as u64);
+        for symbol in &symbols {
+            symbol.encode(&mut out);
+        }
+        let mut bits = vec![0u8; self.num_states().div_ceil(8)];
+        for state in self.states().filter(|state| state.accepting) {
+            let id = state.id.index();
+            bits[id / 8] |= 1 << (id % 8);
+        }
+        out.extend(bits);
+        for state in self.states() {
+            let mut transitions = state
+                .transitions()
+                .map(|(symbol, to)| (symbols.binary_search(&symbol).unwrap(), to))
+                .collect::<Vec<_>>();
+            transitions.sort_unstable();
+            write_varint(&mut out, transitions.len() as u64);
+            for (symbol, to) in transitions {
+                write_varint(&mut out, symbol as u64);
+                write_varint(&mut out, to.index() as u64);
+            }
+        }
+        out
+    }
+
+    /// Decodes an automaton written by [`to_bytes`](Dfa::to_bytes), in this or an older
+    /// version of the format.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BinaryError> {
+        if !bytes.starts_with(MAGIC) {
+            return Err(BinaryError::BadMagic);
+        }
+        let mut decoder = Decoder { bytes, offset: 4 };
+        let version = decoder.byte()?;
+        if version > VERSION {
+            return Err(BinaryError::UnsupportedVersion(version));
+        }
+
+        let num_states = decoder.usize()?;
+        // Every state takes at least one byte, which bounds the allocation on corrupt input:
+        if num_states > bytes.len() {
+            return Err(BinaryError::UnexpectedEnd);
+        }
+        let num_symbols = decoder.usize()?;
+        if num_symbols > bytes.len() {
+            return Err(BinaryError::UnexpectedEnd);
+        }
+        let mut symbols = Vec::with_capacity(num_symbols);
+        for _ in 0..num_symbols {
+            let start = decoder.offset();
+            let symbol = A::decode(&mut decoder)?;
+            if symbols.last().is_some_and(|last| *last >= symbol) {
+                return Err(decoder.invalid_at(start, "symbols are not sorted"));
+            }
+            symbols.push(symbol);
+        }
+
+        let mut dfa = Dfa::new();
+        let mut bits = Vec::with_capacity(num_states.div_ceil(8));
+        for _ in 0..num_states.div_ceil(8) {
+            bits.push(decoder.byte()?);
+        }
+        for id in 0..num_states {
+            dfa.add_state(bits[id / 8] & (1 << (id % 8)) != 0);
+        }
+        for from in (0..num_states).map(StateId::new) {
+            let num_transitions = decoder.usize()?;
+            let mut previous = None;
+            for _ in 0..num_transitions {
+                let start = decoder.offset();
+                let symbol = decoder.usize()?;
+                if symbol >= num_symbols {
+                    return Err(decoder.invalid_at(start, "symbol index out of range"));
+                }
+                if previous.is_some_and(|previous| previous >= symbol) {
+                    return Err(decoder.invalid_at(start, "transitions are not sorted"));
+                }
+                previous = Some(symbol);
+                let start = decoder.offset();
+                let to = decoder.usize()?;
+                if to >= num_states {
+                    return Err(decoder.invalid_at(start, "target state out of range"));
+                }
+                dfa.add_transition(from, symbols[symbol], StateId::new(to));
+            }
+        }
+        if decoder.offset() < bytes.len() {
+            return Err(decoder.invalid_at(decoder.offset(), "trailing bytes"));
+        }
+        Ok(dfa)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, 'b', q1);
+        dfa.add_transition(q0, 'a', q0);
+        dfa.add_transition(q1, 'é', q0);
+        dfa
+    }
+
+    #[test]
+    fn test_to_bytes() {
+        let bytes = example().to_bytes();
+        assert_eq!(
+            bytes,
+            [
+                b'F', b'S', b'M', b'D', VERSION, // header
+                2, 3, b'a', b'b', 0xE9, 0x01, // states and symbols
+                0b10, // accepting
+                2, 0, 0, 1, 1, // transitions of q0
+                1, 2, 0, // transitions of q1
+            ]
+        );
+
+        let dfa = Dfa::<char>::from_bytes(&bytes).unwrap();
+        assert_eq!(dfa.num_states(), 2);
+        assert_eq!(dfa.next(StateId::new(0), 'a'), Some(StateId::new(0)));
+        assert_eq!(dfa.next(StateId::new(0), 'b'), Some(StateId::new(1)));
+        assert_eq!(dfa.next(StateId::new(1), 'é'), Some(StateId::new(0)));
+        assert!(dfa.accepting(StateId::new(1)));
+        assert_eq!(dfa.to_bytes(), bytes);
+
+        let empty = Dfa::<u32>::new();
+        assert!(Dfa::<u32>::from_bytes(&empty.to_bytes())
+            .unwrap()
+            .states()
+            .next()
+            .is_none());
+    }
+
+    #[test]
+    fn test_to_bytes_large() {
+        // A counter modulo 1000 over the digits:
+        let mut dfa = Dfa::new();
+        for i in 0..1000 {
+            dfa.add_state(i % 7 == 0);
+        }
+        for i in 0..1000 {
+            for digit in 0u8..10 {
+                dfa.add_transition(
+                    StateId::new(i),
+                    digit,
+                    StateId::new((i * 10 + digit as usize) % 1000),
+                );
+            }
+        }
+        let bytes = dfa.to_bytes();
+        assert!(bytes.len() < 4 * dfa.num_transitions());
+        let decoded = Dfa::<u8>::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.num_transitions(), 10_000);
+        assert_eq!(decoded.to_bytes(), bytes);
+    }
+
+    #[test]
+    fn test_from_bytes_errors() {
+        let bytes = example().to_bytes();
+        assert_eq!(
+            Dfa::<char>::from_bytes(b"JSON").unwrap_err(),
+            BinaryError::BadMagic
+        );
+        assert_eq!(
+            Dfa::<char>::from_bytes(b"FSMD\x09").unwrap_err(),
+            BinaryError::UnsupportedVersion(9)
+        );
+        for len in 4..bytes.len() {
+            assert_eq!(
+                Dfa::<char>::from_bytes(&bytes[..len]).unwrap_err(),
+                BinaryError::UnexpectedEnd,
+                "{}",
+                len
+            );
+        }
+
+        let corrupt = |offset: usize, value: u8| {
+            let mut bytes = bytes.clone();
+            bytes[offset] = value;
+            match Dfa::<char>::from_bytes(&bytes) {
+                Err(BinaryError::Invalid { offset, message }) => (offset, message),
+                other => panic!("{:?}", other),
+            }
+        };
+        assert_eq!(corrupt(7, b'c'), (8, "symbols are not sorted".to_string()));
+        assert_eq!(
+            corrupt(15, 3),
+            (15, "symbol index out of range".to_string())
+        );
+        assert_eq!(
+            corrupt(15, 0),
+            (15, "transitions are not sorted".to_string())
+        );
+        assert_eq!(
+            corrupt(19, 2),
+            (19, "target state out of range".to_string())
+        );
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(
+            Dfa::<char>::from_bytes(&trailing),
+            Err(BinaryError::Invalid { .. })
+        ));
+        assert!(matches!(
+            Dfa::<bool>::from_bytes(&bytes),
+            Err(BinaryError::Invalid { offset: 7, .. })
+        ));
+    }
+}
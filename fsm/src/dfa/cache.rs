@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// Memoizes the state reached by running fixed-size input chunks, see [`Dfa::run_cache`].
+///
+/// Inputs are split into chunks of `chunk_size` symbols; the target of every full chunk
+/// is looked up by `(state, chunk)` and computed only on a miss. The trailing partial chunk
+/// is always run directly. This pays off for highly repetitive inputs, e.g. batches of
+/// log lines sharing long prefixes.
+///
+/// By default the cache grows without bound, one entry per distinct `(state, chunk)` pair.
+/// Use [`with_capacity`](RunCache::with_capacity) to bound it for long-running validation.
+#[derive(Debug)]
+pub struct RunCache<'a, A: Alphabet> {
+    dfa: &'a Dfa<A>,
+    chunk_size: usize,
+    /// Targets by state and then by chunk, so lookups can borrow the chunk.
+    cache: HashMap<StateId, HashMap<Vec<A>, Option<StateId>>>,
+    len: usize,
+    capacity: usize,
+    hits: usize,
+    misses: usize,
+}
+
+impl<'a, A: Alphabet> RunCache<'a, A> {
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn new(dfa: &'a Dfa<A>, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        Self {
+            dfa,
+            chunk_size,
+            cache: HashMap::new(),
+            len: 0,
+            capacity: usize::MAX,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Bounds the number of cached chunks: when a miss finds the cache full,
+    /// all cached chunks are dropped before the new one is stored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        self.capacity = capacity;
+        self
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Number of cached chunks.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of chunk lookups answered from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Number of chunk lookups that had to run the automaton.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.len = 0;
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    /// Runs `word` from `state`, returning the reached state,
+    /// or `None` if some transition is missing.
+    pub fn run_from(&mut self, state: StateId, word: &[A]) -> Option<StateId> {
+        let mut current = state;
+        let mut chunks = word.chunks_exact(self.chunk_size);
+        for chunk in chunks.by_ref() {
+            let cached = (self.cache.get(&current)).and_then(|targets| targets.get(chunk));
+            current = match cached {
+                Some(&target) => {
+                    self.hits += 1;
+                    target
+                }
+                None => {
+                    self.misses += 1;
+                    let target = self.step(current, chunk);
+                    if self.len >= self.capacity {
+                        self.cache.clear();
+                        self.len = 0;
+                    }
+                    let targets = self.cache.entry(current).or_default();
+                    targets.insert(chunk.to_vec(), target);
+                    self.len += 1;
+                    target
+                }
+            }?;
+        }
+        self.step(current, chunks.remainder())
+    }
+
+    /// Same as [`Dfa::accepts`], but using the cache.
+    pub fn accepts(&mut self, word: &[A]) -> bool {
+        if self.dfa.states.is_empty() {
+            return false;
+        }
+        self.run_from(StateId::new(0), word)
+            .is_some_and(|state| self.dfa.accepting(state))
+    }
+
+    fn step(&self, state: StateId, chunk: &[A]) -> Option<StateId> {
+        chunk
+            .iter()
+            .try_fold(state, |current, &symbol| self.dfa.next(current, symbol))
+    }
+}
+
+impl<A: Alphabet> Dfa<A> {
+    /// Creates a [`RunCache`] for validating many (repetitive) inputs against this DFA.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn run_cache(&self, chunk_size: usize) -> RunCache<'_, A> {
+        RunCache::new(self, chunk_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    /// Words over {a, b} containing "ab".
+    fn contains_ab() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', q0);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, 'b', q2);
+        dfa.add_transition(q2, 'a', q2);
+        dfa.add_transition(q2, 'b', q2);
+        dfa
+    }
+
+    #[test]
+    fn test_run_cache_agrees() {
+        let dfa = contains_ab();
+        for chunk_size in 1..4 {
+            let mut cache = dfa.run_cache(chunk_size);
+            for word in generate_strings(&['a', 'b', 'c'], 6) {
+                let word = word.chars().collect::<Vec<_>>();
+                assert_eq!(cache.accepts(&word), dfa.accepts(word.iter().copied()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_cache_hits() {
+        let dfa = contains_ab();
+        let mut cache = dfa.run_cache(4);
+        let line = "bbbbbbbbab".chars().collect::<Vec<_>>();
+        assert!(cache.accepts(&line));
+        // "bbbb" twice from state 0, then the remainder "ab" is run directly:
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 1, 1));
+        assert!(cache.accepts(&line));
+        assert_eq!((cache.hits(), cache.misses()), (3, 1));
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.run_from(StateId::new(0), &['c', 'c', 'c', 'c']), None);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_run_cache_capacity() {
+        let dfa = contains_ab();
+        let mut cache = dfa.run_cache(2).with_capacity(2);
+        assert_eq!(cache.capacity(), 2);
+        let words = ["bbaa", "abab", "aabb"].map(|word| word.chars().collect::<Vec<_>>());
+        for word in &words {
+            assert_eq!(cache.accepts(word), dfa.accepts(word.iter().copied()));
+            assert!(cache.len() <= 2);
+        }
+        // Every chunk is new, and every word starts with a full cache after the first one,
+        // so only the two chunks of "aabb" remain:
+        assert_eq!((cache.hits(), cache.misses(), cache.len()), (0, 6, 2));
+        assert!(cache.accepts(&words[2]));
+        assert_eq!(cache.hits(), 2);
+    }
+}
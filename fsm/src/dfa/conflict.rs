@@ -0,0 +1,40 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+
+/// What to do when a transition is added for a `(state, symbol)` pair that already has one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum ConflictPolicy {
+    /// Replace the existing transition.
+    #[default]
+    Overwrite,
+    /// Keep the existing transition and report a [`TransitionConflict`].
+    Error,
+    /// Panic in debug builds, overwrite in release builds.
+    DebugPanic,
+}
+
+/// A transition that would make the automaton nondeterministic.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct TransitionConflict<A: Alphabet> {
+    pub from: StateId,
+    pub symbol: A,
+    /// Target of the already existing transition.
+    pub existing: StateId,
+    /// Target of the rejected transition.
+    pub rejected: StateId,
+}
+
+impl<A: Alphabet> Display for TransitionConflict<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "state {} already has a transition on {:?} to {}, refusing to add one to {}",
+            self.from, self.symbol, self.existing, self.rejected
+        )
+    }
+}
+
+impl<A: Alphabet> Error for TransitionConflict<A> {}
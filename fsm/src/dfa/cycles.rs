@@ -0,0 +1,198 @@
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// States lying on some cycle, i.e. states `q` with a non-empty word leading from `q` back to `q`.
+    pub fn cyclic_states(&self) -> BTreeSet<StateId> {
+        let states = self.states().map(|state| state.id).collect();
+        self.sccs(&states)
+            .into_iter()
+            .filter(|component| self.is_cyclic(component))
+            .flatten()
+            .collect()
+    }
+
+    /// Whether the language is finite, i.e. no cycle lies on a path from the initial state
+    /// to an accepting state.
+    ///
+    /// When the language is finite, [`Dfa::max_word_length`] bounds the words to enumerate.
+    pub fn is_finite(&self) -> bool {
+        let useful = self.useful_states();
+        self.cyclic_states().is_disjoint(&useful)
+    }
+
+    /// Length of the longest accepted word, or `None` if the language is infinite or empty.
+    pub fn max_word_length(&self) -> Option<usize> {
+        let useful = self.useful_states();
+        if !useful.contains(&StateId::new(0)) || !self.cyclic_states().is_disjoint(&useful) {
+            return None;
+        }
+        // Longest path to an accepting state, over the acyclic useful part, in post-order:
+        let mut longest: HashMap<StateId, usize> = HashMap::new();
+        let mut stack = vec![(StateId::new(0), false)];
+        while let Some((state, expanded)) = stack.pop() {
+            if expanded {
+                let successors = self
+                    .state(state)
+                    .transitions()
+                    .filter_map(|(_, to)| longest.get(&to).map(|length| length + 1));
+                let length = successors.max().unwrap_or(0);
+                longest.insert(state, length);
+            } else if !longest.contains_key(&state) {
+                stack.push((state, true));
+                for (_, to) in self.state(state).transitions() {
+                    if useful.contains(&to) && !longest.contains_key(&to) {
+                        stack.push((to, false));
+                    }
+                }
+            }
+        }
+        Some(longest[&StateId::new(0)])
+    }
+
+    /// Accessible and co-accessible states.
+    fn useful_states(&self) -> BTreeSet<StateId> {
+        let coaccessible = self.coaccessible_states();
+        self.accessible_states()
+            .into_iter()
+            .filter(|state| coaccessible.contains(state))
+            .collect()
+    }
+
+    /// Finds a shortest non-empty word leading from `state` back to itself,
+    /// or `None` if `state` does not lie on a cycle.
+    ///
+    /// Among the shortest words, the lexicographically smallest one is chosen.
+    pub fn shortest_cycle_through(&self, state: StateId) -> Option<Vec<A>> {
+        let mut parents: HashMap<StateId, (StateId, A)> = HashMap::new();
+        let mut queue = VecDeque::from([state]);
+        while let Some(current) = queue.pop_front() {
+            let mut transitions = self.state(current).transitions().collect::<Vec<_>>();
+            transitions.sort_unstable();
+            for (symbol, to) in transitions {
+                if to == state {
+                    let mut word = vec![symbol];
+                    let mut at = current;
+                    while at != state {
+                        let (parent, symbol) = parents[&at];
+                        word.push(symbol);
+                        at = parent;
+                    }
+                    word.reverse();
+                    return Some(word);
+                }
+                if let Entry::Vacant(entry) = parents.entry(to) {
+                    entry.insert((current, symbol));
+                    queue.push_back(to);
+                }
+            }
+        }
+        None
+    }
+
+    /// Finds a shortest lasso through `state`: its access word (see [`Dfa::access_words`])
+    /// and a [shortest cycle](Dfa::shortest_cycle_through) through it.
+    ///
+    /// Returns `None` if `state` is unreachable or does not lie on a cycle.
+    /// If `state` is coaccessible, the language is infinite: the stem followed by
+    /// any number of repetitions of the loop leads to `state`.
+    pub fn shortest_lasso(&self, state: StateId) -> Option<(Vec<A>, Vec<A>)> {
+        let stem = self.access_words().remove(&state)?;
+        let cycle = self.shortest_cycle_through(state)?;
+        Some((stem, cycle))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Dfa<char> {
+        // 0 -a-> 1 -b-> 2 -c-> 1, 2 -a-> 3 -a-> 3, 4 -a-> 0
+        let mut dfa = Dfa::new();
+        let s = (0..5).map(|i| dfa.add_state(i == 3)).collect::<Vec<_>>();
+        dfa.add_transition(s[0], 'a', s[1]);
+        dfa.add_transition(s[1], 'b', s[2]);
+        dfa.add_transition(s[2], 'c', s[1]);
+        dfa.add_transition(s[2], 'a', s[3]);
+        dfa.add_transition(s[3], 'a', s[3]);
+        dfa.add_transition(s[4], 'a', s[0]);
+        dfa
+    }
+
+    #[test]
+    fn test_cyclic_states() {
+        let dfa = example();
+        assert_eq!(
+            dfa.cyclic_states(),
+            BTreeSet::from([1, 2, 3].map(StateId::new))
+        );
+    }
+
+    #[test]
+    fn test_is_finite() {
+        let dfa = example();
+        assert!(!dfa.is_finite());
+        assert_eq!(dfa.max_word_length(), None);
+
+        // {a, ab, abc, bc}, plus a cycle on a dead state:
+        let mut dfa = Dfa::new();
+        let s = (0..6)
+            .map(|i| dfa.add_state(i == 1 || i == 2 || i == 3))
+            .collect::<Vec<_>>();
+        dfa.add_transition(s[0], 'a', s[1]);
+        dfa.add_transition(s[1], 'b', s[2]);
+        dfa.add_transition(s[2], 'c', s[3]);
+        dfa.add_transition(s[0], 'b', s[4]);
+        dfa.add_transition(s[4], 'c', s[3]);
+        dfa.add_transition(s[3], 'd', s[5]);
+        dfa.add_transition(s[5], 'd', s[5]);
+        assert!(dfa.is_finite());
+        assert_eq!(dfa.max_word_length(), Some(3));
+        assert_eq!(dfa.words_up_to(10).count(), 4);
+
+        let mut empty = Dfa::<char>::new();
+        assert!(empty.is_finite());
+        assert_eq!(empty.max_word_length(), None);
+        empty.add_state(true);
+        assert_eq!(empty.max_word_length(), Some(0));
+    }
+
+    #[test]
+    fn test_shortest_cycle_through() {
+        let dfa = example();
+        assert_eq!(dfa.shortest_cycle_through(StateId::new(0)), None);
+        assert_eq!(
+            dfa.shortest_cycle_through(StateId::new(1)),
+            Some(vec!['b', 'c'])
+        );
+        assert_eq!(
+            dfa.shortest_cycle_through(StateId::new(2)),
+            Some(vec!['c', 'b'])
+        );
+        assert_eq!(dfa.shortest_cycle_through(StateId::new(3)), Some(vec!['a']));
+    }
+
+    #[test]
+    fn test_shortest_lasso() {
+        let dfa = example();
+        let (stem, cycle) = dfa.shortest_lasso(StateId::new(2)).unwrap();
+        assert_eq!(
+            (stem.as_slice(), cycle.as_slice()),
+            (&['a', 'b'][..], &['c', 'b'][..])
+        );
+        // Pumping the loop stays in the same state:
+        let mut word = stem.clone();
+        for _ in 0..3 {
+            word.extend(&cycle);
+            assert_eq!(dfa.run(word.iter().copied()).last(), Some(&StateId::new(2)));
+        }
+        assert_eq!(dfa.shortest_lasso(StateId::new(0)), None);
+        // State 4 lies on no cycle and is unreachable anyway:
+        assert_eq!(dfa.shortest_lasso(StateId::new(4)), None);
+    }
+}
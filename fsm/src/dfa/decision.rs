@@ -0,0 +1,190 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Finds a shortest accepted word, or `None` if the language is empty.
+    ///
+    /// Among the shortest words, the lexicographically smallest one is chosen.
+    pub fn shortest_word(&self) -> Option<Vec<A>> {
+        if self.states.is_empty() {
+            return None;
+        }
+        let mut words: HashMap<StateId, Vec<A>> = HashMap::new();
+        for discovery in self.bfs_from(StateId::new(0)) {
+            let word = match discovery.parent {
+                None => Vec::new(),
+                Some((parent, symbol)) => {
+                    let mut word = words[&parent].clone();
+                    word.push(symbol);
+                    word
+                }
+            };
+            if self.accepting(discovery.state) {
+                return Some(word);
+            }
+            words.insert(discovery.state, word);
+        }
+        None
+    }
+
+    /// Whether the automaton accepts no words at all.
+    ///
+    /// Use [`Dfa::shortest_word`] to get a witness when the language is non-empty.
+    pub fn is_empty(&self) -> bool {
+        self.shortest_word().is_none()
+    }
+
+    /// Finds a shortest word over `alphabet` rejected by the automaton,
+    /// or `None` if all words over `alphabet` are accepted.
+    ///
+    /// Only the reachable part is explored, and no complement automaton is built:
+    /// the first reachable non-accepting state or missing transition gives the witness.
+    pub fn universality_counterexample(
+        &self,
+        alphabet: impl IntoIterator<Item = A>,
+    ) -> Option<Vec<A>> {
+        if self.states.is_empty() || !self.accepting(StateId::new(0)) {
+            return Some(Vec::new());
+        }
+        let mut alphabet = alphabet.into_iter().collect::<Vec<_>>();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+
+        // Every state is checked when discovered, so witnesses are found in order of length.
+        let mut words: HashMap<StateId, Vec<A>> = HashMap::from([(StateId::new(0), Vec::new())]);
+        let mut queue = VecDeque::from([StateId::new(0)]);
+        while let Some(state) = queue.pop_front() {
+            for &symbol in &alphabet {
+                let next = match self.next(state, symbol) {
+                    Some(next) if self.accepting(next) => next,
+                    _ => {
+                        let mut word = words[&state].clone();
+                        word.push(symbol);
+                        return Some(word);
+                    }
+                };
+                if !words.contains_key(&next) {
+                    let mut word = words[&state].clone();
+                    word.push(symbol);
+                    words.insert(next, word);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Whether the automaton accepts every word over `alphabet`.
+    pub fn is_universal(&self, alphabet: impl IntoIterator<Item = A>) -> bool {
+        self.universality_counterexample(alphabet).is_none()
+    }
+
+    /// Finds a shortest word accepted by both automata, or `None` if their languages are disjoint.
+    pub fn shortest_common_word(&self, other: &Dfa<A>) -> Option<Vec<A>> {
+        self.intersection(other).shortest_word()
+    }
+
+    /// Whether no word is accepted by both automata.
+    pub fn is_disjoint(&self, other: &Dfa<A>) -> bool {
+        self.shortest_common_word(other).is_none()
+    }
+
+    /// Finds a shortest word accepted by `self` but not by `other`,
+    /// or `None` if the language of `self` is included in the language of `other`.
+    pub fn inclusion_counterexample(&self, other: &Dfa<A>) -> Option<Vec<A>> {
+        self.difference(other).shortest_word()
+    }
+
+    /// Whether every word accepted by `self` is also accepted by `other`.
+    ///
+    /// For refinement checking, `self` is the implementation and `other` the specification.
+    pub fn is_subset_of(&self, other: &Dfa<A>) -> bool {
+        self.inclusion_counterexample(other).is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Words over {a, b} of length `n` modulo `m`.
+    fn length_mod(n: usize, m: usize) -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let states = (0..m).map(|i| dfa.add_state(i == n)).collect::<Vec<_>>();
+        for i in 0..m {
+            dfa.add_transition(states[i], 'b', states[(i + 1) % m]);
+            dfa.add_transition(states[i], 'a', states[(i + 1) % m]);
+        }
+        dfa
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let dfa = length_mod(2, 3);
+        assert!(!dfa.is_empty());
+        assert_eq!(dfa.shortest_word(), Some(vec!['a', 'a']));
+
+        assert!(Dfa::<char>::new().is_empty());
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let _unreachable = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q0);
+        assert!(dfa.is_empty());
+        assert_eq!(dfa.shortest_word(), None);
+    }
+
+    #[test]
+    fn test_is_disjoint() {
+        // Lengths 1 mod 2 vs 0 mod 4: disjoint.
+        assert!(length_mod(1, 2).is_disjoint(&length_mod(0, 4)));
+        // Lengths 1 mod 2 vs 1 mod 3: both contain length 1.
+        let (left, right) = (length_mod(1, 2), length_mod(1, 3));
+        assert!(!left.is_disjoint(&right));
+        assert_eq!(left.shortest_common_word(&right), Some(vec!['a']));
+        // Lengths 1 mod 2 vs 2 mod 3: length 5.
+        let right = length_mod(2, 3);
+        assert_eq!(left.shortest_common_word(&right), Some(vec!['a'; 5]));
+    }
+
+    #[test]
+    fn test_is_subset_of() {
+        // Lengths 0 mod 4 are included in lengths 0 mod 2, but not vice versa:
+        let (small, large) = (length_mod(0, 4), length_mod(0, 2));
+        assert!(small.is_subset_of(&large));
+        assert!(!large.is_subset_of(&small));
+        assert_eq!(large.inclusion_counterexample(&small), Some(vec!['a', 'a']));
+        assert!(Dfa::new().is_subset_of(&small));
+        assert!(!small.is_subset_of(&Dfa::new()));
+    }
+
+    #[test]
+    fn test_is_universal() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'a', q0);
+        dfa.add_transition(q0, 'b', q0);
+        assert!(!dfa.is_universal(['a', 'b']));
+        assert_eq!(
+            dfa.universality_counterexample(['a', 'b']),
+            Some(vec!['a', 'b'])
+        );
+        assert!(dfa.is_universal(['a']));
+
+        dfa.add_transition(q1, 'b', q1);
+        assert!(dfa.is_universal(['a', 'b']));
+        assert_eq!(dfa.universality_counterexample(['a', 'c']), Some(vec!['c']));
+
+        assert_eq!(Dfa::new().universality_counterexample(['a']), Some(vec![]));
+        // Agrees with the complement-based check:
+        let trap = length_mod(0, 2);
+        assert_eq!(
+            trap.universality_counterexample(['a', 'b']),
+            trap.complement().shortest_word()
+        );
+    }
+}
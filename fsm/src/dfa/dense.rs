@@ -0,0 +1,99 @@
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// Table-based DFA with a row of `symbols.len()` entries per state, see [`Dfa::to_dense`].
+///
+/// Looking up a transition is a binary search over the (sorted) symbols
+/// followed by a table access, with no hashing involved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DenseDfa<A: Alphabet> {
+    symbols: Vec<A>,
+    table: Vec<Option<StateId>>,
+    accepting: Vec<bool>,
+}
+
+impl<A: Alphabet> DenseDfa<A> {
+    pub fn num_states(&self) -> usize {
+        self.accepting.len()
+    }
+
+    pub fn num_transitions(&self) -> usize {
+        self.table.iter().filter(|to| to.is_some()).count()
+    }
+
+    /// Symbols of the table columns, in sorted order.
+    pub fn symbols(&self) -> &[A] {
+        &self.symbols
+    }
+
+    pub fn accepting(&self, state: StateId) -> bool {
+        self.accepting[state.index()]
+    }
+
+    pub fn next(&self, state: StateId, symbol: A) -> Option<StateId> {
+        let column = self.symbols.binary_search(&symbol).ok()?;
+        self.table[state.index() * self.symbols.len() + column]
+    }
+
+    pub fn accepts(&self, word: impl IntoIterator<Item = A>) -> bool {
+        if self.accepting.is_empty() {
+            return false;
+        }
+        let mut current = StateId::new(0);
+        for symbol in word {
+            match self.next(current, symbol) {
+                Some(next) => current = next,
+                None => return false,
+            }
+        }
+        self.accepting(current)
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Compiles into a [`DenseDfa`] with the same states, over [`symbols`](Dfa::symbols).
+    pub fn to_dense(&self) -> DenseDfa<A> {
+        let symbols = self.symbols();
+        let table = self
+            .states()
+            .flat_map(|state| symbols.iter().map(|&symbol| state.next(symbol)))
+            .collect();
+        let accepting = self.states().map(|state| state.accepting).collect();
+        DenseDfa {
+            symbols,
+            table,
+            accepting,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    #[test]
+    fn test_to_dense() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, 'x', q1);
+        dfa.add_transition(q1, 'y', q0);
+        dfa.add_transition(q1, 'z', q1);
+
+        let dense = dfa.to_dense();
+        assert_eq!(dense.num_states(), 2);
+        assert_eq!(dense.num_transitions(), 3);
+        assert_eq!(dense.symbols(), ['x', 'y', 'z']);
+        assert_eq!(dense.next(q1, 'y'), Some(q0));
+        assert_eq!(dense.next(q0, 'y'), None);
+        assert_eq!(dense.next(q0, 'w'), None);
+        for word in generate_strings(&['w', 'x', 'y', 'z'], 5) {
+            assert_eq!(dense.accepts(word.chars()), dfa.accepts(word.chars()));
+        }
+        assert!(!Dfa::<char>::new().to_dense().accepts("".chars()));
+    }
+}
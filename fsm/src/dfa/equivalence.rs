@@ -0,0 +1,256 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// Union-find over `0..n`, with path halving and union by size.
+struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        Self {
+            parent: (0..n).collect(),
+            size: vec![1; n],
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            self.parent[x] = self.parent[self.parent[x]];
+            x = self.parent[x];
+        }
+        x
+    }
+
+    /// Merges the classes of `x` and `y`, returning `false` if they were already merged.
+    fn union(&mut self, x: usize, y: usize) -> bool {
+        let (mut x, mut y) = (self.find(x), self.find(y));
+        if x == y {
+            return false;
+        }
+        if self.size[x] < self.size[y] {
+            std::mem::swap(&mut x, &mut y);
+        }
+        self.parent[y] = x;
+        self.size[x] += self.size[y];
+        true
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Whether both automata recognize the same language.
+    ///
+    /// Uses the near-linear Hopcroft–Karp algorithm, see [`Dfa::distinguishing_word`].
+    pub fn equivalent(&self, other: &Dfa<A>) -> bool {
+        self.distinguishing_word(other).is_none()
+    }
+
+    /// Finds a word accepted by exactly one of the automata, or `None` if they are equivalent.
+    ///
+    /// Pairs of states reached by the same word are merged with union-find (Hopcroft–Karp),
+    /// so every state pair is explored at most once and no product automaton is built.
+    /// Missing transitions lead to an implicit dead state in each automaton.
+    /// The word is not necessarily a shortest one, see [`Dfa::symmetric_difference`] for that.
+    pub fn distinguishing_word(&self, other: &Dfa<A>) -> Option<Vec<A>> {
+        // States of `self` are `0..n`, then its dead state `n`, then the states of `other`
+        // shifted by `n + 1`, then the dead state of `other`.
+        let n = self.num_states();
+        let m = other.num_states();
+        let left_dead = n;
+        let right_dead = n + 1 + m;
+        let accepting = |state: usize| {
+            if state < n {
+                self.accepting(StateId::new(state))
+            } else if state > n && state < right_dead {
+                other.accepting(StateId::new(state - n - 1))
+            } else {
+                false
+            }
+        };
+        let next = |state: usize, symbol: A| -> usize {
+            if state < n {
+                self.next(StateId::new(state), symbol)
+                    .map_or(left_dead, StateId::index)
+            } else if state > n && state < right_dead {
+                other
+                    .next(StateId::new(state - n - 1), symbol)
+                    .map_or(right_dead, |to| to.index() + n + 1)
+            } else {
+                state
+            }
+        };
+
+        let mut symbols = self.symbols();
+        symbols.extend(other.symbols());
+        symbols.sort_unstable();
+        symbols.dedup();
+
+        let initial = (
+            if n > 0 { 0 } else { left_dead },
+            if m > 0 { n + 1 } else { right_dead },
+        );
+        let mut classes = UnionFind::new(n + m + 2);
+        classes.union(initial.0, initial.1);
+        // Explored pairs, each with the index of its parent pair and the symbol leading from it:
+        let mut pairs = vec![(initial, None)];
+        let mut queue = VecDeque::from([0]);
+        while let Some(index) = queue.pop_front() {
+            let ((p, q), _) = pairs[index];
+            if accepting(p) != accepting(q) {
+                let mut word = Vec::new();
+                let mut at = index;
+                while let (_, Some((parent, symbol))) = pairs[at] {
+                    word.push(symbol);
+                    at = parent;
+                }
+                word.reverse();
+                return Some(word);
+            }
+            for &symbol in &symbols {
+                let (p, q) = (next(p, symbol), next(q, symbol));
+                if classes.union(p, q) {
+                    pairs.push(((p, q), Some((index, symbol))));
+                    queue.push_back(pairs.len() - 1);
+                }
+            }
+        }
+        None
+    }
+
+    /// Whether both automata agree on all words of length at most `max_length`.
+    ///
+    /// See [`Dfa::disagreement_up_to`].
+    pub fn agree_up_to(&self, other: &Dfa<A>, max_length: usize) -> bool {
+        self.disagreement_up_to(other, max_length).is_none()
+    }
+
+    /// Finds a shortest word of length at most `max_length` accepted by exactly one of the automata.
+    ///
+    /// Explores the reachable pairs of states (the product automaton) breadth-first,
+    /// up to depth `max_length`, so the cost is bounded by both the depth budget and
+    /// the number of reachable pairs, without minimizing anything.
+    pub fn disagreement_up_to(&self, other: &Dfa<A>, max_length: usize) -> Option<Vec<A>> {
+        let mut symbols = self.symbols();
+        symbols.extend(other.symbols());
+        symbols.sort_unstable();
+        symbols.dedup();
+
+        // `None` is the implicit dead state:
+        let initial = (
+            (self.num_states() > 0).then_some(StateId::new(0)),
+            (other.num_states() > 0).then_some(StateId::new(0)),
+        );
+        let accepting = |(p, q): (Option<StateId>, Option<StateId>)| {
+            (
+                p.is_some_and(|p| self.accepting(p)),
+                q.is_some_and(|q| other.accepting(q)),
+            )
+        };
+        let mut words: HashMap<(Option<StateId>, Option<StateId>), Vec<A>> =
+            HashMap::from([(initial, Vec::new())]);
+        let mut queue = VecDeque::from([initial]);
+        while let Some(pair) = queue.pop_front() {
+            let (left, right) = accepting(pair);
+            if left != right {
+                return words.remove(&pair);
+            }
+            let word = words[&pair].clone();
+            if word.len() == max_length {
+                continue;
+            }
+            for &symbol in &symbols {
+                let next = (
+                    pair.0.and_then(|p| self.next(p, symbol)),
+                    pair.1.and_then(|q| other.next(q, symbol)),
+                );
+                if next == (None, None) {
+                    continue;
+                }
+                if let Entry::Vacant(entry) = words.entry(next) {
+                    let mut next_word = word.clone();
+                    next_word.push(symbol);
+                    entry.insert(next_word);
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Words over {a, b} whose number of `a`s is divisible by `n`.
+    fn count_a(n: usize) -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let states = (0..n).map(|i| dfa.add_state(i == 0)).collect::<Vec<_>>();
+        for i in 0..n {
+            dfa.add_transition(states[i], 'a', states[(i + 1) % n]);
+            dfa.add_transition(states[i], 'b', states[i]);
+        }
+        dfa
+    }
+
+    #[test]
+    fn test_agree_up_to() {
+        // Number of `a`s divisible by 2 vs. by 6 first differ on "aa":
+        assert!(count_a(2).agree_up_to(&count_a(6), 1));
+        assert!(!count_a(2).agree_up_to(&count_a(6), 2));
+        assert_eq!(
+            count_a(2).disagreement_up_to(&count_a(6), 10),
+            Some(vec!['a', 'a'])
+        );
+        assert!(count_a(3).agree_up_to(&count_a(3), 100));
+
+        // Missing transitions reject, just like a dead state:
+        let mut partial = Dfa::new();
+        let q0 = partial.add_state(true);
+        partial.add_transition(q0, 'b', q0);
+        assert_eq!(
+            partial.disagreement_up_to(&count_a(2), 3),
+            Some(vec!['a', 'a'])
+        );
+        assert_eq!(Dfa::new().disagreement_up_to(&count_a(2), 3), Some(vec![]));
+    }
+
+    #[test]
+    fn test_equivalent() {
+        let dfa = count_a(3);
+        assert!(dfa.equivalent(&dfa));
+        assert!(dfa.equivalent(&count_a(6).union(&dfa)));
+        assert!(dfa.equivalent(&dfa.minimize_brzozowski()));
+        assert!(!dfa.equivalent(&count_a(2)));
+
+        // Partial vs complete automata for the same language:
+        let mut partial = Dfa::new();
+        let q0 = partial.add_state(false);
+        let q1 = partial.add_state(true);
+        partial.add_transition(q0, 'a', q1);
+        let mut complete = partial.clone_structure();
+        complete.complete(['a', 'b']);
+        assert!(partial.equivalent(&complete));
+        assert!(
+            Dfa::<char>::new().equivalent(&complete.complement().complement().difference(&partial))
+        );
+    }
+
+    #[test]
+    fn test_distinguishing_word() {
+        let (left, right) = (count_a(2), count_a(3));
+        let word = left.distinguishing_word(&right).unwrap();
+        assert_ne!(left.accepts(word.clone()), right.accepts(word));
+
+        for (left, right) in [(count_a(4), count_a(2)), (count_a(1), Dfa::new())] {
+            let word = left.distinguishing_word(&right).unwrap();
+            assert_ne!(left.accepts(word.clone()), right.accepts(word));
+        }
+        assert_eq!(left.distinguishing_word(&left.minimize()), None);
+    }
+}
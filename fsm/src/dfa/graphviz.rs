@@ -1,3 +1,4 @@
+use std::collections::{BTreeMap, HashSet};
 use std::fmt::Display;
 
 use graphviz_rust::dot_generator::{attr, edge, graph, id, node, node_id, stmt};
@@ -6,27 +7,127 @@ use graphviz_rust::dot_structures::{
 };
 use graphviz_rust::printer::{DotPrinter, PrinterContext};
 
-use crate::alphabet::Alphabet;
+use crate::alphabet::{Alphabet, SymbolGroups};
+use crate::dfa::state::StateId;
 use crate::dfa::Dfa;
 
-impl<A: Alphabet + Display> Dfa<A> {
+/// Escapes the chars that end or break a quoted DOT string, to be wrapped in quotes
+/// (e.g. by `attr!(.., esc ..)`), so that any symbol can be read back by [`Dfa::from_dot`].
+pub(crate) fn escape_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// How [`Dfa::render_graphviz_with`] draws dead states, i.e. states from which
+/// no accepting state is reachable.
+///
+/// The initial state is always drawn as is, even when dead.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum SinkRendering {
+    /// Draw dead states like all other states.
+    #[default]
+    Show,
+    /// Merge all dead states into a single `dead` node, dropping transitions between them.
+    Collapse,
+    /// Omit dead states and all transitions leading to them.
+    Hide,
+}
+
+/// Options for [`Dfa::render_graphviz_with`].
+#[derive(Debug, Clone)]
+pub struct GraphvizOptions<'a, A: Alphabet> {
+    /// How to draw dead states.
+    pub sinks: SinkRendering,
+    /// When set, parallel transitions are merged into a single edge
+    /// whose label names the groups covering its symbols.
+    pub groups: Option<&'a SymbolGroups<A>>,
+}
+
+impl<A: Alphabet> Default for GraphvizOptions<'_, A> {
+    fn default() -> Self {
+        Self {
+            sinks: SinkRendering::default(),
+            groups: None,
+        }
+    }
+}
+
+impl<A: Alphabet + Display, S, T> Dfa<A, S, T> {
     pub fn render_graphviz(&self) -> String {
+        self.render_graphviz_with(&GraphvizOptions::default())
+    }
+
+    /// Renders the automaton according to `options`.
+    ///
+    /// When some states are collapsed or hidden, a legend node notes the omission.
+    pub fn render_graphviz_with(&self, options: &GraphvizOptions<'_, A>) -> String {
+        self.render_graphviz_labeled_by(options, |_| None)
+    }
+
+    /// Renders the automaton, with node labels given by `label` instead of the state ids.
+    fn render_graphviz_labeled_by(
+        &self,
+        options: &GraphvizOptions<'_, A>,
+        label: impl Fn(StateId) -> Option<String>,
+    ) -> String {
+        let sinks = options.sinks;
         let mut stmts = Vec::new();
 
         stmts.push(stmt!(attr!("rankdir", "LR")));
 
+        let dead = if sinks == SinkRendering::Show {
+            HashSet::new()
+        } else {
+            let coaccessible = self.coaccessible_states();
+            self.states()
+                .map(|state| state.id)
+                .filter(|&id| id.index() != 0 && !coaccessible.contains(&id))
+                .collect()
+        };
+
         // States:
-        for state in self.states() {
+        let access_words = self.access_words();
+        for state in self.states().filter(|state| !dead.contains(&state.id)) {
             let name = format!("{}", state.id);
-            let attr = attr!(
+            let mut attrs = vec![attr!(
                 "shape",
                 if state.accepting {
                     "doublecircle"
                 } else {
                     "circle"
                 }
-            );
-            let node = node!( name; attr );
+            )];
+            // Tooltip with the access word, for reachable states:
+            if let Some(word) = access_words.get(&state.id) {
+                let word = if word.is_empty() {
+                    "ε".to_string()
+                } else {
+                    word.iter().map(|symbol| format!("{}", symbol)).collect()
+                };
+                attrs.push(attr!("tooltip", esc escape_label(&word)));
+            }
+            if let Some(label) = label(state.id) {
+                attrs.push(attr!("label", esc label));
+            }
+            let node = node!(name, attrs);
+            stmts.push(stmt!(node));
+        }
+
+        // Collapsed or hidden dead states:
+        if !dead.is_empty() {
+            let legend = match sinks {
+                SinkRendering::Show => unreachable!(),
+                SinkRendering::Collapse => {
+                    let attr = attr!("shape", "box");
+                    let style = attr!("style", "dashed");
+                    let node = node!( "dead"; attr, style );
+                    stmts.push(stmt!(node));
+                    format!("{} dead state(s) collapsed into 'dead'", dead.len())
+                }
+                SinkRendering::Hide => format!("{} dead state(s) hidden", dead.len()),
+            };
+            let attr = attr!("shape", "plaintext");
+            let label = attr!("label", esc legend);
+            let node = node!( "legend"; attr, label );
             stmts.push(stmt!(node));
         }
 
@@ -39,14 +140,54 @@ impl<A: Alphabet + Display> Dfa<A> {
             stmts.push(stmt!(edge));
         }
 
-        // Transitions:
+        // Transitions, grouped by their endpoints:
+        let mut edges: BTreeMap<(StateId, StateId), Vec<A>> = BTreeMap::new();
+        let mut into_dead: BTreeMap<StateId, Vec<A>> = BTreeMap::new();
         for (from, symbol, to) in self.transitions() {
-            let from = format!("{}", from.id);
-            let to = format!("{}", to.id);
-            let symbol = format!("{}", symbol);
-            let attr = attr!("label", symbol);
-            let edge = edge!( node_id!(from) => node_id!(to); attr );
-            stmts.push(stmt!(edge));
+            if dead.contains(&from.id) {
+                continue;
+            }
+            if dead.contains(&to.id) {
+                into_dead.entry(from.id).or_default().push(symbol);
+            } else {
+                edges.entry((from.id, to.id)).or_default().push(symbol);
+            }
+        }
+        let label = |symbols: &mut Vec<A>| {
+            symbols.sort_unstable();
+            match options.groups {
+                Some(groups) => groups.label(symbols.iter()),
+                None => symbols
+                    .iter()
+                    .map(|symbol| format!("{}", symbol))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            }
+        };
+        for ((from, to), mut symbols) in edges {
+            let from = format!("{}", from);
+            let to = format!("{}", to);
+            if options.groups.is_some() {
+                let attr = attr!("label", esc escape_label(&label(&mut symbols)));
+                let edge = edge!( node_id!(from) => node_id!(to); attr );
+                stmts.push(stmt!(edge));
+            } else {
+                for symbol in symbols {
+                    let symbol = format!("{}", symbol);
+                    let attr = attr!("label", esc escape_label(&symbol));
+                    let edge = edge!( node_id!(from) => node_id!(to); attr );
+                    stmts.push(stmt!(edge));
+                }
+            }
+        }
+        if sinks == SinkRendering::Collapse {
+            // One edge per source state, labeled with all symbols leading to dead states:
+            for (from, mut symbols) in into_dead {
+                let from = format!("{}", from);
+                let attr = attr!("label", esc escape_label(&label(&mut symbols)));
+                let edge = edge!( node_id!(from) => node_id!("dead"); attr );
+                stmts.push(stmt!(edge));
+            }
         }
 
         let g = graph!( strict di id!("DFA"), stmts );
@@ -90,3 +231,96 @@ impl<A: Alphabet + Display> Dfa<A> {
     //     Ok(())
     // }
 }
+
+impl<A: Alphabet + Display, S: Display, T> Dfa<A, S, T> {
+    /// Same as [`render_graphviz_with`](Dfa::render_graphviz_with), but labeling every state
+    /// with its id and, on the next line, its payload.
+    pub fn render_graphviz_labeled(&self, options: &GraphvizOptions<'_, A>) -> String {
+        self.clone_structure()
+            .render_graphviz_labeled_by(options, |id| {
+                let data = escape_label(&self.state_data(id).to_string());
+                Some(format!("{}\\n{}", id, data))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn completed() -> Dfa<char> {
+        // Only "a" is accepted; all other words end up in two chained sinks.
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let sink1 = dfa.add_state(false);
+        let sink2 = dfa.add_state(false);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', sink1);
+        dfa.add_transition(q1, 'a', sink1);
+        dfa.add_transition(q1, 'b', sink2);
+        dfa.add_transition(sink1, 'a', sink2);
+        dfa.add_transition(sink2, 'a', sink2);
+        dfa
+    }
+
+    #[test]
+    fn test_render_sinks() {
+        let dfa = completed();
+        let shown = dfa.render_graphviz();
+        assert!(!shown.contains("legend"));
+        assert!(shown.contains("3 -> 3"));
+
+        let collapsed = dfa.render_graphviz_with(&GraphvizOptions {
+            sinks: SinkRendering::Collapse,
+            ..Default::default()
+        });
+        assert!(collapsed.contains("2 dead state(s) collapsed"));
+        assert!(collapsed.contains("1 -> dead"));
+        assert!(!collapsed.contains("2 -> "));
+        assert!(!collapsed.contains("3 -> "));
+
+        let hidden = dfa.render_graphviz_with(&GraphvizOptions {
+            sinks: SinkRendering::Hide,
+            ..Default::default()
+        });
+        assert!(hidden.contains("2 dead state(s) hidden"));
+        assert!(hidden.contains("0 -> 1"));
+        assert!(!hidden.contains("-> 2"));
+    }
+
+    #[test]
+    fn test_render_groups() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        for digit in '0'..='9' {
+            dfa.add_transition(q0, digit, q1);
+            dfa.add_transition(q1, digit, q1);
+        }
+        dfa.add_transition(q1, '_', q1);
+
+        let groups = SymbolGroups::new().with("digit", '0'..='9');
+        let dot = dfa.render_graphviz_with(&GraphvizOptions {
+            groups: Some(&groups),
+            ..Default::default()
+        });
+        assert!(dot.contains("0 -> 1 [label=\"digit\"]"));
+        assert!(dot.contains("1 -> 1 [label=\"digit, _\"]"));
+        assert_eq!(dot.matches("->").count(), 3);
+    }
+
+    #[test]
+    fn test_render_labeled() {
+        let mut dfa = Dfa::<char, &str>::default();
+        let idle = dfa.add_state_with(false, "idle");
+        let busy = dfa.add_state_with(true, "\"busy\"");
+        dfa.add_transition(idle, 'x', busy);
+        *dfa.state_data_mut(idle) = "waiting";
+
+        let dot = dfa.render_graphviz_labeled(&GraphvizOptions::default());
+        assert!(dot.contains(r#"label="0\nwaiting""#));
+        assert!(dot.contains(r#"label="1\n\"busy\"""#));
+        assert!(dot.contains("0 -> 1"));
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// Viewer page, with the automaton spliced in place of `/*DATA*/`.
+const TEMPLATE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>DFA</title>
+<style>
+  body { margin: 0; display: flex; height: 100vh; font-family: sans-serif; }
+  #canvas { flex: 1; cursor: grab; background: #fafafa; }
+  #panel { width: 280px; padding: 12px; border-left: 1px solid #ccc; overflow-y: auto; }
+  .state circle { fill: #fff; stroke: #333; stroke-width: 1.5; cursor: pointer; }
+  .state.selected circle { stroke: #06c; stroke-width: 3; }
+  .state.current circle { fill: #fd6; }
+  .edge path { fill: none; stroke: #666; marker-end: url(#arrow); }
+  .edge text, .state text { font-size: 12px; text-anchor: middle; dominant-baseline: middle; pointer-events: none; }
+  #status.accepted { color: #080; } #status.rejected { color: #c00; }
+</style>
+</head>
+<body>
+<svg id="canvas">
+  <defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="#666"/></marker></defs>
+  <g id="viewport"></g>
+</svg>
+<div id="panel">
+  <h3>Run a word</h3>
+  <input id="word" placeholder="input word">
+  <button id="reset">Reset</button> <button id="step">Step</button> <button id="run">Run</button>
+  <p id="status"></p>
+  <h3>State</h3>
+  <div id="info">Click a state to inspect it.</div>
+</div>
+<script>
+const dfa = /*DATA*/;
+const svg = document.getElementById("canvas");
+const viewport = document.getElementById("viewport");
+const ns = "http://www.w3.org/2000/svg";
+const R = 18;
+const el = (name, attrs, parent) => {
+  const e = document.createElementNS(ns, name);
+  for (const [k, v] of Object.entries(attrs)) e.setAttribute(k, v);
+  parent.appendChild(e);
+  return e;
+};
+
+// Edges, curved when there is an edge in the opposite direction:
+const pairs = new Set(dfa.edges.map(e => e.from + ">" + e.to));
+for (const e of dfa.edges) {
+  const a = dfa.states[e.from], b = dfa.states[e.to];
+  const g = el("g", { class: "edge" }, viewport);
+  let d, lx, ly;
+  if (e.from === e.to) {
+    d = `M ${a.x - 8} ${a.y - R} C ${a.x - 30} ${a.y - 70}, ${a.x + 30} ${a.y - 70}, ${a.x + 8} ${a.y - R}`;
+    lx = a.x; ly = a.y - 62;
+  } else {
+    const dx = b.x - a.x, dy = b.y - a.y, len = Math.hypot(dx, dy);
+    const bend = pairs.has(e.to + ">" + e.from) ? 25 : 0;
+    const mx = (a.x + b.x) / 2 - bend * dy / len, my = (a.y + b.y) / 2 + bend * dx / len;
+    const sx = a.x + R * dx / len, sy = a.y + R * dy / len, tx = b.x - R * dx / len, ty = b.y - R * dy / len;
+    d = `M ${sx} ${sy} Q ${2 * mx - (sx + tx) / 2} ${2 * my - (sy + ty) / 2} ${tx} ${ty}`;
+    lx = mx; ly = my - 8;
+  }
+  el("path", { d }, g);
+  el("text", { x: lx, y: ly }, g).textContent = e.symbols.join(", ");
+}
+
+// States:
+const nodes = dfa.states.map(s => {
+  const g = el("g", { class: "state" }, viewport);
+  el("circle", { cx: s.x, cy: s.y, r: R }, g);
+  if (s.accepting) el("circle", { cx: s.x, cy: s.y, r: R - 4 }, g);
+  el("text", { x: s.x, y: s.y }, g).textContent = s.id;
+  g.addEventListener("click", () => select(s.id));
+  return g;
+});
+if (dfa.states.length > 0) {
+  const s = dfa.states[0];
+  const g = el("g", { class: "edge" }, viewport);
+  el("path", { d: `M ${s.x - 50} ${s.y} L ${s.x - R} ${s.y}` }, g);
+}
+
+function select(id) {
+  nodes.forEach((g, i) => g.classList.toggle("selected", i === id));
+  const s = dfa.states[id];
+  const info = document.getElementById("info");
+  info.innerHTML = "";
+  const add = text => { const p = document.createElement("p"); p.textContent = text; info.appendChild(p); };
+  add(`State ${id}${s.accepting ? " (accepting)" : ""}`);
+  add(s.word === null ? "Unreachable" : `Access word: ${s.word.length ? s.word.join(" ") : "ε"}`);
+  for (const e of dfa.edges.filter(e => e.from === id)) add(`${e.symbols.join(", ")} → ${e.to}`);
+}
+
+// Stepping through a word; single-char symbols are read per char, others are space-separated:
+const next = new Map(dfa.edges.flatMap(e => e.symbols.map(sym => [e.from + "\u0000" + sym, e.to])));
+const chars = dfa.symbols.every(sym => [...sym].length === 1);
+let run = null;
+function reset() {
+  const text = document.getElementById("word").value;
+  run = { symbols: chars ? [...text] : text.split(/\s+/).filter(x => x), pos: 0, state: dfa.states.length ? 0 : null };
+  show();
+}
+function step() {
+  if (run === null) reset();
+  if (run.state === null || run.pos >= run.symbols.length) return;
+  const to = next.get(run.state + "\u0000" + run.symbols[run.pos]);
+  run.state = to === undefined ? null : to;
+  run.pos++;
+  show();
+}
+function show() {
+  nodes.forEach((g, i) => g.classList.toggle("current", i === run.state));
+  const status = document.getElementById("status");
+  const done = run.state === null || run.pos >= run.symbols.length;
+  const accepted = run.state !== null && run.pos >= run.symbols.length && dfa.states[run.state].accepting;
+  status.className = done ? (accepted ? "accepted" : "rejected") : "";
+  status.textContent = `Read ${run.pos}/${run.symbols.length}` +
+    (run.state === null ? ", stuck: rejected" : done ? (accepted ? ", accepted" : ", rejected") : `, in state ${run.state}`);
+}
+document.getElementById("reset").onclick = reset;
+document.getElementById("step").onclick = step;
+document.getElementById("run").onclick = () => { reset(); while (run.state !== null && run.pos < run.symbols.length) step(); };
+
+// Pan and zoom:
+let view = { x: 0, y: 0, k: 1 }, drag = null;
+const apply = () => viewport.setAttribute("transform", `translate(${view.x} ${view.y}) scale(${view.k})`);
+svg.addEventListener("mousedown", e => { drag = { x: e.clientX - view.x, y: e.clientY - view.y }; });
+window.addEventListener("mouseup", () => { drag = null; });
+window.addEventListener("mousemove", e => { if (drag) { view.x = e.clientX - drag.x; view.y = e.clientY - drag.y; apply(); } });
+svg.addEventListener("wheel", e => {
+  e.preventDefault();
+  const f = e.deltaY < 0 ? 1.1 : 1 / 1.1;
+  view.x = e.offsetX - (e.offsetX - view.x) * f;
+  view.y = e.offsetY - (e.offsetY - view.y) * f;
+  view.k *= f;
+  apply();
+});
+</script>
+</body>
+</html>
+"##;
+
+/// Horizontal and vertical distance between neighboring states in the layout.
+const SPACING: (usize, usize) = (130, 90);
+
+/// Writes `s` as a JSON string literal, escaping `<` so it can't close the `<script>` element.
+fn write_json_string(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '<' => out.push_str("\\u003c"),
+            c if c.is_control() => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn write_json_strings(out: &mut String, strings: impl IntoIterator<Item = String>) {
+    out.push('[');
+    for (i, s) in strings.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        write_json_string(out, &s);
+    }
+    out.push(']');
+}
+
+impl<A: Alphabet + Display, S, T> Dfa<A, S, T> {
+    /// Renders a self-contained HTML page with an interactive viewer: the automaton can be
+    /// panned and zoomed, clicking a state shows its access word and transitions,
+    /// and an input word can be run step by step.
+    ///
+    /// States are laid out in columns by their BFS depth from the initial state,
+    /// with unreachable states in a last column. No external resources are loaded.
+    pub fn render_html(&self) -> String {
+        let mut columns: BTreeMap<usize, Vec<StateId>> = BTreeMap::new();
+        let mut depths = vec![None; self.num_states()];
+        if !self.states.is_empty() {
+            for discovery in self.bfs_from(StateId::new(0)) {
+                depths[discovery.state.index()] = Some(discovery.depth);
+            }
+        }
+        let last = depths.iter().flatten().max().map_or(0, |&depth| depth + 1);
+        for (state, depth) in depths.iter().enumerate() {
+            columns
+                .entry(depth.unwrap_or(last))
+                .or_default()
+                .push(StateId::new(state));
+        }
+        let mut positions = vec![(0, 0); self.num_states()];
+        for (&column, states) in &columns {
+            for (row, &state) in states.iter().enumerate() {
+                positions[state.index()] = (SPACING.0 * column + 80, SPACING.1 * row + 80);
+            }
+        }
+
+        let access_words = self.access_words();
+        let mut data = String::from("{\"states\":[");
+        for state in self.states() {
+            if state.id.index() > 0 {
+                data.push(',');
+            }
+            let (x, y) = positions[state.id.index()];
+            write!(
+                data,
+                "{{\"id\":{},\"accepting\":{},\"x\":{},\"y\":{},\"word\":",
+                state.id, state.accepting, x, y
+            )
+            .unwrap();
+            match access_words.get(&state.id) {
+                Some(word) => write_json_strings(&mut data, word.iter().map(|s| s.to_string())),
+                None => data.push_str("null"),
+            }
+            data.push('}');
+        }
+
+        data.push_str("],\"edges\":[");
+        let mut edges: BTreeMap<(StateId, StateId), Vec<A>> = BTreeMap::new();
+        for (from, symbol, to) in self.transitions() {
+            edges.entry((from.id, to.id)).or_default().push(symbol);
+        }
+        for (i, ((from, to), mut symbols)) in edges.into_iter().enumerate() {
+            if i > 0 {
+                data.push(',');
+            }
+            symbols.sort_unstable();
+            write!(data, "{{\"from\":{},\"to\":{},\"symbols\":", from, to).unwrap();
+            write_json_strings(&mut data, symbols.iter().map(|s| s.to_string()));
+            data.push('}');
+        }
+
+        data.push_str("],\"symbols\":");
+        write_json_strings(&mut data, self.symbols().iter().map(|s| s.to_string()));
+        data.push('}');
+
+        TEMPLATE.replace("/*DATA*/", &data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_html() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let unreachable = dfa.add_state(false);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', q1);
+        dfa.add_transition(q1, '<', q0);
+        dfa.add_transition(unreachable, '"', q0);
+
+        let html = dfa.render_html();
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("/*DATA*/"));
+        assert!(html.contains("{\"id\":1,\"accepting\":true,\"x\":210,\"y\":80,\"word\":[\"a\"]}"));
+        assert!(html.contains("{\"id\":2,\"accepting\":false,\"x\":340,\"y\":80,\"word\":null}"));
+        assert!(html.contains("{\"from\":0,\"to\":1,\"symbols\":[\"a\",\"b\"]}"));
+        assert!(html.contains("\"symbols\":[\"\\\"\",\"\\u003c\",\"a\",\"b\"]"));
+        // Only the closing tag of the viewer script itself:
+        assert_eq!(html.matches("</script>").count(), 1);
+    }
+}
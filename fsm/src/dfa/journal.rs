@@ -0,0 +1,195 @@
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// A single construction step recorded in a [`Journal`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Event<A: Alphabet> {
+    AddState {
+        accepting: bool,
+    },
+    AddTransition {
+        from: StateId,
+        symbol: A,
+        to: StateId,
+    },
+    RemoveState {
+        state: StateId,
+    },
+    RemoveTransition {
+        from: StateId,
+        symbol: A,
+    },
+}
+
+/// Log of construction steps of a [`Dfa`], see [`Dfa::with_journal`].
+///
+/// Only the structure is recorded: payloads of states and transitions are not,
+/// so a replay has none. Since replayed ids match the original ones, payloads can be
+/// attached back with [`Dfa::map_states`] when they are still available.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Journal<A: Alphabet> {
+    events: Vec<Event<A>>,
+}
+
+impl<A: Alphabet> Journal<A> {
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    pub fn push(&mut self, event: Event<A>) {
+        self.events.push(event);
+    }
+
+    pub fn events(&self) -> &[Event<A>] {
+        &self.events
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Rebuilds the automaton from all recorded events, without payloads.
+    pub fn replay(&self) -> Dfa<A> {
+        self.replay_prefix(self.events.len())
+    }
+
+    /// Rebuilds the automaton from the first `n` recorded events.
+    ///
+    /// The replayed automaton records its own journal, so it can be extended and replayed again.
+    pub fn replay_prefix(&self, n: usize) -> Dfa<A> {
+        let mut dfa = Dfa::new().with_journal();
+        for event in &self.events[..n] {
+            match *event {
+                Event::AddState { accepting } => {
+                    dfa.add_state(accepting);
+                }
+                Event::AddTransition { from, symbol, to } => {
+                    dfa.add_transition(from, symbol, to);
+                }
+                Event::RemoveState { state } => {
+                    dfa.remove_state(state);
+                }
+                Event::RemoveTransition { from, symbol } => {
+                    dfa.remove_transition(from, symbol);
+                }
+            }
+        }
+        dfa
+    }
+
+    /// Finds the length of the shortest prefix of the journal whose replay is `bad`.
+    ///
+    /// Assumes that once a replayed prefix becomes bad, all longer prefixes are bad, too,
+    /// and uses binary search over prefix lengths. Returns `None` if even the full replay is fine.
+    /// The last event of the found prefix is the one introducing the problem.
+    pub fn bisect<F>(&self, mut bad: F) -> Option<usize>
+    where
+        F: FnMut(&Dfa<A>) -> bool,
+    {
+        if !bad(&self.replay()) {
+            return None;
+        }
+        if bad(&self.replay_prefix(0)) {
+            return Some(0);
+        }
+        // Invariant: prefix of length `hi` is bad, prefix of length `lo` is not.
+        let (mut lo, mut hi) = (0, self.events.len());
+        while hi - lo > 1 {
+            let mid = lo + (hi - lo) / 2;
+            if bad(&self.replay_prefix(mid)) {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+        Some(hi)
+    }
+}
+
+impl<A: Alphabet> Default for Journal<A> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build() -> Dfa<char> {
+        let mut dfa = Dfa::new().with_journal();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+        dfa.add_transition(b, 'x', a);
+        // The "bad" step, making "xy" accepted:
+        dfa.add_transition(b, 'y', b);
+        dfa.add_transition(a, 'z', a);
+        dfa
+    }
+
+    #[test]
+    fn test_journal_replay() {
+        let dfa = build();
+        let journal = dfa.journal().unwrap();
+        assert_eq!(journal.len(), 6);
+        assert_eq!(journal.events()[0], Event::AddState { accepting: false });
+
+        let replayed = journal.replay();
+        assert_eq!(replayed.num_states(), 2);
+        assert_eq!(replayed.num_transitions(), 4);
+        assert_eq!(replayed.journal(), dfa.journal());
+
+        let partial = journal.replay_prefix(3);
+        assert_eq!(partial.num_states(), 2);
+        assert_eq!(partial.num_transitions(), 1);
+    }
+
+    #[test]
+    fn test_journal_bisect() {
+        let dfa = build();
+        let journal = dfa.journal().unwrap();
+        let bad = journal.bisect(|dfa| dfa.accepts("xy".chars()));
+        assert_eq!(bad, Some(5));
+        assert!(matches!(
+            journal.events()[4],
+            Event::AddTransition { symbol: 'y', .. }
+        ));
+        assert_eq!(journal.bisect(|dfa| dfa.num_states() > 5), None);
+    }
+
+    #[test]
+    fn test_journal_disabled() {
+        let mut dfa = Dfa::<char>::new();
+        dfa.add_state(true);
+        assert!(dfa.journal().is_none());
+    }
+
+    #[test]
+    fn test_journal_replay_drops_payloads() {
+        let mut dfa = Dfa::<char, &str, u8>::default().with_journal();
+        let a = dfa.add_state_with(false, "a");
+        let b = dfa.add_state_with(true, "b");
+        let c = dfa.add_state_with(true, "c");
+        dfa.add_transition_with(a, 'x', c, 1);
+        dfa.add_transition_with(c, 'y', b, 2);
+        dfa.remove_state(b);
+
+        let replayed: Dfa<char> = dfa.journal().unwrap().replay();
+        assert_eq!(replayed.num_states(), 2);
+        assert_eq!(replayed.transition_data(a, 'x'), Some(&()));
+        let c = StateId::new(1);
+        assert_eq!(replayed.next(a, 'x'), Some(c));
+
+        // Ids match, so the state payloads can be attached back:
+        let restored = replayed.map_states(|id, ()| *dfa.state_data(id));
+        assert_eq!(restored.state_data(c), &"c");
+    }
+}
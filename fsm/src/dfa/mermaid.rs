@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+use std::fmt::{Display, Write};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// Escapes the chars that end or break a Mermaid transition label with entity codes.
+pub(crate) fn escape_label(label: &str) -> String {
+    let mut escaped = String::new();
+    for c in label.chars() {
+        match c {
+            '#' | ':' | ';' | '"' | '<' | '>' | '\n' => write!(escaped, "#{};", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Writes a `stateDiagram-v2` with states `q0`, `q1`, ..., the initial state 0,
+/// and one edge per pair of states, labeled with `labels` joined by commas.
+pub(crate) fn write_state_diagram<Id: Display>(
+    accepting: impl Iterator<Item = bool>,
+    edges: BTreeMap<(Id, Id), Vec<String>>,
+) -> String {
+    let mut out = String::from("stateDiagram-v2\n    direction LR\n");
+    let accepting = accepting.collect::<Vec<_>>();
+    if !accepting.is_empty() {
+        writeln!(out, "    [*] --> q0").unwrap();
+    }
+    for ((from, to), labels) in edges {
+        let label = escape_label(&labels.join(", "));
+        writeln!(out, "    q{} --> q{} : {}", from, to, label).unwrap();
+    }
+    for (state, accepting) in accepting.into_iter().enumerate() {
+        if accepting {
+            writeln!(out, "    q{} --> [*]", state).unwrap();
+        }
+    }
+    out
+}
+
+impl<A: Alphabet + Display, S, T> Dfa<A, S, T> {
+    /// Renders the automaton as a Mermaid `stateDiagram-v2`, e.g. for a ` ```mermaid ` block
+    /// in Markdown.
+    ///
+    /// States are named `q0`, `q1`, ... after their ids. The initial state has an edge
+    /// from the start marker `[*]`, and accepting states have an edge to the end marker.
+    /// Parallel transitions are merged into a single edge labeled with all their symbols.
+    pub fn render_mermaid(&self) -> String {
+        let mut edges: BTreeMap<(StateId, StateId), Vec<A>> = BTreeMap::new();
+        for (from, symbol, to) in self.transitions() {
+            edges.entry((from.id, to.id)).or_default().push(symbol);
+        }
+        let edges = edges
+            .into_iter()
+            .map(|(edge, mut symbols)| {
+                symbols.sort_unstable();
+                (edge, symbols.iter().map(|s| s.to_string()).collect())
+            })
+            .collect();
+        write_state_diagram(self.states().map(|state| state.accepting), edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_mermaid() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, 'b', q1);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, ':', q1);
+        assert_eq!(
+            dfa.render_mermaid(),
+            "stateDiagram-v2\n    direction LR\n    [*] --> q0\n    q0 --> q1 : a, b\n    q1 --> q1 : #58;\n    q1 --> [*]\n"
+        );
+        assert_eq!(
+            Dfa::<char>::new().render_mermaid(),
+            "stateDiagram-v2\n    direction LR\n"
+        );
+    }
+}
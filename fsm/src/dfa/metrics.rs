@@ -0,0 +1,217 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+use crate::util::scc::scc;
+
+/// Structural complexity metrics of a [`Dfa`], see [`Dfa::metrics`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Metrics<A: Alphabet> {
+    pub num_states: usize,
+    pub num_transitions: usize,
+    /// Number of states reachable from the initial state.
+    pub num_reachable_states: usize,
+    /// Maximal distance from the initial state to a reachable state.
+    pub depth: usize,
+    /// Number of strongly connected components.
+    pub num_sccs: usize,
+    /// Number of strongly connected components containing a cycle.
+    pub num_cyclic_sccs: usize,
+    /// Upper bound on the cycle rank of the transition graph.
+    ///
+    /// The cycle rank bounds the star height of the recognized language from above,
+    /// so this is an upper bound for the star height, too.
+    pub cycle_rank: usize,
+    /// Number of transitions labeled with each used symbol.
+    pub symbol_usage: BTreeMap<A, usize>,
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Computes structural complexity metrics, e.g., for comparing models across learning runs.
+    pub fn metrics(&self) -> Metrics<A> {
+        let depth = if self.states.is_empty() {
+            0
+        } else {
+            self.bfs_from(StateId::new(0))
+                .map(|d| d.depth)
+                .max()
+                .unwrap_or(0)
+        };
+        let num_reachable_states = self.accessible_states().len();
+
+        let all_states = self.states().map(|state| state.id).collect::<BTreeSet<_>>();
+        let components = self.sccs(&all_states);
+        let num_cyclic_sccs = components
+            .iter()
+            .filter(|component| self.is_cyclic(component))
+            .count();
+
+        let mut symbol_usage = BTreeMap::new();
+        for (_, symbol, _) in self.transitions() {
+            *symbol_usage.entry(symbol).or_default() += 1;
+        }
+
+        Metrics {
+            num_states: self.num_states(),
+            num_transitions: self.num_transitions(),
+            num_reachable_states,
+            depth,
+            num_sccs: components.len(),
+            num_cyclic_sccs,
+            cycle_rank: self.cycle_rank_bound(&all_states),
+            symbol_usage,
+        }
+    }
+
+    /// Strongly connected components of the subgraph induced by `states`.
+    pub(super) fn sccs(&self, states: &BTreeSet<StateId>) -> Vec<BTreeSet<StateId>> {
+        scc(states.iter().copied(), |state| {
+            self.state(state)
+                .transitions()
+                .map(|(_, to)| to)
+                .filter(|to| states.contains(to))
+                .collect::<Vec<_>>()
+        })
+        .into_iter()
+        .map(|component| component.into_iter().collect())
+        .collect()
+    }
+
+    /// Whether the strongly connected `component` contains a cycle.
+    pub(super) fn is_cyclic(&self, component: &BTreeSet<StateId>) -> bool {
+        component.len() > 1 || {
+            let &state = component.first().unwrap();
+            self.state(state).transitions().any(|(_, to)| to == state)
+        }
+    }
+
+    /// Upper bound on the cycle rank of the subgraph induced by `states`.
+    fn cycle_rank_bound(&self, states: &BTreeSet<StateId>) -> usize {
+        let edges = self
+            .transitions()
+            .map(|(from, _, to)| (from.id, to.id))
+            .filter(|(from, to)| states.contains(from) && states.contains(to))
+            .collect::<Vec<_>>();
+        cycle_rank_bound(states.iter().copied().collect(), edges)
+    }
+}
+
+/// Upper bound on the cycle rank of the graph with `nodes` and (possibly repeated) `edges`.
+///
+/// Exact cycle rank is NP-hard, so instead of trying all vertices, each cyclic
+/// component greedily drops the vertex with the most edges inside the component.
+/// Every level of the recursion splits the edges among the components, so degrees
+/// are computed once per component from its own edges.
+fn cycle_rank_bound(nodes: Vec<StateId>, edges: Vec<(StateId, StateId)>) -> usize {
+    let mut successors: HashMap<StateId, Vec<StateId>> = HashMap::new();
+    for &(from, to) in &edges {
+        successors.entry(from).or_default().push(to);
+    }
+    let components = scc(nodes, |state| {
+        successors.get(&state).cloned().unwrap_or_default()
+    });
+    let component_of = (components.iter().enumerate())
+        .flat_map(|(index, component)| component.iter().map(move |&state| (state, index)))
+        .collect::<HashMap<_, _>>();
+    let mut inner_edges = vec![Vec::new(); components.len()];
+    for (from, to) in edges {
+        if component_of[&from] == component_of[&to] {
+            inner_edges[component_of[&from]].push((from, to));
+        }
+    }
+
+    components
+        .into_iter()
+        .zip(inner_edges)
+        .filter(|(component, edges)| component.len() > 1 || !edges.is_empty())
+        .map(|(mut component, edges)| {
+            let mut degree: HashMap<StateId, usize> = HashMap::new();
+            for &(from, to) in &edges {
+                *degree.entry(from).or_default() += 1;
+                *degree.entry(to).or_default() += 1;
+            }
+            // The last of the states with the maximal degree, in the order of ids:
+            component.sort_unstable();
+            let &hub = component
+                .iter()
+                .max_by_key(|&state| degree.get(state).copied().unwrap_or(0))
+                .unwrap();
+            component.retain(|&state| state != hub);
+            let edges = (edges.into_iter())
+                .filter(|&(from, to)| from != hub && to != hub)
+                .collect();
+            1 + cycle_rank_bound(component, edges)
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metrics() {
+        // 0 -a-> 1 -b-> 2, loops: 1 -a-> 1, 2 -a-> 1
+        let mut dfa = Dfa::new();
+        let s0 = dfa.add_state(false);
+        let s1 = dfa.add_state(false);
+        let s2 = dfa.add_state(true);
+        let _s3 = dfa.add_state(false);
+        dfa.add_transition(s0, 'a', s1);
+        dfa.add_transition(s1, 'a', s1);
+        dfa.add_transition(s1, 'b', s2);
+        dfa.add_transition(s2, 'a', s1);
+
+        let metrics = dfa.metrics();
+        assert_eq!(metrics.num_states, 4);
+        assert_eq!(metrics.num_transitions, 4);
+        assert_eq!(metrics.num_reachable_states, 3);
+        assert_eq!(metrics.depth, 2);
+        assert_eq!(metrics.num_sccs, 3);
+        assert_eq!(metrics.num_cyclic_sccs, 1);
+        // Removing state 1 breaks all cycles:
+        assert_eq!(metrics.cycle_rank, 1);
+        assert_eq!(metrics.symbol_usage, BTreeMap::from([('a', 3), ('b', 1)]));
+    }
+
+    #[test]
+    fn test_metrics_acyclic() {
+        let mut dfa = Dfa::new();
+        let s0 = dfa.add_state(false);
+        let s1 = dfa.add_state(true);
+        dfa.add_transition(s0, 'x', s1);
+        let metrics = dfa.metrics();
+        assert_eq!(metrics.cycle_rank, 0);
+        assert_eq!(metrics.num_cyclic_sccs, 0);
+    }
+
+    #[test]
+    fn test_metrics_nested_cycles() {
+        // Two self-loops nested in a 2-cycle: 0 <-a-> 1, 0 -b-> 0, 1 -b-> 1
+        let mut dfa = Dfa::new();
+        let s0 = dfa.add_state(true);
+        let s1 = dfa.add_state(false);
+        dfa.add_transition(s0, 'a', s1);
+        dfa.add_transition(s1, 'a', s0);
+        dfa.add_transition(s0, 'b', s0);
+        dfa.add_transition(s1, 'b', s1);
+        assert_eq!(dfa.metrics().cycle_rank, 2);
+    }
+
+    #[test]
+    fn test_metrics_complete_graph() {
+        // Every state has an edge to every state, so each removal leaves a smaller complete graph:
+        let n = 40;
+        let mut dfa = Dfa::new();
+        let states = (0..n).map(|_| dfa.add_state(false)).collect::<Vec<_>>();
+        for &from in &states {
+            for (symbol, &to) in states.iter().enumerate() {
+                dfa.add_transition(from, symbol, to);
+            }
+        }
+        assert_eq!(dfa.metrics().cycle_rank, n);
+    }
+}
@@ -0,0 +1,534 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+use crate::nfa::state::NfaStateId;
+use crate::nfa::Nfa;
+use crate::progress::{self, Cancelled, Progress, REPORT_EVERY};
+
+/// Intermediate state of [`Dfa::minimize`], which can be [run](Minimization::run) in
+/// bounded chunks and, with the `serde` feature, saved in between as a checkpoint,
+/// so that a long minimization can be resumed after a restart.
+///
+/// The checkpoint holds the trimmed automaton and the current partition of its states.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Minimization<A: Alphabet> {
+    /// Trimmed copy of the automaton; state `num_states()` is the implicit dead state,
+    /// target of all missing transitions.
+    dfa: Dfa<A>,
+    /// Blocks of state indices, including the dead state.
+    blocks: Vec<Vec<usize>>,
+    block_of: Vec<usize>,
+    /// Blocks still to be used as splitters.
+    work: Vec<usize>,
+    in_work: Vec<bool>,
+    /// Symbols and inverse transitions, rebuilt after loading a checkpoint.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    predecessors: Option<(Vec<A>, Predecessors<A>)>,
+}
+
+/// Inverse transitions, per symbol, between state indices.
+type Predecessors<A> = HashMap<(A, usize), Vec<usize>>;
+
+/// Index of the target of a transition in a trimmed `dfa` completed with the dead state.
+fn target<A: Alphabet>(dfa: &Dfa<A>, state: usize, symbol: A) -> usize {
+    let dead = dfa.num_states();
+    if state == dead {
+        dead
+    } else {
+        dfa.next(StateId::new(state), symbol)
+            .map_or(dead, StateId::index)
+    }
+}
+
+impl<A: Alphabet> Minimization<A> {
+    /// Starts minimizing `dfa`, with the initial partition into accepting
+    /// and non-accepting (including dead) states.
+    pub fn new<S, T>(dfa: &Dfa<A, S, T>) -> Self {
+        let mut dfa = dfa.clone_structure();
+        dfa.trim();
+        if dfa.num_states() == 0 {
+            return Minimization {
+                dfa,
+                blocks: Vec::new(),
+                block_of: Vec::new(),
+                work: Vec::new(),
+                in_work: Vec::new(),
+                predecessors: None,
+            };
+        }
+
+        let n = dfa.num_states();
+        let dead = n;
+        let (accepting, rejecting): (Vec<_>, Vec<_>) =
+            (0..=n).partition(|&state| state != dead && dfa.accepting(StateId::new(state)));
+        let mut blocks = vec![rejecting];
+        if !accepting.is_empty() {
+            blocks.push(accepting);
+        }
+        let mut block_of = vec![0; n + 1];
+        for (id, block) in blocks.iter().enumerate() {
+            for &state in block {
+                block_of[state] = id;
+            }
+        }
+        Minimization {
+            dfa,
+            work: (0..blocks.len()).collect(),
+            in_work: vec![true; blocks.len()],
+            blocks,
+            block_of,
+            predecessors: None,
+        }
+    }
+
+    /// Whether the partition is final, so that [`into_dfa`](Minimization::into_dfa) can be called.
+    pub fn is_done(&self) -> bool {
+        self.work.is_empty()
+    }
+
+    /// Number of blocks in the current partition, a lower bound on the number
+    /// of states of the minimal DFA (plus one for the dead block).
+    pub fn num_blocks(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Refines the partition with up to `steps` splitters, returning whether it is done.
+    pub fn run(&mut self, steps: usize) -> bool {
+        let dfa = &self.dfa;
+        let (symbols, predecessors) = self.predecessors.get_or_insert_with(|| {
+            let symbols = dfa.symbols();
+            let mut predecessors: Predecessors<A> = HashMap::new();
+            for state in 0..=dfa.num_states() {
+                for &symbol in &symbols {
+                    predecessors
+                        .entry((symbol, target(dfa, state, symbol)))
+                        .or_default()
+                        .push(state);
+                }
+            }
+            (symbols, predecessors)
+        });
+        let blocks = &mut self.blocks;
+        let block_of = &mut self.block_of;
+        let in_work = &mut self.in_work;
+
+        for _ in 0..steps {
+            let Some(splitter) = self.work.pop() else {
+                break;
+            };
+            in_work[splitter] = false;
+            let splitter_states = blocks[splitter].clone();
+            for &symbol in symbols.iter() {
+                // Group the predecessors of the splitter by their blocks:
+                let mut touched: HashMap<usize, Vec<usize>> = HashMap::new();
+                for &state in &splitter_states {
+                    for &pred in predecessors.get(&(symbol, state)).into_iter().flatten() {
+                        touched.entry(block_of[pred]).or_default().push(pred);
+                    }
+                }
+                let mut touched = touched.into_iter().collect::<Vec<_>>();
+                touched.sort_unstable();
+                for (block, mut inside) in touched {
+                    if inside.len() == blocks[block].len() {
+                        continue;
+                    }
+                    inside.sort_unstable();
+                    let outside = blocks[block]
+                        .iter()
+                        .copied()
+                        .filter(|state| inside.binary_search(state).is_err())
+                        .collect::<Vec<_>>();
+                    let new_block = blocks.len();
+                    for &state in &outside {
+                        block_of[state] = new_block;
+                    }
+                    blocks[block] = inside;
+                    blocks.push(outside);
+                    in_work.push(false);
+                    let to_add = if in_work[block] || blocks[block].len() > blocks[new_block].len()
+                    {
+                        new_block
+                    } else {
+                        block
+                    };
+                    self.work.push(to_add);
+                    in_work[to_add] = true;
+                }
+            }
+        }
+        self.is_done()
+    }
+
+    /// Runs to completion, reporting the number of blocks and pending splitters to `progress`.
+    ///
+    /// On cancellation, the partition refined so far is kept, so the run can be resumed later.
+    pub fn run_with_progress(&mut self, progress: &mut impl Progress) -> Result<(), Cancelled> {
+        while !self.run(REPORT_EVERY) {
+            progress::report(progress, self.num_blocks(), self.work.len())?;
+        }
+        Ok(())
+    }
+
+    /// Builds the quotient automaton of the final partition, see [`Dfa::minimize`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the minimization [is not done](Minimization::is_done).
+    pub fn into_dfa(self) -> Dfa<A> {
+        assert!(self.is_done(), "minimization is not finished");
+        let Minimization {
+            dfa,
+            blocks,
+            block_of,
+            ..
+        } = self;
+        let mut minimal = Dfa::new();
+        if dfa.num_states() == 0 {
+            return minimal;
+        }
+        let symbols = dfa.symbols();
+        let dead = dfa.num_states();
+
+        // Build the quotient automaton in BFS order, skipping the dead block:
+        let mut block2state = HashMap::new();
+        let mut queue = VecDeque::new();
+        let initial = StateId::new(0);
+        block2state.insert(block_of[0], minimal.add_state(dfa.accepting(initial)));
+        queue.push_back(block_of[0]);
+        while let Some(block) = queue.pop_front() {
+            let from = block2state[&block];
+            let representative = blocks[block][0];
+            for &symbol in &symbols {
+                let next_block = block_of[target(&dfa, representative, symbol)];
+                if next_block == block_of[dead] {
+                    continue;
+                }
+                let to = *block2state.entry(next_block).or_insert_with(|| {
+                    queue.push_back(next_block);
+                    minimal.add_state(dfa.accepting(StateId::new(blocks[next_block][0])))
+                });
+                minimal.add_transition(from, symbol, to);
+            }
+        }
+        minimal
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Builds the minimal DFA recognizing the same language, using Hopcroft's algorithm.
+    ///
+    /// The result is trimmed (it has no dead states, missing transitions mean rejection),
+    /// and its states are numbered in BFS order from the initial state, following
+    /// transitions in symbol order. Hence, two DFAs recognize the same language
+    /// if and only if their minimizations are identical up to the order of transitions.
+    ///
+    /// See [`Minimization`] for running it in resumable chunks.
+    pub fn minimize(&self) -> Dfa<A> {
+        let mut minimization = Minimization::new(self);
+        minimization.run(usize::MAX);
+        minimization.into_dfa()
+    }
+
+    /// Same as [`minimize`](Dfa::minimize), but reporting to `progress`,
+    /// which may cancel it, see [`Minimization::run_with_progress`].
+    pub fn minimize_with_progress(
+        &self,
+        progress: &mut impl Progress,
+    ) -> Result<Dfa<A>, Cancelled> {
+        let mut minimization = Minimization::new(self);
+        minimization.run_with_progress(progress)?;
+        Ok(minimization.into_dfa())
+    }
+
+    /// Builds the minimal DFA using Brzozowski's double-reversal algorithm:
+    /// reverse, determinize, reverse, determinize.
+    ///
+    /// The result is trimmed and recognizes the same language as [`Dfa::minimize`] does
+    /// (with the same number of states), but states may be numbered differently.
+    pub fn minimize_brzozowski(&self) -> Dfa<A> {
+        self.reverse_determinize().reverse_determinize()
+    }
+
+    /// Determinizes the [reverse](Dfa::reverse) of this DFA.
+    ///
+    /// Subsets start from the set of accepting states directly, rather than from the
+    /// closure of the fresh initial state, which would otherwise duplicate the initial subset.
+    fn reverse_determinize(&self) -> Dfa<A> {
+        let mut dfa = Dfa::new();
+        let initial = self
+            .states()
+            .filter(|state| state.accepting)
+            .map(|state| state.id)
+            .collect::<BTreeSet<_>>();
+        if initial.is_empty() {
+            return dfa;
+        }
+
+        let symbols = self.symbols();
+        let mut predecessors: HashMap<(StateId, A), Vec<StateId>> = HashMap::new();
+        for (from, symbol, to) in self.transitions() {
+            predecessors
+                .entry((to.id, symbol))
+                .or_default()
+                .push(from.id);
+        }
+
+        let mut subset2state = HashMap::new();
+        let mut queue = VecDeque::new();
+        subset2state.insert(
+            initial.clone(),
+            dfa.add_state(initial.contains(&StateId::new(0))),
+        );
+        queue.push_back(initial);
+        while let Some(subset) = queue.pop_front() {
+            let from = subset2state[&subset];
+            for &symbol in &symbols {
+                let next = subset
+                    .iter()
+                    .filter_map(|&state| predecessors.get(&(state, symbol)))
+                    .flatten()
+                    .copied()
+                    .collect::<BTreeSet<_>>();
+                if next.is_empty() {
+                    continue;
+                }
+                let to = match subset2state.get(&next) {
+                    Some(&to) => to,
+                    None => {
+                        let to = dfa.add_state(next.contains(&StateId::new(0)));
+                        subset2state.insert(next.clone(), to);
+                        queue.push_back(next);
+                        to
+                    }
+                };
+                dfa.add_transition(from, symbol, to);
+            }
+        }
+        dfa
+    }
+
+    /// Builds an NFA recognizing the reversed language, i.e. all words of this automaton read backwards.
+    ///
+    /// All transitions are flipped, and the accepting states of this DFA become initial:
+    /// state `0` of the result is a fresh initial state with ε-transitions to them.
+    /// State `i` of this DFA corresponds to state `i + 1` of the result,
+    /// and only the former initial state is accepting.
+    pub fn reverse(&self) -> Nfa<A> {
+        let mut nfa = Nfa::new();
+        let initial = nfa.add_state(false);
+        for state in self.states() {
+            let id = nfa.add_state(state.id.index() == 0);
+            if state.accepting {
+                nfa.add_epsilon_transition(initial, id);
+            }
+        }
+        let shift = |id: StateId| NfaStateId::new(id.index() + 1);
+        for (from, symbol, to) in self.transitions() {
+            nfa.add_transition(shift(to.id), symbol, shift(from.id));
+        }
+        nfa
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Same as [`minimize`](Dfa::minimize), but carrying the payloads of states: every state
+    /// of the minimal DFA gets `merge` of the payloads of the useful states it replaces,
+    /// in id order. Payloads of useless states and of transitions are dropped.
+    pub fn minimize_with<U>(&self, mut merge: impl FnMut(&[&S]) -> U) -> Dfa<A, U> {
+        let mut minimization = Minimization::new(self);
+        minimization.run(usize::MAX);
+        let minimal = minimization.into_dfa();
+
+        // Run both automata side by side, to find the states merged into each minimal one:
+        let mut merged = vec![Vec::new(); minimal.num_states()];
+        let mut visited = vec![false; self.num_states()];
+        let mut queue = VecDeque::new();
+        if minimal.num_states() > 0 {
+            let initial = StateId::new(0);
+            visited[0] = true;
+            queue.push_back((initial, initial));
+        }
+        while let Some((state, block)) = queue.pop_front() {
+            merged[block.index()].push(state);
+            for (symbol, next) in self.state(state).transitions() {
+                if let Some(next_block) = minimal.next(block, symbol) {
+                    if !std::mem::replace(&mut visited[next.index()], true) {
+                        queue.push_back((next, next_block));
+                    }
+                }
+            }
+        }
+
+        let mut data = merged
+            .into_iter()
+            .map(|mut states| {
+                states.sort_unstable();
+                let data = states
+                    .into_iter()
+                    .map(|state| self.state_data(state))
+                    .collect::<Vec<_>>();
+                merge(&data)
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+        minimal.map_data(|_, ()| data.next().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    /// Words over {a, b} whose length is divisible by 3, with redundant states.
+    fn redundant() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        // Two copies of the mod-3 counter, switching copies on 'b':
+        let s = (0..6)
+            .map(|i| dfa.add_state(i % 3 == 0))
+            .collect::<Vec<_>>();
+        for copy in 0..2 {
+            for i in 0..3 {
+                let from = s[copy * 3 + i];
+                dfa.add_transition(from, 'a', s[copy * 3 + (i + 1) % 3]);
+                dfa.add_transition(from, 'b', s[(1 - copy) * 3 + (i + 1) % 3]);
+            }
+        }
+        // Dead and unreachable states:
+        let dead = dfa.add_state(false);
+        dfa.add_transition(s[0], 'c', dead);
+        dfa.add_transition(dead, 'a', dead);
+        let unreachable = dfa.add_state(true);
+        dfa.add_transition(unreachable, 'a', s[0]);
+        dfa
+    }
+
+    #[test]
+    fn test_minimize() {
+        let dfa = redundant();
+        let minimal = dfa.minimize();
+        assert_eq!(minimal.num_states(), 3);
+        assert_eq!(minimal.num_transitions(), 6);
+        for word in generate_strings(&['a', 'b', 'c'], 6) {
+            assert_eq!(minimal.accepts(word.chars()), dfa.accepts(word.chars()));
+        }
+        // Minimization is idempotent:
+        assert_eq!(minimal.minimize().num_states(), 3);
+    }
+
+    #[test]
+    fn test_minimize_in_steps() {
+        let dfa = redundant();
+        let mut minimization = Minimization::new(&dfa);
+        let mut runs = 0;
+        while !minimization.run(1) {
+            runs += 1;
+            #[cfg(feature = "serde")]
+            {
+                // Resume from a checkpoint after every step:
+                let json = serde_json::to_string(&minimization).unwrap();
+                minimization = serde_json::from_str(&json).unwrap();
+            }
+        }
+        assert!(runs > 0);
+        let minimal = minimization.into_dfa();
+        let expected = dfa.minimize();
+        assert_eq!(minimal.num_states(), expected.num_states());
+        let edges = |dfa: &Dfa<char>| {
+            dfa.transitions()
+                .map(|(from, symbol, to)| (from.id, symbol, to.id))
+                .collect::<BTreeSet<_>>()
+        };
+        assert_eq!(edges(&minimal), edges(&expected));
+    }
+
+    #[test]
+    fn test_minimize_with_progress() {
+        // A chain of 1000 distinguishable states:
+        let mut dfa = Dfa::new();
+        let states = (0..1000)
+            .map(|i| dfa.add_state(i == 999))
+            .collect::<Vec<_>>();
+        for pair in states.windows(2) {
+            dfa.add_transition(pair[0], 'a', pair[1]);
+        }
+
+        let mut reports = 0;
+        let minimal = dfa
+            .minimize_with_progress(&mut |_, _| {
+                reports += 1;
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert_eq!(minimal.num_states(), 1000);
+        assert!(reports > 0);
+
+        let result = dfa.minimize_with_progress(&mut |_, _| ControlFlow::Break(()));
+        assert_eq!(result.unwrap_err(), Cancelled);
+    }
+
+    #[test]
+    fn test_minimize_brzozowski() {
+        let dfa = redundant();
+        let hopcroft = dfa.minimize();
+        let brzozowski = dfa.minimize_brzozowski();
+        assert_eq!(brzozowski.num_states(), hopcroft.num_states());
+        assert_eq!(brzozowski.num_transitions(), hopcroft.num_transitions());
+        for word in generate_strings(&['a', 'b', 'c'], 6) {
+            assert_eq!(brzozowski.accepts(word.chars()), dfa.accepts(word.chars()));
+        }
+    }
+
+    #[test]
+    fn test_minimize_empty_language() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        dfa.add_transition(q0, 'a', q0);
+        assert_eq!(dfa.minimize().num_states(), 0);
+        assert_eq!(dfa.minimize_brzozowski().num_states(), 0);
+    }
+
+    #[test]
+    fn test_reverse() {
+        // Words ending with "ab":
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', q0);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, 'b', q2);
+        dfa.add_transition(q2, 'a', q1);
+        dfa.add_transition(q2, 'b', q0);
+
+        let reversed = dfa.reverse();
+        for word in generate_strings(&['a', 'b'], 6) {
+            let backwards = word.chars().rev().collect::<String>();
+            assert_eq!(
+                reversed.accepts(word.chars()),
+                dfa.accepts(backwards.chars())
+            );
+        }
+    }
+
+    #[test]
+    fn test_minimize_with() {
+        let dfa = redundant().map_data(|id, ()| id.index());
+        let minimal = dfa.minimize_with(|merged| merged.iter().map(|&&i| i).collect::<Vec<_>>());
+        assert_eq!(minimal.num_states(), 3);
+        // Dead and unreachable states are dropped:
+        assert_eq!(minimal.state_data(StateId::new(0)), &vec![0, 3]);
+        assert_eq!(minimal.state_data(StateId::new(1)), &vec![1, 4]);
+        assert_eq!(minimal.state_data(StateId::new(2)), &vec![2, 5]);
+
+        let empty = Dfa::<char, u8>::default().minimize_with(|_| ());
+        assert_eq!(empty.num_states(), 0);
+    }
+}
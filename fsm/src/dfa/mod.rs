@@ -1,43 +1,307 @@
+use std::cmp::Ordering;
 use std::ops::{Index, IndexMut};
 
+use conflict::{ConflictPolicy, TransitionConflict};
+use journal::{Event, Journal};
 use state::{State, StateId};
 
 use crate::alphabet::Alphabet;
 use crate::util::arena::Arena;
 
+pub mod analysis;
+pub mod array;
+pub mod autocomplete;
+pub mod binary;
+pub mod cache;
+pub mod conflict;
+pub mod cycles;
+pub mod decision;
+pub mod dense;
+pub mod equivalence;
 pub mod graphviz;
+pub mod html;
+pub mod journal;
+pub mod mermaid;
+pub mod metrics;
+pub mod minimize;
+pub mod parallel;
+pub mod pumping;
+pub mod regex;
+pub mod relabel;
+pub mod report;
+pub mod runner;
+pub mod sampling;
+pub mod scan;
 pub mod state;
+pub mod summary;
+pub mod tikz;
+pub mod trace;
+pub mod transform;
+pub mod traversal;
+pub mod words;
+pub mod worst_case;
 
 #[cfg(feature = "serde")]
 mod serde;
 
+/// Deterministic finite automaton over the alphabet `A`, with state 0 being initial.
+///
+/// Every state carries a user payload of type `S`, see [`add_state_with`](Dfa::add_state_with),
+/// and every transition carries a payload of type `T`, see [`add_transition_with`](Dfa::add_transition_with).
+/// Automata with payloads are created by [`Dfa::default`], e.g. `Dfa::<char, Location>::default()`,
+/// and without them by [`Dfa::new`].
+///
+/// Payloads are deliberately not serialized: with the `serde` feature, only the states and
+/// transitions are written, and reading an automaton back gives every state and transition
+/// the default payload.
 #[derive(Debug)]
-pub struct Dfa<A: Alphabet> {
-    states: Arena<State<A>>,
+pub struct Dfa<A: Alphabet, S = (), T = ()> {
+    states: Arena<State<A, T>, StateId>,
+    data: Arena<S, StateId>,
+    conflict_policy: ConflictPolicy,
+    journal: Option<Journal<A>>,
 }
 
 impl<A: Alphabet> Dfa<A> {
     pub fn new() -> Self {
-        Self {
-            states: Arena::new(),
+        Self::default()
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Enables recording of all subsequent additions and removals of states and transitions
+    /// into a [`Journal`]. Payloads are not recorded, so replays have none.
+    pub fn with_journal(mut self) -> Self {
+        self.journal.get_or_insert_with(Journal::new);
+        self
+    }
+
+    pub fn journal(&self) -> Option<&Journal<A>> {
+        self.journal.as_ref()
+    }
+
+    /// Stops recording, returning the journal recorded so far.
+    pub fn take_journal(&mut self) -> Option<Journal<A>> {
+        self.journal.take()
+    }
+
+    /// Sets the policy for adding a transition on a `(state, symbol)` pair that already has one.
+    pub fn with_conflict_policy(mut self, policy: ConflictPolicy) -> Self {
+        self.conflict_policy = policy;
+        self
+    }
+
+    pub fn conflict_policy(&self) -> ConflictPolicy {
+        self.conflict_policy
+    }
+
+    pub fn set_conflict_policy(&mut self, policy: ConflictPolicy) {
+        self.conflict_policy = policy;
+    }
+
+    /// Copies states and transitions, leaving out the payloads, the conflict policy and the journal.
+    pub(crate) fn clone_structure(&self) -> Dfa<A> {
+        let mut dfa = Dfa::new();
+        for state in self.states() {
+            dfa.add_state(state.accepting);
         }
+        for (from, symbol, to) in self.transitions() {
+            dfa.add_transition(from.id, symbol, to.id);
+        }
+        dfa
     }
 
-    pub fn add_state(&mut self, accepting: bool) -> StateId {
+    /// Adds a state carrying `data`. The journal only records the `accepting` flag.
+    pub fn add_state_with(&mut self, accepting: bool, data: S) -> StateId {
+        if let Some(journal) = &mut self.journal {
+            journal.push(Event::AddState { accepting });
+        }
+        self.data.alloc(data);
         self.states.alloc_with_id(|id| State::new(id, accepting))
     }
 
-    pub fn add_transition(&mut self, from: StateId, symbol: A, to: StateId) {
-        self.state_mut(from).add_transition(symbol, to);
+    /// Adds a transition carrying the default payload, resolving conflicts according to
+    /// the [conflict policy](ConflictPolicy).
+    ///
+    /// # Panics
+    ///
+    /// Panics on a conflict under [`ConflictPolicy::Error`],
+    /// use [`try_add_transition`](Dfa::try_add_transition) to handle it instead.
+    pub fn add_transition(&mut self, from: StateId, symbol: A, to: StateId)
+    where
+        T: Default,
+    {
+        if let Err(conflict) = self.try_add_transition(from, symbol, to) {
+            panic!("{}", conflict);
+        }
+    }
+
+    /// Adds a transition, failing on a conflict under [`ConflictPolicy::Error`].
+    ///
+    /// Re-adding an existing transition (with the same target) is not a conflict.
+    /// A replaced transition gets the default payload.
+    pub fn try_add_transition(
+        &mut self,
+        from: StateId,
+        symbol: A,
+        to: StateId,
+    ) -> Result<(), TransitionConflict<A>>
+    where
+        T: Default,
+    {
+        self.try_add_transition_with(from, symbol, to, T::default())
+    }
+
+    /// Same as [`add_transition`](Dfa::add_transition), but attaching `data` to the transition.
+    /// The journal only records the transition itself.
+    pub fn add_transition_with(&mut self, from: StateId, symbol: A, to: StateId, data: T) {
+        if let Err(conflict) = self.try_add_transition_with(from, symbol, to, data) {
+            panic!("{}", conflict);
+        }
+    }
+
+    /// Same as [`try_add_transition`](Dfa::try_add_transition), but attaching `data`
+    /// to the transition, replacing its previous payload.
+    pub fn try_add_transition_with(
+        &mut self,
+        from: StateId,
+        symbol: A,
+        to: StateId,
+        data: T,
+    ) -> Result<(), TransitionConflict<A>> {
+        if let Some(existing) = self.next(from, symbol).filter(|&existing| existing != to) {
+            let conflict = TransitionConflict {
+                from,
+                symbol,
+                existing,
+                rejected: to,
+            };
+            match self.conflict_policy {
+                ConflictPolicy::Overwrite => {}
+                ConflictPolicy::Error => return Err(conflict),
+                ConflictPolicy::DebugPanic => debug_assert!(false, "{}", conflict),
+            }
+        }
+        if let Some(journal) = &mut self.journal {
+            journal.push(Event::AddTransition { from, symbol, to });
+        }
+        self.state_mut(from).add_transition_with(symbol, to, data);
+        Ok(())
+    }
+
+    /// Payload of the transition from `from` on `symbol`, if there is one.
+    pub fn transition_data(&self, from: StateId, symbol: A) -> Option<&T> {
+        self.state(from).transition_data(symbol)
+    }
+    pub fn transition_data_mut(&mut self, from: StateId, symbol: A) -> Option<&mut T> {
+        self.state_mut(from).transition_data_mut(symbol)
+    }
+
+    /// Removes the transition from `from` on `symbol` with its payload, returning its target,
+    /// or `None` if there is no such transition.
+    pub fn remove_transition(&mut self, from: StateId, symbol: A) -> Option<StateId> {
+        let to = self.state_mut(from).remove_transition(symbol)?;
+        if let Some(journal) = &mut self.journal {
+            journal.push(Event::RemoveTransition { from, symbol });
+        }
+        Some(to)
+    }
+
+    /// Removes a state with its payload and all transitions from and to it.
+    ///
+    /// The states are kept contiguous: all states after the removed one move one id down,
+    /// so removing the initial state makes the next one initial. Returns the new id
+    /// of every old one (indexed by the old [index](StateId::index)), `None` for the removed state.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such state.
+    pub fn remove_state(&mut self, id: StateId) -> Vec<Option<StateId>> {
+        assert!(id.index() < self.num_states(), "no state {} to remove", id);
+        let remap = (0..self.num_states())
+            .map(|index| match index.cmp(&id.index()) {
+                Ordering::Less => Some(StateId::new(index)),
+                Ordering::Equal => None,
+                Ordering::Greater => Some(StateId::new(index - 1)),
+            })
+            .collect::<Vec<_>>();
+        if let Some(journal) = &mut self.journal {
+            journal.push(Event::RemoveState { state: id });
+        }
+        self.states.remove(id);
+        self.data.remove(id);
+        for state in self.states.iter_mut() {
+            state.id = remap[state.id.index()].unwrap();
+            state.retarget(|to| remap[to.index()]);
+        }
+        remap
     }
 
-    pub fn state(&self, index: StateId) -> &State<A> {
+    pub fn state(&self, index: StateId) -> &State<A, T> {
         &self.states[index]
     }
-    pub fn state_mut(&mut self, index: StateId) -> &mut State<A> {
+    pub fn state_mut(&mut self, index: StateId) -> &mut State<A, T> {
         &mut self.states[index]
     }
 
+    pub fn state_data(&self, index: StateId) -> &S {
+        &self.data[index]
+    }
+    pub fn state_data_mut(&mut self, index: StateId) -> &mut S {
+        &mut self.data[index]
+    }
+
+    /// Replaces the payload of every state by `f(id, data)`, keeping everything else.
+    pub fn map_data<U>(self, mut f: impl FnMut(StateId, S) -> U) -> Dfa<A, U, T> {
+        let mut data = Arena::new();
+        for (state, old) in self.states.iter().zip(self.data) {
+            data.alloc(f(state.id, old));
+        }
+        Dfa {
+            states: self.states,
+            data,
+            conflict_policy: self.conflict_policy,
+            journal: self.journal,
+        }
+    }
+
+    /// Replaces the payload of every transition by `f(from, symbol, data)`, keeping everything else.
+    pub fn map_transition_data<U>(self, mut f: impl FnMut(StateId, A, T) -> U) -> Dfa<A, S, U> {
+        let mut states = Arena::new();
+        for mut old in self.states {
+            let mut state = State::new(old.id, old.accepting);
+            for (symbol, to, data) in old.take_transitions() {
+                state.add_transition_with(symbol, to, f(old.id, symbol, data));
+            }
+            states.alloc(state);
+        }
+        Dfa {
+            states,
+            data: self.data,
+            conflict_policy: self.conflict_policy,
+            journal: self.journal,
+        }
+    }
+
+    /// Same as [`map_data`](Dfa::map_data), but building a copy with `f(id, &data)`,
+    /// e.g. to bake analysis results into a machine for rendering or export.
+    pub fn map_states<S2>(&self, f: impl Fn(StateId, &S) -> S2) -> Dfa<A, S2, T>
+    where
+        T: Clone,
+    {
+        let mut data = Arena::new();
+        for old in self.data.iter() {
+            data.alloc(old);
+        }
+        let borrowed = Dfa {
+            states: self.states.clone(),
+            data,
+            conflict_policy: self.conflict_policy,
+            journal: self.journal.clone(),
+        };
+        borrowed.map_data(f)
+    }
+
     pub fn accepting(&self, state: StateId) -> bool {
         self.state(state).accepting
     }
@@ -50,49 +314,112 @@ impl<A: Alphabet> Dfa<A> {
         self.states().map(|state| state.num_transitions()).sum()
     }
 
-    pub fn states(&self) -> impl Iterator<Item = &State<A>> {
+    pub fn states(&self) -> impl Iterator<Item = &State<A, T>> {
         self.states.iter()
     }
 
-    pub fn transitions(&self) -> impl Iterator<Item = (&State<A>, A, &State<A>)> + '_ {
+    /// Symbols used on the transitions of this automaton, in sorted order.
+    pub fn symbols(&self) -> Vec<A> {
+        let mut symbols = self
+            .transitions()
+            .map(|(_, symbol, _)| symbol)
+            .collect::<Vec<_>>();
+        symbols.sort_unstable();
+        symbols.dedup();
+        symbols
+    }
+
+    pub fn transitions(&self) -> impl Iterator<Item = (&State<A, T>, A, &State<A, T>)> + '_ {
         self.states().flat_map(move |state| {
             state
                 .transitions()
                 .map(move |(symbol, to)| (state, symbol, self.state(to)))
         })
     }
+
+    /// Same as [`transitions`](Dfa::transitions), but with the payload of every transition.
+    #[allow(clippy::type_complexity)]
+    pub fn transitions_with_data(
+        &self,
+    ) -> impl Iterator<Item = (&State<A, T>, A, &State<A, T>, &T)> + '_ {
+        self.states().flat_map(move |state| {
+            state
+                .transitions_with_data()
+                .map(move |(symbol, to, data)| (state, symbol, self.state(to), data))
+        })
+    }
 }
 
-impl<A: Alphabet> Default for Dfa<A> {
+impl<A: Alphabet, S: Default, T> Dfa<A, S, T> {
+    /// Adds a state carrying the default payload.
+    pub fn add_state(&mut self, accepting: bool) -> StateId {
+        self.add_state_with(accepting, S::default())
+    }
+}
+
+impl<A: Alphabet, S, T> Default for Dfa<A, S, T> {
     fn default() -> Self {
-        Self::new()
+        Self {
+            states: Arena::new(),
+            data: Arena::new(),
+            conflict_policy: ConflictPolicy::default(),
+            journal: None,
+        }
     }
 }
 
-impl<A: Alphabet> Index<StateId> for Dfa<A> {
-    type Output = State<A>;
+impl<A: Alphabet, S, T> Index<StateId> for Dfa<A, S, T> {
+    type Output = State<A, T>;
 
     fn index(&self, index: StateId) -> &Self::Output {
         self.state(index)
     }
 }
 
-impl<A: Alphabet> IndexMut<StateId> for Dfa<A> {
+impl<A: Alphabet, S, T> IndexMut<StateId> for Dfa<A, S, T> {
     fn index_mut(&mut self, index: StateId) -> &mut Self::Output {
         self.state_mut(index)
     }
 }
 
-impl<A: Alphabet> Dfa<A> {
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
     pub fn next(&self, current_state: StateId, symbol: A) -> Option<StateId> {
         self.state(current_state).next(symbol)
     }
 
+    /// Runs the automaton on `word`, returning the sequence of visited states.
+    ///
+    /// For a word of length `n` that can be read completely, the result has `n + 1` states,
+    /// starting with the initial one. If some symbol has no transition, the run stops
+    /// and only the states visited so far are returned.
+    pub fn run(&self, word: impl IntoIterator<Item = A>) -> Vec<StateId> {
+        if self.states.is_empty() {
+            return Vec::new();
+        }
+        let mut current_state = StateId::new(0);
+        let mut states = vec![current_state];
+        for symbol in word {
+            if let Some(next_state) = self.next(current_state, symbol) {
+                current_state = next_state;
+                states.push(current_state);
+            } else {
+                break;
+            }
+        }
+        states
+    }
+
     pub fn accepts(&self, word: impl IntoIterator<Item = A>) -> bool {
         if self.states.is_empty() {
             return false;
         }
-        let mut current_state = 0;
+        self.accepts_from(StateId::new(0), word)
+    }
+
+    /// Checks whether reading `word` starting in `state` (instead of the initial state)
+    /// leads to an accepting state.
+    pub fn accepts_from(&self, state: StateId, word: impl IntoIterator<Item = A>) -> bool {
+        let mut current_state = state;
         for symbol in word {
             if let Some(next_state) = self.next(current_state, symbol) {
                 current_state = next_state;
@@ -106,6 +433,8 @@ impl<A: Alphabet> Dfa<A> {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use super::*;
 
     #[test]
@@ -142,4 +471,204 @@ mod tests {
         assert!(!dfa.accepts(vec![One, One, Zero, Zero, Zero]));
         assert!(!dfa.accepts(vec![One, One, Zero, Zero, One, Zero]));
     }
+
+    #[test]
+    fn test_transition_data() {
+        #[derive(Debug, Default, PartialEq)]
+        enum Action {
+            #[default]
+            Nothing,
+            Open,
+            Close,
+        }
+
+        let mut dfa = Dfa::<char, (), Action>::default();
+        let closed = dfa.add_state(true);
+        let open = dfa.add_state(false);
+        dfa.add_transition_with(closed, 'o', open, Action::Open);
+        dfa.add_transition_with(open, 'c', closed, Action::Close);
+        dfa.add_transition(open, 'x', open);
+        assert_eq!(dfa.transition_data(closed, 'o'), Some(&Action::Open));
+        assert_eq!(dfa.transition_data(open, 'x'), Some(&Action::Nothing));
+        assert_eq!(dfa.transition_data(open, 'o'), None);
+
+        let mut with_data = dfa
+            .transitions_with_data()
+            .map(|(from, symbol, to, data)| (from.id, symbol, to.id, data))
+            .collect::<Vec<_>>();
+        with_data.sort_unstable_by_key(|&(from, symbol, _, _)| (from, symbol));
+        assert_eq!(
+            with_data,
+            vec![
+                (closed, 'o', open, &Action::Open),
+                (open, 'c', closed, &Action::Close),
+                (open, 'x', open, &Action::Nothing),
+            ]
+        );
+
+        // Replacing a transition replaces its payload:
+        dfa.add_transition(open, 'c', open);
+        assert_eq!(dfa.transition_data(open, 'c'), Some(&Action::Nothing));
+        dfa.add_transition_with(open, 'x', closed, Action::Close);
+        assert_eq!(dfa.transition_data(open, 'x'), Some(&Action::Close));
+
+        let dfa = dfa
+            .map_transition_data(|from, symbol, action| format!("{}{}{:?}", from, symbol, action));
+        assert_eq!(dfa.transition_data(closed, 'o').unwrap(), "0oOpen");
+        assert_eq!(dfa.transition_data(open, 'x').unwrap(), "1xClose");
+    }
+
+    #[test]
+    fn test_conflict_policy() {
+        let mut dfa = Dfa::new().with_conflict_policy(ConflictPolicy::Error);
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(true);
+        dfa.add_transition(a, 'x', a);
+        assert_eq!(dfa.try_add_transition(a, 'x', a), Ok(()));
+        assert_eq!(
+            dfa.try_add_transition(a, 'x', b),
+            Err(TransitionConflict {
+                from: a,
+                symbol: 'x',
+                existing: a,
+                rejected: b,
+            })
+        );
+        assert_eq!(dfa.next(a, 'x'), Some(a));
+
+        dfa.set_conflict_policy(ConflictPolicy::Overwrite);
+        assert_eq!(dfa.try_add_transition(a, 'x', b), Ok(()));
+        assert_eq!(dfa.next(a, 'x'), Some(b));
+    }
+
+    #[test]
+    #[should_panic(expected = "already has a transition")]
+    fn test_conflict_policy_panics() {
+        let mut dfa = Dfa::new().with_conflict_policy(ConflictPolicy::Error);
+        let a = dfa.add_state(false);
+        dfa.add_transition(a, 'x', a);
+        let b = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+    }
+
+    #[test]
+    fn test_accepts_from() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(false);
+        let c = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+        dfa.add_transition(b, 'y', c);
+
+        assert!(dfa.accepts("xy".chars()));
+        assert!(dfa.accepts_from(b, "y".chars()));
+        assert!(dfa.accepts_from(c, "".chars()));
+        assert!(!dfa.accepts_from(b, "xy".chars()));
+    }
+
+    #[test]
+    fn test_run() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+        dfa.add_transition(b, 'y', a);
+
+        assert_eq!(dfa.run("".chars()), vec![a]);
+        assert_eq!(dfa.run("xyx".chars()), vec![a, b, a, b]);
+        // Rejected after reading "x", no transition on 'x' from b:
+        assert_eq!(dfa.run("xxy".chars()), vec![a, b]);
+        assert!(Dfa::<char>::new().run("x".chars()).is_empty());
+    }
+
+    #[test]
+    fn test_map_states() {
+        let mut dfa = Dfa::new().with_conflict_policy(ConflictPolicy::Error);
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(false);
+        let c = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+        dfa.add_transition(b, 'y', c);
+        dfa.add_transition(a, 'z', c);
+
+        let depths = dfa
+            .bfs_from(a)
+            .map(|discovery| (discovery.state, discovery.depth))
+            .collect::<HashMap<_, _>>();
+        let depths = dfa.map_states(|id, ()| depths[&id]);
+        assert_eq!(depths.state_data(a), &0);
+        assert_eq!(depths.state_data(b), &1);
+        assert_eq!(depths.state_data(c), &1);
+        assert_eq!(depths.num_transitions(), dfa.num_transitions());
+        assert!(depths.accepts("xy".chars()));
+        assert_eq!(depths.conflict_policy(), ConflictPolicy::Error);
+
+        let named = depths.map_states(|id, depth| format!("{}@{}", id, depth));
+        assert_eq!(named.state_data(c), "2@1");
+        assert_eq!(depths.state_data(c), &1);
+
+        // Transition payloads are kept:
+        let mut dfa = Dfa::<char, &str, u8>::default();
+        let a = dfa.add_state_with(false, "a");
+        dfa.add_transition_with(a, 'x', a, 7);
+        let dfa = dfa.map_states(|id, name| format!("{}{}", name, id));
+        assert_eq!(dfa.state_data(a), "a0");
+        assert_eq!(dfa.transition_data(a, 'x'), Some(&7));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut dfa = Dfa::<char, &str, u8>::default().with_journal();
+        let a = dfa.add_state_with(false, "a");
+        let b = dfa.add_state_with(false, "b");
+        let c = dfa.add_state_with(true, "c");
+        dfa.add_transition_with(a, 'x', b, 1);
+        dfa.add_transition_with(a, 'y', c, 2);
+        dfa.add_transition_with(b, 'y', c, 3);
+        dfa.add_transition(c, 'x', a);
+
+        assert_eq!(dfa.remove_transition(a, 'y'), Some(c));
+        assert_eq!(dfa.remove_transition(a, 'y'), None);
+        assert_eq!(dfa.transition_data(a, 'y'), None);
+        assert!(dfa.accepts("xy".chars()));
+        assert!(!dfa.accepts("y".chars()));
+
+        let remap = dfa.remove_state(b);
+        assert_eq!(remap, vec![Some(a), None, Some(StateId::new(1))]);
+        let c = StateId::new(1);
+        assert_eq!(dfa.num_states(), 2);
+        assert_eq!(dfa.state(c).id, c);
+        assert_eq!(dfa.state_data(c), &"c");
+        assert!(dfa.accepting(c));
+        assert_eq!(dfa.num_transitions(), 1);
+        assert_eq!(dfa.next(c, 'x'), Some(a));
+        // The payload of the edge to the removed state is gone with it:
+        assert_eq!(dfa.transition_data(a, 'x'), None);
+        assert_eq!(dfa.transition_data(c, 'x'), Some(&0));
+
+        // Re-adding a removed edge does not resurrect its payload:
+        dfa.add_transition_with(a, 'x', c, 4);
+        assert_eq!(dfa.state_mut(a).remove_transition('x'), Some(c));
+        dfa.add_transition(a, 'x', c);
+        assert_eq!(dfa.transition_data(a, 'x'), Some(&0));
+
+        // Removals are journaled:
+        let replayed = dfa.journal().unwrap().replay();
+        assert_eq!(replayed.num_states(), 2);
+        assert_eq!(replayed.next(c, 'x'), Some(a));
+        assert!(!replayed.accepts("xy".chars()));
+    }
+
+    #[test]
+    fn test_remove_missing_state() {
+        let mut dfa = Dfa::<char>::new().with_journal();
+        dfa.add_state(true);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            dfa.remove_state(StateId::new(1));
+        }));
+        assert!(result.is_err());
+        // Nothing is journaled for the failed removal:
+        assert_eq!(dfa.journal().unwrap().len(), 1);
+        assert_eq!(dfa.journal().unwrap().replay().num_states(), 1);
+    }
 }
@@ -0,0 +1,97 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// CSP-style parallel composition with `other`.
+    ///
+    /// Symbols used by both automata are *shared*: they can only be taken when both components
+    /// take them simultaneously. All other symbols belong to a single component and interleave,
+    /// moving only that component. A composed state is accepting when both components are.
+    ///
+    /// The alphabet of each component is the set of symbols on its transitions.
+    /// Only the reachable part of the composition is built.
+    pub fn parallel(&self, other: &Dfa<A>) -> Dfa<A> {
+        let mut dfa = Dfa::new();
+        if self.states.is_empty() || other.states.is_empty() {
+            return dfa;
+        }
+
+        let left_symbols = self.symbols();
+        let right_symbols = other.symbols().into_iter().collect::<HashSet<_>>();
+        let mut symbols = left_symbols.clone();
+        symbols.extend(right_symbols.iter().copied());
+        symbols.sort_unstable();
+        symbols.dedup();
+        let left_symbols = left_symbols.into_iter().collect::<HashSet<_>>();
+
+        let mut pair2state = HashMap::new();
+        let mut queue = VecDeque::new();
+        let initial = (StateId::new(0), StateId::new(0));
+        pair2state.insert(
+            initial,
+            dfa.add_state(self.accepting(initial.0) && other.accepting(initial.1)),
+        );
+        queue.push_back(initial);
+
+        while let Some((left, right)) = queue.pop_front() {
+            let from = pair2state[&(left, right)];
+            for &symbol in &symbols {
+                let next = match (
+                    left_symbols.contains(&symbol),
+                    right_symbols.contains(&symbol),
+                ) {
+                    (true, true) => self.next(left, symbol).zip(other.next(right, symbol)),
+                    (true, false) => self.next(left, symbol).map(|l| (l, right)),
+                    (false, true) => other.next(right, symbol).map(|r| (left, r)),
+                    (false, false) => unreachable!(),
+                };
+                if let Some(pair) = next {
+                    let to = *pair2state.entry(pair).or_insert_with(|| {
+                        queue.push_back(pair);
+                        dfa.add_state(self.accepting(pair.0) && other.accepting(pair.1))
+                    });
+                    dfa.add_transition(from, symbol, to);
+                }
+            }
+        }
+
+        dfa
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parallel() {
+        // Producer: 'p'roduce, then 's'end, repeatedly.
+        let mut producer = Dfa::new();
+        let p0 = producer.add_state(true);
+        let p1 = producer.add_state(false);
+        producer.add_transition(p0, 'p', p1);
+        producer.add_transition(p1, 's', p0);
+
+        // Consumer: 's'end (receive), then 'c'onsume, repeatedly.
+        let mut consumer = Dfa::new();
+        let c0 = consumer.add_state(true);
+        let c1 = consumer.add_state(false);
+        consumer.add_transition(c0, 's', c1);
+        consumer.add_transition(c1, 'c', c0);
+
+        let system = producer.parallel(&consumer);
+        assert_eq!(system.num_states(), 4);
+        assert!(system.accepts("".chars()));
+        assert!(system.accepts("psc".chars()));
+        // Producing the next item interleaves with consuming the previous one:
+        assert!(system.accepts("pspcsc".chars()));
+        assert!(system.accepts("pscpsc".chars()));
+        // Synchronization on 's' is required:
+        assert!(!system.accepts("ps".chars()));
+        assert!(!system.accepts("pp".chars()));
+        assert!(!system.accepts("c".chars()));
+    }
+}
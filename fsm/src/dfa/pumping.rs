@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::Dfa;
+
+/// Decomposition `w = x y z` of an accepted word, see [`Dfa::pumping_decomposition`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decomposition<A: Alphabet> {
+    pub x: Vec<A>,
+    /// Non-empty part that can be repeated any number of times.
+    pub y: Vec<A>,
+    pub z: Vec<A>,
+}
+
+impl<A: Alphabet> Decomposition<A> {
+    /// The word `x yⁱ z`.
+    pub fn pump(&self, i: usize) -> Vec<A> {
+        let mut word = self.x.clone();
+        for _ in 0..i {
+            word.extend(&self.y);
+        }
+        word.extend(&self.z);
+        word
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Splits an accepted `word` into `x y z` with non-empty `y`, such that `x yⁱ z`
+    /// is accepted for all `i ≥ 0`.
+    ///
+    /// The decomposition is based on the first repeated state of the run, so `|x y|`
+    /// is at most the number of states, as in the pumping lemma. Returns `None` if `word`
+    /// is rejected or its run visits no state twice (which is always the case
+    /// for words shorter than the number of states).
+    pub fn pumping_decomposition(&self, word: &[A]) -> Option<Decomposition<A>> {
+        if !self.accepts(word.iter().copied()) {
+            return None;
+        }
+        let mut first_visit = HashMap::new();
+        for (j, state) in self.run(word.iter().copied()).into_iter().enumerate() {
+            if let Some(&i) = first_visit.get(&state) {
+                return Some(Decomposition {
+                    x: word[..i].to_vec(),
+                    y: word[i..j].to_vec(),
+                    z: word[j..].to_vec(),
+                });
+            }
+            first_visit.insert(state, j);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Words over {a, b} ending with "ab".
+    fn ends_with_ab() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', q0);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, 'b', q2);
+        dfa.add_transition(q2, 'a', q1);
+        dfa.add_transition(q2, 'b', q0);
+        dfa
+    }
+
+    #[test]
+    fn test_pumping_decomposition() {
+        let dfa = ends_with_ab();
+        let word = "bbaab".chars().collect::<Vec<_>>();
+        let decomposition = dfa.pumping_decomposition(&word).unwrap();
+        assert_eq!(decomposition.x, vec![]);
+        assert_eq!(decomposition.y, vec!['b']);
+        assert_eq!(decomposition.z, "baab".chars().collect::<Vec<_>>());
+        assert_eq!(decomposition.pump(1), word);
+        for i in 0..5 {
+            assert!(dfa.accepts(decomposition.pump(i)));
+        }
+    }
+
+    #[test]
+    fn test_pumping_decomposition_none() {
+        let dfa = ends_with_ab();
+        // Rejected:
+        assert_eq!(dfa.pumping_decomposition(&['a', 'b', 'a']), None);
+        // Too short to repeat a state:
+        assert_eq!(dfa.pumping_decomposition(&['a', 'b']), None);
+        let word = ['a', 'a', 'b'];
+        let decomposition = dfa.pumping_decomposition(&word).unwrap();
+        assert_eq!(decomposition.y, vec!['a']);
+        assert!(dfa.accepts(decomposition.pump(0)));
+    }
+}
@@ -0,0 +1,408 @@
+use std::fmt::{self, Display};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::Dfa;
+
+/// Regular expression over the symbols of an automaton, see [`Dfa::to_regex`].
+///
+/// Expressions are kept in a normal form: concatenations and alternations are flattened,
+/// `ε` and `∅` only occur on their own (or `ε` as a branch of an alternation),
+/// and the branches of an alternation are sorted and distinct.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Expr<A: Alphabet> {
+    /// Matches nothing (`∅`).
+    Nothing,
+    /// Matches only the empty word (`ε`).
+    Empty,
+    Symbol(A),
+    Concat(Vec<Expr<A>>),
+    Alternation(Vec<Expr<A>>),
+    Star(Box<Expr<A>>),
+}
+
+impl<A: Alphabet> Expr<A> {
+    pub fn concat(items: impl IntoIterator<Item = Expr<A>>) -> Expr<A> {
+        let mut flat = Vec::new();
+        for item in items {
+            match item {
+                Expr::Nothing => return Expr::Nothing,
+                Expr::Empty => {}
+                Expr::Concat(inner) => flat.extend(inner),
+                item => flat.push(item),
+            }
+        }
+        match flat.len() {
+            0 => Expr::Empty,
+            1 => flat.pop().unwrap(),
+            _ => Expr::Concat(flat),
+        }
+    }
+
+    pub fn alternation(branches: impl IntoIterator<Item = Expr<A>>) -> Expr<A> {
+        let mut flat = Vec::new();
+        for branch in branches {
+            match branch {
+                Expr::Nothing => {}
+                Expr::Alternation(inner) => flat.extend(inner),
+                branch => flat.push(branch),
+            }
+        }
+        flat.sort_unstable();
+        flat.dedup();
+        // `ε | r*` is `r*`:
+        if flat.iter().any(|branch| matches!(branch, Expr::Star(_))) {
+            flat.retain(|branch| *branch != Expr::Empty);
+        }
+        match flat.len() {
+            0 => Expr::Nothing,
+            1 => flat.pop().unwrap(),
+            _ => Expr::Alternation(flat),
+        }
+    }
+
+    pub fn star(inner: Expr<A>) -> Expr<A> {
+        match inner {
+            Expr::Nothing | Expr::Empty => Expr::Empty,
+            Expr::Star(_) => inner,
+            // `(ε | r)*` is `r*`:
+            Expr::Alternation(branches) if branches.contains(&Expr::Empty) => {
+                let rest = branches.into_iter().filter(|branch| *branch != Expr::Empty);
+                Expr::star(Expr::alternation(rest))
+            }
+            inner => Expr::Star(Box::new(inner)),
+        }
+    }
+
+    /// Whether the expression matches the empty word.
+    pub fn nullable(&self) -> bool {
+        match self {
+            Expr::Nothing | Expr::Symbol(_) => false,
+            Expr::Empty | Expr::Star(_) => true,
+            Expr::Concat(items) => items.iter().all(Expr::nullable),
+            Expr::Alternation(branches) => branches.iter().any(Expr::nullable),
+        }
+    }
+
+    /// Brzozowski derivative: the expression matching `w` wherever this one matches `symbol w`.
+    pub fn derivative(&self, symbol: A) -> Expr<A> {
+        match self {
+            Expr::Nothing | Expr::Empty => Expr::Nothing,
+            Expr::Symbol(s) if *s == symbol => Expr::Empty,
+            Expr::Symbol(_) => Expr::Nothing,
+            Expr::Concat(items) => {
+                let mut branches = Vec::new();
+                for (i, item) in items.iter().enumerate() {
+                    let rest = items[i + 1..].iter().cloned();
+                    branches.push(Expr::concat(
+                        std::iter::once(item.derivative(symbol)).chain(rest),
+                    ));
+                    if !item.nullable() {
+                        break;
+                    }
+                }
+                Expr::alternation(branches)
+            }
+            Expr::Alternation(branches) => {
+                Expr::alternation(branches.iter().map(|branch| branch.derivative(symbol)))
+            }
+            Expr::Star(inner) => Expr::concat([inner.derivative(symbol), self.clone()]),
+        }
+    }
+
+    /// Checks whether the expression matches `word`.
+    pub fn matches(&self, word: impl IntoIterator<Item = A>) -> bool {
+        let mut expr = self.clone();
+        for symbol in word {
+            expr = expr.derivative(symbol);
+            if expr == Expr::Nothing {
+                return false;
+            }
+        }
+        expr.nullable()
+    }
+}
+
+impl<A: Alphabet + Display> Expr<A> {
+    /// Writes the expression, parenthesized for the context:
+    /// `0` for a whole expression or a branch, `1` for an item of a concatenation,
+    /// `2` for the operand of `*`, `+` or `?`.
+    fn write(&self, f: &mut fmt::Formatter<'_>, context: u8) -> fmt::Result {
+        match self {
+            Expr::Nothing => write!(f, "∅"),
+            Expr::Empty => write!(f, "ε"),
+            Expr::Symbol(symbol) => {
+                let symbol = symbol.to_string();
+                if symbol.len() == 1 && r"\.+*?()|[]{}^$".contains(&symbol) {
+                    write!(f, "\\")?;
+                }
+                write!(f, "{}", symbol)
+            }
+            Expr::Concat(items) => {
+                if context >= 2 {
+                    write!(f, "(")?;
+                }
+                let mut i = 0;
+                while i < items.len() {
+                    // `r r*` is `r+`:
+                    if let Some(Expr::Star(next)) = items.get(i + 1) {
+                        if **next == items[i] {
+                            items[i].write(f, 2)?;
+                            write!(f, "+")?;
+                            i += 2;
+                            continue;
+                        }
+                    }
+                    items[i].write(f, 1)?;
+                    i += 1;
+                }
+                if context >= 2 {
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            Expr::Alternation(branches) if branches.contains(&Expr::Empty) => {
+                // `ε | r` is `r?`:
+                let rest = Expr::alternation(
+                    branches
+                        .iter()
+                        .filter(|branch| **branch != Expr::Empty)
+                        .cloned(),
+                );
+                if context >= 2 {
+                    write!(f, "(")?;
+                }
+                rest.write(f, 2)?;
+                write!(f, "?")?;
+                if context >= 2 {
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            Expr::Alternation(branches) => {
+                if context >= 1 {
+                    write!(f, "(")?;
+                }
+                for (i, branch) in branches.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "|")?;
+                    }
+                    branch.write(f, 0)?;
+                }
+                if context >= 1 {
+                    write!(f, ")")?;
+                }
+                Ok(())
+            }
+            Expr::Star(inner) => {
+                inner.write(f, 2)?;
+                write!(f, "*")
+            }
+        }
+    }
+}
+
+/// Writes the expression in the usual regex syntax, with `(...)` for grouping,
+/// `|`, `*`, `+` and `?`. Symbols are written with their [`Display`], escaped with `\`
+/// if they are a single regex metacharacter, so for `char` symbols the result is a valid pattern,
+/// except for `∅` and `ε` which are only written for the whole expression.
+impl<A: Alphabet + Display> Display for Expr<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+impl<A: Alphabet> Dfa<A> {
+    /// Converts the automaton into an equivalent regular expression by state elimination.
+    ///
+    /// The automaton is minimized first, then its states are eliminated one by one
+    /// (those with the fewest paths through them first), each time replacing the paths
+    /// through the eliminated state with expressions on the edges between the remaining ones.
+    /// The result can still be much larger than the automaton, as it may grow exponentially
+    /// with the number of states.
+    pub fn to_regex(&self) -> Expr<A> {
+        let dfa = self.minimize();
+        let n = dfa.num_states();
+        if n == 0 {
+            return Expr::Nothing;
+        }
+        // Generalized automaton with a new initial state `n` and a new final state `n + 1`:
+        let (initial, last) = (n, n + 1);
+        let mut edges = vec![vec![Expr::Nothing; n + 2]; n + 2];
+        edges[initial][0] = Expr::Empty;
+        for state in dfa.states() {
+            if state.accepting {
+                edges[state.id.index()][last] = Expr::Empty;
+            }
+        }
+        for (from, symbol, to) in dfa.transitions() {
+            let (from, to) = (from.id.index(), to.id.index());
+            let edge = std::mem::replace(&mut edges[from][to], Expr::Nothing);
+            edges[from][to] = Expr::alternation([edge, Expr::Symbol(symbol)]);
+        }
+
+        let mut remaining = (0..n).collect::<Vec<_>>();
+        while !remaining.is_empty() {
+            let paths_through = |k: usize| {
+                let incoming = (0..n + 2)
+                    .filter(|&i| i != k && edges[i][k] != Expr::Nothing)
+                    .count();
+                let outgoing = (0..n + 2)
+                    .filter(|&j| j != k && edges[k][j] != Expr::Nothing)
+                    .count();
+                incoming * outgoing
+            };
+            let (index, &k) = remaining
+                .iter()
+                .enumerate()
+                .min_by_key(|&(_, &k)| paths_through(k))
+                .unwrap();
+            remaining.swap_remove(index);
+
+            let looping = Expr::star(edges[k][k].clone());
+            let incoming = (0..n + 2)
+                .filter(|&i| i != k && edges[i][k] != Expr::Nothing)
+                .collect::<Vec<_>>();
+            let outgoing = (0..n + 2)
+                .filter(|&j| j != k && edges[k][j] != Expr::Nothing)
+                .collect::<Vec<_>>();
+            for &i in &incoming {
+                for &j in &outgoing {
+                    let path =
+                        Expr::concat([edges[i][k].clone(), looping.clone(), edges[k][j].clone()]);
+                    let edge = std::mem::replace(&mut edges[i][j], Expr::Nothing);
+                    edges[i][j] = Expr::alternation([edge, path]);
+                }
+            }
+            for row in edges.iter_mut() {
+                row[k] = Expr::Nothing;
+            }
+            edges[k].fill(Expr::Nothing);
+        }
+        std::mem::replace(&mut edges[initial][last], Expr::Nothing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Words over {a, b} ending with "ab".
+    fn ends_with_ab() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', q0);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, 'b', q2);
+        dfa.add_transition(q2, 'a', q1);
+        dfa.add_transition(q2, 'b', q0);
+        dfa
+    }
+
+    /// Words over {a, b} with an even number of a's.
+    fn even_a() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let even = dfa.add_state(true);
+        let odd = dfa.add_state(false);
+        dfa.add_transition(even, 'a', odd);
+        dfa.add_transition(even, 'b', even);
+        dfa.add_transition(odd, 'a', even);
+        dfa.add_transition(odd, 'b', odd);
+        dfa
+    }
+
+    fn all_words(symbols: &[char], max_length: usize) -> Vec<Vec<char>> {
+        let mut words = vec![vec![]];
+        let mut last = vec![vec![]];
+        for _ in 0..max_length {
+            last = last
+                .iter()
+                .flat_map(|word: &Vec<char>| {
+                    symbols.iter().map(move |&c| {
+                        let mut word = word.clone();
+                        word.push(c);
+                        word
+                    })
+                })
+                .collect();
+            words.extend(last.iter().cloned());
+        }
+        words
+    }
+
+    #[test]
+    fn test_to_regex() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+        dfa.add_transition(b, 'y', b);
+        assert_eq!(dfa.to_regex().to_string(), "xy*");
+
+        dfa.add_transition(b, 'x', b);
+        assert_eq!(dfa.to_regex().to_string(), "x(x|y)*");
+
+        dfa.add_transition(a, '.', a);
+        assert_eq!(dfa.to_regex().to_string(), r"\.*x(x|y)*");
+
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(true);
+        let b = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+        assert_eq!(dfa.to_regex().to_string(), "x?");
+
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(true);
+        dfa.add_transition(a, 'x', a);
+        dfa.add_transition(a, 'y', a);
+        dfa.add_state(false);
+        assert_eq!(dfa.to_regex().to_string(), "(x|y)*");
+
+        let mut dfa = Dfa::<char>::new();
+        assert_eq!(dfa.to_regex(), Expr::Nothing);
+        let q0 = dfa.add_state(false);
+        assert_eq!(dfa.to_regex(), Expr::Nothing);
+        dfa.state_mut(q0).accepting = true;
+        assert_eq!(dfa.to_regex(), Expr::Empty);
+    }
+
+    #[test]
+    fn test_to_regex_language() {
+        for dfa in [ends_with_ab(), even_a(), ends_with_ab().complement()] {
+            let regex = dfa.to_regex();
+            for word in all_words(&['a', 'b'], 7) {
+                assert_eq!(
+                    regex.matches(word.iter().copied()),
+                    dfa.accepts(word.iter().copied()),
+                    "{} on {:?}",
+                    regex,
+                    word
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_expr() {
+        let x = || Expr::Symbol('x');
+        let y = || Expr::Symbol('y');
+        assert_eq!(
+            Expr::concat([x(), Expr::Empty, Expr::Nothing]),
+            Expr::Nothing
+        );
+        assert_eq!(
+            Expr::alternation([y(), x(), y(), Expr::Nothing]),
+            Expr::alternation([x(), y()])
+        );
+        assert_eq!(
+            Expr::star(Expr::alternation([Expr::Empty, Expr::star(x())])),
+            Expr::star(x())
+        );
+        let expr = Expr::concat([x(), Expr::star(x()), Expr::alternation([Expr::Empty, y()])]);
+        assert_eq!(expr.to_string(), "x+y?");
+        assert!(expr.matches("xxy".chars()));
+        assert!(!expr.matches("y".chars()));
+    }
+}
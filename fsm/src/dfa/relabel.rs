@@ -0,0 +1,108 @@
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// Two symbols that would be merged by [`Dfa::relabel_symbols`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SymbolCollision<A: Alphabet> {
+    /// The two old symbols, in sorted order.
+    pub symbols: (A, A),
+    /// The new symbol both of them map to.
+    pub target: A,
+}
+
+impl<A: Alphabet> Display for SymbolCollision<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "symbols {:?} and {:?} would both be relabeled to {:?}",
+            self.symbols.0, self.symbols.1, self.target
+        )
+    }
+}
+
+impl<A: Alphabet> Error for SymbolCollision<A> {}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Renames symbols in place according to `map`, keeping symbols missing from it.
+    ///
+    /// Fails without changing anything if two symbols of the automaton would end up
+    /// with the same name (including a renamed symbol and a kept one), since merging them
+    /// could make the automaton nondeterministic. Transition payloads move with their transitions.
+    pub fn relabel_symbols(&mut self, map: &HashMap<A, A>) -> Result<(), SymbolCollision<A>> {
+        let rename = |symbol: A| map.get(&symbol).copied().unwrap_or(symbol);
+        let mut sources: BTreeMap<A, A> = BTreeMap::new();
+        for symbol in self.symbols() {
+            let target = rename(symbol);
+            if let Some(&previous) = sources.get(&target) {
+                return Err(SymbolCollision {
+                    symbols: (previous, symbol),
+                    target,
+                });
+            }
+            sources.insert(target, symbol);
+        }
+
+        for id in 0..self.num_states() {
+            let state = self.state_mut(StateId::new(id));
+            let transitions = state.take_transitions().collect::<Vec<_>>();
+            for (symbol, to, data) in transitions {
+                state.add_transition_with(rename(symbol), to, data);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relabel_symbols() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, "open", q1);
+        dfa.add_transition(q1, "close", q0);
+        dfa.add_transition(q1, "ping", q1);
+
+        // Swapping names is fine:
+        let map = HashMap::from([("open", "close"), ("close", "open"), ("ping", "heartbeat")]);
+        assert_eq!(dfa.relabel_symbols(&map), Ok(()));
+        assert_eq!(dfa.symbols(), ["close", "heartbeat", "open"]);
+        assert!(dfa.accepts(["close", "heartbeat", "open", "close"]));
+        assert!(!dfa.accepts(["open"]));
+
+        // Merging a renamed symbol into a kept one is not:
+        let map = HashMap::from([("heartbeat", "open")]);
+        assert_eq!(
+            dfa.relabel_symbols(&map),
+            Err(SymbolCollision {
+                symbols: ("heartbeat", "open"),
+                target: "open",
+            })
+        );
+        assert_eq!(dfa.symbols(), ["close", "heartbeat", "open"]);
+    }
+
+    #[test]
+    fn test_relabel_symbols_keeps_payloads() {
+        let mut dfa = Dfa::<&str, (), u32>::default();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition_with(q0, "open", q1, 1);
+        dfa.add_transition_with(q1, "close", q0, 2);
+
+        let map = HashMap::from([("open", "close"), ("close", "open")]);
+        assert_eq!(dfa.relabel_symbols(&map), Ok(()));
+        assert_eq!(dfa.transition_data(q0, "close"), Some(&1));
+        assert_eq!(dfa.transition_data(q1, "open"), Some(&2));
+        assert_eq!(dfa.transition_data(q0, "open"), None);
+        assert_eq!(dfa.transition_data(q1, "close"), None);
+    }
+}
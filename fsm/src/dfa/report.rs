@@ -0,0 +1,111 @@
+use crate::alphabet::Alphabet;
+use crate::dfa::Dfa;
+
+/// Summary of the main properties of a [`Dfa`], see [`Dfa::report`].
+///
+/// Meant to be serialized, e.g. for dashboards or for assertions in CI jobs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Report<A: Alphabet> {
+    pub num_states: usize,
+    pub num_transitions: usize,
+    /// Symbols used on transitions, in sorted order.
+    pub alphabet: Vec<A>,
+    /// Whether every state has a transition on every symbol of the alphabet.
+    pub complete: bool,
+    /// Whether no two useful states are equivalent, i.e. whether the automaton
+    /// without its useless states is minimal.
+    pub minimal: bool,
+    /// Number of states reachable from the initial state.
+    pub num_accessible_states: usize,
+    /// Number of states from which an accepting state is reachable.
+    pub num_coaccessible_states: usize,
+    /// Number of states that are not both accessible and co-accessible.
+    pub num_useless_states: usize,
+    /// Whether no word is accepted.
+    pub empty: bool,
+    /// Whether finitely many words are accepted.
+    pub finite: bool,
+}
+
+impl<A: Alphabet> Dfa<A> {
+    /// Computes a [`Report`] of the automaton.
+    pub fn report(&self) -> Report<A> {
+        let alphabet = self.symbols();
+        let complete = self
+            .states()
+            .all(|state| state.num_transitions() == alphabet.len());
+        let accessible = self.accessible_states();
+        let coaccessible = self.coaccessible_states();
+        let num_useful_states = accessible.intersection(&coaccessible).count();
+        Report {
+            num_states: self.num_states(),
+            num_transitions: self.num_transitions(),
+            complete,
+            minimal: self.minimize().num_states() == num_useful_states,
+            num_accessible_states: accessible.len(),
+            num_coaccessible_states: coaccessible.len(),
+            num_useless_states: self.num_states() - num_useful_states,
+            empty: self.is_empty(),
+            finite: self.is_finite(),
+            alphabet,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        // (ab)*, with a redundant copy of the initial state and a trap state:
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        let trap = dfa.add_state(false);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', trap);
+        dfa.add_transition(q1, 'b', q2);
+        dfa.add_transition(q1, 'a', trap);
+        dfa.add_transition(q2, 'a', q1);
+        dfa.add_transition(q2, 'b', trap);
+        dfa.add_transition(trap, 'a', trap);
+        dfa.add_transition(trap, 'b', trap);
+
+        let report = dfa.report();
+        assert_eq!(
+            report,
+            Report {
+                num_states: 4,
+                num_transitions: 8,
+                alphabet: vec!['a', 'b'],
+                complete: true,
+                minimal: false,
+                num_accessible_states: 4,
+                num_coaccessible_states: 3,
+                num_useless_states: 1,
+                empty: false,
+                finite: false,
+            }
+        );
+        assert!(dfa.minimize().report().minimal);
+
+        let report = Dfa::<char>::new().report();
+        assert!(report.empty && report.finite && report.minimal && report.complete);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_report_json() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, 'x', q1);
+        let json = serde_json::to_value(dfa.report()).unwrap();
+        assert_eq!(json["num_states"], 2);
+        assert_eq!(json["alphabet"], serde_json::json!(["x"]));
+        assert_eq!(json["finite"], true);
+    }
+}
@@ -0,0 +1,82 @@
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// Runs a DFA one symbol at a time, holding the current state between steps,
+/// see [`Dfa::runner`].
+///
+/// Once a symbol without a transition is read, the run is stuck (there is no current state)
+/// until [`reset`](DfaRunner::reset).
+#[derive(Debug)]
+pub struct DfaRunner<'a, A: Alphabet, S = (), T = ()> {
+    dfa: &'a Dfa<A, S, T>,
+    current: Option<StateId>,
+}
+
+impl<'a, A: Alphabet, S, T> DfaRunner<'a, A, S, T> {
+    /// Starts in the initial state, if there is one.
+    pub fn new(dfa: &'a Dfa<A, S, T>) -> Self {
+        let mut runner = Self { dfa, current: None };
+        runner.reset();
+        runner
+    }
+
+    /// Goes back to the initial state.
+    pub fn reset(&mut self) {
+        self.current = (self.dfa.num_states() > 0).then(|| StateId::new(0));
+    }
+
+    /// Reads `symbol`, returning the new current state.
+    pub fn step(&mut self, symbol: A) -> Option<StateId> {
+        self.current = self.current.and_then(|state| self.dfa.next(state, symbol));
+        self.current
+    }
+
+    pub fn current_state(&self) -> Option<StateId> {
+        self.current
+    }
+
+    /// Whether the symbols read so far form an accepted word.
+    pub fn is_accepting(&self) -> bool {
+        self.current.is_some_and(|state| self.dfa.accepting(state))
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Creates a [`DfaRunner`] in the initial state.
+    pub fn runner(&self) -> DfaRunner<'_, A, S, T> {
+        DfaRunner::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dfa_runner() {
+        // A turnstile: 'c'oin unlocks, 'p'ush locks, pushing a locked one is an error:
+        let mut dfa = Dfa::new();
+        let locked = dfa.add_state(true);
+        let unlocked = dfa.add_state(false);
+        dfa.add_transition(locked, 'c', unlocked);
+        dfa.add_transition(unlocked, 'c', unlocked);
+        dfa.add_transition(unlocked, 'p', locked);
+
+        let mut runner = dfa.runner();
+        assert_eq!(runner.current_state(), Some(locked));
+        assert!(runner.is_accepting());
+        assert_eq!(runner.step('c'), Some(unlocked));
+        assert!(!runner.is_accepting());
+        assert_eq!(runner.step('p'), Some(locked));
+        assert!(runner.is_accepting());
+        assert_eq!(runner.step('p'), None);
+        assert!(!runner.is_accepting());
+        // Stuck until reset:
+        assert_eq!(runner.step('c'), None);
+        runner.reset();
+        assert_eq!(runner.step('c'), Some(unlocked));
+
+        assert_eq!(Dfa::<char>::new().runner().current_state(), None);
+    }
+}
@@ -0,0 +1,115 @@
+use rand::Rng;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::Dfa;
+
+/// Outcome of [`Dfa::probably_equivalent`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EquivalenceEstimate<A: Alphabet> {
+    /// Number of sampled words.
+    pub samples: usize,
+    /// First sampled word on which the automata disagree, if any.
+    pub counterexample: Option<Vec<A>>,
+}
+
+impl<A: Alphabet> EquivalenceEstimate<A> {
+    /// Whether no disagreement was found.
+    pub fn is_equivalent(&self) -> bool {
+        self.counterexample.is_none()
+    }
+
+    /// Upper bound on the fraction of disagreeing words (w.r.t. the sampling distribution)
+    /// that holds with the given `confidence` (e.g. `0.95`), assuming no disagreement was found.
+    ///
+    /// If the disagreement rate were `p`, all samples would agree with probability `(1 - p)ⁿ ≤ e^{-pn}`,
+    /// so `p ≤ ln(1 / (1 - confidence)) / n`. Returns `1.0` when a counterexample was found.
+    pub fn error_bound(&self, confidence: f64) -> f64 {
+        if !self.is_equivalent() || self.samples == 0 {
+            return 1.0;
+        }
+        (-(1.0 - confidence).ln() / self.samples as f64).min(1.0)
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Compares the languages on `samples` random words, without building a product automaton.
+    ///
+    /// Every sample first picks a length uniformly from `0..=n₁ + n₂` (with `nᵢ` the numbers of states;
+    /// a shortest distinguishing word is never longer), and then a uniformly random word
+    /// of that length over the joint alphabet. Sampling stops at the first disagreement.
+    pub fn probably_equivalent<R: Rng + ?Sized>(
+        &self,
+        other: &Dfa<A>,
+        samples: usize,
+        rng: &mut R,
+    ) -> EquivalenceEstimate<A> {
+        let mut symbols = self.symbols();
+        symbols.extend(other.symbols());
+        symbols.sort_unstable();
+        symbols.dedup();
+        let max_length = self.num_states() + other.num_states();
+
+        for i in 0..samples {
+            let length = rng.gen_range(0..=max_length);
+            let word = if symbols.is_empty() {
+                Vec::new()
+            } else {
+                (0..length)
+                    .map(|_| symbols[rng.gen_range(0..symbols.len())])
+                    .collect::<Vec<_>>()
+            };
+            if self.accepts(word.iter().copied()) != other.accepts(word.iter().copied()) {
+                return EquivalenceEstimate {
+                    samples: i + 1,
+                    counterexample: Some(word),
+                };
+            }
+        }
+        EquivalenceEstimate {
+            samples,
+            counterexample: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+
+    /// Words over {a, b} whose number of `a`s is divisible by `n`.
+    fn count_a(n: usize) -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let states = (0..n).map(|i| dfa.add_state(i == 0)).collect::<Vec<_>>();
+        for i in 0..n {
+            dfa.add_transition(states[i], 'a', states[(i + 1) % n]);
+            dfa.add_transition(states[i], 'b', states[i]);
+        }
+        dfa
+    }
+
+    #[test]
+    fn test_probably_equivalent() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let dfa = count_a(3);
+        // Same language, different automaton:
+        let estimate = dfa.probably_equivalent(&count_a(6).union(&dfa), 1000, &mut rng);
+        assert!(estimate.is_equivalent());
+        assert_eq!(estimate.samples, 1000);
+        let bound = estimate.error_bound(0.95);
+        assert!(bound > 0.0 && bound < 0.01, "bound = {bound}");
+    }
+
+    #[test]
+    fn test_probably_equivalent_counterexample() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let (left, right) = (count_a(2), count_a(3));
+        let estimate = left.probably_equivalent(&right, 1000, &mut rng);
+        let word = estimate.counterexample.clone().unwrap();
+        assert_ne!(left.accepts(word.clone()), right.accepts(word));
+        assert!(estimate.samples <= 1000);
+        assert_eq!(estimate.error_bound(0.95), 1.0);
+    }
+}
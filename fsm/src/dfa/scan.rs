@@ -0,0 +1,100 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// Iterator over the matches found by [`Dfa::scan`].
+pub struct Scan<'a, A: Alphabet, I> {
+    dfa: &'a Dfa<A>,
+    symbols: I,
+    position: usize,
+    /// Current state of every run still alive, with the earliest position it started at.
+    runs: BTreeMap<StateId, usize>,
+}
+
+impl<A: Alphabet, I: Iterator<Item = A>> Iterator for Scan<'_, A, I> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.dfa.num_states() == 0 {
+            return None;
+        }
+        for symbol in self.symbols.by_ref() {
+            self.runs.entry(StateId::new(0)).or_insert(self.position);
+            let mut runs = BTreeMap::new();
+            for (&state, &start) in &self.runs {
+                if let Some(next) = self.dfa.next(state, symbol) {
+                    runs.entry(next)
+                        .and_modify(|earliest: &mut usize| *earliest = (*earliest).min(start))
+                        .or_insert(start);
+                }
+            }
+            self.runs = runs;
+            self.position += 1;
+            let start = self
+                .runs
+                .iter()
+                .filter(|&(&state, _)| self.dfa.accepting(state))
+                .map(|(_, &start)| start)
+                .min();
+            if let Some(start) = start {
+                return Some(start..self.position);
+            }
+        }
+        None
+    }
+}
+
+impl<A: Alphabet> Dfa<A> {
+    /// Scans a stream of symbols for non-empty accepted subwords, e.g. motifs in a sequence.
+    ///
+    /// Yields one range per position where some match ends, with the earliest start
+    /// of a match ending there. The input is read lazily, one symbol per step, and the
+    /// memory used is bounded by the number of states.
+    pub fn scan<I: IntoIterator<Item = A>>(&self, symbols: I) -> Scan<'_, A, I::IntoIter> {
+        Scan {
+            dfa: self,
+            symbols: symbols.into_iter(),
+            position: 0,
+            runs: BTreeMap::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alphabet::presets::Dna;
+
+    use super::*;
+
+    #[test]
+    fn test_scan() {
+        // The motif "GA(T|C)":
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(false);
+        let q3 = dfa.add_state(true);
+        dfa.add_transition(q0, Dna::G, q1);
+        dfa.add_transition(q1, Dna::A, q2);
+        dfa.add_transition(q2, Dna::T, q3);
+        dfa.add_transition(q2, Dna::C, q3);
+
+        let sequence = Dna::parse("GATGGACAGAG").unwrap();
+        let matches = dfa.scan(sequence).collect::<Vec<_>>();
+        assert_eq!(matches, [0..3, 4..7]);
+        assert_eq!(dfa.scan([]).count(), 0);
+        assert_eq!(Dfa::new().scan([Dna::A]).count(), 0);
+
+        // Overlapping matches of "aa" are all reported:
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'a', q2);
+        assert_eq!(dfa.scan("aaab".chars()).collect::<Vec<_>>(), [0..2, 1..3]);
+    }
+}
@@ -1,18 +1,49 @@
 use std::collections::HashMap;
 
+use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::alphabet::Alphabet;
 use crate::dfa::Dfa;
 
-use super::State;
+use super::{State, StateId};
 
-impl<A: Alphabet + Serialize> Serialize for Dfa<A> {
-    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+/// (De)serialization of the transitions of a [`State`] as a map from symbols to targets,
+/// without the payloads.
+pub(super) mod transitions {
+    use super::*;
+
+    pub fn serialize<A, T, Ser>(
+        transitions: &HashMap<A, (StateId, T)>,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        A: Alphabet + Serialize,
+        Ser: Serializer,
+    {
+        serializer.collect_map(transitions.iter().map(|(symbol, (to, _))| (symbol, to)))
+    }
+
+    pub fn deserialize<'de, A, T, D>(deserializer: D) -> Result<HashMap<A, (StateId, T)>, D::Error>
+    where
+        A: Alphabet + Deserialize<'de>,
+        T: Default,
+        D: Deserializer<'de>,
+    {
+        let transitions = HashMap::<A, StateId>::deserialize(deserializer)?;
+        Ok(transitions
+            .into_iter()
+            .map(|(symbol, to)| (symbol, (to, T::default())))
+            .collect())
+    }
+}
+
+impl<A: Alphabet + Serialize, S, T> Serialize for Dfa<A, S, T> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
         #[derive(Serialize)]
-        #[serde(rename = "Dfa")]
-        struct DfaHelper<'a, A: Alphabet> {
-            states: Vec<&'a State<A>>,
+        #[serde(rename = "Dfa", bound = "A: Serialize")]
+        struct DfaHelper<'a, A: Alphabet, T> {
+            states: Vec<&'a State<A, T>>,
         }
 
         let helper = DfaHelper {
@@ -22,28 +53,51 @@ impl<A: Alphabet + Serialize> Serialize for Dfa<A> {
     }
 }
 
-impl<'de, A: Alphabet + Deserialize<'de>> Deserialize<'de> for Dfa<A> {
+impl<'de, A, S, T> Deserialize<'de> for Dfa<A, S, T>
+where
+    A: Alphabet + Deserialize<'de>,
+    S: Default,
+    T: Default,
+{
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         #[derive(Deserialize)]
-        #[serde(rename = "Dfa")]
-        struct DfaHelper<A: Alphabet> {
-            states: Vec<State<A>>,
+        #[serde(rename = "Dfa", bound = "A: Deserialize<'de>, T: Default")]
+        struct DfaHelper<A: Alphabet, T> {
+            states: Vec<State<A, T>>,
         }
 
         let helper = DfaHelper::deserialize(deserializer)?;
-        let mut dfa = Dfa::new();
-        let old2new: HashMap<_, _> = helper
-            .states
-            .iter()
-            .map(|state| (state.id, dfa.add_state(state.accepting)))
-            .collect();
-        for old_from_state in &helper.states {
-            let new_from = old2new[&old_from_state.id];
-            for (symbol, old_to) in old_from_state.transitions() {
-                dfa.add_transition(new_from, symbol, old2new[&old_to]);
+        // Dense ids (`0..n` in any order) are kept, so they survive a round-trip,
+        // otherwise states are renumbered in the order they are listed:
+        let mut states = helper.states;
+        let num_states = states.len();
+        if states.iter().all(|state| state.id.index() < num_states) {
+            states.sort_by_key(|state| state.id);
+        }
+        let mut old2new = HashMap::new();
+        for (index, state) in states.iter().enumerate() {
+            if old2new.insert(state.id, StateId::new(index)).is_some() {
+                return Err(D::Error::custom(format!("duplicate state id {}", state.id)));
+            }
+        }
+
+        let mut dfa = Dfa::default();
+        for state in &states {
+            dfa.add_state(state.accepting);
+        }
+        for state in &mut states {
+            let from = old2new[&state.id];
+            for (symbol, to, data) in state.take_transitions() {
+                let Some(&to) = old2new.get(&to) else {
+                    return Err(D::Error::custom(format!(
+                        "transition from {} to unknown state {}",
+                        state.id, to
+                    )));
+                };
+                dfa.add_transition_with(from, symbol, to, data);
             }
         }
         Ok(dfa)
@@ -73,4 +127,81 @@ mod tests {
             assert_eq!(dfa.accepts(word.chars()), dfa2.accepts(word.chars()));
         }
     }
+
+    #[test]
+    fn test_dfa_serde_stable_ids() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+        dfa.add_transition(b, 'y', a);
+
+        let json = serde_json::to_string(&dfa).unwrap();
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["states"].as_array_mut().unwrap().reverse();
+        let dfa2: Dfa<char> = serde_json::from_value(value).unwrap();
+        assert!(!dfa2.state(a).accepting);
+        assert!(dfa2.state(b).accepting);
+        assert_eq!(dfa2.state(a).next('x'), Some(b));
+        assert_eq!(dfa2.state(b).next('y'), Some(a));
+
+        let err = serde_json::from_str::<Dfa<char>>(
+            r#"{"states":[{"id":0,"accepting":true,"transitions":{"x":1}}]}"#,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("transition from 0 to unknown state 1"));
+    }
+
+    #[test]
+    fn test_dfa_serde_sparse_ids() {
+        // Ids that are not exactly `0..n` are renumbered in the order states are listed:
+        let json = r#"{"states":[
+            {"id":10,"accepting":false,"transitions":{"a":3}},
+            {"id":3,"accepting":true,"transitions":{"b":10}}
+        ]}"#;
+        let dfa: Dfa<char> = serde_json::from_str(json).unwrap();
+        let (q0, q1) = (StateId::new(0), StateId::new(1));
+        assert_eq!(dfa.num_states(), 2);
+        assert!(!dfa.state(q0).accepting);
+        assert!(dfa.state(q1).accepting);
+        assert_eq!(dfa.next(q0, 'a'), Some(q1));
+        assert_eq!(dfa.next(q1, 'b'), Some(q0));
+
+        let err = serde_json::from_str::<Dfa<char>>(
+            r#"{"states":[{"id":5,"accepting":true,"transitions":{"x":6}}]}"#,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("transition from 5 to unknown state 6"));
+    }
+
+    #[test]
+    fn test_dfa_serde_payloads() {
+        let mut dfa = Dfa::<char, &str, u8>::default();
+        let a = dfa.add_state_with(false, "a");
+        let b = dfa.add_state_with(true, "b");
+        dfa.add_transition_with(a, 'x', b, 7);
+        dfa.add_transition_with(b, 'y', a, 8);
+
+        // Payloads are not written, so the format is the same as without them:
+        let json = serde_json::to_string(&dfa).unwrap();
+        assert_eq!(json, serde_json::to_string(&dfa.clone_structure()).unwrap());
+        assert_eq!(
+            serde_json::to_string(dfa.state(a)).unwrap(),
+            r#"{"id":0,"accepting":false,"transitions":{"x":1}}"#
+        );
+
+        let dfa2: Dfa<char, String, u8> = serde_json::from_str(&json).unwrap();
+        assert!(dfa2.accepts("xyx".chars()));
+        assert_eq!(dfa2.state_data(b), "");
+        assert_eq!(dfa2.transition_data(a, 'x'), Some(&0));
+
+        let state: State<char, u8> =
+            serde_json::from_str(r#"{"id":1,"accepting":true,"transitions":{"y":0}}"#).unwrap();
+        assert_eq!(state.next('y'), Some(a));
+        assert_eq!(state.transition_data('y'), Some(&0));
+    }
 }
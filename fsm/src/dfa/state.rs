@@ -1,18 +1,37 @@
 use std::collections::HashMap;
 
 use crate::alphabet::Alphabet;
+use crate::util::arena::state_id;
 
-pub type StateId = usize;
+state_id! {
+    /// Id of a state of a DFA.
+    DfaStateId
+}
+
+pub type StateId = DfaStateId;
 
-#[derive(Debug)]
+/// State of a DFA, with its outgoing transitions.
+///
+/// Every transition carries a payload of type `T`, see [`Dfa::add_transition_with`](crate::dfa::Dfa::add_transition_with).
+/// Payloads are deliberately not serialized: only the targets of the transitions are written,
+/// and reading a state back gives every transition the default payload.
+#[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct State<A: Alphabet> {
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "A: serde::Serialize",
+        deserialize = "A: serde::Deserialize<'de>, T: Default"
+    ))
+)]
+pub struct State<A: Alphabet, T = ()> {
     pub id: StateId,
     pub accepting: bool,
-    transitions: HashMap<A, StateId>,
+    #[cfg_attr(feature = "serde", serde(with = "super::serde::transitions"))]
+    transitions: HashMap<A, (StateId, T)>,
 }
 
-impl<A: Alphabet> State<A> {
+impl<A: Alphabet, T> State<A, T> {
     pub fn new(id: StateId, accepting: bool) -> Self {
         Self {
             id,
@@ -21,8 +40,36 @@ impl<A: Alphabet> State<A> {
         }
     }
 
-    pub fn add_transition(&mut self, symbol: A, to: StateId) {
-        self.transitions.insert(symbol, to);
+    /// Adds a transition carrying `data`, replacing the previous transition on `symbol`.
+    pub fn add_transition_with(&mut self, symbol: A, to: StateId, data: T) {
+        self.transitions.insert(symbol, (to, data));
+    }
+
+    pub fn remove_transition(&mut self, symbol: A) -> Option<StateId> {
+        self.remove_transition_with(symbol).map(|(to, _)| to)
+    }
+
+    /// Removes the transition on `symbol`, returning its target and payload.
+    pub fn remove_transition_with(&mut self, symbol: A) -> Option<(StateId, T)> {
+        self.transitions.remove(&symbol)
+    }
+
+    /// Redirects every transition to `f(to)`, dropping those for which it is `None`.
+    pub(crate) fn retarget(&mut self, f: impl Fn(StateId) -> Option<StateId>) {
+        self.transitions.retain(|_, (to, _)| match f(*to) {
+            Some(new) => {
+                *to = new;
+                true
+            }
+            None => false,
+        });
+    }
+
+    /// Takes all transitions with their payloads out of the state.
+    pub(crate) fn take_transitions(&mut self) -> impl Iterator<Item = (A, StateId, T)> {
+        std::mem::take(&mut self.transitions)
+            .into_iter()
+            .map(|(symbol, (to, data))| (symbol, to, data))
     }
 
     pub fn num_transitions(&self) -> usize {
@@ -30,10 +77,31 @@ impl<A: Alphabet> State<A> {
     }
 
     pub fn transitions(&self) -> impl Iterator<Item = (A, StateId)> + '_ {
-        self.transitions.iter().map(|(&symbol, &to)| (symbol, to))
+        self.transitions
+            .iter()
+            .map(|(&symbol, &(to, _))| (symbol, to))
+    }
+
+    pub fn transitions_with_data(&self) -> impl Iterator<Item = (A, StateId, &T)> + '_ {
+        (self.transitions.iter()).map(|(&symbol, (to, data))| (symbol, *to, data))
     }
 
     pub fn next(&self, symbol: A) -> Option<StateId> {
-        self.transitions.get(&symbol).copied()
+        self.transitions.get(&symbol).map(|&(to, _)| to)
+    }
+
+    /// Payload of the transition on `symbol`, if there is one.
+    pub fn transition_data(&self, symbol: A) -> Option<&T> {
+        self.transitions.get(&symbol).map(|(_, data)| data)
+    }
+    pub fn transition_data_mut(&mut self, symbol: A) -> Option<&mut T> {
+        self.transitions.get_mut(&symbol).map(|(_, data)| data)
+    }
+}
+
+impl<A: Alphabet, T: Default> State<A, T> {
+    /// Adds a transition carrying the default payload, replacing the previous transition on `symbol`.
+    pub fn add_transition(&mut self, symbol: A, to: StateId) {
+        self.add_transition_with(symbol, to, T::default());
     }
 }
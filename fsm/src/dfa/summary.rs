@@ -0,0 +1,114 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// What can be read from a state on the way to acceptance, see [`Dfa::symbol_summaries`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolSummary<A: Alphabet> {
+    /// Symbols that begin some non-empty accepted continuation.
+    pub first: BTreeSet<A>,
+    /// Symbols that end some non-empty accepted continuation.
+    pub last: BTreeSet<A>,
+    /// Whether some continuation (possibly empty) is accepted.
+    pub coaccessible: bool,
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Symbols that begin some accepted continuation from `state`,
+    /// i.e. symbols leading to a co-accessible state.
+    pub fn first_symbols(&self, state: StateId) -> BTreeSet<A> {
+        let coaccessible = self.coaccessible_states();
+        self.first_symbols_with(state, |to| coaccessible.contains(&to))
+    }
+
+    fn first_symbols_with(
+        &self,
+        state: StateId,
+        coaccessible: impl Fn(StateId) -> bool,
+    ) -> BTreeSet<A> {
+        self.state(state)
+            .transitions()
+            .filter(|&(_, to)| coaccessible(to))
+            .map(|(symbol, _)| symbol)
+            .collect()
+    }
+
+    /// Computes a [`SymbolSummary`] for every state at once, indexed by `StateId`.
+    ///
+    /// The co-accessible set is computed once and the last symbols are propagated
+    /// backwards along transitions, so this is much cheaper than querying states one by one.
+    pub fn symbol_summaries(&self) -> Vec<SymbolSummary<A>> {
+        let coaccessible = self.coaccessible_states();
+        let mut predecessors: HashMap<StateId, Vec<StateId>> = HashMap::new();
+        for (from, _, to) in self.transitions() {
+            predecessors.entry(to.id).or_default().push(from.id);
+        }
+
+        let mut last = vec![BTreeSet::new(); self.num_states()];
+        let mut worklist = Vec::new();
+        for (from, symbol, to) in self.transitions() {
+            if to.accepting && last[from.id.index()].insert(symbol) {
+                worklist.push((from.id, symbol));
+            }
+        }
+        while let Some((state, symbol)) = worklist.pop() {
+            for &predecessor in predecessors.get(&state).into_iter().flatten() {
+                if last[predecessor.index()].insert(symbol) {
+                    worklist.push((predecessor, symbol));
+                }
+            }
+        }
+
+        last.into_iter()
+            .enumerate()
+            .map(|(state, last)| (StateId::new(state), last))
+            .map(|(state, last)| SymbolSummary {
+                first: self.first_symbols_with(state, |to| coaccessible.contains(&to)),
+                last,
+                coaccessible: coaccessible.contains(&state),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_summaries() {
+        // Keywords "if" and "in", plus a dead branch on 'x':
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        let dead = dfa.add_state(false);
+        dfa.add_transition(q0, 'i', q1);
+        dfa.add_transition(q1, 'f', q2);
+        dfa.add_transition(q1, 'n', q2);
+        dfa.add_transition(q0, 'x', dead);
+
+        assert_eq!(dfa.first_symbols(q0), BTreeSet::from(['i']));
+        assert_eq!(dfa.first_symbols(q1), BTreeSet::from(['f', 'n']));
+        assert!(dfa.first_symbols(q2).is_empty());
+
+        let summaries = dfa.symbol_summaries();
+        assert_eq!(summaries.len(), 4);
+        assert_eq!(
+            summaries[q0.index()],
+            SymbolSummary {
+                first: BTreeSet::from(['i']),
+                last: BTreeSet::from(['f', 'n']),
+                coaccessible: true,
+            }
+        );
+        assert_eq!(summaries[q1.index()].last, BTreeSet::from(['f', 'n']));
+        assert!(summaries[q2.index()].coaccessible);
+        assert!(!summaries[dead.index()].coaccessible);
+        for (state, summary) in summaries.iter().enumerate() {
+            assert_eq!(summary.first, dfa.first_symbols(StateId::new(state)));
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt::{Display, Write};
+use std::hash::Hash;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+use crate::util::arena::ArenaId;
+
+/// Options for [`Dfa::render_tikz_with`], and for
+/// [`Nfa::render_tikz_with`](crate::nfa::Nfa::render_tikz_with) with NFA state ids.
+#[derive(Debug, Clone)]
+pub struct TikzOptions<Id = StateId> {
+    /// Manual positions `(x, y)` of states, in cm. States without one are laid out
+    /// automatically, in columns by their BFS depth from the initial state.
+    pub positions: HashMap<Id, (f64, f64)>,
+    /// Distance between the columns and the rows of the automatic layout, in cm.
+    pub node_distance: f64,
+}
+
+impl<Id> Default for TikzOptions<Id> {
+    fn default() -> Self {
+        Self {
+            positions: HashMap::new(),
+            node_distance: 2.5,
+        }
+    }
+}
+
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '#' | '$' | '%' | '&' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            'ε' => escaped.push_str("$\\varepsilon$"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Writes a `tikzpicture` using the `automata` library, with one edge per pair of states,
+/// labeled with `labels` joined by commas.
+pub(crate) fn write_tikz<Id: ArenaId + Ord + Hash + Display>(
+    accepting: &[bool],
+    edges: &BTreeMap<(Id, Id), Vec<String>>,
+    options: &TikzOptions<Id>,
+) -> String {
+    // Automatic layout, unreachable states in a last column:
+    let mut depths = vec![None; accepting.len()];
+    let mut queue = VecDeque::new();
+    if !accepting.is_empty() {
+        depths[0] = Some(0);
+        queue.push_back(0);
+    }
+    while let Some(state) = queue.pop_front() {
+        let depth = depths[state].unwrap();
+        let (from, until) = (Id::from_index(state), Id::from_index(state + 1));
+        let origin = Id::from_index(0);
+        for to in edges
+            .range((from, origin)..(until, origin))
+            .map(|(&(_, to), _)| to.index())
+        {
+            if depths[to].is_none() {
+                depths[to] = Some(depth + 1);
+                queue.push_back(to);
+            }
+        }
+    }
+    let last = depths.iter().flatten().max().map_or(0, |&depth| depth + 1);
+    let mut rows: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut positions = Vec::new();
+    for (state, depth) in depths.iter().enumerate() {
+        let column = depth.unwrap_or(last);
+        let row = rows.entry(column).or_default();
+        let distance = options.node_distance;
+        // Adding `0.0` turns `-0` into `0`:
+        let position = (distance * column as f64, -(distance * *row as f64) + 0.0);
+        *row += 1;
+        let manual = options.positions.get(&Id::from_index(state));
+        positions.push(manual.copied().unwrap_or(position));
+    }
+
+    let mut out = String::new();
+    writeln!(
+        out,
+        "\\begin{{tikzpicture}}[shorten >=1pt, auto, >={{Stealth[round]}}]"
+    )
+    .unwrap();
+    for (state, &accepting) in accepting.iter().enumerate() {
+        let mut style = String::from("state");
+        if state == 0 {
+            style.push_str(", initial");
+        }
+        if accepting {
+            style.push_str(", accepting");
+        }
+        let (x, y) = positions[state];
+        writeln!(
+            out,
+            "  \\node[{}] (q{}) at ({}, {}) {{$q_{{{}}}$}};",
+            style, state, x, y, state
+        )
+        .unwrap();
+    }
+    if !edges.is_empty() {
+        writeln!(out, "  \\path[->]").unwrap();
+        for ((from, to), labels) in edges {
+            let label = escape_latex(&labels.join(", "));
+            let style = if from == to {
+                "[loop above] "
+            } else if edges.contains_key(&(*to, *from)) {
+                "[bend left] "
+            } else {
+                ""
+            };
+            let to = if from == to {
+                String::new()
+            } else {
+                format!("q{}", to)
+            };
+            writeln!(
+                out,
+                "    (q{}) edge {}node {{{}}} ({})",
+                from, style, label, to
+            )
+            .unwrap();
+        }
+        writeln!(out, "  ;").unwrap();
+    }
+    writeln!(out, "\\end{{tikzpicture}}").unwrap();
+    out
+}
+
+impl<A: Alphabet + Display, S, T> Dfa<A, S, T> {
+    pub fn render_tikz(&self) -> String {
+        self.render_tikz_with(&TikzOptions::default())
+    }
+
+    /// Renders the automaton as a TikZ picture for LaTeX, in the style of the `automata`
+    /// library (`\usetikzlibrary{automata, arrows.meta}`).
+    ///
+    /// States are named `q0`, `q1`, ... after their ids and placed according to `options`.
+    /// Parallel transitions are merged into a single edge labeled with all their symbols,
+    /// and opposite edges between two states are bent apart.
+    pub fn render_tikz_with(&self, options: &TikzOptions) -> String {
+        let mut edges: BTreeMap<(StateId, StateId), Vec<A>> = BTreeMap::new();
+        for (from, symbol, to) in self.transitions() {
+            edges.entry((from.id, to.id)).or_default().push(symbol);
+        }
+        let edges = edges
+            .into_iter()
+            .map(|(edge, mut symbols)| {
+                symbols.sort_unstable();
+                (edge, symbols.iter().map(|s| s.to_string()).collect())
+            })
+            .collect();
+        let accepting = self
+            .states()
+            .map(|state| state.accepting)
+            .collect::<Vec<_>>();
+        write_tikz(&accepting, &edges, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_tikz() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let q2 = dfa.add_state(false);
+        let unreachable = dfa.add_state(false);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, '_', q2);
+        dfa.add_transition(q1, 'a', q0);
+        dfa.add_transition(q1, 'b', q1);
+        dfa.add_transition(unreachable, 'a', q0);
+
+        assert_eq!(
+            dfa.render_tikz(),
+            "\\begin{tikzpicture}[shorten >=1pt, auto, >={Stealth[round]}]\n\
+             \x20 \\node[state, initial] (q0) at (0, 0) {$q_{0}$};\n\
+             \x20 \\node[state, accepting] (q1) at (2.5, 0) {$q_{1}$};\n\
+             \x20 \\node[state] (q2) at (2.5, -2.5) {$q_{2}$};\n\
+             \x20 \\node[state] (q3) at (5, 0) {$q_{3}$};\n\
+             \x20 \\path[->]\n\
+             \x20   (q0) edge [bend left] node {a} (q1)\n\
+             \x20   (q0) edge node {\\_} (q2)\n\
+             \x20   (q1) edge [bend left] node {a} (q0)\n\
+             \x20   (q1) edge [loop above] node {b} ()\n\
+             \x20   (q3) edge node {a} (q0)\n\
+             \x20 ;\n\
+             \\end{tikzpicture}\n"
+        );
+
+        let options = TikzOptions {
+            positions: HashMap::from([(q2, (1.0, -1.5))]),
+            node_distance: 3.0,
+        };
+        let tikz = dfa.render_tikz_with(&options);
+        assert!(tikz.contains("(q1) at (3, 0)"));
+        assert!(tikz.contains("(q2) at (1, -1.5)"));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// Record of reading a word, see [`Dfa::accepts_with_trace`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trace<A: Alphabet> {
+    /// Transitions taken, as `(from, symbol, to)`, in order.
+    pub steps: Vec<(StateId, A, StateId)>,
+    /// Symbol without a transition from the last reached state, which ended the run early.
+    pub stuck_on: Option<A>,
+    /// Whether the word is accepted.
+    pub accepted: bool,
+}
+
+impl<A: Alphabet> Trace<A> {
+    /// Number of symbols read before the run ended.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Same as [`accepts`](Dfa::accepts), but also recording the transitions taken,
+    /// to see where a word goes wrong.
+    ///
+    /// The run stops on the first symbol without a transition, see [`Trace::stuck_on`].
+    /// An automaton without states rejects every word with an empty trace.
+    pub fn accepts_with_trace(&self, word: impl IntoIterator<Item = A>) -> Trace<A> {
+        let mut trace = Trace {
+            steps: Vec::new(),
+            stuck_on: None,
+            accepted: false,
+        };
+        if self.states.is_empty() {
+            return trace;
+        }
+        let mut current_state = StateId::new(0);
+        for symbol in word {
+            let Some(next_state) = self.next(current_state, symbol) else {
+                trace.stuck_on = Some(symbol);
+                return trace;
+            };
+            trace.steps.push((current_state, symbol, next_state));
+            current_state = next_state;
+        }
+        trace.accepted = self.accepting(current_state);
+        trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepts_with_trace() {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+        dfa.add_transition(b, 'y', a);
+
+        let trace = dfa.accepts_with_trace("xyx".chars());
+        assert!(trace.accepted);
+        assert_eq!(trace.steps, vec![(a, 'x', b), (b, 'y', a), (a, 'x', b)]);
+        assert_eq!(trace.stuck_on, None);
+
+        // Read completely, but ends in a rejecting state:
+        let trace = dfa.accepts_with_trace("xy".chars());
+        assert!(!trace.accepted);
+        assert_eq!(trace.len(), 2);
+        assert_eq!(trace.stuck_on, None);
+
+        // No transition on the second 'x':
+        let trace = dfa.accepts_with_trace("xxy".chars());
+        assert!(!trace.accepted);
+        assert_eq!(trace.steps, vec![(a, 'x', b)]);
+        assert_eq!(trace.stuck_on, Some('x'));
+
+        let trace = Dfa::<char>::new().accepts_with_trace("".chars());
+        assert!(!trace.accepted);
+        assert!(trace.is_empty());
+    }
+}
@@ -0,0 +1,244 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::journal::Event;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Removes useless states, i.e. states that are unreachable from the initial state
+    /// or from which no accepting state is reachable.
+    ///
+    /// The remaining states are renumbered, preserving their relative order,
+    /// so the initial state stays `0`. If the language is empty, no states remain.
+    /// Payloads of the remaining states and transitions are kept, as are the conflict policy
+    /// and the journal, which records the trimming as removals of the useless states.
+    pub fn trim(&mut self) {
+        let accessible = self.accessible_states();
+        let coaccessible = self.coaccessible_states();
+        let mut trimmed = Dfa::default().with_conflict_policy(self.conflict_policy);
+        let data = std::mem::take(&mut self.data);
+        let mut states = std::mem::take(&mut self.states);
+        let old2new: HashMap<_, _> = (states.iter())
+            .zip(data)
+            .filter(|(state, _)| accessible.contains(&state.id) && coaccessible.contains(&state.id))
+            .map(|(state, data)| (state.id, trimmed.add_state_with(state.accepting, data)))
+            .collect();
+        for state in states.iter_mut() {
+            let Some(&new_from) = old2new.get(&state.id) else {
+                continue;
+            };
+            for (symbol, to, data) in state.take_transitions() {
+                if let Some(&new_to) = old2new.get(&to) {
+                    trimmed.add_transition_with(new_from, symbol, new_to, data);
+                }
+            }
+        }
+        // Removing from the last state keeps the ids of the earlier ones valid during replay:
+        trimmed.journal = self.journal.take().map(|mut journal| {
+            for state in (0..states.len()).rev().map(StateId::new) {
+                if !old2new.contains_key(&state) {
+                    journal.push(Event::RemoveState { state });
+                }
+            }
+            journal
+        });
+        *self = trimmed;
+    }
+}
+
+impl<A: Alphabet> Dfa<A> {
+    /// Builds the (trimmed) automaton accepting exactly the accepted words
+    /// that only use the given `symbols`.
+    pub fn restrict_alphabet(&self, symbols: impl IntoIterator<Item = A>) -> Dfa<A> {
+        let symbols = symbols.into_iter().collect::<HashSet<_>>();
+        let mut dfa = Dfa::new();
+        for state in self.states() {
+            dfa.add_state(state.accepting);
+        }
+        for (from, symbol, to) in self.transitions() {
+            if symbols.contains(&symbol) {
+                dfa.add_transition(from.id, symbol, to.id);
+            }
+        }
+        dfa.trim();
+        dfa
+    }
+
+    /// Makes the automaton complete over `alphabet`: adds a non-accepting trap state
+    /// (with self-loops on all symbols) and redirects all missing transitions to it.
+    ///
+    /// Returns the trap state, or `None` if the automaton was already complete,
+    /// in which case nothing is added. An empty automaton gets the trap as its initial state.
+    /// Existing transitions on symbols outside `alphabet` are kept.
+    pub fn complete(&mut self, alphabet: impl IntoIterator<Item = A>) -> Option<StateId> {
+        let mut alphabet = alphabet.into_iter().collect::<Vec<_>>();
+        alphabet.sort_unstable();
+        alphabet.dedup();
+        let missing = self
+            .states()
+            .flat_map(|state| alphabet.iter().map(move |&symbol| (state, symbol)))
+            .filter(|(state, symbol)| state.next(*symbol).is_none())
+            .map(|(state, symbol)| (state.id, symbol))
+            .collect::<Vec<_>>();
+        if missing.is_empty() && !self.states.is_empty() {
+            return None;
+        }
+        let trap = self.add_state(false);
+        for (from, symbol) in missing {
+            self.add_transition(from, symbol, trap);
+        }
+        for symbol in alphabet {
+            self.add_transition(trap, symbol, trap);
+        }
+        Some(trap)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::dfa::conflict::ConflictPolicy;
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    #[test]
+    fn test_trim() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let unreachable = dfa.add_state(true);
+        let q1 = dfa.add_state(true);
+        let trap = dfa.add_state(false);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', trap);
+        dfa.add_transition(unreachable, 'a', q1);
+        dfa.add_transition(q1, 'a', q0);
+
+        // Restricting to the full alphabet only trims:
+        let trimmed = dfa.restrict_alphabet(['a', 'b']);
+        assert_eq!(trimmed.num_states(), 2);
+        assert_eq!(trimmed.num_transitions(), 2);
+        for word in generate_strings(&['a', 'b'], 6) {
+            assert_eq!(dfa.accepts(word.chars()), trimmed.accepts(word.chars()));
+        }
+
+        dfa.trim();
+        assert_eq!(dfa.num_states(), 2);
+
+        let mut empty = Dfa::new();
+        let q0 = empty.add_state(false);
+        empty.add_transition(q0, 'a', q0);
+        empty.trim();
+        assert_eq!(empty.num_states(), 0);
+        assert!(!empty.accepts("".chars()));
+    }
+
+    #[test]
+    fn test_restrict_alphabet() {
+        // Commands: 'o'pen, 'c'lose, 'r'eset; accepting when closed.
+        let mut dfa = Dfa::new();
+        let closed = dfa.add_state(true);
+        let open = dfa.add_state(false);
+        let broken = dfa.add_state(false);
+        dfa.add_transition(closed, 'o', open);
+        dfa.add_transition(open, 'c', closed);
+        dfa.add_transition(open, 'o', broken);
+        dfa.add_transition(broken, 'r', closed);
+
+        let restricted = dfa.restrict_alphabet(['o', 'c']);
+        assert_eq!(restricted.num_states(), 2);
+        for word in generate_strings(&['o', 'c', 'r'], 6) {
+            let expected = !word.contains('r') && dfa.accepts(word.chars());
+            assert_eq!(restricted.accepts(word.chars()), expected, "{}", word);
+        }
+    }
+
+    #[test]
+    fn test_complete() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'c', q0);
+        let words = generate_strings(&['a', 'b', 'c'], 5);
+        let expected = words
+            .iter()
+            .map(|word| dfa.accepts(word.chars()))
+            .collect::<Vec<_>>();
+
+        let trap = dfa.complete(['a', 'b']).unwrap();
+        assert_eq!(trap, StateId::new(2));
+        assert!(!dfa.accepting(trap));
+        // 3 states over {a, b}, plus the kept `c` transition:
+        assert_eq!(dfa.num_transitions(), 7);
+        assert_eq!(dfa.next(q1, 'a'), Some(trap));
+        for (word, expected) in words.iter().zip(expected) {
+            assert_eq!(dfa.accepts(word.chars()), expected);
+        }
+        assert_eq!(dfa.complete(['b', 'a']), None);
+        assert_eq!(dfa.num_states(), 3);
+
+        let mut empty = Dfa::new();
+        assert_eq!(empty.complete(['a']), Some(StateId::new(0)));
+        assert!(!empty.accepts("aa".chars()));
+    }
+
+    #[test]
+    fn test_trim_keeps_data() {
+        let mut dfa = Dfa::<char, &str, u32>::default();
+        let q0 = dfa.add_state_with(false, "start");
+        let dead = dfa.add_state_with(false, "dead");
+        let q1 = dfa.add_state_with(true, "end");
+        dfa.add_transition_with(q0, 'a', dead, 1);
+        dfa.add_transition_with(q0, 'b', q1, 2);
+        dfa.add_transition(q1, 'b', q1);
+
+        dfa.trim();
+        assert_eq!(dfa.num_states(), 2);
+        let q1 = StateId::new(1);
+        assert_eq!(dfa.state_data(q0), &"start");
+        assert_eq!(dfa.state_data(q1), &"end");
+        assert_eq!(dfa.next(q0, 'b'), Some(q1));
+        assert_eq!(dfa.transition_data(q0, 'b'), Some(&2));
+        assert_eq!(dfa.transition_data(q0, 'a'), None);
+        assert_eq!(dfa.transition_data(q1, 'b'), Some(&0));
+    }
+
+    #[test]
+    fn test_trim_keeps_journal_and_policy() {
+        let mut dfa = Dfa::new()
+            .with_conflict_policy(ConflictPolicy::Error)
+            .with_journal();
+        let q0 = dfa.add_state(false);
+        let dead = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let _unreachable = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', dead);
+        dfa.add_transition(q0, 'b', q1);
+        dfa.add_transition(q1, 'b', q1);
+
+        dfa.trim();
+        assert_eq!(dfa.num_states(), 2);
+        assert_eq!(dfa.conflict_policy(), ConflictPolicy::Error);
+        let journal = dfa.journal().unwrap();
+        assert_eq!(
+            &journal.events()[journal.len() - 2..],
+            [
+                Event::RemoveState {
+                    state: StateId::new(3)
+                },
+                Event::RemoveState { state: dead },
+            ]
+        );
+        let replayed = journal.replay();
+        assert_eq!(replayed.num_states(), 2);
+        assert!(replayed.equivalent(&dfa));
+        assert_eq!(replayed.next(q0, 'b'), dfa.next(q0, 'b'));
+
+        // Construction continues to be checked and journaled:
+        let q1 = StateId::new(1);
+        assert!(dfa.try_add_transition(q1, 'b', q0).is_err());
+        dfa.add_transition(q1, 'a', q0);
+        assert!(dfa.journal().unwrap().replay().accepts("bab".chars()));
+    }
+}
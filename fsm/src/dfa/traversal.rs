@@ -0,0 +1,244 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+/// Order in which a [`Traversal`] discovers states.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Order {
+    BreadthFirst,
+    DepthFirst,
+}
+
+/// A state discovered during a traversal, together with the way it was reached.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Discovery<A: Alphabet> {
+    /// Discovered state.
+    pub state: StateId,
+    /// Number of transitions on the traversal-tree path from the start state.
+    pub depth: usize,
+    /// Predecessor on the traversal-tree path and the symbol leading from it,
+    /// or `None` for the start state.
+    pub parent: Option<(StateId, A)>,
+}
+
+/// Callbacks invoked by [`Dfa::visit`].
+///
+/// All methods have empty default implementations, so a visitor only needs
+/// to override the events it is interested in.
+pub trait Visitor<A: Alphabet> {
+    /// Called once for every state, in discovery order.
+    fn discover(&mut self, _discovery: &Discovery<A>) {}
+
+    /// Called for every outgoing transition of a discovered state,
+    /// right after the state itself is discovered.
+    fn examine_transition(&mut self, _from: StateId, _symbol: A, _to: StateId) {}
+}
+
+/// Iterator over the states reachable from some start state.
+///
+/// Outgoing transitions are explored in the order of their symbols,
+/// so the traversal is deterministic.
+pub struct Traversal<'a, A: Alphabet, S = (), T = ()> {
+    dfa: &'a Dfa<A, S, T>,
+    order: Order,
+    frontier: VecDeque<Discovery<A>>,
+    visited: HashSet<StateId>,
+}
+
+impl<'a, A: Alphabet, S, T> Traversal<'a, A, S, T> {
+    pub fn new(dfa: &'a Dfa<A, S, T>, start: StateId, order: Order) -> Self {
+        let mut frontier = VecDeque::new();
+        frontier.push_back(Discovery {
+            state: start,
+            depth: 0,
+            parent: None,
+        });
+        Self {
+            dfa,
+            order,
+            frontier,
+            visited: HashSet::new(),
+        }
+    }
+
+    fn pop(&mut self) -> Option<Discovery<A>> {
+        match self.order {
+            Order::BreadthFirst => self.frontier.pop_front(),
+            Order::DepthFirst => self.frontier.pop_back(),
+        }
+    }
+}
+
+impl<A: Alphabet, S, T> Iterator for Traversal<'_, A, S, T> {
+    type Item = Discovery<A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(discovery) = self.pop() {
+            if !self.visited.insert(discovery.state) {
+                continue;
+            }
+            let mut transitions = self
+                .dfa
+                .state(discovery.state)
+                .transitions()
+                .collect::<Vec<_>>();
+            transitions.sort_unstable();
+            // Push in reverse for DFS, so that the smallest symbol is explored first.
+            if self.order == Order::DepthFirst {
+                transitions.reverse();
+            }
+            for (symbol, to) in transitions {
+                if !self.visited.contains(&to) {
+                    self.frontier.push_back(Discovery {
+                        state: to,
+                        depth: discovery.depth + 1,
+                        parent: Some((discovery.state, symbol)),
+                    });
+                }
+            }
+            return Some(discovery);
+        }
+        None
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Breadth-first traversal of the states reachable from `start`.
+    ///
+    /// The `depth` of each discovered state is its distance from `start`.
+    pub fn bfs_from(&self, start: StateId) -> Traversal<'_, A, S, T> {
+        Traversal::new(self, start, Order::BreadthFirst)
+    }
+
+    /// Depth-first traversal of the states reachable from `start`.
+    ///
+    /// The `depth` of each discovered state is the length of the DFS-tree path to it.
+    pub fn dfs_from(&self, start: StateId) -> Traversal<'_, A, S, T> {
+        Traversal::new(self, start, Order::DepthFirst)
+    }
+
+    /// Computes a shortest word leading from the initial state to each reachable state.
+    ///
+    /// Words are taken from the BFS tree, so among the shortest words the
+    /// lexicographically smallest one (w.r.t. symbol order) is chosen.
+    /// Unreachable states are absent from the table.
+    pub fn access_words(&self) -> HashMap<StateId, Vec<A>> {
+        let mut words: HashMap<StateId, Vec<A>> = HashMap::new();
+        if self.states.is_empty() {
+            return words;
+        }
+        for discovery in self.bfs_from(StateId::new(0)) {
+            let word = match discovery.parent {
+                None => Vec::new(),
+                Some((parent, symbol)) => {
+                    let mut word = words[&parent].clone();
+                    word.push(symbol);
+                    word
+                }
+            };
+            words.insert(discovery.state, word);
+        }
+        words
+    }
+
+    /// Traverses the states reachable from `start` in the given order, reporting events to `visitor`.
+    pub fn visit<V: Visitor<A>>(&self, start: StateId, order: Order, visitor: &mut V) {
+        for discovery in Traversal::new(self, start, order) {
+            visitor.discover(&discovery);
+            for (symbol, to) in self.state(discovery.state).transitions() {
+                visitor.examine_transition(discovery.state, symbol, to);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Dfa<char> {
+        // 0 -a-> 1 -a-> 2 -a-> 3
+        // 0 -b-> 3
+        // 3 -a-> 0
+        let mut dfa = Dfa::new();
+        let s0 = dfa.add_state(false);
+        let s1 = dfa.add_state(false);
+        let s2 = dfa.add_state(false);
+        let s3 = dfa.add_state(true);
+        let _unreachable = dfa.add_state(true);
+        dfa.add_transition(s0, 'a', s1);
+        dfa.add_transition(s1, 'a', s2);
+        dfa.add_transition(s2, 'a', s3);
+        dfa.add_transition(s0, 'b', s3);
+        dfa.add_transition(s3, 'a', s0);
+        dfa
+    }
+
+    #[test]
+    fn test_bfs_from() {
+        let dfa = example();
+        let visited = dfa
+            .bfs_from(StateId::new(0))
+            .map(|d| (d.state.index(), d.depth))
+            .collect::<Vec<_>>();
+        assert_eq!(visited, vec![(0, 0), (1, 1), (3, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn test_dfs_from() {
+        let dfa = example();
+        let visited = dfa.dfs_from(StateId::new(0)).collect::<Vec<_>>();
+        let states = visited.iter().map(|d| d.state.index()).collect::<Vec<_>>();
+        assert_eq!(states, vec![0, 1, 2, 3]);
+        assert_eq!(visited[3].depth, 3);
+        assert_eq!(visited[3].parent, Some((StateId::new(2), 'a')));
+    }
+
+    #[test]
+    fn test_visitor() {
+        #[derive(Default)]
+        struct Counter {
+            states: usize,
+            transitions: usize,
+            max_depth: usize,
+        }
+        impl Visitor<char> for Counter {
+            fn discover(&mut self, discovery: &Discovery<char>) {
+                self.states += 1;
+                self.max_depth = self.max_depth.max(discovery.depth);
+            }
+            fn examine_transition(&mut self, _from: StateId, _symbol: char, _to: StateId) {
+                self.transitions += 1;
+            }
+        }
+
+        let dfa = example();
+        let mut counter = Counter::default();
+        dfa.visit(StateId::new(0), Order::BreadthFirst, &mut counter);
+        assert_eq!(counter.states, 4);
+        assert_eq!(counter.transitions, 5);
+        assert_eq!(counter.max_depth, 2);
+    }
+
+    #[test]
+    fn test_access_words() {
+        let dfa = example();
+        let words = dfa.access_words();
+        assert_eq!(words.len(), 4);
+        let word = |state: usize| &words[&StateId::new(state)];
+        assert_eq!(word(0), &vec![]);
+        assert_eq!(word(1), &vec!['a']);
+        assert_eq!(word(2), &vec!['a', 'a']);
+        assert_eq!(word(3), &vec!['b']);
+        assert!(!words.contains_key(&StateId::new(4)));
+        for (state, word) in words {
+            let mut current = StateId::new(0);
+            for symbol in word {
+                current = dfa.next(current, symbol).unwrap();
+            }
+            assert_eq!(current, state);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+type PrefixFilter<'a, A> = Box<dyn FnMut(&[A]) -> bool + 'a>;
+
+/// Iterator over accepted words of bounded length, see [`Dfa::words_up_to`].
+pub struct Words<'a, A: Alphabet, S = (), T = ()> {
+    dfa: &'a Dfa<A, S, T>,
+    max_length: usize,
+    queue: VecDeque<(Vec<A>, StateId)>,
+    coaccessible: BTreeSet<StateId>,
+    keep_prefix: Option<PrefixFilter<'a, A>>,
+}
+
+impl<'a, A: Alphabet, S, T> Words<'a, A, S, T> {
+    /// Words leading from `start` to an accepting state.
+    pub(super) fn new(dfa: &'a Dfa<A, S, T>, start: StateId, max_length: usize) -> Self {
+        let coaccessible = dfa.coaccessible_states();
+        let mut queue = VecDeque::new();
+        if coaccessible.contains(&start) {
+            queue.push_back((Vec::new(), start));
+        }
+        Self {
+            dfa,
+            max_length,
+            queue,
+            coaccessible,
+            keep_prefix: None,
+        }
+    }
+
+    /// Only explores prefixes for which `keep_prefix` returns `true`.
+    ///
+    /// A rejected prefix is not yielded, and none of its extensions are explored.
+    /// The empty prefix is never passed to the predicate.
+    pub fn prune<F>(mut self, keep_prefix: F) -> Self
+    where
+        F: FnMut(&[A]) -> bool + 'a,
+    {
+        self.keep_prefix = Some(Box::new(keep_prefix));
+        self
+    }
+}
+
+impl<A: Alphabet, S, T> Iterator for Words<'_, A, S, T> {
+    type Item = Vec<A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((word, state)) = self.queue.pop_front() {
+            if word.len() < self.max_length {
+                let mut transitions = self.dfa.state(state).transitions().collect::<Vec<_>>();
+                transitions.sort_unstable();
+                for (symbol, to) in transitions {
+                    if !self.coaccessible.contains(&to) {
+                        continue;
+                    }
+                    let mut next = word.clone();
+                    next.push(symbol);
+                    if let Some(keep_prefix) = &mut self.keep_prefix {
+                        if !keep_prefix(&next) {
+                            continue;
+                        }
+                    }
+                    self.queue.push_back((next, to));
+                }
+            }
+            if self.dfa.accepting(state) {
+                return Some(word);
+            }
+        }
+        None
+    }
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Enumerates all accepted words of length at most `max_length`.
+    ///
+    /// Words are produced in order of increasing length, and words of the same length
+    /// are ordered lexicographically. Only prefixes that can still be extended
+    /// to an accepted word are explored.
+    pub fn words_up_to(&self, max_length: usize) -> Words<'_, A, S, T> {
+        Words::new(self, StateId::new(0), max_length)
+    }
+
+    /// Counts the accepted words of each length from `0` to `max_length` (inclusive),
+    /// without enumerating them.
+    ///
+    /// Runs in `O(max_length * num_transitions)` time, propagating the number of words
+    /// leading to each state one symbol at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some count does not fit into `u64`.
+    pub fn count_words(&self, max_length: usize) -> Vec<u64> {
+        let mut counts = Vec::with_capacity(max_length + 1);
+        if self.states.is_empty() {
+            counts.resize(max_length + 1, 0);
+            return counts;
+        }
+        let mut current = vec![0u64; self.num_states()];
+        current[0] = 1;
+        for length in 0..=max_length {
+            let accepted = self
+                .states()
+                .filter(|state| state.accepting)
+                .map(|state| current[state.id.index()])
+                .try_fold(0u64, |total, count| total.checked_add(count));
+            counts.push(accepted.expect("word count overflows u64"));
+            if length == max_length {
+                break;
+            }
+            let mut next = vec![0u64; self.num_states()];
+            for (from, _, to) in self.transitions() {
+                next[to.id.index()] = next[to.id.index()]
+                    .checked_add(current[from.id.index()])
+                    .expect("word count overflows u64");
+            }
+            current = next;
+        }
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    fn even_zeros() -> Dfa<u8> {
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(true);
+        let b = dfa.add_state(false);
+        dfa.add_transition(a, 1, a);
+        dfa.add_transition(b, 1, b);
+        dfa.add_transition(a, 0, b);
+        dfa.add_transition(b, 0, a);
+        dfa
+    }
+
+    #[test]
+    fn test_words_up_to() {
+        let dfa = even_zeros();
+        let words = dfa.words_up_to(2).collect::<Vec<_>>();
+        assert_eq!(words, vec![vec![], vec![1], vec![0, 0], vec![1, 1]]);
+
+        let expected = generate_words(&[0, 1], 6)
+            .into_iter()
+            .filter(|word| dfa.accepts(word.clone()))
+            .count();
+        assert_eq!(dfa.words_up_to(6).count(), expected);
+
+        let labeled = dfa.map_states(|id, ()| id.index());
+        assert_eq!(labeled.words_up_to(6).count(), expected);
+    }
+
+    #[test]
+    fn test_count_words() {
+        let dfa = even_zeros();
+        // Words of length n with an even number of zeros: 2^(n-1) for n > 0.
+        assert_eq!(dfa.count_words(5), vec![1, 1, 2, 4, 8, 16]);
+        for (length, &count) in dfa.count_words(6).iter().enumerate() {
+            let expected = dfa
+                .words_up_to(6)
+                .filter(|word| word.len() == length)
+                .count();
+            assert_eq!(count, expected as u64);
+        }
+        assert_eq!(dfa.count_words(63)[63], 1 << 62);
+        assert_eq!(Dfa::<u8>::new().count_words(2), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_words_up_to_pruned() {
+        let dfa = even_zeros();
+        // Only words starting with 1:
+        let words = dfa
+            .words_up_to(3)
+            .prune(|prefix| prefix[0] == 1)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            words,
+            vec![vec![], vec![1], vec![1, 1], vec![1, 0, 0], vec![1, 1, 1]]
+        );
+    }
+}
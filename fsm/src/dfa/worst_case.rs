@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Synthesizes an input of (at most) `length` symbols that keeps the automaton churning
+    /// through as many different states as possible.
+    ///
+    /// At every step, the transition into the least visited state is chosen (ties are broken
+    /// by symbol order), which defeats caches keyed by state and exercises the whole reachable
+    /// part of the automaton. The word is shorter than `length` only if a state without
+    /// outgoing transitions is reached.
+    pub fn worst_case_input(&self, length: usize) -> Vec<A> {
+        let mut word = Vec::with_capacity(length);
+        if self.states.is_empty() {
+            return word;
+        }
+        let mut visits: HashMap<_, usize> = HashMap::new();
+        let mut current = StateId::new(0);
+        visits.insert(current, 1);
+        while word.len() < length {
+            let mut transitions = self.state(current).transitions().collect::<Vec<_>>();
+            transitions.sort_unstable();
+            let Some((symbol, next)) = transitions
+                .into_iter()
+                .min_by_key(|(_, to)| visits.get(to).copied().unwrap_or(0))
+            else {
+                break;
+            };
+            word.push(symbol);
+            *visits.entry(next).or_default() += 1;
+            current = next;
+        }
+        word
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worst_case_input() {
+        // Ring 0 -> 1 -> 2 -> 0 on 'n', self-loops on 's':
+        let mut dfa = Dfa::new();
+        let states = [false, false, true].map(|accepting| dfa.add_state(accepting));
+        for i in 0..3 {
+            dfa.add_transition(states[i], 'n', states[(i + 1) % 3]);
+            dfa.add_transition(states[i], 's', states[i]);
+        }
+        assert_eq!(dfa.symbols(), vec!['n', 's']);
+
+        let word = dfa.worst_case_input(6);
+        assert_eq!(word.len(), 6);
+        let visited = dfa.run(word.iter().copied());
+        // Every state is visited twice, i.e. the self-loops are never taken:
+        let visited = visited.iter().map(|s| s.index()).collect::<Vec<_>>();
+        assert_eq!(visited, vec![0, 1, 2, 0, 1, 2, 0]);
+
+        // Dead end:
+        let mut dfa = Dfa::new();
+        let a = dfa.add_state(false);
+        let b = dfa.add_state(true);
+        dfa.add_transition(a, 'x', b);
+        assert_eq!(dfa.worst_case_input(5), vec!['x']);
+    }
+}
@@ -0,0 +1,586 @@
+//! Import of automata from Graphviz DOT, see [`Dfa::from_dot`] and [`Nfa::from_dot`].
+//!
+//! The accepted subset of DOT covers the output of `render_graphviz` and typical
+//! hand-written diagrams:
+//!
+//! - A single `digraph` (optionally `strict` and named) without subgraphs.
+//! - Node statements `name [attr=value, ...]` and edge statements `a -> b -> c [...]`.
+//!   Names and values are identifiers, numerals or quoted strings. Graph attributes
+//!   (`rankdir=LR`) and defaults (`node [...]`) are ignored, as are `//`, `/* */`
+//!   and `#` comments.
+//! - Every node is a state, numbered in the order of appearance. A node with
+//!   `shape=doublecircle` is accepting.
+//! - The initial state is the target of the edge from a `shape=point` node.
+//!   It becomes state 0, and without such an edge, the first state is initial.
+//!   Nodes with `shape=plaintext` (notes, such as the legend of hidden states) are ignored.
+//! - The `label` of an edge is its symbol, parsed with [`FromStr`]. When it does not parse
+//!   as a whole, it is split at commas into several symbols, as in the merged edges of
+//!   [`GraphvizOptions::groups`](crate::dfa::graphviz::GraphvizOptions::groups) (groups
+//!   themselves cannot be read back). In an NFA, edges labeled `ε` or without a label are
+//!   ε-transitions.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::conflict::ConflictPolicy;
+use crate::dfa::state::DfaStateId;
+use crate::dfa::Dfa;
+use crate::nfa::state::NfaStateId;
+use crate::nfa::Nfa;
+
+/// Error of reading an automaton from DOT.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotError {
+    /// Line number, starting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for DotError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for DotError {}
+
+fn error(line: usize, message: impl Into<String>) -> DotError {
+    DotError {
+        line,
+        message: message.into(),
+    }
+}
+
+#[derive(Debug)]
+struct Token {
+    text: String,
+    /// Quoted strings are always identifiers, even when they look like punctuation.
+    quoted: bool,
+    line: usize,
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '.'
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, DotError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    let mut line = 1;
+    let punct = |text: &str, line| Token {
+        text: text.to_string(),
+        quoted: false,
+        line,
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => line += 1,
+            c if c.is_whitespace() => {}
+            '#' => while chars.next_if(|&c| c != '\n').is_some() {},
+            '/' if chars.next_if_eq(&'/').is_some() => {
+                while chars.next_if(|&c| c != '\n').is_some() {}
+            }
+            '/' if chars.next_if_eq(&'*').is_some() => {
+                let start = line;
+                let mut previous = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if previous == '*' => break,
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            previous = c;
+                        }
+                        None => return Err(error(start, "unterminated comment")),
+                    }
+                }
+            }
+            '"' => {
+                let start = line;
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => value.push(c),
+                            Some(c) => {
+                                value.push('\\');
+                                value.push(c);
+                            }
+                            None => return Err(error(start, "unterminated string")),
+                        },
+                        Some(c) => {
+                            if c == '\n' {
+                                line += 1;
+                            }
+                            value.push(c);
+                        }
+                        None => return Err(error(start, "unterminated string")),
+                    }
+                }
+                tokens.push(Token {
+                    text: value,
+                    quoted: true,
+                    line: start,
+                });
+            }
+            '-' if chars.next_if_eq(&'>').is_some() => tokens.push(punct("->", line)),
+            '-' if chars.next_if_eq(&'-').is_some() => tokens.push(punct("--", line)),
+            '{' | '}' | '[' | ']' | '=' | ';' | ',' => {
+                tokens.push(punct(c.encode_utf8(&mut [0; 4]), line))
+            }
+            c if is_id_char(c) => {
+                let mut value = c.to_string();
+                while let Some(c) = chars.next_if(|&c| is_id_char(c)) {
+                    value.push(c);
+                }
+                tokens.push(Token {
+                    text: value,
+                    quoted: false,
+                    line,
+                });
+            }
+            c => return Err(error(line, format!("unexpected character {:?}", c))),
+        }
+    }
+    Ok(tokens)
+}
+
+type Attributes = Vec<(String, String)>;
+
+/// Statements of a graph, before interpreting them as an automaton.
+#[derive(Default)]
+struct Statements {
+    /// Node names in the order of appearance.
+    names: Vec<String>,
+    nodes: Vec<(String, Attributes)>,
+    edges: Vec<(usize, String, String, Attributes)>,
+}
+
+impl Statements {
+    fn mention(&mut self, name: &str) {
+        if !self.names.iter().any(|known| known == name) {
+            self.names.push(name.to_string());
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek_is(&self, punct: &str) -> bool {
+        self.tokens
+            .get(self.pos)
+            .is_some_and(|token| !token.quoted && token.text == punct)
+    }
+
+    fn line(&self) -> usize {
+        match self.tokens.get(self.pos).or(self.tokens.last()) {
+            Some(token) => token.line,
+            None => 1,
+        }
+    }
+
+    fn eat(&mut self, punct: &str) -> bool {
+        let found = self.peek_is(punct);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, punct: &str) -> Result<(), DotError> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(error(self.line(), format!("expected '{}'", punct)))
+        }
+    }
+
+    fn id(&mut self) -> Result<String, DotError> {
+        match self.tokens.get(self.pos) {
+            Some(token) if token.quoted || is_id_char(token.text.chars().next().unwrap()) => {
+                self.pos += 1;
+                Ok(token.text.clone())
+            }
+            Some(token) => Err(error(
+                token.line,
+                format!("expected a name, found '{}'", token.text),
+            )),
+            None => Err(error(self.line(), "unexpected end of input")),
+        }
+    }
+
+    /// Zero or more attribute lists `[a=b, ...]`, merged.
+    fn attributes(&mut self) -> Result<Attributes, DotError> {
+        let mut attributes = Vec::new();
+        while self.eat("[") {
+            while !self.eat("]") {
+                let key = self.id()?;
+                self.expect("=")?;
+                let value = self.id()?;
+                attributes.push((key, value));
+                if !self.eat(",") {
+                    self.eat(";");
+                }
+            }
+        }
+        Ok(attributes)
+    }
+
+    fn graph(&mut self) -> Result<Statements, DotError> {
+        self.eat("strict");
+        if self.peek_is("graph") {
+            return Err(error(self.line(), "undirected graphs are not supported"));
+        }
+        self.expect("digraph")?;
+        if !self.peek_is("{") {
+            self.id()?;
+        }
+        self.expect("{")?;
+        let mut statements = Statements::default();
+        while !self.eat("}") {
+            self.statement(&mut statements)?;
+            self.eat(";");
+        }
+        if self.pos < self.tokens.len() {
+            return Err(error(self.line(), "unexpected input after the graph"));
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self, statements: &mut Statements) -> Result<(), DotError> {
+        let line = self.line();
+        if self.peek_is("subgraph") || self.peek_is("{") {
+            return Err(error(line, "subgraphs are not supported"));
+        }
+        let is_default = ["graph", "node", "edge"].iter().any(|kw| self.peek_is(kw));
+        let name = self.id()?;
+        if is_default {
+            self.attributes()?;
+            return Ok(());
+        }
+        if self.eat("=") {
+            self.id()?;
+            return Ok(());
+        }
+        let mut names = vec![name];
+        while self.eat("->") {
+            names.push(self.id()?);
+        }
+        if self.peek_is("--") {
+            return Err(error(self.line(), "undirected edges are not supported"));
+        }
+        let attributes = self.attributes()?;
+        for name in &names {
+            statements.mention(name);
+        }
+        if let [name] = names.as_slice() {
+            statements.nodes.push((name.clone(), attributes));
+        } else {
+            for pair in names.windows(2) {
+                let (from, to) = (pair[0].clone(), pair[1].clone());
+                statements.edges.push((line, from, to, attributes.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn attribute<'a>(attributes: &'a Attributes, key: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .rev()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value.as_str())
+}
+
+struct Transition {
+    line: usize,
+    from: usize,
+    to: usize,
+    label: Option<String>,
+}
+
+/// Automaton read from DOT, with states numbered from the initial one.
+struct Graph {
+    accepting: Vec<bool>,
+    transitions: Vec<Transition>,
+}
+
+fn parse_graph(text: &str) -> Result<Graph, DotError> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        pos: 0,
+    };
+    let statements = parser.graph()?;
+
+    let mut shapes = HashMap::new();
+    for (name, attributes) in &statements.nodes {
+        if let Some(shape) = attribute(attributes, "shape") {
+            shapes.insert(name.as_str(), shape);
+        }
+    }
+    let shape = |name: &str| shapes.get(name).copied().unwrap_or_default();
+    let is_state = |name: &str| !matches!(shape(name), "point" | "plaintext");
+
+    let mut initial = None;
+    for (line, from, to, _) in &statements.edges {
+        if shape(from) == "point" && is_state(to) {
+            if initial.is_some_and(|initial| initial != to) {
+                return Err(error(*line, "more than one initial state"));
+            }
+            initial = Some(to);
+        }
+    }
+    let mut names = statements
+        .names
+        .iter()
+        .filter(|name| is_state(name))
+        .collect::<Vec<_>>();
+    if let Some(initial) = initial {
+        let index = names.iter().position(|&name| name == initial).unwrap();
+        let initial = names.remove(index);
+        names.insert(0, initial);
+    }
+    let ids = names
+        .iter()
+        .enumerate()
+        .map(|(id, name)| (name.as_str(), id))
+        .collect::<HashMap<_, _>>();
+
+    let accepting = names
+        .iter()
+        .map(|name| shape(name) == "doublecircle")
+        .collect();
+    let transitions = statements
+        .edges
+        .iter()
+        .filter(|(_, from, to, _)| is_state(from) && is_state(to))
+        .map(|(line, from, to, attributes)| Transition {
+            line: *line,
+            from: ids[from.as_str()],
+            to: ids[to.as_str()],
+            label: attribute(attributes, "label").map(str::to_string),
+        })
+        .collect();
+    Ok(Graph {
+        accepting,
+        transitions,
+    })
+}
+
+/// Symbols of an edge label, see the [module docs](self).
+fn symbols<A: FromStr>(label: &str, line: usize) -> Result<Vec<A>, DotError> {
+    if let Ok(symbol) = label.parse() {
+        return Ok(vec![symbol]);
+    }
+    label
+        .split(',')
+        .map(|part| {
+            part.trim()
+                .parse()
+                .map_err(|_| error(line, format!("invalid symbol {:?}", part.trim())))
+        })
+        .collect()
+}
+
+impl<A: Alphabet + FromStr> Dfa<A> {
+    /// Reads a DFA from a subset of DOT, e.g. the output of [`render_graphviz`](Dfa::render_graphviz),
+    /// see the [module docs](crate::dot) for the details.
+    ///
+    /// Fails on syntax errors, unlabeled edges, and edges on the same symbol
+    /// leaving a state to different targets.
+    pub fn from_dot(text: &str) -> Result<Self, DotError> {
+        let graph = parse_graph(text)?;
+        let mut dfa = Dfa::new().with_conflict_policy(ConflictPolicy::Error);
+        for accepting in graph.accepting {
+            dfa.add_state(accepting);
+        }
+        for transition in graph.transitions {
+            let line = transition.line;
+            let Some(label) = transition.label else {
+                return Err(error(line, "transition without a label"));
+            };
+            let from = DfaStateId::new(transition.from);
+            let to = DfaStateId::new(transition.to);
+            for symbol in symbols(&label, line)? {
+                dfa.try_add_transition(from, symbol, to)
+                    .map_err(|conflict| error(line, conflict.to_string()))?;
+            }
+        }
+        dfa.set_conflict_policy(ConflictPolicy::default());
+        Ok(dfa)
+    }
+}
+
+impl<A: Alphabet + FromStr> Nfa<A> {
+    /// Reads an NFA from a subset of DOT, e.g. the output of [`render_graphviz`](Nfa::render_graphviz),
+    /// see the [module docs](crate::dot) for the details.
+    pub fn from_dot(text: &str) -> Result<Self, DotError> {
+        let graph = parse_graph(text)?;
+        let mut nfa = Nfa::new();
+        for accepting in graph.accepting {
+            nfa.add_state(accepting);
+        }
+        for transition in graph.transitions {
+            let from = NfaStateId::new(transition.from);
+            let to = NfaStateId::new(transition.to);
+            match transition.label.as_deref() {
+                None | Some("ε") => nfa.add_epsilon_transition(from, to),
+                Some(label) => {
+                    for symbol in symbols(label, transition.line)? {
+                        nfa.add_transition(from, symbol, to);
+                    }
+                }
+            }
+        }
+        Ok(nfa)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dfa::graphviz::{GraphvizOptions, SinkRendering};
+
+    #[test]
+    fn test_dfa_from_dot() {
+        let dot = r#"
+            // Words over {a, b} ending with "ab"
+            strict digraph "ends with ab" {
+                rankdir=LR; node [shape=circle]
+                q2 [shape=doublecircle, label="done"];
+                /* The initial state is
+                   declared last */
+                start [shape=point]; start -> q0
+                q0 -> q1 -> q1 [label=a]
+                q1 -> q2 [label="b"]
+                q2 -> q1 [label=a]; q2 -> q0 [label=b]; q0 -> q0 [label=b]
+            }
+        "#;
+        let dfa = Dfa::<char>::from_dot(dot).unwrap();
+        assert_eq!(dfa.num_states(), 3);
+        assert_eq!(dfa.num_transitions(), 6);
+        assert!(dfa.accepts("ab".chars()));
+        assert!(dfa.accepts("bbaab".chars()));
+        assert!(!dfa.accepts("aba".chars()));
+        // States are numbered in the order of appearance, after the initial one:
+        assert!(dfa.accepting(DfaStateId::new(1)));
+        assert_eq!(dfa.next(DfaStateId::new(0), 'a'), Some(DfaStateId::new(2)));
+    }
+
+    #[test]
+    fn test_dfa_from_dot_round_trip() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let sink = dfa.add_state(false);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q0, 'b', sink);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, 'b', sink);
+        dfa.add_transition(sink, 'a', sink);
+        dfa.add_transition(sink, 'b', sink);
+
+        let read = Dfa::<char>::from_dot(&dfa.render_graphviz()).unwrap();
+        assert_eq!(read.num_states(), 3);
+        assert_eq!(read.num_transitions(), 6);
+        assert!(read.equivalent(&dfa));
+
+        for sinks in [SinkRendering::Collapse, SinkRendering::Hide] {
+            let options = GraphvizOptions {
+                sinks,
+                ..Default::default()
+            };
+            let read = Dfa::<char>::from_dot(&dfa.render_graphviz_with(&options)).unwrap();
+            assert!(read.equivalent(&dfa), "{:?}", sinks);
+        }
+    }
+
+    #[test]
+    fn test_nfa_from_dot() {
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let q2 = nfa.add_state(true);
+        nfa.add_transition(q0, 'a', q0);
+        nfa.add_transition(q0, 'a', q1);
+        nfa.add_epsilon_transition(q1, q2);
+        nfa.add_transition(q2, 'b', q0);
+
+        let read = Nfa::<char>::from_dot(&nfa.render_graphviz()).unwrap();
+        assert_eq!(read.num_states(), 3);
+        assert_eq!(read.num_transitions(), 3);
+        assert_eq!(read.num_epsilon_transitions(), 1);
+        assert!(read.determinize().equivalent(&nfa.determinize()));
+
+        let nfa =
+            Nfa::<u32>::from_dot("digraph { 0 -> 1 [label=\"1, 2\"]; 1 [shape=doublecircle] }")
+                .unwrap();
+        assert!(nfa.accepts([2]));
+        assert!(!nfa.accepts([3]));
+    }
+
+    #[test]
+    fn test_from_dot_errors() {
+        let check = |dot: &str, line: usize, message: &str| {
+            let error = Dfa::<char>::from_dot(dot).unwrap_err();
+            assert_eq!(error.line, line, "{}", error);
+            assert!(error.message.contains(message), "{}", error);
+        };
+        check("digraph {\n  0 -> 1\n}", 2, "without a label");
+        check(
+            "digraph {\n  0 -> 1 [label=a]\n  0 -> 0 [label=a]\n}",
+            3,
+            "already has",
+        );
+        check("digraph {\n  0 -> 1 [label=ab]\n}", 2, "invalid symbol");
+        check("graph { 0 -- 1 }", 1, "undirected");
+        check(
+            "digraph {\n  0 -> 1 [label=\"a]\n}",
+            2,
+            "unterminated string",
+        );
+        check("digraph {\n  0 -> 1 [label=a]\n", 2, "unexpected end");
+        check("digraph { 0 -> 1 [label=a] } x", 1, "after the graph");
+        check(
+            "digraph { s [shape=point]; s -> 0; s -> 1 }",
+            1,
+            "more than one initial",
+        );
+    }
+
+    #[test]
+    fn test_from_dot_round_trip_punctuation() {
+        let symbols = [',', '"', '\\', ';', '=', ']', '{', '#', ' '];
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        let mut nfa = Nfa::new();
+        let p0 = nfa.add_state(false);
+        let p1 = nfa.add_state(true);
+        for (i, &symbol) in symbols.iter().enumerate() {
+            let to = if i % 2 == 0 { q0 } else { q1 };
+            dfa.add_transition(q0, symbol, to);
+            dfa.add_transition(q1, symbol, q1);
+            nfa.add_transition(p0, symbol, p1);
+            nfa.add_transition(p1, symbol, p0);
+        }
+
+        let read = Dfa::<char>::from_dot(&dfa.render_graphviz()).unwrap();
+        assert_eq!(read.num_transitions(), dfa.num_transitions());
+        assert!(read.equivalent(&dfa));
+
+        let read = Nfa::<char>::from_dot(&nfa.render_graphviz()).unwrap();
+        assert_eq!(read.num_transitions(), nfa.num_transitions());
+        assert!(read.determinize().equivalent(&nfa.determinize()));
+    }
+}
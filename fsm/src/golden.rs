@@ -0,0 +1,393 @@
+//! Golden-file testing of textual outputs (DOT, Mermaid, JSON), for this crate and for
+//! downstream renderers.
+//!
+//! [`assert_golden`] compares an output with the file storing its expected version.
+//! Both are [normalized](Format::normalize) first, so that an incidental ordering (e.g. of
+//! transitions stored in a hash map) does not break the test. A missing golden file is
+//! created, and all golden files are rewritten when the [`UPDATE_ENV`] environment variable
+//! is set, so the workflow after an intended change is:
+//!
+//! ```text
+//! UPDATE_GOLDEN=1 cargo test
+//! git diff  # review the new outputs
+//! ```
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Environment variable which, when set, makes [`assert_golden`] overwrite the golden files.
+pub const UPDATE_ENV: &str = "UPDATE_GOLDEN";
+
+/// Format of a golden file, telling how to normalize it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// Graphviz, one statement per line: the statements of every block are sorted.
+    Dot,
+    /// Mermaid: the lines after the diagram header are sorted.
+    Mermaid,
+    /// JSON: object keys are sorted and the document is pretty-printed.
+    /// Array order is significant and kept.
+    Json,
+    /// Any other text: only line endings and trailing whitespace are normalized.
+    Text,
+}
+
+impl Format {
+    /// Normalizes `text`, ending it with a single newline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `text` is not valid JSON in the [`Format::Json`] format.
+    pub fn normalize(self, text: &str) -> String {
+        let lines = text.lines().map(str::trim_end);
+        let mut normalized = match self {
+            Format::Dot => normalize_dot(lines),
+            Format::Mermaid => {
+                let mut lines = lines.map(str::trim).filter(|line| !line.is_empty());
+                let header = lines.next().map(str::to_string);
+                let mut body = lines.map(|line| format!("  {}", line)).collect::<Vec<_>>();
+                body.sort();
+                header
+                    .into_iter()
+                    .chain(body)
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+            Format::Json => {
+                let mut parser = JsonParser { text, offset: 0 };
+                let value = parser
+                    .document()
+                    .unwrap_or_else(|offset| panic!("invalid JSON at offset {}", offset));
+                let mut out = String::new();
+                value.write(&mut out, 0);
+                out
+            }
+            Format::Text => lines.collect::<Vec<_>>().join("\n"),
+        };
+        let len = normalized.trim_end().len();
+        normalized.truncate(len);
+        normalized.push('\n');
+        normalized
+    }
+}
+
+/// Sorts the statements of every `{ ... }` block, keeping nested blocks together.
+fn normalize_dot<'a>(lines: impl Iterator<Item = &'a str>) -> String {
+    // Statements of the open blocks, innermost last, each starting with its header:
+    let mut blocks: Vec<Vec<String>> = vec![Vec::new()];
+    for line in lines.map(str::trim).filter(|line| !line.is_empty()) {
+        let line = line.trim_end_matches(';');
+        let indent = "  ".repeat(blocks.len() - 1);
+        if line.ends_with('{') {
+            blocks.push(vec![format!("{}{}", indent, line)]);
+        } else if line == "}" && blocks.len() > 1 {
+            let mut block = blocks.pop().unwrap();
+            block[1..].sort();
+            block.push(format!("{}}}", "  ".repeat(blocks.len() - 1)));
+            // A nested block is sorted as a single statement of its parent:
+            blocks.last_mut().unwrap().push(block.join("\n"));
+        } else {
+            blocks
+                .last_mut()
+                .unwrap()
+                .push(format!("{}{}", indent, line));
+        }
+    }
+    blocks.concat().join("\n")
+}
+
+enum JsonValue<'a> {
+    /// A number, `true`, `false` or `null`, as written.
+    Scalar(&'a str),
+    /// A string literal, as written (with quotes and escapes).
+    String(&'a str),
+    Array(Vec<JsonValue<'a>>),
+    Object(BTreeMap<&'a str, JsonValue<'a>>),
+}
+
+impl JsonValue<'_> {
+    fn write(&self, out: &mut String, depth: usize) {
+        let indent = |depth: usize| "  ".repeat(depth);
+        match self {
+            JsonValue::Scalar(text) | JsonValue::String(text) => out.push_str(text),
+            JsonValue::Array(items) if items.is_empty() => out.push_str("[]"),
+            JsonValue::Object(entries) if entries.is_empty() => out.push_str("{}"),
+            JsonValue::Array(items) => {
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(",\n");
+                    }
+                    out.push_str(&indent(depth + 1));
+                    item.write(out, depth + 1);
+                }
+                write!(out, "\n{}]", indent(depth)).unwrap();
+            }
+            JsonValue::Object(entries) => {
+                out.push_str("{\n");
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(",\n");
+                    }
+                    write!(out, "{}{}: ", indent(depth + 1), key).unwrap();
+                    value.write(out, depth + 1);
+                }
+                write!(out, "\n{}}}", indent(depth)).unwrap();
+            }
+        }
+    }
+}
+
+/// Minimal JSON parser keeping scalars and strings as written. Errors are byte offsets.
+struct JsonParser<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn document(&mut self) -> Result<JsonValue<'a>, usize> {
+        let value = self.value()?;
+        if self.peek().is_some() {
+            return Err(self.offset);
+        }
+        Ok(value)
+    }
+
+    /// Next byte after whitespace, which is skipped.
+    fn peek(&mut self) -> Option<u8> {
+        let rest = &self.text[self.offset..];
+        self.offset += rest.len() - rest.trim_start().len();
+        self.text.as_bytes().get(self.offset).copied()
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), usize> {
+        if self.peek() != Some(byte) {
+            return Err(self.offset);
+        }
+        self.offset += 1;
+        Ok(())
+    }
+
+    /// Parses comma-separated items up to the `close` byte, the opening one being consumed.
+    fn items(
+        &mut self,
+        close: u8,
+        mut item: impl FnMut(&mut Self) -> Result<(), usize>,
+    ) -> Result<(), usize> {
+        if self.peek() == Some(close) {
+            self.offset += 1;
+            return Ok(());
+        }
+        loop {
+            item(self)?;
+            if self.peek() != Some(b',') {
+                break;
+            }
+            self.offset += 1;
+        }
+        self.expect(close)
+    }
+
+    fn value(&mut self) -> Result<JsonValue<'a>, usize> {
+        match self.peek().ok_or(self.offset)? {
+            b'{' => {
+                self.offset += 1;
+                let mut entries = BTreeMap::new();
+                self.items(b'}', |parser| {
+                    if parser.peek() != Some(b'"') {
+                        return Err(parser.offset);
+                    }
+                    let key = parser.string()?;
+                    parser.expect(b':')?;
+                    entries.insert(key, parser.value()?);
+                    Ok(())
+                })?;
+                Ok(JsonValue::Object(entries))
+            }
+            b'[' => {
+                self.offset += 1;
+                let mut items = Vec::new();
+                self.items(b']', |parser| {
+                    items.push(parser.value()?);
+                    Ok(())
+                })?;
+                Ok(JsonValue::Array(items))
+            }
+            b'"' => Ok(JsonValue::String(self.string()?)),
+            _ => {
+                let start = self.offset;
+                let rest = &self.text[start..];
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || "+-.".contains(c)))
+                    .unwrap_or(rest.len());
+                if len == 0 {
+                    return Err(start);
+                }
+                self.offset += len;
+                Ok(JsonValue::Scalar(&rest[..len]))
+            }
+        }
+    }
+
+    /// Parses the string literal at the current offset, returning it with its quotes.
+    fn string(&mut self) -> Result<&'a str, usize> {
+        let start = self.offset;
+        let mut escaped = false;
+        for (i, c) in self.text[start + 1..].char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => {
+                    self.offset = start + 1 + i + 1;
+                    return Ok(&self.text[start..self.offset]);
+                }
+                _ => {}
+            }
+        }
+        Err(start)
+    }
+}
+
+/// Asserts that `actual` matches the golden file at `path`, after normalizing both
+/// according to `format`.
+///
+/// Relative paths are resolved against the working directory, which `cargo test` sets
+/// to the root of the tested crate. If the file does not exist, or the [`UPDATE_ENV`]
+/// variable is set, the normalized output is written there instead (creating directories).
+///
+/// # Panics
+///
+/// Panics on a mismatch, showing the first differing line, or if the file can't be accessed.
+pub fn assert_golden(path: impl AsRef<Path>, format: Format, actual: &str) {
+    let path = path.as_ref();
+    let actual = format.normalize(actual);
+    if std::env::var_os(UPDATE_ENV).is_some() || !path.exists() {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .unwrap_or_else(|e| panic!("can't create {}: {}", dir.display(), e));
+        }
+        std::fs::write(path, &actual)
+            .unwrap_or_else(|e| panic!("can't write {}: {}", path.display(), e));
+        return;
+    }
+    let expected = std::fs::read_to_string(path)
+        .unwrap_or_else(|e| panic!("can't read {}: {}", path.display(), e));
+    let expected = format.normalize(&expected);
+    if actual == expected {
+        return;
+    }
+    let mut expected_lines = expected.lines().map(Some).chain(std::iter::repeat(None));
+    let mut actual_lines = actual.lines().map(Some).chain(std::iter::repeat(None));
+    let mut line = 1;
+    loop {
+        let (e, a) = (expected_lines.next().unwrap(), actual_lines.next().unwrap());
+        if e != a {
+            panic!(
+                "output differs from {} at line {}:\n  expected: {}\n  actual:   {}\n\
+                 rerun with {}=1 to update the golden file",
+                path.display(),
+                line,
+                e.unwrap_or("<end of file>"),
+                a.unwrap_or("<end of output>"),
+                UPDATE_ENV
+            );
+        }
+        line += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let dot =
+            "digraph {\n  rankdir=LR;\n  1 -> 0\n  0 -> 1\n  subgraph x {\n    b\n    a\n  }\n}";
+        let shuffled =
+            "digraph {\n  subgraph x {\n a\n b\n }\r\n  0 -> 1\n  1 -> 0\n  rankdir=LR\n}\n";
+        assert_eq!(Format::Dot.normalize(dot), Format::Dot.normalize(shuffled));
+        assert_eq!(
+            Format::Dot.normalize(dot),
+            "digraph {\n  0 -> 1\n  1 -> 0\n  rankdir=LR\n  subgraph x {\n    a\n    b\n  }\n}\n"
+        );
+
+        let mermaid = "graph TD\n  n1 --> n0\n  n0[\"a\"]\n";
+        assert_eq!(
+            Format::Mermaid.normalize(mermaid),
+            "graph TD\n  n0[\"a\"]\n  n1 --> n0\n"
+        );
+
+        let json = r#"{"states": [{"id": 0, "transitions": {"b": 1, "a": 0}}], "name": "x\"}"}"#;
+        assert_eq!(
+            Format::Json.normalize(json),
+            "{\n  \"name\": \"x\\\"}\",\n  \"states\": [\n    {\n      \"id\": 0,\n      \
+             \"transitions\": {\n        \"a\": 0,\n        \"b\": 1\n      }\n    }\n  ]\n}\n"
+        );
+        assert_eq!(Format::Json.normalize(" [ ] "), "[]\n");
+
+        assert_eq!(Format::Text.normalize("a  \r\nb\n\n\n"), "a\nb\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid JSON at offset 6")]
+    fn test_normalize_invalid_json() {
+        Format::Json.normalize("[1, 2,]");
+    }
+
+    #[test]
+    fn test_assert_golden() {
+        let dir = std::env::temp_dir().join(format!("fsm-golden-{}", std::process::id()));
+        let path = dir.join("dfa.dot");
+        let _ = std::fs::remove_file(&path);
+
+        // The first run creates the golden file:
+        assert_golden(&path, Format::Dot, "digraph {\n  0 -> 1\n  1 -> 0\n}");
+        assert!(path.exists());
+        // Ordering changes are fine:
+        assert_golden(&path, Format::Dot, "digraph {\n  1 -> 0\n  0 -> 1\n}");
+
+        let result = std::panic::catch_unwind(|| {
+            assert_golden(&path, Format::Dot, "digraph {\n  0 -> 0\n  1 -> 0\n}");
+        });
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("at line 2"), "{}", message);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_assert_golden_nfa_json() {
+        use crate::nfa::Nfa;
+
+        let dir = std::env::temp_dir().join(format!("fsm-golden-nfa-{}", std::process::id()));
+        let path = dir.join("nfa.json");
+        let _ = std::fs::remove_file(&path);
+
+        // Each NFA has its own hash sets, so their iteration order differs between runs:
+        let build = || {
+            let mut nfa = Nfa::<char>::new();
+            let q0 = nfa.add_state(false);
+            for _ in 0..8 {
+                let q = nfa.add_state(true);
+                nfa.add_epsilon_transition(q0, q);
+            }
+            nfa
+        };
+        let json = serde_json::to_string(&build()).unwrap();
+        assert!(
+            json.contains(r#""epsilon_transitions":[1,2,3,4,5,6,7,8]"#),
+            "{}",
+            json
+        );
+        for _ in 0..10 {
+            assert_golden(
+                &path,
+                Format::Json,
+                &serde_json::to_string(&build()).unwrap(),
+            );
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,175 @@
+//! Export of automata in the [Hanoi Omega-Automata format](https://adl.github.io/hoaf/) (HOA),
+//! for exchange with tools such as Spot and Owl, see [`Dfa::to_hoa`] and [`Nfa::to_hoa`].
+//!
+//! HOA describes automata over infinite words, so the export only carries the structure
+//! of a finite automaton: its states, transitions and accepting states. Accepting states
+//! are marked with the acceptance set 0 of a Büchi condition (`Inf(0)`), and the tools reading
+//! the file interpret it over infinite words. There is one atomic proposition per symbol,
+//! named by its [`Display`], and a transition on a symbol is labeled with the conjunction
+//! requiring exactly its proposition.
+//!
+//! Only export is supported, as reading HOA needs automata with omega acceptance conditions.
+
+use std::fmt::{Display, Write};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Writes a HOA file with transitions `(from, symbol index, to)`, sorted by their source.
+fn write_hoa(
+    accepting: &[bool],
+    symbols: &[String],
+    mut transitions: Vec<(usize, usize, usize)>,
+    deterministic: bool,
+) -> String {
+    transitions.sort_unstable();
+    let mut out = String::new();
+    writeln!(out, "HOA: v1").unwrap();
+    writeln!(out, "States: {}", accepting.len()).unwrap();
+    if !accepting.is_empty() {
+        writeln!(out, "Start: 0").unwrap();
+    }
+    write!(out, "AP: {}", symbols.len()).unwrap();
+    for symbol in symbols {
+        write!(out, " {}", quote(symbol)).unwrap();
+    }
+    writeln!(out).unwrap();
+    writeln!(out, "acc-name: Buchi").unwrap();
+    writeln!(out, "Acceptance: 1 Inf(0)").unwrap();
+    write!(out, "properties: trans-labels explicit-labels state-acc").unwrap();
+    if deterministic {
+        write!(out, " deterministic").unwrap();
+    }
+    writeln!(out).unwrap();
+    writeln!(out, "--BODY--").unwrap();
+    let label = |symbol: usize| {
+        (0..symbols.len())
+            .map(|i| {
+                if i == symbol {
+                    format!("{}", i)
+                } else {
+                    format!("!{}", i)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("&")
+    };
+    let mut transitions = transitions.into_iter().peekable();
+    for (state, &accepting) in accepting.iter().enumerate() {
+        write!(out, "State: {}", state).unwrap();
+        if accepting {
+            write!(out, " {{0}}").unwrap();
+        }
+        writeln!(out).unwrap();
+        while let Some((_, symbol, to)) = transitions.next_if(|&(from, _, _)| from == state) {
+            writeln!(out, "[{}] {}", label(symbol), to).unwrap();
+        }
+    }
+    writeln!(out, "--END--").unwrap();
+    out
+}
+
+impl<A: Alphabet + Display, S, T> Dfa<A, S, T> {
+    /// Writes the structure of the automaton in the HOA format, see the [module docs](crate::hoa).
+    pub fn to_hoa(&self) -> String {
+        let symbols = self.symbols();
+        let accepting = self
+            .states()
+            .map(|state| state.accepting)
+            .collect::<Vec<_>>();
+        let transitions = self
+            .transitions()
+            .map(|(from, symbol, to)| {
+                let symbol = symbols.binary_search(&symbol).unwrap();
+                (from.id.index(), symbol, to.id.index())
+            })
+            .collect();
+        let names = symbols
+            .iter()
+            .map(|symbol| symbol.to_string())
+            .collect::<Vec<_>>();
+        write_hoa(&accepting, &names, transitions, true)
+    }
+}
+
+impl<A: Alphabet + Display> Nfa<A> {
+    /// Writes the structure of the automaton in the HOA format, see the [module docs](crate::hoa).
+    ///
+    /// HOA has no ε-transitions, so they are [removed](Nfa::remove_epsilon) first.
+    pub fn to_hoa(&self) -> String {
+        let nfa = self.remove_epsilon();
+        let symbols = nfa.symbols();
+        let accepting = nfa
+            .states()
+            .map(|state| state.accepting)
+            .collect::<Vec<_>>();
+        let transitions = nfa
+            .transitions()
+            .map(|(from, symbol, to)| {
+                let symbol = symbols.binary_search(&symbol).unwrap();
+                (from.id.index(), symbol, to.id.index())
+            })
+            .collect();
+        let names = symbols
+            .iter()
+            .map(|symbol| symbol.to_string())
+            .collect::<Vec<_>>();
+        write_hoa(&accepting, &names, transitions, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dfa_to_hoa() {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'a', q1);
+        dfa.add_transition(q1, '"', q0);
+        assert_eq!(
+            dfa.to_hoa(),
+            "HOA: v1\n\
+             States: 2\n\
+             Start: 0\n\
+             AP: 2 \"\\\"\" \"a\"\n\
+             acc-name: Buchi\n\
+             Acceptance: 1 Inf(0)\n\
+             properties: trans-labels explicit-labels state-acc deterministic\n\
+             --BODY--\n\
+             State: 0\n\
+             [!0&1] 1\n\
+             State: 1 {0}\n\
+             [0&!1] 0\n\
+             [!0&1] 1\n\
+             --END--\n"
+        );
+
+        let empty = Dfa::<char>::new().to_hoa();
+        assert!(empty.contains("States: 0\nAP: 0\n"));
+        assert!(!empty.contains("Start"));
+    }
+
+    #[test]
+    fn test_nfa_to_hoa() {
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let q2 = nfa.add_state(true);
+        nfa.add_transition(q0, 'x', q0);
+        nfa.add_transition(q0, 'x', q1);
+        nfa.add_epsilon_transition(q1, q2);
+        let hoa = nfa.to_hoa();
+        assert!(hoa.contains("AP: 1 \"x\"\n"));
+        assert!(hoa.contains("state-acc\n"));
+        assert!(hoa.contains("State: 0\n[0] 0\n[0] 1\nState: 1 {0}\nState: 2 {0}\n"));
+    }
+}
@@ -0,0 +1,644 @@
+//! Import and export of automata in the XML format of [JFLAP](https://www.jflap.org) (`.jff` files),
+//! see [`Dfa::from_jflap`] and [`Dfa::to_jflap`] (and the same for [`Nfa`]).
+//!
+//! Only finite automata (`<type>fa</type>`) are supported, in the layout of JFLAP 7
+//! (states and transitions inside `<automaton>`) or of older versions (directly inside
+//! `<structure>`). States are numbered in the order of the file, after the `<initial/>` one,
+//! which becomes state 0. The `<read>` of a transition is its symbol, parsed with [`FromStr`] and
+//! taken verbatim (so `<read> </read>` reads a space), and only an empty `<read/>` is a
+//! λ-transition (JFLAP's name for ε-transitions). Multi-symbol reads are not supported.
+//! State coordinates are ignored on import, and on export states are laid out on a grid,
+//! to be rearranged in JFLAP.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Write};
+use std::str::FromStr;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::conflict::ConflictPolicy;
+use crate::dfa::state::DfaStateId;
+use crate::dfa::Dfa;
+use crate::nfa::state::NfaStateId;
+use crate::nfa::Nfa;
+
+/// Error of reading an automaton from a JFLAP file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JflapError {
+    /// Line number, starting from 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for JflapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for JflapError {}
+
+fn error(line: usize, message: impl Into<String>) -> JflapError {
+    JflapError {
+        line,
+        message: message.into(),
+    }
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Element>,
+    text: String,
+    line: usize,
+}
+
+impl Element {
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn children<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |child| child.name == name)
+    }
+
+    fn child(&self, name: &str) -> Option<&Element> {
+        self.children.iter().find(|child| child.name == name)
+    }
+
+    fn child_text(&self, name: &str) -> Result<&str, JflapError> {
+        match self.child(name) {
+            Some(child) => Ok(&child.text),
+            None => Err(error(
+                self.line,
+                format!("<{}> without <{}>", self.name, name),
+            )),
+        }
+    }
+}
+
+/// Minimal XML reader, enough for the files written by JFLAP.
+struct Reader<'a> {
+    text: &'a str,
+    pos: usize,
+    line: usize,
+}
+
+impl Reader<'_> {
+    fn rest(&self) -> &str {
+        &self.text[self.pos..]
+    }
+
+    fn bump(&mut self, len: usize) {
+        self.line += self.text[self.pos..self.pos + len].matches('\n').count();
+        self.pos += len;
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        let found = self.rest().starts_with(prefix);
+        if found {
+            self.bump(prefix.len());
+        }
+        found
+    }
+
+    fn expect(&mut self, prefix: &str) -> Result<(), JflapError> {
+        if self.eat(prefix) {
+            Ok(())
+        } else {
+            Err(error(self.line, format!("expected {:?}", prefix)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let len = self.rest().len() - self.rest().trim_start().len();
+        self.bump(len);
+    }
+
+    /// Consumes everything up to and including `end`, returning the part before it.
+    fn until(&mut self, end: &str) -> Result<&str, JflapError> {
+        let start = self.pos;
+        match self.rest().find(end) {
+            Some(len) => {
+                self.bump(len + end.len());
+                Ok(&self.text[start..start + len])
+            }
+            None => Err(error(self.line, format!("missing {:?}", end))),
+        }
+    }
+
+    fn name(&mut self) -> Result<String, JflapError> {
+        let len = self
+            .rest()
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '='))
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(error(self.line, "expected a name"));
+        }
+        let name = self.rest()[..len].to_string();
+        self.bump(len);
+        Ok(name)
+    }
+
+    /// Skips the declaration, comments, processing instructions and the doctype.
+    fn skip_misc(&mut self) -> Result<(), JflapError> {
+        loop {
+            self.skip_whitespace();
+            if self.eat("<?") {
+                self.until("?>")?;
+            } else if self.eat("<!--") {
+                self.until("-->")?;
+            } else if self.eat("<!DOCTYPE") {
+                self.until(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn element(&mut self) -> Result<Element, JflapError> {
+        let line = self.line;
+        self.expect("<")?;
+        let mut element = Element {
+            name: self.name()?,
+            attributes: Vec::new(),
+            children: Vec::new(),
+            text: String::new(),
+            line,
+        };
+        loop {
+            self.skip_whitespace();
+            if self.eat("/>") {
+                return Ok(element);
+            }
+            if self.eat(">") {
+                break;
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let quote = if self.eat("\"") { "\"" } else { "'" };
+            if quote == "'" {
+                self.expect("'")?;
+            }
+            let line = self.line;
+            let value = self.until(quote)?;
+            element.attributes.push((key, decode(value, line)?));
+        }
+        loop {
+            if self.eat("</") {
+                let name = self.name()?;
+                if name != element.name {
+                    return Err(error(
+                        self.line,
+                        format!("</{}> closing <{}>", name, element.name),
+                    ));
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Ok(element);
+            } else if self.eat("<!--") {
+                self.until("-->")?;
+            } else if self.eat("<![CDATA[") {
+                element.text.push_str(self.until("]]>")?);
+            } else if self.eat("<?") {
+                self.until("?>")?;
+            } else if self.rest().starts_with('<') {
+                element.children.push(self.element()?);
+            } else if self.rest().is_empty() {
+                return Err(error(
+                    self.line,
+                    format!("unclosed element <{}>", element.name),
+                ));
+            } else {
+                let line = self.line;
+                let len = self.rest().find('<').unwrap_or(self.rest().len());
+                let text = &self.text[self.pos..self.pos + len];
+                self.bump(len);
+                element.text.push_str(&decode(text, line)?);
+            }
+        }
+    }
+}
+
+/// Replaces the predefined entities and char references.
+fn decode(text: &str, line: usize) -> Result<String, JflapError> {
+    let mut decoded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find(';') else {
+            return Err(error(line, "unterminated entity"));
+        };
+        let entity = &rest[start + 1..start + len];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => entity
+                    .strip_prefix('#')
+                    .and_then(|decimal| decimal.parse().ok())
+                    .and_then(char::from_u32),
+            },
+        };
+        match c {
+            Some(c) => decoded.push(c),
+            None => return Err(error(line, format!("unknown entity &{};", entity))),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    decoded.push_str(rest);
+    Ok(decoded)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+struct Transition {
+    line: usize,
+    from: usize,
+    to: usize,
+    /// `None` for a λ-transition.
+    read: Option<String>,
+}
+
+/// Automaton read from a JFLAP file, with states numbered from the initial one.
+struct Automaton {
+    accepting: Vec<bool>,
+    transitions: Vec<Transition>,
+}
+
+fn parse_automaton(text: &str) -> Result<Automaton, JflapError> {
+    let mut reader = Reader {
+        text,
+        pos: 0,
+        line: 1,
+    };
+    reader.skip_misc()?;
+    let root = reader.element()?;
+    reader.skip_misc()?;
+    if !reader.rest().is_empty() {
+        return Err(error(
+            reader.line,
+            "unexpected input after the root element",
+        ));
+    }
+    if root.name != "structure" {
+        return Err(error(
+            root.line,
+            format!("expected <structure>, found <{}>", root.name),
+        ));
+    }
+    if let Some(kind) = root.child("type") {
+        if kind.text.trim() != "fa" {
+            return Err(error(
+                kind.line,
+                format!("not a finite automaton: {:?}", kind.text.trim()),
+            ));
+        }
+    }
+    let automaton = root.child("automaton").unwrap_or(&root);
+
+    let mut states = automaton.children("state").collect::<Vec<_>>();
+    if let Some(index) = states
+        .iter()
+        .position(|state| state.child("initial").is_some())
+    {
+        let initial = states.remove(index);
+        states.insert(0, initial);
+    } else if let Some(state) = states.first() {
+        return Err(error(state.line, "no initial state"));
+    }
+    let mut ids = HashMap::new();
+    for (id, state) in states.iter().enumerate() {
+        let Some(name) = state.attribute("id") else {
+            return Err(error(state.line, "<state> without an id"));
+        };
+        if ids.insert(name, id).is_some() {
+            return Err(error(state.line, format!("duplicate state {:?}", name)));
+        }
+    }
+    if let Some(state) = states
+        .iter()
+        .skip(1)
+        .find(|state| state.child("initial").is_some())
+    {
+        return Err(error(state.line, "more than one initial state"));
+    }
+
+    let accepting = states
+        .iter()
+        .map(|state| state.child("final").is_some())
+        .collect();
+    let mut transitions = Vec::new();
+    for transition in automaton.children("transition") {
+        let state = |name: &str| {
+            let id = transition.child_text(name)?.trim();
+            ids.get(id)
+                .copied()
+                .ok_or_else(|| error(transition.line, format!("unknown state {:?}", id)))
+        };
+        let read = transition.child_text("read")?;
+        transitions.push(Transition {
+            line: transition.line,
+            from: state("from")?,
+            to: state("to")?,
+            read: (!read.is_empty()).then(|| read.to_string()),
+        });
+    }
+    Ok(Automaton {
+        accepting,
+        transitions,
+    })
+}
+
+fn symbol<A: FromStr>(read: &str, line: usize) -> Result<A, JflapError> {
+    read.parse()
+        .map_err(|_| error(line, format!("invalid symbol {:?}", read)))
+}
+
+/// Writes a JFLAP file with the given states and transitions (`None` for λ).
+fn write_automaton(
+    accepting: impl Iterator<Item = bool>,
+    transitions: impl Iterator<Item = (usize, Option<String>, usize)>,
+) -> String {
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#
+    )
+    .unwrap();
+    writeln!(out, "<structure>").unwrap();
+    writeln!(out, "\t<type>fa</type>").unwrap();
+    writeln!(out, "\t<automaton>").unwrap();
+    for (id, accepting) in accepting.enumerate() {
+        writeln!(out, "\t\t<state id=\"{}\" name=\"q{}\">", id, id).unwrap();
+        writeln!(out, "\t\t\t<x>{:.1}</x>", 100.0 + 150.0 * (id % 8) as f64).unwrap();
+        writeln!(out, "\t\t\t<y>{:.1}</y>", 100.0 + 150.0 * (id / 8) as f64).unwrap();
+        if id == 0 {
+            writeln!(out, "\t\t\t<initial/>").unwrap();
+        }
+        if accepting {
+            writeln!(out, "\t\t\t<final/>").unwrap();
+        }
+        writeln!(out, "\t\t</state>").unwrap();
+    }
+    for (from, read, to) in transitions {
+        writeln!(out, "\t\t<transition>").unwrap();
+        writeln!(out, "\t\t\t<from>{}</from>", from).unwrap();
+        writeln!(out, "\t\t\t<to>{}</to>", to).unwrap();
+        match read {
+            Some(read) => writeln!(out, "\t\t\t<read>{}</read>", escape(&read)).unwrap(),
+            None => writeln!(out, "\t\t\t<read/>").unwrap(),
+        }
+        writeln!(out, "\t\t</transition>").unwrap();
+    }
+    writeln!(out, "\t</automaton>").unwrap();
+    writeln!(out, "</structure>").unwrap();
+    out
+}
+
+impl<A: Alphabet + FromStr> Dfa<A> {
+    /// Reads a DFA from a JFLAP file, see the [module docs](crate::jflap) for the details.
+    ///
+    /// Fails on malformed files, λ-transitions, and transitions on the same symbol
+    /// leaving a state to different targets.
+    pub fn from_jflap(text: &str) -> Result<Self, JflapError> {
+        let automaton = parse_automaton(text)?;
+        let mut dfa = Dfa::new().with_conflict_policy(ConflictPolicy::Error);
+        for accepting in automaton.accepting {
+            dfa.add_state(accepting);
+        }
+        for transition in automaton.transitions {
+            let line = transition.line;
+            let Some(read) = transition.read else {
+                return Err(error(line, "λ-transition in a DFA"));
+            };
+            let from = DfaStateId::new(transition.from);
+            let to = DfaStateId::new(transition.to);
+            dfa.try_add_transition(from, symbol(&read, line)?, to)
+                .map_err(|conflict| error(line, conflict.to_string()))?;
+        }
+        dfa.set_conflict_policy(ConflictPolicy::default());
+        Ok(dfa)
+    }
+}
+
+impl<A: Alphabet + Display, S, T> Dfa<A, S, T> {
+    /// Writes the automaton as a JFLAP file, with symbols written by [`Display`].
+    pub fn to_jflap(&self) -> String {
+        write_automaton(
+            self.states().map(|state| state.accepting),
+            self.transitions().map(|(from, symbol, to)| {
+                (from.id.index(), Some(symbol.to_string()), to.id.index())
+            }),
+        )
+    }
+}
+
+impl<A: Alphabet + FromStr> Nfa<A> {
+    /// Reads an NFA from a JFLAP file, see the [module docs](crate::jflap) for the details.
+    pub fn from_jflap(text: &str) -> Result<Self, JflapError> {
+        let automaton = parse_automaton(text)?;
+        let mut nfa = Nfa::new();
+        for accepting in automaton.accepting {
+            nfa.add_state(accepting);
+        }
+        for transition in automaton.transitions {
+            let from = NfaStateId::new(transition.from);
+            let to = NfaStateId::new(transition.to);
+            match transition.read {
+                Some(read) => nfa.add_transition(from, symbol(&read, transition.line)?, to),
+                None => nfa.add_epsilon_transition(from, to),
+            }
+        }
+        Ok(nfa)
+    }
+}
+
+impl<A: Alphabet + Display> Nfa<A> {
+    /// Writes the automaton as a JFLAP file, with symbols written by [`Display`]
+    /// and ε-transitions as λ-transitions.
+    pub fn to_jflap(&self) -> String {
+        let transitions = self
+            .transitions()
+            .map(|(from, symbol, to)| (from.id.index(), Some(symbol.to_string()), to.id.index()));
+        let epsilon_transitions = self
+            .epsilon_transitions()
+            .map(|(from, to)| (from.id.index(), None, to.id.index()));
+        write_automaton(
+            self.states().map(|state| state.accepting),
+            transitions.chain(epsilon_transitions),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Written by JFLAP 7.1: words over {a, b} ending with "ab", the initial state last.
+    const ENDS_WITH_AB: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?><!--Created with JFLAP 7.1.--><structure>&#13;
+	<type>fa</type>&#13;
+	<automaton>&#13;
+		<!--The list of states.-->&#13;
+		<state id="1" name="q1">&#13;
+			<x>216.0</x>&#13;
+			<y>118.0</y>&#13;
+		</state>&#13;
+		<state id="2" name="q2">&#13;
+			<x>372.0</x>&#13;
+			<y>118.0</y>&#13;
+			<final/>&#13;
+		</state>&#13;
+		<state id="0" name="q0">&#13;
+			<x>68.0</x>&#13;
+			<y>118.0</y>&#13;
+			<initial/>&#13;
+		</state>&#13;
+		<!--The list of transitions.-->&#13;
+		<transition>&#13;
+			<from>0</from>&#13;
+			<to>1</to>&#13;
+			<read>a</read>&#13;
+		</transition>&#13;
+		<transition><from>0</from><to>0</to><read>b</read></transition>
+		<transition><from>1</from><to>1</to><read>a</read></transition>
+		<transition><from>1</from><to>2</to><read>b</read></transition>
+		<transition><from>2</from><to>1</to><read>a</read></transition>
+		<transition><from>2</from><to>0</to><read>b</read></transition>
+	</automaton>&#13;
+</structure>"#;
+
+    #[test]
+    fn test_dfa_from_jflap() {
+        let dfa = Dfa::<char>::from_jflap(ENDS_WITH_AB).unwrap();
+        assert_eq!(dfa.num_states(), 3);
+        assert_eq!(dfa.num_transitions(), 6);
+        assert!(dfa.accepts("bbaab".chars()));
+        assert!(!dfa.accepts("aba".chars()));
+        // The initial state comes first, then the others in the order of the file:
+        assert_eq!(dfa.next(DfaStateId::new(0), 'a'), Some(DfaStateId::new(1)));
+        assert!(dfa.accepting(DfaStateId::new(2)));
+
+        let read = Dfa::<char>::from_jflap(&dfa.to_jflap()).unwrap();
+        assert_eq!(read.num_states(), 3);
+        assert!(read.equivalent(&dfa));
+    }
+
+    #[test]
+    fn test_nfa_from_jflap() {
+        // Older layout, without <automaton>:
+        let text = "<structure><type>fa</type>
+            <state id='0'><initial/></state>
+            <state id='1'><final/></state>
+            <transition><from>0</from><to>0</to><read>&lt;</read></transition>
+            <transition><from>0</from><to>1</to><read/></transition>
+            <transition><from>0</from><to>1</to><read><![CDATA[&]]></read></transition>
+        </structure>";
+        let nfa = Nfa::<char>::from_jflap(text).unwrap();
+        assert_eq!(nfa.num_epsilon_transitions(), 1);
+        assert!(nfa.accepts("<<".chars()));
+        assert!(nfa.accepts("<&".chars()));
+        assert!(!nfa.accepts("&&".chars()));
+
+        let read = Nfa::<char>::from_jflap(&nfa.to_jflap()).unwrap();
+        assert_eq!(read.num_transitions(), 2);
+        assert_eq!(read.num_epsilon_transitions(), 1);
+        assert!(read.determinize().equivalent(&nfa.determinize()));
+    }
+
+    #[test]
+    fn test_from_jflap_errors() {
+        let check = |text: &str, line: usize, message: &str| {
+            let error = Dfa::<char>::from_jflap(text).unwrap_err();
+            assert_eq!(error.line, line, "{}", error);
+            assert!(error.message.contains(message), "{}", error);
+        };
+        let state = "<state id=\"0\"><initial/></state>";
+        let transition = |read: &str| {
+            format!(
+                "\n<transition><from>0</from><to>0</to>{}</transition>",
+                read
+            )
+        };
+        check(
+            "<structure><type>turing</type></structure>",
+            1,
+            "not a finite automaton",
+        );
+        check(
+            &format!("<structure>{}{}</structure>", state, transition("<read/>")),
+            2,
+            "λ-transition",
+        );
+        check(
+            &format!(
+                "<structure>{}{}</structure>",
+                state,
+                transition("<read>ab</read>")
+            ),
+            2,
+            "invalid symbol",
+        );
+        check(
+            "<structure><state id='0'><initial/></state><state id='1'/>
+                <transition><from>0</from><to>0</to><read>a</read></transition>
+                <transition><from>0</from><to>1</to><read>a</read></transition></structure>",
+            3,
+            "already has",
+        );
+        check(
+            &format!("<structure>{}{}", state, transition("")),
+            2,
+            "unclosed",
+        );
+        check(
+            "<structure><state id=\"0\"/></structure>",
+            1,
+            "no initial state",
+        );
+        check(
+            "<structure><state id='0'><initial/></state>\n<transition><from>0</from><to>1</to><read>a</read></transition></structure>",
+            2,
+            "unknown state",
+        );
+    }
+
+    #[test]
+    fn test_jflap_space_symbol() {
+        let mut dfa = Dfa::<char>::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(true);
+        dfa.add_transition(q0, ' ', q1);
+        dfa.add_transition(q1, 'a', q1);
+        let text = dfa.to_jflap();
+        assert!(text.contains("<read> </read>"), "{}", text);
+        let read = Dfa::<char>::from_jflap(&text).unwrap();
+        assert_eq!(read.next(q0, ' '), Some(q1));
+        assert!(read.equivalent(&dfa));
+
+        let mut nfa = Nfa::<char>::new();
+        let p0 = nfa.add_state(false);
+        let p1 = nfa.add_state(true);
+        nfa.add_transition(p0, ' ', p1);
+        nfa.add_epsilon_transition(p0, p0);
+        let read = Nfa::<char>::from_jflap(&nfa.to_jflap()).unwrap();
+        assert_eq!(read.num_transitions(), 1);
+        assert_eq!(read.num_epsilon_transitions(), 1);
+        assert!(read.accepts(" ".chars()));
+        assert!(!read.accepts("".chars()));
+    }
+}
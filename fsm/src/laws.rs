@@ -0,0 +1,218 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+
+/// A violated algebraic law, see the checks in this module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LawViolation<A: Alphabet> {
+    /// Name of the law, e.g. `"double complement"`.
+    pub law: &'static str,
+    /// A shortest word on which both sides of the law disagree.
+    pub counterexample: Vec<A>,
+}
+
+impl<A: Alphabet> Display for LawViolation<A> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "law '{}' is violated on {:?}",
+            self.law, self.counterexample
+        )
+    }
+}
+
+impl<A: Alphabet> Error for LawViolation<A> {}
+
+/// Finds a shortest word (lexicographically smallest among those) accepted by exactly one of the automata.
+pub fn counterexample<A: Alphabet>(left: &Dfa<A>, right: &Dfa<A>) -> Option<Vec<A>> {
+    left.symmetric_difference(right).shortest_word()
+}
+
+/// Checks that both sides of the law `law` recognize the same language.
+///
+/// This is the building block of all checks here, and can be used to validate new operations.
+pub fn check_equivalent<A: Alphabet>(
+    law: &'static str,
+    left: &Dfa<A>,
+    right: &Dfa<A>,
+) -> Result<(), LawViolation<A>> {
+    match counterexample(left, right) {
+        None => Ok(()),
+        Some(counterexample) => Err(LawViolation {
+            law,
+            counterexample,
+        }),
+    }
+}
+
+/// Completes a copy of `dfa` over `alphabet`, so that complements are taken w.r.t. the same alphabet.
+fn completed<A: Alphabet>(dfa: &Dfa<A>, alphabet: &[A]) -> Dfa<A> {
+    let mut dfa = dfa.clone_structure();
+    dfa.complete(alphabet.iter().copied());
+    dfa
+}
+
+fn joint_symbols<A: Alphabet>(a: &Dfa<A>, b: &Dfa<A>) -> Vec<A> {
+    let mut symbols = a.symbols();
+    symbols.extend(b.symbols());
+    symbols.sort_unstable();
+    symbols.dedup();
+    symbols
+}
+
+/// `¬¬a = a`
+pub fn double_complement<A: Alphabet>(a: &Dfa<A>) -> Result<(), LawViolation<A>> {
+    check_equivalent("double complement", &a.complement().complement(), a)
+}
+
+/// `¬(a ∪ b) = ¬a ∩ ¬b`, with complements over the joint alphabet.
+pub fn de_morgan_union<A: Alphabet>(a: &Dfa<A>, b: &Dfa<A>) -> Result<(), LawViolation<A>> {
+    let symbols = joint_symbols(a, b);
+    let (a, b) = (completed(a, &symbols), completed(b, &symbols));
+    let left = a.union(&b).complement();
+    let right = a.complement().intersection(&b.complement());
+    check_equivalent("De Morgan (union)", &left, &right)
+}
+
+/// `¬(a ∩ b) = ¬a ∪ ¬b`, with complements over the joint alphabet.
+pub fn de_morgan_intersection<A: Alphabet>(a: &Dfa<A>, b: &Dfa<A>) -> Result<(), LawViolation<A>> {
+    let symbols = joint_symbols(a, b);
+    let (a, b) = (completed(a, &symbols), completed(b, &symbols));
+    let left = a.intersection(&b).complement();
+    let right = a.complement().union(&b.complement());
+    check_equivalent("De Morgan (intersection)", &left, &right)
+}
+
+/// `a ∖ b = a ∩ ¬b` and `a △ b = (a ∖ b) ∪ (b ∖ a)`, with complements over the joint alphabet.
+pub fn difference<A: Alphabet>(a: &Dfa<A>, b: &Dfa<A>) -> Result<(), LawViolation<A>> {
+    let symbols = joint_symbols(a, b);
+    let (a, b) = (completed(a, &symbols), completed(b, &symbols));
+    check_equivalent(
+        "difference",
+        &a.difference(&b),
+        &a.intersection(&b.complement()),
+    )?;
+    check_equivalent(
+        "symmetric difference",
+        &a.symmetric_difference(&b),
+        &a.difference(&b).union(&b.difference(&a)),
+    )
+}
+
+/// Minimization preserves the language, is idempotent, and agrees with Brzozowski's algorithm.
+pub fn minimize<A: Alphabet>(a: &Dfa<A>) -> Result<(), LawViolation<A>> {
+    let minimal = a.minimize();
+    check_equivalent("minimize preserves language", &minimal, a)?;
+    check_equivalent("minimize idempotence", &minimal.minimize(), &minimal)?;
+    check_equivalent(
+        "Brzozowski minimization",
+        &a.minimize_brzozowski(),
+        &minimal,
+    )?;
+    let sizes = [
+        minimal.minimize().num_states(),
+        a.minimize_brzozowski().num_states(),
+    ];
+    if sizes != [minimal.num_states(); 2] {
+        // Equivalent, but not minimal: no word witnesses this.
+        return Err(LawViolation {
+            law: "minimal size",
+            counterexample: Vec::new(),
+        });
+    }
+    Ok(())
+}
+
+/// `minimize ∘ determinize` preserves the language of an NFA, is idempotent,
+/// and is not affected by removing ε-transitions first.
+pub fn determinize_minimize<A: Alphabet>(nfa: &Nfa<A>) -> Result<(), LawViolation<A>> {
+    let dfa = nfa.determinize();
+    let minimal = dfa.minimize();
+    check_equivalent("determinize preserves language", &minimal, &dfa)?;
+    let eps_free = nfa.remove_epsilon().determinize().minimize();
+    check_equivalent("ε-removal preserves language", &eps_free, &minimal)?;
+    if eps_free.num_states() != minimal.num_states() {
+        return Err(LawViolation {
+            law: "ε-removal preserves minimal size",
+            counterexample: Vec::new(),
+        });
+    }
+    minimize(&dfa)
+}
+
+/// Runs all single- and two-argument checks.
+pub fn check_all<A: Alphabet>(a: &Dfa<A>, b: &Dfa<A>) -> Result<(), LawViolation<A>> {
+    double_complement(a)?;
+    double_complement(b)?;
+    de_morgan_union(a, b)?;
+    de_morgan_intersection(a, b)?;
+    difference(a, b)?;
+    minimize(a)?;
+    minimize(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Words over {a, b} whose number of `a`s is divisible by `n`.
+    fn count_a(n: usize) -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let states = (0..n).map(|i| dfa.add_state(i == 0)).collect::<Vec<_>>();
+        for i in 0..n {
+            dfa.add_transition(states[i], 'a', states[(i + 1) % n]);
+            dfa.add_transition(states[i], 'b', states[i]);
+        }
+        dfa
+    }
+
+    /// The single word "ac", as a partial DFA.
+    fn ac() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'c', q2);
+        dfa
+    }
+
+    #[test]
+    fn test_laws_hold() {
+        let automata = [count_a(2), count_a(3), ac(), Dfa::new()];
+        for a in &automata {
+            for b in &automata {
+                check_all(a, b).unwrap();
+            }
+        }
+
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(true);
+        nfa.add_transition(q0, 'a', q0);
+        nfa.add_transition(q0, 'b', q0);
+        nfa.add_transition(q0, 'a', q1);
+        nfa.add_epsilon_transition(q1, q0);
+        determinize_minimize(&nfa).unwrap();
+    }
+
+    #[test]
+    fn test_violation_found() {
+        // A broken "complement" that just flips flags, ignoring missing transitions:
+        let a = ac();
+        let mut broken = a.clone_structure();
+        for id in 0..broken.num_states() {
+            let state = broken.state_mut(crate::dfa::state::DfaStateId::new(id));
+            state.accepting = !state.accepting;
+        }
+        let violation = check_equivalent("complement", &broken, &a.complement()).unwrap_err();
+        assert_eq!(violation.counterexample, vec!['c']);
+        assert_eq!(
+            violation.to_string(),
+            "law 'complement' is violated on ['c']"
+        );
+    }
+}
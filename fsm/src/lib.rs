@@ -1,8 +1,22 @@
 pub mod alphabet;
+pub mod att;
+pub mod bench_support;
+pub mod codegen;
+pub mod csv;
+pub mod dawg;
 pub mod dfa;
+pub mod dot;
+pub mod golden;
+pub mod hoa;
+pub mod jflap;
+pub mod laws;
 pub mod mealy;
 pub mod moore;
 pub mod nfa;
+pub mod ops;
+pub mod pipeline;
+pub mod progress;
+pub mod shrink;
 
 pub(crate) mod util;
 
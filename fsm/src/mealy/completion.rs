@@ -0,0 +1,164 @@
+use crate::alphabet::Alphabet;
+use crate::mealy::state::StateId;
+use crate::mealy::Mealy;
+
+/// Strategy for handling unspecified `(state, input)` pairs, see [`Mealy::complete`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Completion<O> {
+    /// Stay in the same state, producing the given (e.g. "empty") output.
+    SelfLoop(O),
+    /// Go to a dedicated error state, producing the given output.
+    /// The error state loops to itself on every input, producing the same output.
+    ErrorState(O),
+    /// Keep unspecified pairs without a transition, marking them as "don't care"
+    /// (see [`Mealy::is_dont_care`]), so a synthesis procedure can later choose
+    /// both the next state and the output freely.
+    DontCare,
+}
+
+impl<I: Alphabet, O: Alphabet> Mealy<I, O> {
+    /// Lists `(state, input)` pairs without a transition, ordered by state and input.
+    pub fn unspecified(&self, inputs: impl IntoIterator<Item = I>) -> Vec<(StateId, I)> {
+        let mut inputs = inputs.into_iter().collect::<Vec<_>>();
+        inputs.sort_unstable();
+        inputs.dedup();
+        self.states()
+            .flat_map(|state| {
+                inputs
+                    .iter()
+                    .filter(|&&input| state.next(input).is_none())
+                    .map(|&input| (state.id, input))
+            })
+            .collect()
+    }
+
+    /// Whether every state has a transition on every input.
+    pub fn is_complete(&self, inputs: impl IntoIterator<Item = I>) -> bool {
+        self.unspecified(inputs).is_empty()
+    }
+
+    /// Completes the machine over the given `inputs` using the given `strategy`.
+    ///
+    /// Returns the `(state, input)` pairs that were unspecified before the completion.
+    /// With [`Completion::DontCare`], no transitions are added, and the returned
+    /// pairs are marked as "don't care" in the machine.
+    pub fn complete(
+        &mut self,
+        inputs: impl IntoIterator<Item = I>,
+        strategy: Completion<O>,
+    ) -> Vec<(StateId, I)> {
+        let inputs = inputs.into_iter().collect::<Vec<_>>();
+        let unspecified = self.unspecified(inputs.iter().copied());
+        match strategy {
+            Completion::SelfLoop(output) => {
+                for &(state, input) in &unspecified {
+                    self.add_transition(state, input, state, output);
+                }
+            }
+            Completion::ErrorState(output) => {
+                if !unspecified.is_empty() {
+                    let error = self.add_state();
+                    for &(state, input) in &unspecified {
+                        self.add_transition(state, input, error, output);
+                    }
+                    for &input in &inputs {
+                        self.add_transition(error, input, error, output);
+                    }
+                }
+            }
+            Completion::DontCare => {
+                self.dont_care.extend(unspecified.iter().copied());
+            }
+        }
+        unspecified
+    }
+
+    /// Whether the `(state, input)` pair was left unspecified by [`Completion::DontCare`]
+    /// and has not got a transition since.
+    pub fn is_dont_care(&self, state: StateId, input: I) -> bool {
+        self.dont_care.contains(&(state, input))
+    }
+
+    /// All ["don't care"](Mealy::is_dont_care) pairs, ordered by state and input.
+    pub fn dont_cares(&self) -> impl Iterator<Item = (StateId, I)> + '_ {
+        self.dont_care.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial() -> Mealy<char, u8> {
+        let mut fsm = Mealy::new();
+        let idle = fsm.add_state();
+        let busy = fsm.add_state();
+        fsm.add_transition(idle, 's', busy, 1);
+        fsm.add_transition(busy, 'f', idle, 2);
+        fsm
+    }
+
+    #[test]
+    fn test_unspecified() {
+        let fsm = partial();
+        assert_eq!(
+            fsm.unspecified(['s', 'f']),
+            vec![(StateId::new(0), 'f'), (StateId::new(1), 's')]
+        );
+        assert!(!fsm.is_complete(['s', 'f']));
+        assert_eq!(fsm.unspecified(['s']), vec![(StateId::new(1), 's')]);
+    }
+
+    #[test]
+    fn test_complete_self_loop() {
+        let mut fsm = partial();
+        let completed = fsm.complete(['s', 'f'], Completion::SelfLoop(0));
+        assert_eq!(completed.len(), 2);
+        assert!(fsm.is_complete(['s', 'f']));
+        assert_eq!(fsm.num_states(), 2);
+        let outputs = fsm
+            .run(['f', 's', 's', 'f'])
+            .map(|(_, _, o)| o)
+            .collect::<Vec<_>>();
+        assert_eq!(outputs, vec![0, 1, 0, 2]);
+    }
+
+    #[test]
+    fn test_complete_error_state() {
+        let mut fsm = partial();
+        fsm.complete(['s', 'f'], Completion::ErrorState(9));
+        assert!(fsm.is_complete(['s', 'f']));
+        assert_eq!(fsm.num_states(), 3);
+        let outputs = fsm
+            .run(['s', 's', 'f'])
+            .map(|(_, _, o)| o)
+            .collect::<Vec<_>>();
+        assert_eq!(outputs, vec![1, 9, 9]);
+    }
+
+    #[test]
+    fn test_complete_dont_care() {
+        let mut fsm = partial();
+        let dont_care = fsm.complete(['s', 'f'], Completion::DontCare);
+        assert_eq!(
+            dont_care,
+            vec![(StateId::new(0), 'f'), (StateId::new(1), 's')]
+        );
+        assert_eq!(fsm.num_transitions(), 2);
+        assert_eq!(fsm.dont_cares().collect::<Vec<_>>(), dont_care);
+        assert!(fsm.is_dont_care(StateId::new(0), 'f'));
+        assert!(!fsm.is_dont_care(StateId::new(0), 's'));
+
+        // Choosing a transition for a pair clears its mark:
+        fsm.add_transition(StateId::new(1), 's', StateId::new(1), 7);
+        assert_eq!(
+            fsm.dont_cares().collect::<Vec<_>>(),
+            vec![(StateId::new(0), 'f')]
+        );
+
+        // Other strategies do not mark anything:
+        let mut fsm = partial();
+        fsm.complete(['s', 'f'], Completion::SelfLoop(0));
+        assert_eq!(fsm.dont_cares().count(), 0);
+    }
+}
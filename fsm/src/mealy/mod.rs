@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::ops::{Index, IndexMut};
 
 use state::{State, StateId};
@@ -5,6 +6,7 @@ use state::{State, StateId};
 use crate::alphabet::Alphabet;
 use crate::util::arena::Arena;
 
+pub mod completion;
 pub mod state;
 
 // #[cfg(feature = "serde")]
@@ -19,13 +21,16 @@ pub mod state;
 /// - $\omega : Q \times \Sigma \to \Lambda$ is an output function.
 #[derive(Debug)]
 pub struct Mealy<I: Alphabet, O: Alphabet> {
-    states: Arena<State<I, O>>,
+    states: Arena<State<I, O>, StateId>,
+    /// Pairs marked by [`Completion::DontCare`](completion::Completion::DontCare).
+    dont_care: BTreeSet<(StateId, I)>,
 }
 
 impl<I: Alphabet, O: Alphabet> Mealy<I, O> {
     pub fn new() -> Self {
         Self {
             states: Arena::new(),
+            dont_care: BTreeSet::new(),
         }
     }
 
@@ -33,7 +38,12 @@ impl<I: Alphabet, O: Alphabet> Mealy<I, O> {
         self.states.alloc_with_id(|id| State::new(id))
     }
 
+    /// Adds a transition, which also clears a ["don't care"](Mealy::is_dont_care) mark
+    /// of the `(from, input)` pair.
     pub fn add_transition(&mut self, from: StateId, input: I, to: StateId, output: O) {
+        if !self.dont_care.is_empty() {
+            self.dont_care.remove(&(from, input));
+        }
         self.state_mut(from).add_transition(input, to, output);
     }
 
@@ -93,12 +103,12 @@ impl<I: Alphabet, O: Alphabet> Mealy<I, O> {
         self.state(current_state).next(input)
     }
 
-    pub fn run<'a, Inputs>(&'a self, inputs: Inputs) -> impl Iterator<Item = (I, StateId, O)> + '_
+    pub fn run<'a, Inputs>(&'a self, inputs: Inputs) -> impl Iterator<Item = (I, StateId, O)> + 'a
     where
         Inputs: IntoIterator<Item = I>,
         <Inputs as IntoIterator>::IntoIter: 'a,
     {
-        let mut current_state = 0;
+        let mut current_state = StateId::new(0);
         inputs.into_iter().map(move |input: I| {
             let (next_state, output) = self.next(current_state, input).unwrap();
             current_state = next_state;
@@ -126,7 +136,7 @@ mod tests {
 
         let inputs = vec![0, 1, 1, 0, 0];
         println!("Running Moore machine on {:?}", inputs);
-        let mut current_state = 0;
+        let mut current_state = StateId::new(0);
         let mut outputs = Vec::new();
         println!("initial state {}", current_state);
         for (input, new_state, output) in fsm.run(inputs) {
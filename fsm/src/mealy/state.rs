@@ -1,8 +1,14 @@
 use std::collections::HashMap;
 
 use crate::alphabet::Alphabet;
+use crate::util::arena::state_id;
 
-pub type StateId = usize;
+state_id! {
+    /// Id of a state of a Mealy machine.
+    MealyStateId
+}
+
+pub type StateId = MealyStateId;
 
 #[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -19,7 +19,7 @@ pub mod state;
 /// - $\omega : Q \to \Lambda$ is an output function.
 #[derive(Debug)]
 pub struct Moore<I: Alphabet, O: Alphabet> {
-    states: Arena<State<I, O>>,
+    states: Arena<State<I, O>, StateId>,
 }
 
 impl<I: Alphabet, O: Alphabet> Moore<I, O> {
@@ -95,12 +95,12 @@ impl<I: Alphabet, O: Alphabet> Moore<I, O> {
             .map(|next_state| (next_state, self.state(next_state).output))
     }
 
-    pub fn run<'a, Inputs>(&'a self, inputs: Inputs) -> impl Iterator<Item = (I, StateId, O)> + '_
+    pub fn run<'a, Inputs>(&'a self, inputs: Inputs) -> impl Iterator<Item = (I, StateId, O)> + 'a
     where
         Inputs: IntoIterator<Item = I>,
         <Inputs as IntoIterator>::IntoIter: 'a,
     {
-        let mut current_state = 0;
+        let mut current_state = StateId::new(0);
         inputs.into_iter().map(move |input: I| {
             let (next_state, output) = self.next(current_state, input).unwrap();
             current_state = next_state;
@@ -128,7 +128,7 @@ mod tests {
 
         let inputs = vec![1, 0, 1, 0, 1, 1, 0];
         println!("Running Moore machine on {:?}", inputs);
-        let mut current_state = 0;
+        let mut current_state = StateId::new(0);
         let mut outputs = vec![fsm.state(current_state).output];
         println!("initial state {}, output {}", current_state, outputs[0]);
         for (input, new_state, output) in fsm.run(inputs) {
@@ -0,0 +1,223 @@
+use std::collections::{HashMap, VecDeque};
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+use crate::util::scc::scc;
+
+/// What to do when an ε-transition closes a cycle of ε-transitions (including a self-loop).
+///
+/// Such cycles are harmless for the language (all closures are computed with a visited set),
+/// but they are almost always a construction mistake, and they make every closure through
+/// them visit the whole cycle. To find them without refusing them, use
+/// [`Nfa::closed_epsilon_cycle`] before adding a transition, or [`Nfa::epsilon_cycles`] afterwards.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum EpsilonCyclePolicy {
+    /// Add the transition without checking.
+    #[default]
+    Allow,
+    /// Refuse to add the transition and report an [`EpsilonCycle`].
+    Error,
+    /// Panic in debug builds, add the transition without checking in release builds.
+    DebugPanic,
+}
+
+/// An ε-transition that would close a cycle of ε-transitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpsilonCycle {
+    pub from: StateId,
+    pub to: StateId,
+    /// States on the cycle, starting with `to` and ending with `from`
+    /// (a single state for a self-loop).
+    pub cycle: Vec<StateId>,
+}
+
+impl Display for EpsilonCycle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let cycle = (self.cycle.iter())
+            .map(|state| state.to_string())
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        write!(
+            f,
+            "ε-transition from {} to {} closes the ε-cycle {}",
+            self.from, self.to, cycle
+        )
+    }
+}
+
+impl Error for EpsilonCycle {}
+
+impl<A: Alphabet, S> Nfa<A, S> {
+    /// Sets the policy for adding an ε-transition that closes an ε-cycle.
+    pub fn with_epsilon_cycle_policy(mut self, policy: EpsilonCyclePolicy) -> Self {
+        self.epsilon_cycle_policy = policy;
+        self
+    }
+
+    pub fn epsilon_cycle_policy(&self) -> EpsilonCyclePolicy {
+        self.epsilon_cycle_policy
+    }
+
+    pub fn set_epsilon_cycle_policy(&mut self, policy: EpsilonCyclePolicy) {
+        self.epsilon_cycle_policy = policy;
+    }
+
+    /// Adds an ε-transition, failing under [`EpsilonCyclePolicy::Error`] if it closes an ε-cycle
+    /// (and panicking under [`EpsilonCyclePolicy::DebugPanic`] in debug builds).
+    pub fn try_add_epsilon_transition(
+        &mut self,
+        from: StateId,
+        to: StateId,
+    ) -> Result<(), EpsilonCycle> {
+        match self.epsilon_cycle_policy {
+            EpsilonCyclePolicy::Allow => {}
+            EpsilonCyclePolicy::Error => {
+                if let Some(cycle) = self.closed_epsilon_cycle(from, to) {
+                    return Err(cycle);
+                }
+            }
+            EpsilonCyclePolicy::DebugPanic => {
+                if cfg!(debug_assertions) {
+                    if let Some(cycle) = self.closed_epsilon_cycle(from, to) {
+                        panic!("{}", cycle);
+                    }
+                }
+            }
+        }
+        self.state_mut(from).add_epsilon_transition(to);
+        Ok(())
+    }
+
+    /// The ε-cycle that a new ε-transition from `from` to `to` would close, if any.
+    ///
+    /// An ε-transition that already exists closes nothing new.
+    pub fn closed_epsilon_cycle(&self, from: StateId, to: StateId) -> Option<EpsilonCycle> {
+        if self.next_epsilon(from).contains(&to) {
+            return None;
+        }
+        let cycle = self.epsilon_path(to, from)?;
+        Some(EpsilonCycle { from, to, cycle })
+    }
+
+    /// Shortest path of ε-transitions from `source` to `target`, including both ends.
+    fn epsilon_path(&self, source: StateId, target: StateId) -> Option<Vec<StateId>> {
+        let mut parents = HashMap::from([(source, source)]);
+        let mut queue = VecDeque::from([source]);
+        while let Some(state) = queue.pop_front() {
+            if state == target {
+                let mut path = vec![state];
+                let mut current = state;
+                while current != source {
+                    current = parents[&current];
+                    path.push(current);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &next in self.next_epsilon(state) {
+                parents.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    state
+                });
+            }
+        }
+        None
+    }
+
+    /// Groups of states lying on common ε-cycles, i.e. the strongly connected components
+    /// of the ε-transitions that contain a cycle. Groups are sorted, in order of their smallest state.
+    ///
+    /// All states of a group have the same ε-closure, so they can be merged
+    /// without changing the language.
+    pub fn epsilon_cycles(&self) -> Vec<Vec<StateId>> {
+        let components = scc(self.states().map(|state| state.id), |state| {
+            self.next_epsilon(state).iter().copied().collect::<Vec<_>>()
+        });
+        let mut groups = components
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1 || self.next_epsilon(component[0]).contains(&component[0])
+            })
+            .map(|mut component| {
+                component.sort_unstable();
+                component
+            })
+            .collect::<Vec<_>>();
+        groups.sort_unstable();
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_epsilon_cycle_policy() {
+        let mut nfa = Nfa::<char>::new().with_epsilon_cycle_policy(EpsilonCyclePolicy::Error);
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let q2 = nfa.add_state(true);
+        nfa.add_epsilon_transition(q0, q1);
+        nfa.add_epsilon_transition(q1, q2);
+        assert_eq!(
+            nfa.try_add_epsilon_transition(q2, q0),
+            Err(EpsilonCycle {
+                from: q2,
+                to: q0,
+                cycle: vec![q0, q1, q2],
+            })
+        );
+        assert_eq!(
+            nfa.try_add_epsilon_transition(q1, q1).unwrap_err().cycle,
+            vec![q1]
+        );
+        assert_eq!(nfa.num_epsilon_transitions(), 2);
+        assert!(nfa.epsilon_cycles().is_empty());
+        assert_eq!(
+            nfa.closed_epsilon_cycle(q2, q0).unwrap().to_string(),
+            "ε-transition from 2 to 0 closes the ε-cycle 0 -> 1 -> 2"
+        );
+        assert_eq!(nfa.closed_epsilon_cycle(q0, q1), None);
+        assert_eq!(nfa.closed_epsilon_cycle(q0, q2), None);
+
+        nfa.set_epsilon_cycle_policy(EpsilonCyclePolicy::Allow);
+        nfa.add_epsilon_transition(q2, q1);
+        nfa.add_transition(q0, 'a', q0);
+        assert!(nfa.accepts("aa".chars()));
+        assert_eq!(nfa.epsilon_cycles(), vec![vec![q1, q2]]);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "ε-transition from 1 to 0 closes the ε-cycle 0 -> 1")]
+    fn test_epsilon_cycle_policy_debug_panic() {
+        let mut nfa = Nfa::<char>::new().with_epsilon_cycle_policy(EpsilonCyclePolicy::DebugPanic);
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(true);
+        nfa.add_epsilon_transition(q0, q1);
+        assert_eq!(nfa.try_add_epsilon_transition(q0, q1), Ok(()));
+        let _ = nfa.try_add_epsilon_transition(q1, q0);
+    }
+
+    #[test]
+    fn test_epsilon_cycles() {
+        let mut nfa = Nfa::<char>::new();
+        assert_eq!(EpsilonCyclePolicy::default(), EpsilonCyclePolicy::Allow);
+        let q = (0..6).map(|_| nfa.add_state(false)).collect::<Vec<_>>();
+        nfa.add_epsilon_transition(q[3], q[1]);
+        nfa.add_epsilon_transition(q[1], q[3]);
+        nfa.add_epsilon_transition(q[0], q[4]);
+        nfa.add_epsilon_transition(q[4], q[5]);
+        nfa.add_epsilon_transition(q[5], q[0]);
+        nfa.add_epsilon_transition(q[2], q[2]);
+        nfa.add_epsilon_transition(q[3], q[2]);
+        assert_eq!(
+            nfa.epsilon_cycles(),
+            vec![vec![q[0], q[4], q[5]], vec![q[1], q[3]], vec![q[2]]]
+        );
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+impl<A: Alphabet> Nfa<A> {
+    /// Finds a shortest accepted word, or `None` if the language is empty.
+    ///
+    /// Among the shortest words, the lexicographically smallest one is chosen,
+    /// as for [`Dfa::shortest_word`](crate::dfa::Dfa::shortest_word).
+    /// The search visits every state at most once (no subset construction).
+    pub fn shortest_word(&self) -> Option<Vec<A>> {
+        if self.states.is_empty() {
+            return None;
+        }
+        // States are grouped by their (first) access word, and the queue of groups is kept
+        // sorted by (length, word): each group is extended with symbols in increasing order.
+        // Every state joins at most one group, so no subsets are materialized.
+        let mut visited = BTreeSet::new();
+        let initial = self
+            .epsilon_closure(StateId::new(0))
+            .filter(|&state| visited.insert(state))
+            .collect::<Vec<_>>();
+        let mut queue = VecDeque::from([(Vec::new(), initial)]);
+        while let Some((word, states)) = queue.pop_front() {
+            if self.any_accepting(states.iter().copied()) {
+                return Some(word);
+            }
+            let mut successors: BTreeMap<A, Vec<StateId>> = BTreeMap::new();
+            for &state in &states {
+                for (symbol, to) in self.state(state).transitions() {
+                    successors.entry(symbol).or_default().push(to);
+                }
+            }
+            for (symbol, targets) in successors {
+                let group = self
+                    .multi_epsilon_closure(targets)
+                    .filter(|&state| visited.insert(state))
+                    .collect::<Vec<_>>();
+                if !group.is_empty() {
+                    let mut next = word.clone();
+                    next.push(symbol);
+                    queue.push_back((next, group));
+                }
+            }
+        }
+        None
+    }
+
+    /// Whether the automaton accepts no words at all.
+    pub fn is_empty(&self) -> bool {
+        self.shortest_word().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortest_word() {
+        assert_eq!(Nfa::<char>::new().shortest_word(), None);
+
+        // (b|a)c, where `q3` is first reached via 'b':
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let q2 = nfa.add_state(false);
+        let q3 = nfa.add_state(false);
+        let q4 = nfa.add_state(true);
+        nfa.add_transition(q0, 'b', q1);
+        nfa.add_epsilon_transition(q0, q2);
+        nfa.add_transition(q2, 'a', q3);
+        nfa.add_epsilon_transition(q1, q3);
+        nfa.add_transition(q3, 'c', q4);
+        assert_eq!(nfa.shortest_word(), Some(vec!['a', 'c']));
+        assert_eq!(nfa.shortest_word(), nfa.determinize().shortest_word());
+        nfa.add_transition(q0, 'z', q4);
+        assert_eq!(nfa.shortest_word(), Some(vec!['z']));
+
+        let mut nfa = Nfa::<char>::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(true);
+        nfa.add_epsilon_transition(q0, q1);
+        assert_eq!(nfa.shortest_word(), Some(vec![]));
+
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let _unreachable = nfa.add_state(true);
+        nfa.add_transition(q0, 'a', q0);
+        assert!(nfa.is_empty());
+    }
+}
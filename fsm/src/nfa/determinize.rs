@@ -0,0 +1,269 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::DfaStateId;
+use crate::dfa::Dfa;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+use crate::progress::{self, Cancelled, Progress, REPORT_EVERY};
+
+/// Intermediate state of the subset construction ([`Nfa::to_dfa`]), which can be
+/// [run](Determinization::run) in bounded chunks and, with the `serde` feature, saved
+/// in between as a checkpoint, so that a long determinization can be resumed after a restart.
+///
+/// The checkpoint holds the DFA built so far, the subset of NFA states behind each
+/// of its states, and the frontier of states whose transitions are not built yet.
+/// The NFA itself is not included, so the same one must be passed to every run.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Determinization<A: Alphabet> {
+    alphabet: Vec<A>,
+    dfa: Dfa<A>,
+    /// NFA states of every DFA state, by id.
+    subsets: Vec<BTreeSet<StateId>>,
+    frontier: Vec<DfaStateId>,
+    /// Inverse of `subsets`, rebuilt after loading a checkpoint.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    subset2state: HashMap<BTreeSet<StateId>, DfaStateId>,
+}
+
+impl<A: Alphabet> Determinization<A> {
+    /// Starts determinizing `nfa` over the given `alphabet`, with the initial subset.
+    pub fn new<S>(nfa: &Nfa<A, S>, alphabet: &[A]) -> Self {
+        let mut determinization = Determinization {
+            alphabet: alphabet.to_vec(),
+            dfa: Dfa::new(),
+            subsets: Vec::new(),
+            frontier: Vec::new(),
+            subset2state: HashMap::new(),
+        };
+        if !nfa.states.is_empty() {
+            let initial = nfa
+                .epsilon_closure(StateId::new(0))
+                .collect::<BTreeSet<_>>();
+            determinization.add_subset(nfa, initial);
+        }
+        determinization
+    }
+
+    fn add_subset<S>(&mut self, nfa: &Nfa<A, S>, subset: BTreeSet<StateId>) -> DfaStateId {
+        let state = self
+            .dfa
+            .add_state(nfa.any_accepting(subset.iter().copied()));
+        self.subset2state.insert(subset.clone(), state);
+        self.subsets.push(subset);
+        self.frontier.push(state);
+        state
+    }
+
+    /// Whether all transitions are built, so that [`into_dfa`](Determinization::into_dfa) can be called.
+    pub fn is_done(&self) -> bool {
+        self.frontier.is_empty()
+    }
+
+    /// Number of DFA states built so far.
+    pub fn num_states(&self) -> usize {
+        self.dfa.num_states()
+    }
+
+    /// Number of DFA states whose transitions are not built yet.
+    pub fn num_pending(&self) -> usize {
+        self.frontier.len()
+    }
+
+    /// Builds the transitions of up to `steps` states from the frontier, returning whether it is done.
+    pub fn run<S>(&mut self, nfa: &Nfa<A, S>, steps: usize) -> bool {
+        if self.subset2state.len() != self.subsets.len() {
+            self.subset2state = (self.subsets.iter().cloned())
+                .zip((0..).map(DfaStateId::new))
+                .collect();
+        }
+        for _ in 0..steps {
+            let Some(current) = self.frontier.pop() else {
+                break;
+            };
+            for i in 0..self.alphabet.len() {
+                let symbol = self.alphabet[i];
+                let mut next_subset = BTreeSet::new();
+                for &nfa_state in &self.subsets[current.index()] {
+                    if let Some(next) = nfa.next(nfa_state, symbol) {
+                        next_subset.extend(nfa.multi_epsilon_closure(next.clone()));
+                    }
+                }
+
+                if !next_subset.is_empty() {
+                    let next_state = match self.subset2state.get(&next_subset) {
+                        Some(&state) => state,
+                        None => self.add_subset(nfa, next_subset),
+                    };
+                    self.dfa.add_transition(current, symbol, next_state);
+                }
+            }
+        }
+        self.is_done()
+    }
+
+    /// Runs to completion, reporting the number of finished and pending DFA states to `progress`.
+    ///
+    /// On cancellation, the work done so far is kept, so the run can be resumed later.
+    pub fn run_with_progress<S>(
+        &mut self,
+        nfa: &Nfa<A, S>,
+        progress: &mut impl Progress,
+    ) -> Result<(), Cancelled> {
+        while !self.run(nfa, REPORT_EVERY) {
+            progress::report(
+                progress,
+                self.num_states() - self.num_pending(),
+                self.num_pending(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Returns the resulting DFA.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the determinization [is not done](Determinization::is_done).
+    pub fn into_dfa(self) -> Dfa<A> {
+        assert!(self.is_done(), "determinization is not finished");
+        self.dfa
+    }
+}
+
+impl<A: Alphabet, S> Nfa<A, S> {
+    /// Same as [`determinize`](Nfa::determinize), but reporting to `progress`,
+    /// which may cancel it, see [`Determinization::run_with_progress`].
+    pub fn determinize_with_progress(
+        &self,
+        progress: &mut impl Progress,
+    ) -> Result<Dfa<A>, Cancelled> {
+        let mut determinization = Determinization::new(self, &self.symbols());
+        determinization.run_with_progress(self, progress)?;
+        Ok(determinization.into_dfa())
+    }
+
+    /// Same as [`determinize`](Nfa::determinize), but carrying the payloads of states:
+    /// every DFA state gets `merge` of the payloads of its subset of NFA states, in id order.
+    pub fn determinize_with<T>(&self, mut merge: impl FnMut(&[&S]) -> T) -> Dfa<A, T> {
+        let mut determinization = Determinization::new(self, &self.symbols());
+        determinization.run(self, usize::MAX);
+        let mut data = determinization
+            .subsets
+            .iter()
+            .map(|subset| {
+                let data = subset
+                    .iter()
+                    .map(|&state| self.state_data(state))
+                    .collect::<Vec<_>>();
+                merge(&data)
+            })
+            .collect::<Vec<_>>()
+            .into_iter();
+        determinization
+            .into_dfa()
+            .map_data(|_, ()| data.next().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+
+    use crate::progress::CancellationToken;
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    #[test]
+    fn test_determinize_in_steps() {
+        // Words over {a, b} with an 'a' third from the end:
+        let mut nfa = Nfa::new();
+        let states = (0..4).map(|i| nfa.add_state(i == 3)).collect::<Vec<_>>();
+        nfa.add_transition(states[0], 'a', states[0]);
+        nfa.add_transition(states[0], 'b', states[0]);
+        nfa.add_transition(states[0], 'a', states[1]);
+        for i in 1..3 {
+            nfa.add_transition(states[i], 'a', states[i + 1]);
+            nfa.add_transition(states[i], 'b', states[i + 1]);
+        }
+
+        let mut determinization = Determinization::new(&nfa, &['a', 'b']);
+        while !determinization.run(&nfa, 2) {
+            assert!(determinization.num_pending() > 0);
+            #[cfg(feature = "serde")]
+            {
+                // Resume from a checkpoint after every run:
+                let json = serde_json::to_string(&determinization).unwrap();
+                determinization = serde_json::from_str(&json).unwrap();
+            }
+        }
+        assert_eq!(determinization.num_states(), 8);
+        let dfa = determinization.into_dfa();
+        assert_eq!(dfa.num_transitions(), nfa.determinize().num_transitions());
+        for word in generate_strings(&['a', 'b'], 6) {
+            assert_eq!(dfa.accepts(word.chars()), nfa.accepts(word.chars()));
+        }
+    }
+
+    #[test]
+    fn test_determinize_with_progress() {
+        // Words over {a, b} with an 'a' 10th from the end, 2^10 DFA states:
+        let mut nfa = Nfa::new();
+        let states = (0..=10).map(|i| nfa.add_state(i == 10)).collect::<Vec<_>>();
+        nfa.add_transition(states[0], 'a', states[0]);
+        nfa.add_transition(states[0], 'b', states[0]);
+        nfa.add_transition(states[0], 'a', states[1]);
+        for i in 1..10 {
+            nfa.add_transition(states[i], 'a', states[i + 1]);
+            nfa.add_transition(states[i], 'b', states[i + 1]);
+        }
+
+        let mut reports = Vec::new();
+        let dfa = nfa
+            .determinize_with_progress(&mut |done, pending| {
+                reports.push((done, pending));
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert_eq!(dfa.num_states(), 1024);
+        assert!(!reports.is_empty());
+        assert!(reports.windows(2).all(|w| w[0].0 < w[1].0));
+
+        let token = CancellationToken::new();
+        token.cancel();
+        assert_eq!(
+            nfa.determinize_with_progress(&mut token.clone())
+                .unwrap_err(),
+            Cancelled
+        );
+    }
+
+    #[test]
+    fn test_determinize_with() {
+        // a*b, with the name of every state as its payload:
+        let mut nfa = Nfa::default();
+        let repeat = nfa.add_state_with(false, "repeat");
+        let tail = nfa.add_state_with(false, "tail");
+        let done = nfa.add_state_with(true, "done");
+        nfa.add_transition(repeat, 'a', repeat);
+        nfa.add_epsilon_transition(repeat, tail);
+        nfa.add_transition(tail, 'b', done);
+
+        let dfa = nfa.determinize_with(|names| {
+            names
+                .iter()
+                .map(|&&name| name)
+                .collect::<Vec<_>>()
+                .join("+")
+        });
+        assert_eq!(dfa.num_states(), 2);
+        let initial = DfaStateId::new(0);
+        assert_eq!(dfa.state_data(initial), "repeat+tail");
+        assert_eq!(dfa.next(initial, 'a'), Some(initial));
+        let last = dfa.next(initial, 'b').unwrap();
+        assert_eq!(dfa.state_data(last), "done");
+        assert!(dfa.accepts("aab".chars()));
+    }
+}
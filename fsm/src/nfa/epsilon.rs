@@ -0,0 +1,86 @@
+use std::collections::BTreeSet;
+
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+impl<A: Alphabet> Nfa<A> {
+    /// Builds an equivalent NFA without ε-transitions.
+    ///
+    /// States keep their ids. A state becomes accepting if some state in its ε-closure is,
+    /// and it gets every transition leaving its ε-closure. States that are only reachable
+    /// via ε-transitions may become unreachable, but they are kept.
+    pub fn remove_epsilon(&self) -> Nfa<A> {
+        let mut nfa = Nfa::new();
+        let closures = self
+            .states()
+            .map(|state| self.epsilon_closure(state.id).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        for closure in &closures {
+            nfa.add_state(self.any_accepting(closure.iter().copied()));
+        }
+        for (from, closure) in closures.iter().enumerate() {
+            let transitions = closure
+                .iter()
+                .flat_map(|&state| self.state(state).transitions())
+                .collect::<BTreeSet<_>>();
+            for (symbol, to) in transitions {
+                nfa.add_transition(StateId::new(from), symbol, to);
+            }
+        }
+        nfa
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    #[test]
+    fn test_remove_epsilon() {
+        // (ab)* | a*c, with ε-transitions between the alternatives:
+        let mut nfa = Nfa::new();
+        let start = nfa.add_state(false);
+        let ab0 = nfa.add_state(true);
+        let ab1 = nfa.add_state(false);
+        let ac0 = nfa.add_state(false);
+        let ac1 = nfa.add_state(false);
+        let ac2 = nfa.add_state(true);
+        nfa.add_epsilon_transition(start, ab0);
+        nfa.add_epsilon_transition(start, ac0);
+        nfa.add_transition(ab0, 'a', ab1);
+        nfa.add_transition(ab1, 'b', ab0);
+        nfa.add_transition(ac0, 'a', ac0);
+        nfa.add_transition(ac0, 'c', ac1);
+        nfa.add_epsilon_transition(ac1, ac2);
+
+        let eps_free = nfa.remove_epsilon();
+        assert_eq!(eps_free.num_states(), nfa.num_states());
+        assert_eq!(eps_free.num_epsilon_transitions(), 0);
+        assert!(eps_free.accepting(start));
+        assert!(eps_free.accepting(ac1));
+        for word in generate_strings(&['a', 'b', 'c'], 6) {
+            assert_eq!(
+                eps_free.accepts(word.chars()),
+                nfa.accepts(word.chars()),
+                "word = {word:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_remove_epsilon_cycle() {
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(true);
+        nfa.add_epsilon_transition(q0, q1);
+        nfa.add_epsilon_transition(q1, q0);
+        nfa.add_transition(q1, 'x', q1);
+
+        let eps_free = nfa.remove_epsilon();
+        assert_eq!(eps_free.num_transitions(), 2);
+        assert!(eps_free.accepts("xx".chars()));
+    }
+}
@@ -7,6 +7,7 @@ use graphviz_rust::dot_structures::{
 use graphviz_rust::printer::{DotPrinter, PrinterContext};
 
 use crate::alphabet::Alphabet;
+use crate::dfa::graphviz::escape_label;
 use crate::nfa::Nfa;
 
 impl<A: Alphabet + Display> Nfa<A> {
@@ -44,7 +45,7 @@ impl<A: Alphabet + Display> Nfa<A> {
             let from = format!("{}", from.id);
             let to = format!("{}", to.id);
             let symbol = format!("{}", symbol);
-            let attr = attr!("label", symbol);
+            let attr = attr!("label", esc escape_label(&symbol));
             let edge = edge!( node_id!(from) => node_id!(to); attr );
             stmts.push(stmt!(edge));
         }
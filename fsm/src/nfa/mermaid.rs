@@ -0,0 +1,55 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::mermaid::write_state_diagram;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+impl<A: Alphabet + Display> Nfa<A> {
+    /// Renders the automaton as a Mermaid `stateDiagram-v2`, see [`Dfa::render_mermaid`](crate::dfa::Dfa::render_mermaid).
+    ///
+    /// ε-transitions are labeled `ε`, after the symbols of parallel transitions.
+    pub fn render_mermaid(&self) -> String {
+        let mut edges: BTreeMap<(StateId, StateId), Vec<A>> = BTreeMap::new();
+        for (from, symbol, to) in self.transitions() {
+            edges.entry((from.id, to.id)).or_default().push(symbol);
+        }
+        let mut edges = edges
+            .into_iter()
+            .map(|(edge, mut symbols)| {
+                symbols.sort_unstable();
+                (
+                    edge,
+                    symbols.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        for (from, to) in self.epsilon_transitions() {
+            edges
+                .entry((from.id, to.id))
+                .or_default()
+                .push("ε".to_string());
+        }
+        write_state_diagram(self.states().map(|state| state.accepting), edges)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_mermaid() {
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(true);
+        nfa.add_transition(q0, 'a', q1);
+        nfa.add_epsilon_transition(q0, q1);
+        nfa.add_transition(q1, 'a', q0);
+        assert_eq!(
+            nfa.render_mermaid(),
+            "stateDiagram-v2\n    direction LR\n    [*] --> q0\n    q0 --> q1 : a, ε\n    q1 --> q0 : a\n    q1 --> [*]\n"
+        );
+    }
+}
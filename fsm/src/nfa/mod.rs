@@ -1,32 +1,55 @@
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
 use std::ops::{Index, IndexMut};
 
+use cycle::EpsilonCyclePolicy;
+use determinize::Determinization;
 use state::{State, StateId};
 
-use crate::alphabet::Alphabet;
+use crate::alphabet::{Alphabet, AlphabetMismatch};
 use crate::dfa::Dfa;
 use crate::util::arena::Arena;
 use crate::util::dfs::multi_dfs;
 
+pub mod cycle;
+pub mod decision;
+pub mod determinize;
+pub mod epsilon;
 pub mod graphviz;
+pub mod mermaid;
+pub mod reverse;
+pub mod runner;
 pub mod state;
+pub mod tikz;
+pub mod trace;
+pub mod transform;
+pub mod words;
+pub mod worst_case;
 
 #[cfg(feature = "serde")]
 mod serde;
 
+/// Nondeterministic finite automaton with ε-transitions over the alphabet `A`,
+/// with state 0 being initial.
+///
+/// Every state carries a user payload of type `S`, see [`add_state_with`](Nfa::add_state_with).
+/// Automata with payloads are created by [`Nfa::default`] (or [`Nfa::new`] without payloads).
 #[derive(Debug)]
-pub struct Nfa<A: Alphabet> {
-    states: Arena<State<A>>,
+pub struct Nfa<A: Alphabet, S = ()> {
+    states: Arena<State<A>, StateId>,
+    data: Arena<S, StateId>,
+    epsilon_cycle_policy: EpsilonCyclePolicy,
 }
 
 impl<A: Alphabet> Nfa<A> {
     pub fn new() -> Self {
-        Self {
-            states: Arena::new(),
-        }
+        Self::default()
     }
+}
 
-    pub fn add_state(&mut self, accepting: bool) -> StateId {
+impl<A: Alphabet, S> Nfa<A, S> {
+    pub fn add_state_with(&mut self, accepting: bool, data: S) -> StateId {
+        self.data.alloc(data);
         self.states.alloc_with_id(|id| State::new(id, accepting))
     }
 
@@ -34,8 +57,50 @@ impl<A: Alphabet> Nfa<A> {
         self.state_mut(from).add_transition(symbol, to);
     }
 
+    /// Adds an ε-transition, checking for ε-cycles according to the [policy](EpsilonCyclePolicy).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the transition closes an ε-cycle under [`EpsilonCyclePolicy::Error`]
+    /// (or [`EpsilonCyclePolicy::DebugPanic`] in debug builds),
+    /// use [`try_add_epsilon_transition`](Nfa::try_add_epsilon_transition) to handle it instead.
     pub fn add_epsilon_transition(&mut self, from: StateId, to: StateId) {
-        self.state_mut(from).add_epsilon_transition(to);
+        if let Err(cycle) = self.try_add_epsilon_transition(from, to) {
+            panic!("{}", cycle);
+        }
+    }
+
+    /// Removes the transition from `from` on `symbol` to `to`, returning whether it existed.
+    pub fn remove_transition(&mut self, from: StateId, symbol: A, to: StateId) -> bool {
+        self.state_mut(from).remove_transition(symbol, to)
+    }
+
+    /// Removes the ε-transition from `from` to `to`, returning whether it existed.
+    pub fn remove_epsilon_transition(&mut self, from: StateId, to: StateId) -> bool {
+        self.state_mut(from).remove_epsilon_transition(to)
+    }
+
+    /// Removes a state with its payload and all transitions from and to it,
+    /// renumbering the states after it as [`Dfa::remove_state`] does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there is no such state.
+    pub fn remove_state(&mut self, id: StateId) -> Vec<Option<StateId>> {
+        let remap = (0..self.num_states())
+            .map(|index| match index.cmp(&id.index()) {
+                Ordering::Less => Some(StateId::new(index)),
+                Ordering::Equal => None,
+                Ordering::Greater => Some(StateId::new(index - 1)),
+            })
+            .collect::<Vec<_>>();
+        self.states.remove(id);
+        self.data.remove(id);
+        for state in self.states.iter_mut() {
+            state.id = remap[state.id.index()].unwrap();
+            state.retarget(|to| remap[to.index()]);
+        }
+        remap
     }
 
     pub fn state(&self, index: StateId) -> &State<A> {
@@ -45,6 +110,26 @@ impl<A: Alphabet> Nfa<A> {
         &mut self.states[index]
     }
 
+    pub fn state_data(&self, index: StateId) -> &S {
+        &self.data[index]
+    }
+    pub fn state_data_mut(&mut self, index: StateId) -> &mut S {
+        &mut self.data[index]
+    }
+
+    /// Replaces the payload of every state by `f(id, data)`, keeping everything else.
+    pub fn map_data<T>(self, mut f: impl FnMut(StateId, S) -> T) -> Nfa<A, T> {
+        let mut data = Arena::new();
+        for (state, old) in self.states.iter().zip(self.data) {
+            data.alloc(f(state.id, old));
+        }
+        Nfa {
+            states: self.states,
+            data,
+            epsilon_cycle_policy: self.epsilon_cycle_policy,
+        }
+    }
+
     pub fn num_states(&self) -> usize {
         self.states.len()
     }
@@ -65,6 +150,27 @@ impl<A: Alphabet> Nfa<A> {
         self.states.iter()
     }
 
+    /// Symbols used on the (non-ε) transitions of this automaton, in sorted order.
+    pub fn symbols(&self) -> Vec<A> {
+        let mut symbols = self
+            .transitions()
+            .map(|(_, symbol, _)| symbol)
+            .collect::<Vec<_>>();
+        symbols.sort_unstable();
+        symbols.dedup();
+        symbols
+    }
+
+    /// Compares the symbols used in `corpus` with the [`symbols`](Nfa::symbols) of this automaton.
+    pub fn alphabet_mismatch<W, I>(&self, corpus: I) -> AlphabetMismatch<A>
+    where
+        I: IntoIterator<Item = W>,
+        W: IntoIterator<Item = A>,
+    {
+        let symbols = self.symbols().into_iter().collect();
+        AlphabetMismatch::new(&symbols, &A::collect_from(corpus))
+    }
+
     pub fn transitions(&self) -> impl Iterator<Item = (&State<A>, A, &State<A>)> + '_ {
         self.states().flat_map(move |state| {
             state
@@ -83,13 +189,24 @@ impl<A: Alphabet> Nfa<A> {
     }
 }
 
-impl<A: Alphabet> Default for Nfa<A> {
+impl<A: Alphabet, S: Default> Nfa<A, S> {
+    /// Adds a state carrying the default payload.
+    pub fn add_state(&mut self, accepting: bool) -> StateId {
+        self.add_state_with(accepting, S::default())
+    }
+}
+
+impl<A: Alphabet, S> Default for Nfa<A, S> {
     fn default() -> Self {
-        Self::new()
+        Self {
+            states: Arena::new(),
+            data: Arena::new(),
+            epsilon_cycle_policy: EpsilonCyclePolicy::default(),
+        }
     }
 }
 
-impl<A: Alphabet> Index<StateId> for Nfa<A> {
+impl<A: Alphabet, S> Index<StateId> for Nfa<A, S> {
     type Output = State<A>;
 
     fn index(&self, index: StateId) -> &Self::Output {
@@ -97,13 +214,13 @@ impl<A: Alphabet> Index<StateId> for Nfa<A> {
     }
 }
 
-impl<A: Alphabet> IndexMut<StateId> for Nfa<A> {
+impl<A: Alphabet, S> IndexMut<StateId> for Nfa<A, S> {
     fn index_mut(&mut self, index: StateId) -> &mut Self::Output {
         self.state_mut(index)
     }
 }
 
-impl<A: Alphabet> Nfa<A> {
+impl<A: Alphabet, S> Nfa<A, S> {
     pub fn next(&self, state: StateId, symbol: A) -> Option<&Vec<StateId>> {
         self.state(state).next(symbol)
     }
@@ -116,6 +233,7 @@ impl<A: Alphabet> Nfa<A> {
         self.multi_epsilon_closure(vec![start])
     }
 
+    /// Every state is visited once, so ε-cycles are fine here.
     fn multi_epsilon_closure(&self, start: Vec<StateId>) -> impl Iterator<Item = StateId> + '_ {
         multi_dfs(start, |state| self.next_epsilon(state).iter().copied())
     }
@@ -129,7 +247,9 @@ impl<A: Alphabet> Nfa<A> {
             return false;
         }
 
-        let mut current = self.epsilon_closure(0).collect::<BTreeSet<_>>();
+        let mut current = self
+            .epsilon_closure(StateId::new(0))
+            .collect::<BTreeSet<_>>();
 
         for symbol in word {
             let mut next = BTreeSet::new();
@@ -144,42 +264,22 @@ impl<A: Alphabet> Nfa<A> {
         self.any_accepting(current)
     }
 
-    pub fn to_dfa(&self, alphabet: &[A]) -> Dfa<A> {
-        let mut dfa = Dfa::new();
-        let mut state_map = HashMap::new();
-        let mut queue = Vec::new();
-
-        let initial_nfa_state = self.epsilon_closure(0).collect::<BTreeSet<_>>();
-        let initial_accepting = self.any_accepting(initial_nfa_state.iter().copied());
-        let initial_dfa_state = dfa.add_state(initial_accepting);
-        state_map.insert(initial_nfa_state.clone(), initial_dfa_state);
-        queue.push(initial_nfa_state);
-
-        while let Some(current_nfa_state) = queue.pop() {
-            let current_state = state_map[&current_nfa_state];
-
-            for &symbol in alphabet {
-                let mut next_nfa_state = BTreeSet::new();
-                for &nfa_state in &current_nfa_state {
-                    if let Some(next) = self.next(nfa_state, symbol) {
-                        next_nfa_state.extend(self.multi_epsilon_closure(next.clone()));
-                    }
-                }
-
-                if !next_nfa_state.is_empty() {
-                    let next_dfa_state =
-                        *state_map.entry(next_nfa_state.clone()).or_insert_with(|| {
-                            let accepting = self.any_accepting(next_nfa_state.iter().copied());
-                            let new_dfa_state = dfa.add_state(accepting);
-                            queue.push(next_nfa_state);
-                            new_dfa_state
-                        });
-                    dfa.add_transition(current_state, symbol, next_dfa_state);
-                }
-            }
-        }
+    /// Converts this NFA into an equivalent DFA using the subset construction.
+    ///
+    /// The alphabet is taken from the symbols used on transitions, see [`Nfa::symbols`].
+    /// Only non-empty subsets are materialized, so the resulting DFA is partial:
+    /// missing transitions mean rejection.
+    pub fn determinize(&self) -> Dfa<A> {
+        self.to_dfa(&self.symbols())
+    }
 
-        dfa
+    /// Subset construction over the given `alphabet`, see [`Nfa::determinize`].
+    ///
+    /// See [`Determinization`] for running it in resumable chunks.
+    pub fn to_dfa(&self, alphabet: &[A]) -> Dfa<A> {
+        let mut determinization = Determinization::new(self, alphabet);
+        determinization.run(self, usize::MAX);
+        determinization.into_dfa()
     }
 }
 
@@ -293,4 +393,53 @@ mod tests {
             assert_eq!(dfa.accepts(word.clone()), nfa.accepts(word));
         }
     }
+
+    #[test]
+    fn test_determinize() {
+        // (ab|a)*b with ε-transitions:
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let q2 = nfa.add_state(false);
+        let q3 = nfa.add_state(true);
+        nfa.add_transition(q0, 'a', q1);
+        nfa.add_transition(q1, 'b', q2);
+        nfa.add_epsilon_transition(q1, q0);
+        nfa.add_epsilon_transition(q2, q0);
+        nfa.add_transition(q0, 'b', q3);
+
+        let dfa = nfa.determinize();
+        for word in generate_words(&['a', 'b'], 8) {
+            assert_eq!(dfa.accepts(word.clone()), nfa.accepts(word));
+        }
+
+        assert_eq!(Nfa::<char>::new().determinize().num_states(), 0);
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(false);
+        let c = nfa.add_state(true);
+        nfa.add_transition(a, 'x', b);
+        nfa.add_transition(a, 'x', c);
+        nfa.add_epsilon_transition(a, b);
+        nfa.add_transition(b, 'y', c);
+
+        assert!(nfa.remove_transition(a, 'x', c));
+        assert!(!nfa.remove_transition(a, 'x', c));
+        assert!(!nfa.accepts("x".chars()));
+        assert!(nfa.accepts("y".chars()));
+        assert!(nfa.remove_epsilon_transition(a, b));
+        assert!(!nfa.accepts("y".chars()));
+        assert!(nfa.accepts("xy".chars()));
+
+        let remap = nfa.remove_state(a);
+        assert_eq!(remap, vec![None, Some(a), Some(b)]);
+        assert_eq!(nfa.num_states(), 2);
+        assert_eq!(nfa.num_transitions(), 1);
+        assert!(nfa.accepts("y".chars()));
+        assert_eq!(nfa.state(b).id, b);
+    }
 }
@@ -0,0 +1,75 @@
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+impl<A: Alphabet> Nfa<A> {
+    /// Builds an NFA recognizing the reversed language, i.e. all words of this automaton read backwards.
+    ///
+    /// All transitions (including ε-transitions) are flipped, and the accepting states become initial:
+    /// state `0` of the result is a fresh initial state with ε-transitions to them.
+    /// State `i` of this NFA corresponds to state `i + 1` of the result,
+    /// and only the former initial state is accepting. See also [`Dfa::reverse`](crate::dfa::Dfa::reverse).
+    pub fn reverse(&self) -> Nfa<A> {
+        let mut nfa = Nfa::new();
+        let initial = nfa.add_state(false);
+        for state in self.states() {
+            let id = nfa.add_state(state.id.index() == 0);
+            if state.accepting {
+                nfa.add_epsilon_transition(initial, id);
+            }
+        }
+        let shift = |id: StateId| StateId::new(id.index() + 1);
+        for (from, symbol, to) in self.transitions() {
+            nfa.add_transition(shift(to.id), symbol, shift(from.id));
+        }
+        for (from, to) in self.epsilon_transitions() {
+            nfa.add_epsilon_transition(shift(to.id), shift(from.id));
+        }
+        nfa
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    #[test]
+    fn test_reverse() {
+        // Words starting with "ab", with an ε-transition in between:
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let q2 = nfa.add_state(false);
+        let q3 = nfa.add_state(true);
+        nfa.add_transition(q0, 'a', q1);
+        nfa.add_epsilon_transition(q1, q2);
+        nfa.add_transition(q2, 'b', q3);
+        nfa.add_transition(q3, 'a', q3);
+        nfa.add_transition(q3, 'b', q3);
+
+        let reversed = nfa.reverse();
+        assert_eq!(reversed.num_states(), 5);
+        for word in generate_strings(&['a', 'b'], 6) {
+            let backwards = word.chars().rev().collect::<String>();
+            assert_eq!(
+                reversed.accepts(word.chars()),
+                nfa.accepts(backwards.chars()),
+                "word = {word:?}"
+            );
+            // Reversing twice restores the language:
+            assert_eq!(
+                reversed.reverse().accepts(word.chars()),
+                nfa.accepts(word.chars())
+            );
+        }
+    }
+
+    #[test]
+    fn test_reverse_empty() {
+        let reversed = Nfa::<char>::new().reverse();
+        assert_eq!(reversed.num_states(), 1);
+        assert!(!reversed.accepts("".chars()));
+    }
+}
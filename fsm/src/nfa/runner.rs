@@ -0,0 +1,105 @@
+use std::collections::BTreeSet;
+
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+/// Runs an NFA one symbol at a time, holding the set of current states between steps,
+/// see [`Nfa::runner`].
+///
+/// The current states are closed under ε-transitions. Once they are empty,
+/// the run is stuck until [`reset`](NfaRunner::reset).
+#[derive(Debug)]
+pub struct NfaRunner<'a, A: Alphabet, S = ()> {
+    nfa: &'a Nfa<A, S>,
+    current: BTreeSet<StateId>,
+}
+
+impl<'a, A: Alphabet, S> NfaRunner<'a, A, S> {
+    /// Starts in the ε-closure of the initial state, if there is one.
+    pub fn new(nfa: &'a Nfa<A, S>) -> Self {
+        let mut runner = Self {
+            nfa,
+            current: BTreeSet::new(),
+        };
+        runner.reset();
+        runner
+    }
+
+    /// Goes back to the ε-closure of the initial state.
+    pub fn reset(&mut self) {
+        self.current = if self.nfa.states.is_empty() {
+            BTreeSet::new()
+        } else {
+            self.nfa.epsilon_closure(StateId::new(0)).collect()
+        };
+    }
+
+    /// Reads `symbol`, returning the new current states.
+    pub fn step(&mut self, symbol: A) -> &BTreeSet<StateId> {
+        let mut next = BTreeSet::new();
+        for &state in &self.current {
+            if let Some(next_states) = self.nfa.next(state, symbol) {
+                next.extend(self.nfa.multi_epsilon_closure(next_states.clone()));
+            }
+        }
+        self.current = next;
+        &self.current
+    }
+
+    pub fn current_states(&self) -> &BTreeSet<StateId> {
+        &self.current
+    }
+
+    /// Whether the symbols read so far form an accepted word.
+    pub fn is_accepting(&self) -> bool {
+        self.nfa.any_accepting(self.current.iter().copied())
+    }
+}
+
+impl<A: Alphabet, S> Nfa<A, S> {
+    /// Creates an [`NfaRunner`] in the initial states.
+    pub fn runner(&self) -> NfaRunner<'_, A, S> {
+        NfaRunner::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nfa_runner() {
+        // Words over {a, b} ending with "ab", with an ε-transition into the loop:
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let q2 = nfa.add_state(false);
+        let q3 = nfa.add_state(true);
+        nfa.add_epsilon_transition(q0, q1);
+        nfa.add_transition(q1, 'a', q1);
+        nfa.add_transition(q1, 'b', q1);
+        nfa.add_transition(q1, 'a', q2);
+        nfa.add_transition(q2, 'b', q3);
+
+        let mut runner = nfa.runner();
+        assert_eq!(runner.current_states(), &BTreeSet::from([q0, q1]));
+        assert_eq!(runner.step('a'), &BTreeSet::from([q1, q2]));
+        assert!(!runner.is_accepting());
+        runner.step('b');
+        assert!(runner.is_accepting());
+        runner.step('b');
+        assert!(!runner.is_accepting());
+        runner.reset();
+        assert_eq!(runner.current_states(), &BTreeSet::from([q0, q1]));
+        for symbol in "bab".chars() {
+            runner.step(symbol);
+        }
+        assert!(runner.is_accepting());
+
+        // Stuck after a symbol without transitions:
+        assert!(runner.step('c').is_empty());
+        assert!(!runner.step('a').contains(&q1));
+        assert!(Nfa::<char>::new().runner().current_states().is_empty());
+    }
+}
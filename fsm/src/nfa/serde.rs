@@ -1,11 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
+use serde::de::Error;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use crate::alphabet::Alphabet;
 use crate::nfa::Nfa;
 
-use super::State;
+use super::{State, StateId};
+
+/// Serializes a set of states in increasing order, so that the output does not depend on hashing.
+pub(super) fn sorted<S: Serializer>(
+    states: &HashSet<StateId>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(states.iter().collect::<BTreeSet<_>>())
+}
 
 impl<A: Alphabet + Serialize> Serialize for Nfa<A> {
     fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
@@ -34,19 +43,36 @@ impl<'de, A: Alphabet + Deserialize<'de>> Deserialize<'de> for Nfa<A> {
         }
 
         let helper = NfaHelper::deserialize(deserializer)?;
+        // Dense ids (`0..n` in any order) are kept, so they survive a round-trip,
+        // otherwise states are renumbered in the order they are listed:
+        let mut states = helper.states;
+        let num_states = states.len();
+        if states.iter().all(|state| state.id.index() < num_states) {
+            states.sort_by_key(|state| state.id);
+        }
+        let mut old2new = HashMap::new();
+        for (index, state) in states.iter().enumerate() {
+            if old2new.insert(state.id, StateId::new(index)).is_some() {
+                return Err(D::Error::custom(format!("duplicate state id {}", state.id)));
+            }
+        }
+
         let mut nfa = Nfa::new();
-        let old2new: HashMap<_, _> = helper
-            .states
-            .iter()
-            .map(|state| (state.id, nfa.add_state(state.accepting)))
-            .collect();
-        for old_from_state in &helper.states {
-            let new_from = old2new[&old_from_state.id];
-            for (symbol, old_to) in old_from_state.transitions() {
-                nfa.add_transition(new_from, symbol, old2new[&old_to]);
+        for state in &states {
+            nfa.add_state(state.accepting);
+        }
+        let check = |from: StateId, to: StateId| {
+            old2new.get(&to).copied().ok_or_else(|| {
+                D::Error::custom(format!("transition from {} to unknown state {}", from, to))
+            })
+        };
+        for state in &states {
+            let from = old2new[&state.id];
+            for (symbol, to) in state.transitions() {
+                nfa.add_transition(from, symbol, check(state.id, to)?);
             }
-            for &old_to in old_from_state.next_epsilon() {
-                nfa.add_epsilon_transition(new_from, old2new[&old_to]);
+            for &to in state.next_epsilon() {
+                nfa.add_epsilon_transition(from, check(state.id, to)?);
             }
         }
         Ok(nfa)
@@ -78,4 +104,80 @@ mod tests {
             assert_eq!(nfa.accepts(word.chars()), nfa2.accepts(word.chars()));
         }
     }
+
+    #[test]
+    fn test_nfa_serde_stable_ids() {
+        let mut nfa = Nfa::new();
+        let a = nfa.add_state(false);
+        let b = nfa.add_state(true);
+        let c = nfa.add_state(false);
+        nfa.add_transition(a, 'x', b);
+        nfa.add_transition(a, 'x', c);
+        nfa.add_transition(c, 'y', a);
+        nfa.add_epsilon_transition(b, c);
+        nfa.add_epsilon_transition(c, a);
+
+        let json = serde_json::to_string(&nfa).unwrap();
+        let nfa2 = decltype(&nfa, serde_json::from_str(&json).unwrap());
+        assert_eq!(nfa2.num_states(), 3);
+        for id in [a, b, c] {
+            let (s1, s2) = (nfa.state(id), nfa2.state(id));
+            assert_eq!(s1.accepting, s2.accepting);
+            let mut t1 = s1.transitions().collect::<Vec<_>>();
+            let mut t2 = s2.transitions().collect::<Vec<_>>();
+            t1.sort_unstable();
+            t2.sort_unstable();
+            assert_eq!(t1, t2);
+            assert_eq!(s1.next_epsilon(), s2.next_epsilon());
+        }
+
+        // The order of states in the input does not matter:
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["states"].as_array_mut().unwrap().reverse();
+        let nfa3: Nfa<char> = serde_json::from_value(value).unwrap();
+        assert!(nfa3.state(b).accepting);
+        assert_eq!(nfa3.state(c).next_epsilon(), nfa.state(c).next_epsilon());
+        assert_eq!(nfa3.state(a).next('x'), nfa.state(a).next('x'));
+    }
+
+    #[test]
+    fn test_nfa_serde_invalid() {
+        let parse = |json: &str| {
+            serde_json::from_str::<Nfa<char>>(json)
+                .unwrap_err()
+                .to_string()
+        };
+        let state = |id: usize, eps: &str| {
+            format!(
+                r#"{{"id":{},"accepting":false,"transitions":{{}},"epsilon_transitions":[{}]}}"#,
+                id, eps
+            )
+        };
+        assert!(parse(&format!(
+            r#"{{"states":[{},{}]}}"#,
+            state(0, ""),
+            state(0, "")
+        ))
+        .contains("duplicate state id 0"));
+        assert!(parse(&format!(r#"{{"states":[{}]}}"#, state(0, "3")))
+            .contains("transition from 0 to unknown state 3"));
+    }
+
+    #[test]
+    fn test_nfa_serde_sparse_ids() {
+        // Ids that are not exactly `0..n` are renumbered in the order states are listed:
+        let json = r#"{"states":[
+            {"id":42,"accepting":false,"transitions":{"a":[7]},"epsilon_transitions":[42]},
+            {"id":7,"accepting":true,"transitions":{"b":[42,7]},"epsilon_transitions":[]}
+        ]}"#;
+        let nfa: Nfa<char> = serde_json::from_str(json).unwrap();
+        let (q0, q1) = (StateId::new(0), StateId::new(1));
+        assert_eq!(nfa.num_states(), 2);
+        assert!(!nfa.state(q0).accepting);
+        assert!(nfa.state(q1).accepting);
+        assert_eq!(nfa.state(q0).next('a'), Some(&vec![q1]));
+        assert!(nfa.state(q0).next_epsilon().contains(&q0));
+        assert!(nfa.accepts("abab".chars()));
+        assert!(!nfa.accepts("b".chars()));
+    }
 }
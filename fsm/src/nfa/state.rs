@@ -3,8 +3,14 @@ use std::collections::HashSet;
 use multimap::MultiMap;
 
 use crate::alphabet::Alphabet;
+use crate::util::arena::state_id;
 
-pub type StateId = usize;
+state_id! {
+    /// Id of a state of an NFA.
+    NfaStateId
+}
+
+pub type StateId = NfaStateId;
 
 #[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -12,6 +18,7 @@ pub struct State<A: Alphabet> {
     pub id: StateId,
     pub accepting: bool,
     transitions: MultiMap<A, StateId>,
+    #[cfg_attr(feature = "serde", serde(serialize_with = "super::serde::sorted"))]
     epsilon_transitions: HashSet<StateId>,
 }
 
@@ -33,6 +40,35 @@ impl<A: Alphabet> State<A> {
         self.epsilon_transitions.insert(to);
     }
 
+    /// Removes the transition on `symbol` to `to`, returning whether it existed.
+    pub fn remove_transition(&mut self, symbol: A, to: StateId) -> bool {
+        let Some(targets) = self.transitions.get_vec_mut(&symbol) else {
+            return false;
+        };
+        let Some(index) = targets.iter().position(|&target| target == to) else {
+            return false;
+        };
+        targets.remove(index);
+        if targets.is_empty() {
+            self.transitions.remove(&symbol);
+        }
+        true
+    }
+
+    pub fn remove_epsilon_transition(&mut self, to: StateId) -> bool {
+        self.epsilon_transitions.remove(&to)
+    }
+
+    /// Redirects every transition to `f(to)`, dropping those for which it is `None`.
+    pub(crate) fn retarget(&mut self, f: impl Fn(StateId) -> Option<StateId>) {
+        self.transitions = (self.transitions.flat_iter())
+            .filter_map(|(&symbol, &to)| Some((symbol, f(to)?)))
+            .collect();
+        self.epsilon_transitions = (self.epsilon_transitions.iter())
+            .filter_map(|&to| f(to))
+            .collect();
+    }
+
     pub fn num_transitions(&self) -> usize {
         self.transitions.iter_all().map(|(_, x)| x.len()).sum()
     }
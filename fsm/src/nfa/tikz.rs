@@ -0,0 +1,61 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::tikz::{write_tikz, TikzOptions};
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+impl<A: Alphabet + Display> Nfa<A> {
+    pub fn render_tikz(&self) -> String {
+        self.render_tikz_with(&TikzOptions::default())
+    }
+
+    /// Renders the automaton as a TikZ picture, see [`Dfa::render_tikz_with`](crate::dfa::Dfa::render_tikz_with).
+    ///
+    /// ε-transitions are labeled `$\varepsilon$`, after the symbols of parallel transitions.
+    pub fn render_tikz_with(&self, options: &TikzOptions<StateId>) -> String {
+        let mut edges: BTreeMap<(StateId, StateId), Vec<A>> = BTreeMap::new();
+        for (from, symbol, to) in self.transitions() {
+            edges.entry((from.id, to.id)).or_default().push(symbol);
+        }
+        let mut edges = edges
+            .into_iter()
+            .map(|(edge, mut symbols)| {
+                symbols.sort_unstable();
+                (
+                    edge,
+                    symbols.iter().map(|s| s.to_string()).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<BTreeMap<_, _>>();
+        for (from, to) in self.epsilon_transitions() {
+            edges
+                .entry((from.id, to.id))
+                .or_default()
+                .push("ε".to_string());
+        }
+        let accepting = self
+            .states()
+            .map(|state| state.accepting)
+            .collect::<Vec<_>>();
+        write_tikz(&accepting, &edges, options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_tikz() {
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(true);
+        nfa.add_transition(q0, 'a', q1);
+        nfa.add_epsilon_transition(q0, q1);
+        let tikz = nfa.render_tikz();
+        assert!(tikz.contains("\\node[state, accepting] (q1) at (2.5, 0) {$q_{1}$};"));
+        assert!(tikz.contains("(q0) edge node {a, $\\varepsilon$} (q1)"));
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::{BTreeMap, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+/// A transition taken on a path of an NFA, as `(from, symbol, to)`, with `None` for ε.
+pub type Step<A> = (StateId, Option<A>, StateId);
+
+/// How a state was first reached while reading a prefix: `None` for the initial state.
+type Parents<A> = BTreeMap<StateId, Option<(StateId, Option<A>)>>;
+
+impl<A: Alphabet, S> Nfa<A, S> {
+    /// Finds an accepting path for `word`, including ε-transitions,
+    /// or `None` if the word is rejected.
+    ///
+    /// The path is a representative one: after every prefix, each state is reached the way
+    /// found first in breadth-first order, and the path ends in the accepting state
+    /// with the smallest id.
+    pub fn accepts_with_trace(&self, word: impl IntoIterator<Item = A>) -> Option<Vec<Step<A>>> {
+        if self.states.is_empty() {
+            return None;
+        }
+        let mut layer: Parents<A> = BTreeMap::from([(StateId::new(0), None)]);
+        self.close_layer(&mut layer, vec![StateId::new(0)]);
+        let mut layers = Vec::new();
+        for symbol in word {
+            let mut next: Parents<A> = BTreeMap::new();
+            let mut reached = Vec::new();
+            for &state in layer.keys() {
+                for &to in self.next(state, symbol).into_iter().flatten() {
+                    next.entry(to).or_insert_with(|| {
+                        reached.push(to);
+                        Some((state, Some(symbol)))
+                    });
+                }
+            }
+            self.close_layer(&mut next, reached);
+            layers.push(std::mem::replace(&mut layer, next));
+        }
+        layers.push(layer);
+
+        let mut state = *layers
+            .last()
+            .unwrap()
+            .keys()
+            .find(|&&state| self.accepting(state))?;
+        let mut path = Vec::new();
+        while let Some(layer) = layers.last() {
+            match layer[&state] {
+                None => break,
+                Some((from, symbol)) => {
+                    path.push((from, symbol, state));
+                    if symbol.is_some() {
+                        layers.pop();
+                    }
+                    state = from;
+                }
+            }
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Adds the states reachable from `start` by ε-transitions to `layer`.
+    fn close_layer(&self, layer: &mut Parents<A>, start: Vec<StateId>) {
+        let mut queue = VecDeque::from(start);
+        while let Some(state) = queue.pop_front() {
+            for &to in self.next_epsilon(state) {
+                layer.entry(to).or_insert_with(|| {
+                    queue.push_back(to);
+                    Some((state, None))
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accepts_with_trace() {
+        // Words over {a, b} ending with "ab", with ε-transitions around the loop:
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let q2 = nfa.add_state(false);
+        let q3 = nfa.add_state(false);
+        let q4 = nfa.add_state(true);
+        nfa.add_epsilon_transition(q0, q1);
+        nfa.add_transition(q1, 'a', q1);
+        nfa.add_transition(q1, 'b', q1);
+        nfa.add_transition(q1, 'a', q2);
+        nfa.add_transition(q2, 'b', q3);
+        nfa.add_epsilon_transition(q3, q4);
+
+        let path = nfa.accepts_with_trace("bab".chars()).unwrap();
+        assert_eq!(
+            path,
+            vec![
+                (q0, None, q1),
+                (q1, Some('b'), q1),
+                (q1, Some('a'), q2),
+                (q2, Some('b'), q3),
+                (q3, None, q4),
+            ]
+        );
+        assert_eq!(nfa.accepts_with_trace("aba".chars()), None);
+        assert_eq!(nfa.accepts_with_trace("".chars()), None);
+        assert_eq!(Nfa::<char>::new().accepts_with_trace("".chars()), None);
+
+        // The empty word is accepted by an accepting initial state, with an empty path:
+        nfa.state_mut(q0).accepting = true;
+        assert_eq!(nfa.accepts_with_trace("".chars()), Some(vec![]));
+    }
+}
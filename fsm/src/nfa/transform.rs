@@ -0,0 +1,112 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::alphabet::Alphabet;
+use crate::nfa::cycle::EpsilonCyclePolicy;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+use crate::util::dfs::multi_dfs;
+
+impl<A: Alphabet> Nfa<A> {
+    /// States reachable from the initial state, by symbol and ε-transitions.
+    pub fn accessible_states(&self) -> BTreeSet<StateId> {
+        if self.states.is_empty() {
+            return BTreeSet::new();
+        }
+        multi_dfs(vec![StateId::new(0)], |state| {
+            let state = self.state(state);
+            state
+                .transitions()
+                .map(|(_, to)| to)
+                .chain(state.next_epsilon().iter().copied())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+    }
+
+    /// States from which some accepting state is reachable, by symbol and ε-transitions.
+    pub fn coaccessible_states(&self) -> BTreeSet<StateId> {
+        let mut predecessors: HashMap<StateId, Vec<StateId>> = HashMap::new();
+        let edges = self
+            .transitions()
+            .map(|(from, _, to)| (from, to))
+            .chain(self.epsilon_transitions());
+        for (from, to) in edges {
+            predecessors.entry(to.id).or_default().push(from.id);
+        }
+        let accepting = self
+            .states()
+            .filter(|state| state.accepting)
+            .map(|state| state.id)
+            .collect();
+        multi_dfs(accepting, |state| {
+            predecessors.get(&state).cloned().unwrap_or_default()
+        })
+        .collect()
+    }
+
+    /// Removes useless states, i.e. states that are unreachable from the initial state
+    /// or from which no accepting state is reachable.
+    ///
+    /// The remaining states are renumbered, preserving their relative order,
+    /// so the initial state stays `0`. If the language is empty, no states remain.
+    pub fn trim(&mut self) {
+        let accessible = self.accessible_states();
+        let coaccessible = self.coaccessible_states();
+        let policy = self.epsilon_cycle_policy;
+        // The trimmed automaton only has ε-cycles that were already there:
+        let mut trimmed = Nfa::new().with_epsilon_cycle_policy(EpsilonCyclePolicy::Allow);
+        let old2new: HashMap<_, _> = self
+            .states()
+            .filter(|state| accessible.contains(&state.id) && coaccessible.contains(&state.id))
+            .map(|state| (state.id, trimmed.add_state(state.accepting)))
+            .collect();
+        for (from, symbol, to) in self.transitions() {
+            if let (Some(&from), Some(&to)) = (old2new.get(&from.id), old2new.get(&to.id)) {
+                trimmed.add_transition(from, symbol, to);
+            }
+        }
+        for (from, to) in self.epsilon_transitions() {
+            if let (Some(&from), Some(&to)) = (old2new.get(&from.id), old2new.get(&to.id)) {
+                trimmed.add_epsilon_transition(from, to);
+            }
+        }
+        trimmed.set_epsilon_cycle_policy(policy);
+        *self = trimmed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    #[test]
+    fn test_trim() {
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let dead = nfa.add_state(false);
+        let q2 = nfa.add_state(true);
+        let unreachable = nfa.add_state(true);
+        nfa.add_transition(q0, 'a', q1);
+        nfa.add_transition(q0, 'b', dead);
+        nfa.add_epsilon_transition(q1, q2);
+        nfa.add_transition(unreachable, 'a', q0);
+
+        let original = nfa.determinize();
+        nfa.trim();
+        assert_eq!(nfa.num_states(), 3);
+        assert_eq!(nfa.num_epsilon_transitions(), 1);
+        for word in generate_strings(&['a', 'b'], 3) {
+            assert_eq!(nfa.accepts(word.chars()), original.accepts(word.chars()));
+        }
+
+        let mut empty = Nfa::new();
+        let q0 = empty.add_state(false);
+        empty.add_transition(q0, 'a', q0);
+        empty.trim();
+        assert_eq!(empty.num_states(), 0);
+        assert!(!empty.accepts("".chars()));
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+use crate::util::dfs::multi_dfs;
+
+/// Iterator over accepted words of bounded length, see [`Nfa::words_up_to`].
+pub struct Words<'a, A: Alphabet> {
+    nfa: &'a Nfa<A>,
+    max_length: usize,
+    queue: VecDeque<(Vec<A>, BTreeSet<StateId>)>,
+    coaccessible: HashSet<StateId>,
+}
+
+impl<'a, A: Alphabet> Words<'a, A> {
+    fn new(nfa: &'a Nfa<A>, max_length: usize) -> Self {
+        let coaccessible = nfa.coaccessible_set();
+        let mut queue = VecDeque::new();
+        if !nfa.states.is_empty() {
+            let initial = nfa
+                .epsilon_closure(StateId::new(0))
+                .filter(|state| coaccessible.contains(state))
+                .collect::<BTreeSet<_>>();
+            if !initial.is_empty() {
+                queue.push_back((Vec::new(), initial));
+            }
+        }
+        Self {
+            nfa,
+            max_length,
+            queue,
+            coaccessible,
+        }
+    }
+}
+
+impl<A: Alphabet> Iterator for Words<'_, A> {
+    type Item = Vec<A>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((word, states)) = self.queue.pop_front() {
+            if word.len() < self.max_length {
+                let mut successors: BTreeMap<A, Vec<StateId>> = BTreeMap::new();
+                for &state in &states {
+                    for (symbol, to) in self.nfa.state(state).transitions() {
+                        successors.entry(symbol).or_default().push(to);
+                    }
+                }
+                for (symbol, targets) in successors {
+                    let next_states = self
+                        .nfa
+                        .multi_epsilon_closure(targets)
+                        .filter(|state| self.coaccessible.contains(state))
+                        .collect::<BTreeSet<_>>();
+                    if !next_states.is_empty() {
+                        let mut next = word.clone();
+                        next.push(symbol);
+                        self.queue.push_back((next, next_states));
+                    }
+                }
+            }
+            if self.nfa.any_accepting(states.iter().copied()) {
+                return Some(word);
+            }
+        }
+        None
+    }
+}
+
+impl<A: Alphabet> Nfa<A> {
+    /// States from which some accepting state is reachable (possibly via epsilon-transitions).
+    fn coaccessible_set(&self) -> HashSet<StateId> {
+        let mut predecessors: HashMap<StateId, Vec<StateId>> = HashMap::new();
+        for (from, _, to) in self.transitions() {
+            predecessors.entry(to.id).or_default().push(from.id);
+        }
+        for (from, to) in self.epsilon_transitions() {
+            predecessors.entry(to.id).or_default().push(from.id);
+        }
+        let accepting = self
+            .states()
+            .filter(|state| state.accepting)
+            .map(|state| state.id)
+            .collect();
+        multi_dfs(accepting, |state| {
+            predecessors.get(&state).cloned().unwrap_or_default()
+        })
+        .collect()
+    }
+
+    /// Enumerates all accepted words of length at most `max_length`, without determinization.
+    ///
+    /// Words are produced in the same order as by
+    /// [`Dfa::words_up_to`](crate::dfa::Dfa::words_up_to): by increasing length,
+    /// then lexicographically. Each word is produced once, no matter how many accepting paths
+    /// it has. Sets of current states are tracked only along prefixes that can still be
+    /// extended to an accepted word.
+    pub fn words_up_to(&self, max_length: usize) -> Words<'_, A> {
+        Words::new(self, max_length)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_words;
+
+    use super::*;
+
+    #[test]
+    fn test_words_up_to() {
+        // Words over {a, b} with 'a' at the second-to-last position:
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let q2 = nfa.add_state(false);
+        let q3 = nfa.add_state(true);
+        let dead = nfa.add_state(false);
+        nfa.add_transition(q0, 'a', q0);
+        nfa.add_transition(q0, 'b', q0);
+        nfa.add_transition(q0, 'a', q1);
+        nfa.add_epsilon_transition(q1, q2);
+        nfa.add_transition(q2, 'a', q3);
+        nfa.add_transition(q2, 'b', q3);
+        nfa.add_transition(q0, 'c', dead);
+
+        let words = nfa.words_up_to(3).collect::<Vec<_>>();
+        let expected = ["aa", "ab", "aaa", "aab", "baa", "bab"]
+            .iter()
+            .map(|word| word.chars().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(words, expected);
+
+        let all = generate_words(&['a', 'b', 'c'], 5);
+        let expected = all
+            .into_iter()
+            .filter(|word| nfa.accepts(word.clone()))
+            .count();
+        assert_eq!(nfa.words_up_to(5).count(), expected);
+        assert!(nfa.words_up_to(5).eq(nfa.determinize().words_up_to(5)));
+
+        assert_eq!(Nfa::<char>::new().words_up_to(3).count(), 0);
+    }
+}
@@ -0,0 +1,79 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap};
+
+use crate::alphabet::Alphabet;
+use crate::nfa::state::StateId;
+use crate::nfa::Nfa;
+
+impl<A: Alphabet> Nfa<A> {
+    /// Synthesizes an input of `length` symbols that keeps the simulation frontier
+    /// (the set of current states) as large as possible.
+    ///
+    /// At every step, the symbol leading to the largest ε-closed frontier is chosen
+    /// (ties are broken in favor of less visited frontiers, then by symbol order).
+    /// This is a greedy heuristic, useful for benchmarking the simulation and
+    /// for checking how ReDoS-style inputs are handled.
+    pub fn worst_case_input(&self, length: usize) -> Vec<A> {
+        let mut word = Vec::with_capacity(length);
+        if self.states.is_empty() {
+            return word;
+        }
+        let symbols = self.symbols();
+        if symbols.is_empty() {
+            return word;
+        }
+        let mut visits: HashMap<BTreeSet<_>, usize> = HashMap::new();
+        let mut current = self
+            .epsilon_closure(StateId::new(0))
+            .collect::<BTreeSet<_>>();
+        while word.len() < length {
+            let (symbol, next) = symbols
+                .iter()
+                .map(|&symbol| (symbol, self.step(&current, symbol)))
+                .min_by_key(|(_, next)| {
+                    (Reverse(next.len()), visits.get(next).copied().unwrap_or(0))
+                })
+                .unwrap();
+            *visits.entry(next.clone()).or_default() += 1;
+            word.push(symbol);
+            current = next;
+        }
+        word
+    }
+
+    /// Set of states reachable from `states` by reading `symbol`, including ε-closure.
+    fn step(&self, states: &BTreeSet<StateId>, symbol: A) -> BTreeSet<StateId> {
+        let mut next = BTreeSet::new();
+        for &state in states {
+            if let Some(next_states) = self.next(state, symbol) {
+                next.extend(self.multi_epsilon_closure(next_states.clone()));
+            }
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worst_case_input() {
+        // (a|b)*a(a|b)(a|b): the frontier grows on every 'a'.
+        let mut nfa = Nfa::new();
+        let q0 = nfa.add_state(false);
+        let q1 = nfa.add_state(false);
+        let q2 = nfa.add_state(false);
+        let q3 = nfa.add_state(true);
+        nfa.add_transition(q0, 'a', q0);
+        nfa.add_transition(q0, 'b', q0);
+        nfa.add_transition(q0, 'a', q1);
+        nfa.add_transition(q1, 'a', q2);
+        nfa.add_transition(q1, 'b', q2);
+        nfa.add_transition(q2, 'a', q3);
+        nfa.add_transition(q2, 'b', q3);
+
+        let word = nfa.worst_case_input(5);
+        assert_eq!(word, vec!['a'; 5]);
+    }
+}
@@ -0,0 +1,431 @@
+use std::collections::{HashMap, VecDeque};
+use std::ops::ControlFlow;
+
+use crate::alphabet::Alphabet;
+use crate::dfa::state::StateId;
+use crate::dfa::Dfa;
+use crate::nfa::state::NfaStateId;
+use crate::nfa::Nfa;
+use crate::progress::{self, Cancelled, Progress, REPORT_EVERY};
+use crate::util::dfs::multi_dfs;
+
+/// State of a product automaton: a pair of component states,
+/// where `None` stands for the implicit dead state of a partial DFA.
+type Pair = (Option<StateId>, Option<StateId>);
+
+/// Builds the product of `left` and `right`, see [`Dfa::product_with_progress`].
+fn product<A: Alphabet, S1, T1, S2, T2>(
+    left: &Dfa<A, S1, T1>,
+    right: &Dfa<A, S2, T2>,
+    accept: impl Fn(bool, bool) -> bool,
+) -> Dfa<A> {
+    let mut progress = |_: usize, _: usize| ControlFlow::Continue(());
+    left.product_with_progress(right, accept, &mut progress)
+        .expect("never cancelled")
+}
+
+impl<A: Alphabet, S, T> Dfa<A, S, T> {
+    /// Builds the reachable part of the product of `self` and `other` over the union of their symbols.
+    ///
+    /// A missing transition in a component leads to its implicit dead state, so the product
+    /// is exact for partial DFAs. Pairs where both components are dead are never created.
+    /// A pair is accepting when `accept` holds for the acceptance of its components.
+    ///
+    /// The number of explored and queued pairs is reported to `progress`, which may cancel it.
+    pub fn product_with_progress<S2, T2>(
+        &self,
+        other: &Dfa<A, S2, T2>,
+        accept: impl Fn(bool, bool) -> bool,
+        progress: &mut impl Progress,
+    ) -> Result<Dfa<A>, Cancelled> {
+        let (left, right) = (self, other);
+        let mut dfa = Dfa::new();
+        let initial: Pair = (
+            (left.num_states() > 0).then_some(StateId::new(0)),
+            (right.num_states() > 0).then_some(StateId::new(0)),
+        );
+        if initial == (None, None) {
+            return Ok(dfa);
+        }
+
+        let mut symbols = left.symbols();
+        symbols.extend(right.symbols());
+        symbols.sort_unstable();
+        symbols.dedup();
+
+        let accepting = |(p, q): Pair| {
+            accept(
+                p.is_some_and(|p| left.accepting(p)),
+                q.is_some_and(|q| right.accepting(q)),
+            )
+        };
+
+        let mut pair2state = HashMap::new();
+        let mut queue = VecDeque::new();
+        pair2state.insert(initial, dfa.add_state(accepting(initial)));
+        queue.push_back(initial);
+        let mut steps = 0;
+        while let Some(pair @ (p, q)) = queue.pop_front() {
+            steps += 1;
+            if steps % REPORT_EVERY == 0 {
+                progress::report(progress, steps, queue.len())?;
+            }
+            let from = pair2state[&pair];
+            for &symbol in &symbols {
+                let next: Pair = (
+                    p.and_then(|p| left.next(p, symbol)),
+                    q.and_then(|q| right.next(q, symbol)),
+                );
+                if next == (None, None) {
+                    continue;
+                }
+                let to = *pair2state.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    dfa.add_state(accepting(next))
+                });
+                dfa.add_transition(from, symbol, to);
+            }
+        }
+        Ok(dfa)
+    }
+
+    /// Builds a DFA recognizing words accepted by `self` or `other`, via the product construction.
+    pub fn union<S2, T2>(&self, other: &Dfa<A, S2, T2>) -> Dfa<A> {
+        product(self, other, |a, b| a || b)
+    }
+
+    /// Builds a DFA recognizing words accepted by both `self` and `other`, via the product construction.
+    pub fn intersection<S2, T2>(&self, other: &Dfa<A, S2, T2>) -> Dfa<A> {
+        product(self, other, |a, b| a && b)
+    }
+
+    /// Builds a DFA recognizing words accepted by `self` but not by `other`.
+    pub fn difference<S2, T2>(&self, other: &Dfa<A, S2, T2>) -> Dfa<A> {
+        product(self, other, |a, b| a && !b)
+    }
+
+    /// Builds a DFA recognizing words accepted by exactly one of `self` and `other`.
+    ///
+    /// The result is empty if and only if both automata are equivalent,
+    /// and its accepted words are exactly the ones they disagree on.
+    pub fn symmetric_difference<S2, T2>(&self, other: &Dfa<A, S2, T2>) -> Dfa<A> {
+        product(self, other, |a, b| a != b)
+    }
+
+    /// Builds a DFA recognizing all words over [`symbols`](Dfa::symbols) that `self` rejects.
+    ///
+    /// The automaton is [completed](Dfa::complete) (adding a trap state only when needed),
+    /// and then the acceptance of every state is flipped. Words with symbols outside
+    /// the alphabet are rejected by both automata.
+    pub fn complement(&self) -> Dfa<A> {
+        let mut dfa = self.clone_structure();
+        dfa.complete(self.symbols());
+        for id in 0..dfa.num_states() {
+            let state = dfa.state_mut(StateId::new(id));
+            state.accepting = !state.accepting;
+        }
+        dfa
+    }
+}
+
+impl<A: Alphabet> Nfa<A> {
+    /// Builds an NFA recognizing words accepted by `self` or `other`.
+    ///
+    /// State `0` of the result is a fresh initial state with ε-transitions to the initial
+    /// states of copies of `self` (shifted by `1`) and `other` (shifted by `1 + self.num_states()`).
+    pub fn union(&self, other: &Nfa<A>) -> Nfa<A> {
+        let mut nfa = Nfa::new();
+        let initial = nfa.add_state(false);
+        for component in [self, other] {
+            if component.num_states() == 0 {
+                continue;
+            }
+            let offset = nfa.num_states();
+            let shift = |id: NfaStateId| NfaStateId::new(id.index() + offset);
+            for state in component.states() {
+                nfa.add_state(state.accepting);
+            }
+            for (from, symbol, to) in component.transitions() {
+                nfa.add_transition(shift(from.id), symbol, shift(to.id));
+            }
+            for (from, to) in component.epsilon_transitions() {
+                nfa.add_epsilon_transition(shift(from.id), shift(to.id));
+            }
+            nfa.add_epsilon_transition(initial, NfaStateId::new(offset));
+        }
+        nfa
+    }
+
+    /// Builds an NFA recognizing words made of a word accepted by `self` followed by
+    /// a word accepted by `other`.
+    ///
+    /// States of `self` keep their ids, followed by the states of `other` shifted by
+    /// `self.num_states()`. The accepting states of `self` lose their acceptance and get
+    /// ε-transitions to the initial state of `other` instead.
+    pub fn concat(&self, other: &Nfa<A>) -> Nfa<A> {
+        let mut nfa = Nfa::new();
+        if self.num_states() == 0 || other.num_states() == 0 {
+            return nfa;
+        }
+        let offset = self.num_states();
+        for _ in self.states() {
+            nfa.add_state(false);
+        }
+        for state in other.states() {
+            nfa.add_state(state.accepting);
+        }
+        for state in self.states().filter(|state| state.accepting) {
+            nfa.add_epsilon_transition(state.id, NfaStateId::new(offset));
+        }
+        for (component, offset) in [(self, 0), (other, offset)] {
+            let shift = |id: NfaStateId| NfaStateId::new(id.index() + offset);
+            for (from, symbol, to) in component.transitions() {
+                nfa.add_transition(shift(from.id), symbol, shift(to.id));
+            }
+            for (from, to) in component.epsilon_transitions() {
+                nfa.add_epsilon_transition(shift(from.id), shift(to.id));
+            }
+        }
+        nfa
+    }
+
+    /// Builds an NFA recognizing concatenations of zero or more words accepted by `self`.
+    ///
+    /// State `0` of the result is a fresh accepting initial state with an ε-transition to
+    /// a copy of `self` (shifted by `1`). Instead of ε-transitions back to the start, which
+    /// could close ε-cycles, accepting states get copies of the transitions leaving the
+    /// ε-closure of the initial state of `self`.
+    pub fn star(&self) -> Nfa<A> {
+        let mut nfa = Nfa::new();
+        let initial = nfa.add_state(true);
+        if self.num_states() == 0 {
+            return nfa;
+        }
+        for state in self.states() {
+            nfa.add_state(state.accepting);
+        }
+        let shift = |id: NfaStateId| NfaStateId::new(id.index() + 1);
+        for (from, symbol, to) in self.transitions() {
+            nfa.add_transition(shift(from.id), symbol, shift(to.id));
+        }
+        for (from, to) in self.epsilon_transitions() {
+            nfa.add_epsilon_transition(shift(from.id), shift(to.id));
+        }
+        nfa.add_epsilon_transition(initial, NfaStateId::new(1));
+
+        let restarts = multi_dfs(vec![NfaStateId::new(0)], |state| {
+            self.next_epsilon(state).iter().copied()
+        })
+        .flat_map(|state| self.state(state).transitions())
+        .collect::<Vec<_>>();
+        for accepting in self.states().filter(|state| state.accepting) {
+            for &(symbol, to) in &restarts {
+                nfa.add_transition(shift(accepting.id), symbol, shift(to));
+            }
+        }
+        nfa
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::progress::CancellationToken;
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    /// Words over {a, b} with an even number of `symbol`.
+    fn even(symbol: char) -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(true);
+        let q1 = dfa.add_state(false);
+        dfa.add_transition(q0, symbol, q1);
+        dfa.add_transition(q1, symbol, q0);
+        let other = if symbol == 'a' { 'b' } else { 'a' };
+        dfa.add_transition(q0, other, q0);
+        dfa.add_transition(q1, other, q1);
+        dfa
+    }
+
+    /// The single word "ac", as a partial DFA.
+    fn ac() -> Dfa<char> {
+        let mut dfa = Dfa::new();
+        let q0 = dfa.add_state(false);
+        let q1 = dfa.add_state(false);
+        let q2 = dfa.add_state(true);
+        dfa.add_transition(q0, 'a', q1);
+        dfa.add_transition(q1, 'c', q2);
+        dfa
+    }
+
+    #[test]
+    fn test_dfa_union() {
+        let (left, right) = (even('a'), ac());
+        let union = left.union(&right);
+        for word in generate_strings(&['a', 'b', 'c'], 5) {
+            let expected = left.accepts(word.chars()) || right.accepts(word.chars());
+            assert_eq!(union.accepts(word.chars()), expected, "word = {word:?}");
+        }
+
+        let empty = Dfa::new();
+        assert_eq!(empty.union(&empty).num_states(), 0);
+        let union = empty.union(&right);
+        assert!(union.accepts("ac".chars()));
+        assert!(!union.accepts("a".chars()));
+
+        // Payloads do not take part in the product:
+        let labeled = right.map_states(|id, ()| id.index());
+        assert!(labeled.union(&left).equivalent(&left.union(&right)));
+    }
+
+    #[test]
+    fn test_dfa_intersection() {
+        let (left, right) = (even('a'), even('b'));
+        let intersection = left.intersection(&right);
+        for word in generate_strings(&['a', 'b', 'c'], 5) {
+            let expected = left.accepts(word.chars()) && right.accepts(word.chars());
+            assert_eq!(
+                intersection.accepts(word.chars()),
+                expected,
+                "word = {word:?}"
+            );
+        }
+        assert!(left
+            .intersection(&ac())
+            .states()
+            .all(|state| !state.accepting));
+    }
+
+    #[test]
+    fn test_dfa_difference() {
+        let (left, right) = (even('a'), even('b'));
+        let difference = left.difference(&right);
+        let symmetric = left.symmetric_difference(&right);
+        for word in generate_strings(&['a', 'b', 'c'], 5) {
+            let (a, b) = (left.accepts(word.chars()), right.accepts(word.chars()));
+            assert_eq!(difference.accepts(word.chars()), a && !b, "word = {word:?}");
+            assert_eq!(symmetric.accepts(word.chars()), a != b, "word = {word:?}");
+        }
+
+        // Partial automata: "ac" is not in the completed `even('a')` language over {a, b}.
+        let difference = ac().difference(&left);
+        assert!(difference.accepts("ac".chars()));
+        assert!(!left.difference(&ac()).accepts("ac".chars()));
+    }
+
+    #[test]
+    fn test_dfa_symmetric_difference_equivalent() {
+        let dfa = even('a');
+        let symmetric = dfa.symmetric_difference(&dfa.minimize());
+        assert!(symmetric.states().all(|state| !state.accepting));
+        // A disagreement is found as an accepted word:
+        let symmetric = dfa.symmetric_difference(&even('b'));
+        assert_eq!(symmetric.words_up_to(1).next(), Some(vec!['a']));
+    }
+
+    #[test]
+    fn test_dfa_complement() {
+        let dfa = ac();
+        let complement = dfa.complement();
+        assert_eq!(complement.num_states(), dfa.num_states() + 1);
+        for word in generate_strings(&['a', 'c'], 5) {
+            assert_ne!(complement.accepts(word.chars()), dfa.accepts(word.chars()));
+        }
+        assert!(!complement.accepts("b".chars()));
+
+        // Complete automata don't need a sink:
+        let dfa = even('a');
+        assert_eq!(dfa.complement().num_states(), 2);
+        assert_eq!(dfa.complement().complement().num_states(), 2);
+
+        let complement = Dfa::<char>::new().complement();
+        assert!(complement.accepts("".chars()));
+    }
+
+    #[test]
+    fn test_product_with_progress() {
+        // Words "a^i" for i < 600, intersected with words with an even number of a's:
+        let mut chain = Dfa::new();
+        let states = (0..600).map(|_| chain.add_state(true)).collect::<Vec<_>>();
+        for pair in states.windows(2) {
+            chain.add_transition(pair[0], 'a', pair[1]);
+        }
+        let even = even('a');
+
+        let mut reports = Vec::new();
+        let product = chain
+            .product_with_progress(&even, |a, b| a && b, &mut |done, pending| {
+                reports.push((done, pending));
+                ControlFlow::Continue(())
+            })
+            .unwrap();
+        assert_eq!(product.num_states(), chain.intersection(&even).num_states());
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[0].0, 256);
+
+        let token = CancellationToken::new();
+        token.cancel();
+        let result = chain.product_with_progress(&even, |a, b| a || b, &mut token.clone());
+        assert_eq!(result.unwrap_err(), Cancelled);
+    }
+
+    #[test]
+    fn test_nfa_concat_and_star() {
+        // "ab?" and "c*" (with an ε-path to acceptance):
+        let mut left = Nfa::new();
+        let q0 = left.add_state(false);
+        let q1 = left.add_state(true);
+        let q2 = left.add_state(true);
+        left.add_transition(q0, 'a', q1);
+        left.add_transition(q1, 'b', q2);
+        let mut right = Nfa::new();
+        let p0 = right.add_state(false);
+        let p1 = right.add_state(true);
+        right.add_epsilon_transition(p0, p1);
+        right.add_transition(p1, 'c', p1);
+
+        let concat = left.concat(&right);
+        assert_eq!(concat.num_states(), 5);
+        let star = left.star();
+        let star_concat = concat.star();
+        for word in generate_strings(&['a', 'b', 'c'], 5) {
+            let expected = (0..=word.len()).any(|i| {
+                let (u, v) = word.split_at(i);
+                left.accepts(u.chars()) && right.accepts(v.chars())
+            });
+            assert_eq!(concat.accepts(word.chars()), expected, "word = {word:?}");
+        }
+        // (ab?)* accepts exactly the words over {a, b} starting with 'a' without "bb":
+        for word in generate_strings(&['a', 'b'], 6) {
+            let expected = word.is_empty() || word.starts_with('a') && !word.contains("bb");
+            assert_eq!(star.accepts(word.chars()), expected, "word = {word:?}");
+        }
+        assert!(star_concat.accepts("acab".chars()));
+        assert!(star_concat.accepts("".chars()));
+        assert!(!star_concat.accepts("ca".chars()));
+        assert_eq!(Nfa::<char>::new().star().num_states(), 1);
+        assert_eq!(left.concat(&Nfa::new()).num_states(), 0);
+    }
+
+    #[test]
+    fn test_nfa_union() {
+        let mut left = Nfa::new();
+        let q0 = left.add_state(false);
+        let q1 = left.add_state(true);
+        left.add_transition(q0, 'a', q1);
+        left.add_transition(q1, 'a', q1);
+        let mut right = Nfa::new();
+        let p0 = right.add_state(false);
+        let p1 = right.add_state(false);
+        let p2 = right.add_state(true);
+        right.add_transition(p0, 'b', p1);
+        right.add_epsilon_transition(p1, p2);
+
+        let union = left.union(&right);
+        assert_eq!(union.num_states(), 6);
+        for word in generate_strings(&['a', 'b'], 4) {
+            let expected = left.accepts(word.chars()) || right.accepts(word.chars());
+            assert_eq!(union.accepts(word.chars()), expected, "word = {word:?}");
+        }
+    }
+}
@@ -0,0 +1,242 @@
+use std::fmt::{Display, Formatter};
+use std::time::{Duration, Instant};
+
+use crate::alphabet::Alphabet;
+use crate::dfa::dense::DenseDfa;
+use crate::dfa::Dfa;
+use crate::nfa::Nfa;
+
+/// Size of a value flowing through a [`Pipeline`].
+pub trait Size {
+    fn num_states(&self) -> usize;
+    fn num_transitions(&self) -> usize;
+}
+
+impl<A: Alphabet> Size for Nfa<A> {
+    fn num_states(&self) -> usize {
+        Nfa::num_states(self)
+    }
+    fn num_transitions(&self) -> usize {
+        Nfa::num_transitions(self) + self.num_epsilon_transitions()
+    }
+}
+
+impl<A: Alphabet> Size for Dfa<A> {
+    fn num_states(&self) -> usize {
+        Dfa::num_states(self)
+    }
+    fn num_transitions(&self) -> usize {
+        Dfa::num_transitions(self)
+    }
+}
+
+impl<A: Alphabet> Size for DenseDfa<A> {
+    fn num_states(&self) -> usize {
+        DenseDfa::num_states(self)
+    }
+    fn num_transitions(&self) -> usize {
+        DenseDfa::num_transitions(self)
+    }
+}
+
+/// Statistics of a single pipeline stage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageStats {
+    pub name: &'static str,
+    pub duration: Duration,
+    /// Size of the stage output.
+    pub num_states: usize,
+    pub num_transitions: usize,
+}
+
+/// Chain of automaton transformations, recording time and output size of every stage,
+/// e.g. `Pipeline::new(nfa).determinize().minimize().compact().compile_dense()`.
+#[derive(Debug)]
+pub struct Pipeline<T> {
+    value: T,
+    stats: Vec<StageStats>,
+}
+
+impl<T: Size> Pipeline<T> {
+    /// Starts a pipeline, recording the size of the input as the `input` stage.
+    pub fn new(value: T) -> Self {
+        let stats = vec![StageStats {
+            name: "input",
+            duration: Duration::ZERO,
+            num_states: value.num_states(),
+            num_transitions: value.num_transitions(),
+        }];
+        Self { value, stats }
+    }
+
+    /// Runs a custom stage.
+    pub fn then<U: Size>(mut self, name: &'static str, stage: impl FnOnce(T) -> U) -> Pipeline<U> {
+        let start = Instant::now();
+        let value = stage(self.value);
+        self.stats.push(StageStats {
+            name,
+            duration: start.elapsed(),
+            num_states: value.num_states(),
+            num_transitions: value.num_transitions(),
+        });
+        Pipeline {
+            value,
+            stats: self.stats,
+        }
+    }
+
+    /// Statistics of all stages so far, starting with the input.
+    pub fn stats(&self) -> &[StageStats] {
+        &self.stats
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// Total time spent in all stages.
+    pub fn total_duration(&self) -> Duration {
+        self.stats.iter().map(|stats| stats.duration).sum()
+    }
+
+    /// Renders the statistics as a table, one line per stage.
+    pub fn report(&self) -> Report<'_> {
+        Report(&self.stats)
+    }
+
+    pub fn finish(self) -> T {
+        self.value
+    }
+
+    pub fn into_parts(self) -> (T, Vec<StageStats>) {
+        (self.value, self.stats)
+    }
+}
+
+impl<A: Alphabet> Pipeline<Nfa<A>> {
+    pub fn remove_epsilon(self) -> Self {
+        self.then("remove_epsilon", |nfa| nfa.remove_epsilon())
+    }
+
+    pub fn determinize(self) -> Pipeline<Dfa<A>> {
+        self.then("determinize", |nfa| nfa.determinize())
+    }
+}
+
+impl<A: Alphabet> Pipeline<Dfa<A>> {
+    pub fn minimize(self) -> Self {
+        self.then("minimize", |dfa| dfa.minimize())
+    }
+
+    pub fn minimize_brzozowski(self) -> Self {
+        self.then("minimize_brzozowski", |dfa| dfa.minimize_brzozowski())
+    }
+
+    /// Removes useless states and renumbers the remaining ones, see [`Dfa::trim`].
+    pub fn compact(self) -> Self {
+        self.then("compact", |mut dfa| {
+            dfa.trim();
+            dfa
+        })
+    }
+
+    pub fn compile_dense(self) -> Pipeline<DenseDfa<A>> {
+        self.then("compile_dense", |dfa| dfa.to_dense())
+    }
+}
+
+/// Table of [`StageStats`], see [`Pipeline::report`].
+pub struct Report<'a>(&'a [StageStats]);
+
+impl Display for Report<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<20} {:>10} {:>12} {:>12}",
+            "stage", "states", "transitions", "time"
+        )?;
+        for stats in self.0 {
+            writeln!(
+                f,
+                "{:<20} {:>10} {:>12} {:>12}",
+                stats.name,
+                stats.num_states,
+                stats.num_transitions,
+                format!("{:.3?}", stats.duration)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_common::generate_strings;
+
+    use super::*;
+
+    /// Words over {a, b} with `a` at the third position from the end.
+    fn third_from_end() -> Nfa<char> {
+        let mut nfa = Nfa::new();
+        let states = (0..4).map(|i| nfa.add_state(i == 3)).collect::<Vec<_>>();
+        nfa.add_transition(states[0], 'a', states[0]);
+        nfa.add_transition(states[0], 'b', states[0]);
+        nfa.add_transition(states[0], 'a', states[1]);
+        for i in 1..3 {
+            nfa.add_transition(states[i], 'a', states[i + 1]);
+            nfa.add_transition(states[i], 'b', states[i + 1]);
+        }
+        nfa
+    }
+
+    #[test]
+    fn test_pipeline() {
+        let nfa = third_from_end();
+        let pipeline = Pipeline::new(nfa)
+            .remove_epsilon()
+            .determinize()
+            .minimize()
+            .compact()
+            .compile_dense();
+
+        let names = pipeline
+            .stats()
+            .iter()
+            .map(|stats| stats.name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                "input",
+                "remove_epsilon",
+                "determinize",
+                "minimize",
+                "compact",
+                "compile_dense"
+            ]
+        );
+        // The minimal DFA remembers the last three symbols:
+        assert_eq!(pipeline.stats()[3].num_states, 8);
+        assert_eq!(pipeline.stats()[5].num_transitions, 16);
+
+        let report = pipeline.report().to_string();
+        assert_eq!(report.lines().count(), 7);
+        assert!(report.contains("compile_dense"));
+
+        let nfa = third_from_end();
+        let dense = pipeline.finish();
+        for word in generate_strings(&['a', 'b'], 6) {
+            assert_eq!(dense.accepts(word.chars()), nfa.accepts(word.chars()));
+        }
+    }
+
+    #[test]
+    fn test_pipeline_custom_stage() {
+        let (dfa, stats) = Pipeline::new(third_from_end())
+            .determinize()
+            .then("complement", |dfa| dfa.complement())
+            .into_parts();
+        assert_eq!(stats.last().unwrap().name, "complement");
+        assert!(dfa.accepts("bbb".chars()));
+    }
+}
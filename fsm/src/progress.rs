@@ -0,0 +1,82 @@
+//! Progress reporting and cancellation for long-running algorithms, such as
+//! [`Nfa::determinize_with_progress`](crate::nfa::Nfa::determinize_with_progress),
+//! [`Dfa::minimize_with_progress`](crate::dfa::Dfa::minimize_with_progress)
+//! and [`Dfa::product_with_progress`](crate::dfa::Dfa::product_with_progress).
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Number of steps of an algorithm between two reports.
+pub(crate) const REPORT_EVERY: usize = 256;
+
+/// Observer of a long-running algorithm, called every few hundred steps.
+///
+/// Closures `FnMut(usize, usize) -> ControlFlow<()>` implement it.
+pub trait Progress {
+    /// Reports that `done` units of work are finished and `pending` are known to remain
+    /// (more may be discovered later). Breaking aborts the algorithm with [`Cancelled`].
+    fn report(&mut self, done: usize, pending: usize) -> ControlFlow<()>;
+}
+
+impl<F: FnMut(usize, usize) -> ControlFlow<()>> Progress for F {
+    fn report(&mut self, done: usize, pending: usize) -> ControlFlow<()> {
+        self(done, pending)
+    }
+}
+
+/// Flag for aborting an algorithm from another thread, e.g. a GUI or a request handler.
+///
+/// Clones share the flag, so keep one and pass another to the algorithm.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl Progress for CancellationToken {
+    fn report(&mut self, _done: usize, _pending: usize) -> ControlFlow<()> {
+        if self.is_cancelled() {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+}
+
+/// An algorithm was aborted by its [`Progress`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+/// Reports to `progress`, turning a break into [`Cancelled`].
+pub(crate) fn report(
+    progress: &mut impl Progress,
+    done: usize,
+    pending: usize,
+) -> Result<(), Cancelled> {
+    match progress.report(done, pending) {
+        ControlFlow::Continue(()) => Ok(()),
+        ControlFlow::Break(()) => Err(Cancelled),
+    }
+}
@@ -0,0 +1,95 @@
+/// Shrinks `word` to a locally minimal subsequence for which `fails` still holds,
+/// using the delta-debugging algorithm (ddmin).
+///
+/// The result is *1-minimal*: removing any single symbol from it makes `fails` return `false`.
+/// The order of the remaining symbols is preserved.
+/// If `fails(word)` does not hold in the first place, `word` is returned unchanged.
+pub fn shrink<A, F>(word: &[A], mut fails: F) -> Vec<A>
+where
+    A: Clone,
+    F: FnMut(&[A]) -> bool,
+{
+    let mut current = word.to_vec();
+    if !fails(&current) {
+        return current;
+    }
+    if fails(&[]) {
+        return Vec::new();
+    }
+
+    let mut n = 2;
+    while current.len() >= 2 {
+        let chunk_size = current.len().div_ceil(n);
+        let chunks = (0..current.len())
+            .step_by(chunk_size)
+            .map(|start| start..(start + chunk_size).min(current.len()))
+            .collect::<Vec<_>>();
+
+        // Try to reduce to a single chunk:
+        if let Some(chunk) = chunks
+            .iter()
+            .find(|chunk| fails(&current[(*chunk).clone()]))
+        {
+            current = current[chunk.clone()].to_vec();
+            n = 2;
+            continue;
+        }
+
+        // Try to remove a single chunk:
+        let complement = chunks.iter().find_map(|chunk| {
+            let mut rest = current[..chunk.start].to_vec();
+            rest.extend_from_slice(&current[chunk.end..]);
+            fails(&rest).then_some(rest)
+        });
+        if let Some(rest) = complement {
+            current = rest;
+            n = (n - 1).max(2);
+            continue;
+        }
+
+        // Increase granularity:
+        if n >= current.len() {
+            break;
+        }
+        n = (2 * n).min(current.len());
+    }
+
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shrink() {
+        // Fails when the word contains 'a' before some 'b':
+        let fails = |word: &[char]| {
+            word.iter()
+                .position(|&c| c == 'a')
+                .is_some_and(|i| word[i..].contains(&'b'))
+        };
+        let word = "xxcaxxxcbxcc".chars().collect::<Vec<_>>();
+        assert_eq!(shrink(&word, fails), vec!['a', 'b']);
+    }
+
+    #[test]
+    fn test_shrink_one_minimal() {
+        // Fails when the sum is at least 10:
+        let fails = |word: &[u32]| word.iter().sum::<u32>() >= 10;
+        let word = [1, 2, 3, 4, 5, 6, 1, 1];
+        let shrunk = shrink(&word, fails);
+        assert!(fails(&shrunk));
+        for i in 0..shrunk.len() {
+            let mut smaller = shrunk.clone();
+            smaller.remove(i);
+            assert!(!fails(&smaller));
+        }
+    }
+
+    #[test]
+    fn test_shrink_trivial() {
+        assert_eq!(shrink(&[1, 2, 3], |_| true), Vec::<i32>::new());
+        assert_eq!(shrink(&[1, 2, 3], |_| false), vec![1, 2, 3]);
+    }
+}
@@ -1,31 +1,106 @@
+use std::marker::PhantomData;
 use std::ops::{Index, IndexMut};
 use std::vec::IntoIter;
 
-#[derive(Debug)]
-pub struct Arena<T> {
+/// Index of an item in an [`Arena`].
+///
+/// Each kind of automaton has its own id type, so that an id of a state of one kind
+/// of automaton cannot be used to index the states of another one.
+pub trait ArenaId: Copy {
+    fn from_index(index: usize) -> Self;
+    fn index(self) -> usize;
+}
+
+impl ArenaId for usize {
+    fn from_index(index: usize) -> Self {
+        index
+    }
+
+    fn index(self) -> usize {
+        self
+    }
+}
+
+/// Defines a newtype state id wrapping the index of the state in its automaton.
+macro_rules! state_id {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
+        pub struct $name(usize);
+
+        impl $name {
+            /// Id of the state with the given index, that is, of the `index`-th added state.
+            pub const fn new(index: usize) -> Self {
+                Self(index)
+            }
+
+            /// Index of the state, in the order the states were added.
+            pub const fn index(self) -> usize {
+                self.0
+            }
+        }
+
+        impl $crate::util::arena::ArenaId for $name {
+            fn from_index(index: usize) -> Self {
+                Self(index)
+            }
+
+            fn index(self) -> usize {
+                self.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl From<$name> for usize {
+            fn from(id: $name) -> usize {
+                id.0
+            }
+        }
+    };
+}
+
+pub(crate) use state_id;
+
+#[derive(Debug, Clone)]
+pub struct Arena<T, Id = usize> {
     items: Vec<T>,
+    _id: PhantomData<fn() -> Id>,
 }
 
-impl<T> Arena<T> {
+impl<T, Id: ArenaId> Arena<T, Id> {
     pub fn new() -> Self {
-        Self { items: Vec::new() }
+        Self {
+            items: Vec::new(),
+            _id: PhantomData,
+        }
     }
 
-    pub fn alloc(&mut self, item: T) -> usize {
+    pub fn alloc(&mut self, item: T) -> Id {
         self.alloc_with_id(|_| item)
     }
 
-    pub fn alloc_with_id<F>(&mut self, f: F) -> usize
+    pub fn alloc_with_id<F>(&mut self, f: F) -> Id
     where
-        F: FnOnce(usize) -> T,
+        F: FnOnce(Id) -> T,
     {
         let id = self.next_id();
         self.items.push(f(id));
         id
     }
 
-    pub fn next_id(&self) -> usize {
-        self.items.len()
+    /// Removes the item, shifting the ids of all later items down by one.
+    pub fn remove(&mut self, id: Id) -> T {
+        self.items.remove(id.index())
+    }
+
+    pub fn next_id(&self) -> Id {
+        Id::from_index(self.items.len())
     }
 
     pub fn len(&self) -> usize {
@@ -45,27 +120,27 @@ impl<T> Arena<T> {
     }
 }
 
-impl<T> Default for Arena<T> {
+impl<T, Id: ArenaId> Default for Arena<T, Id> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<T> Index<usize> for Arena<T> {
+impl<T, Id: ArenaId> Index<Id> for Arena<T, Id> {
     type Output = T;
 
-    fn index(&self, index: usize) -> &Self::Output {
-        &self.items[index]
+    fn index(&self, index: Id) -> &Self::Output {
+        &self.items[index.index()]
     }
 }
 
-impl<T> IndexMut<usize> for Arena<T> {
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        &mut self.items[index]
+impl<T, Id: ArenaId> IndexMut<Id> for Arena<T, Id> {
+    fn index_mut(&mut self, index: Id) -> &mut Self::Output {
+        &mut self.items[index.index()]
     }
 }
 
-impl<T> IntoIterator for Arena<T> {
+impl<T, Id> IntoIterator for Arena<T, Id> {
     type Item = T;
     type IntoIter = IntoIter<Self::Item>;
 
@@ -73,3 +148,34 @@ impl<T> IntoIterator for Arena<T> {
         self.items.into_iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    state_id! {
+        TestId
+    }
+
+    #[test]
+    fn test_arena_typed_ids() {
+        let mut arena = Arena::<&str, TestId>::new();
+        let a = arena.alloc("a");
+        let b = arena.alloc_with_id(|id| if id.index() == 1 { "b" } else { "?" });
+        assert_eq!(a, TestId::new(0));
+        assert_eq!(b.index(), 1);
+        assert_eq!(arena[b], "b");
+        assert_eq!(arena.next_id(), TestId::new(2));
+        assert_eq!(b.to_string(), "1");
+        assert_eq!(usize::from(b), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_state_id_serde() {
+        let json = serde_json::to_string(&[TestId::new(3), TestId::new(5)]).unwrap();
+        assert_eq!(json, "[3,5]");
+        let ids: Vec<TestId> = serde_json::from_str(&json).unwrap();
+        assert_eq!(ids, vec![TestId::new(3), TestId::new(5)]);
+    }
+}
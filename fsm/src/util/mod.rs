@@ -1,3 +1,4 @@
 pub mod arena;
 pub mod dfs;
+pub mod scc;
 pub mod set;
@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Computes strongly connected components (Tarjan's algorithm, iterative).
+///
+/// Components are returned in reverse topological order, i.e. every edge between
+/// different components goes from a later component to an earlier one.
+pub fn scc<T, F, I>(nodes: impl IntoIterator<Item = T>, neighbors: F) -> Vec<Vec<T>>
+where
+    T: Hash + Eq + Copy,
+    F: Fn(T) -> I,
+    I: IntoIterator<Item = T>,
+{
+    struct Info {
+        index: usize,
+        lowlink: usize,
+        on_stack: bool,
+    }
+
+    let mut info: HashMap<T, Info> = HashMap::new();
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+
+    for root in nodes {
+        if info.contains_key(&root) {
+            continue;
+        }
+        // Call stack of (node, remaining neighbors):
+        let mut call_stack = vec![(root, neighbors(root).into_iter().collect::<Vec<_>>())];
+        let index = info.len();
+        info.insert(
+            root,
+            Info {
+                index,
+                lowlink: index,
+                on_stack: true,
+            },
+        );
+        stack.push(root);
+
+        while let Some((node, remaining)) = call_stack.last_mut() {
+            let node = *node;
+            if let Some(next) = remaining.pop() {
+                match info.get(&next) {
+                    None => {
+                        let index = info.len();
+                        info.insert(
+                            next,
+                            Info {
+                                index,
+                                lowlink: index,
+                                on_stack: true,
+                            },
+                        );
+                        stack.push(next);
+                        call_stack.push((next, neighbors(next).into_iter().collect()));
+                    }
+                    Some(next_info) if next_info.on_stack => {
+                        let next_index = next_info.index;
+                        let node_info = info.get_mut(&node).unwrap();
+                        node_info.lowlink = node_info.lowlink.min(next_index);
+                    }
+                    Some(_) => {}
+                }
+            } else {
+                call_stack.pop();
+                let Info { index, lowlink, .. } = info[&node];
+                if let Some(&(parent, _)) = call_stack.last() {
+                    let parent_info = info.get_mut(&parent).unwrap();
+                    parent_info.lowlink = parent_info.lowlink.min(lowlink);
+                }
+                if lowlink == index {
+                    let mut component = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        info.get_mut(&member).unwrap().on_stack = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+    }
+
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scc() {
+        let neighbors = |node: u32| match node {
+            0 => vec![1],
+            1 => vec![2, 3],
+            2 => vec![0],
+            3 => vec![4],
+            4 => vec![4],
+            5 => vec![3],
+            _ => unreachable!(),
+        };
+        let mut components = scc(0..6, neighbors)
+            .into_iter()
+            .map(|mut component| {
+                component.sort();
+                component
+            })
+            .collect::<Vec<_>>();
+        // Reverse topological order: {4} before {3} before {0, 1, 2}.
+        let position = |node| components.iter().position(|c| c.contains(&node));
+        assert!(position(4) < position(3));
+        assert!(position(3) < position(0));
+        components.sort();
+        assert_eq!(components, vec![vec![0, 1, 2], vec![3], vec![4], vec![5]]);
+    }
+}
@@ -1,6 +1,7 @@
 use std::collections::{BTreeSet, HashSet};
 use std::hash::Hash;
 
+#[allow(dead_code)]
 pub trait Set<T> {
     fn new() -> Self;
     fn insert(&mut self, item: T) -> bool;
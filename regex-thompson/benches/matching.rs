@@ -0,0 +1,34 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use regex_thompson::bench_support::{alternation, log_lines, pathological};
+use regex_thompson::Regex;
+
+fn compile(c: &mut Criterion) {
+    let pattern = alternation(&["if", "else", "while", "for", "return", "match", "loop"]);
+    c.bench_function("compile/keywords", |b| {
+        b.iter(|| Regex::new(black_box(&pattern)).unwrap())
+    });
+}
+
+fn matching(c: &mut Criterion) {
+    let (pattern, haystack) = pathological(20);
+    let re = Regex::new(&pattern).unwrap();
+    c.bench_function("matches/pathological", |b| {
+        b.iter(|| re.matches(black_box(&haystack)))
+    });
+}
+
+fn search(c: &mut Criterion) {
+    let haystack = log_lines(10_000);
+    let re = Regex::new("ERROR worker-(1|3) processed").unwrap();
+    c.bench_function("search/log", |b| b.iter(|| re.search(black_box(&haystack))));
+    c.bench_function("find/log", |b| b.iter(|| re.find(black_box(&haystack))));
+
+    let re = Regex::new("FATAL").unwrap();
+    c.bench_function("search/log_no_match", |b| {
+        b.iter(|| re.search(black_box(&haystack)))
+    });
+}
+
+criterion_group!(benches, compile, matching, search);
+criterion_main!(benches);
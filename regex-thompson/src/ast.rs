@@ -0,0 +1,697 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Write};
+
+use crate::CharClass;
+
+/// Syntax tree of a pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ast {
+    /// The empty pattern, matching only the empty string.
+    Empty,
+    Literal(char),
+    /// `.`, matching any single char.
+    Any,
+    /// Bracket expression, e.g. `[a-z]`, matching a single char of the class.
+    Class(CharClass),
+    /// `^` or `$`, matching the empty string at the start or the end of the haystack.
+    Anchor(Anchor),
+    Concat(Vec<Ast>),
+    Alternation(Vec<Ast>),
+    /// `*`, zero or more repetitions.
+    Star(Box<Ast>),
+    /// `+`, one or more repetitions.
+    Plus(Box<Ast>),
+    /// `?`, zero or one occurrence.
+    Optional(Box<Ast>),
+    /// Non-greedy version of a quantifier (`*?`, `+?`, `??` or `{..}?`),
+    /// preferring fewer repetitions when extracting captures.
+    Lazy(Box<Ast>),
+    /// Capture group `(...)`, numbered from 1 in the order of opening parentheses.
+    Group {
+        index: usize,
+        inner: Box<Ast>,
+    },
+    /// `{min}`, `{min,}` or `{min,max}`, between `min` and `max` (unbounded if `None`) repetitions.
+    Repeat {
+        inner: Box<Ast>,
+        min: u32,
+        max: Option<u32>,
+    },
+}
+
+/// Largest bound accepted in a counted repetition, since it is expanded into copies of its operand.
+pub const MAX_REPETITION: u32 = 1000;
+
+/// Position asserted by an [`Ast::Anchor`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Anchor {
+    /// `^`, the start of the haystack.
+    Start,
+    /// `$`, the end of the haystack.
+    End,
+    /// `^` in [multi-line](crate::RegexBuilder::multi_line) mode, the start of the haystack
+    /// or right after a `\n`.
+    LineStart,
+    /// `$` in [multi-line](crate::RegexBuilder::multi_line) mode, the end of the haystack
+    /// or right before a `\n`.
+    LineEnd,
+}
+
+/// What went wrong while parsing a pattern.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A `(` without the matching `)`.
+    UnclosedGroup,
+    /// A `)` without the matching `(`.
+    UnmatchedParen,
+    /// An empty alternative or group, e.g. `a|` or `()`.
+    MissingOperand,
+    /// A quantifier with nothing before it, e.g. `*a` or `a|+`.
+    NothingToRepeat,
+    /// A `[` without the matching `]`.
+    UnclosedClass,
+    /// A range in a class whose end is before its start, e.g. `[z-a]`.
+    InvalidRange,
+    /// A malformed counted repetition, e.g. `a{}`, `a{x}` or `a{3,2}`.
+    InvalidRepetition,
+    /// A bound of a counted repetition above [`MAX_REPETITION`].
+    RepetitionTooLarge,
+    /// A `\` at the end of the pattern.
+    DanglingEscape,
+    /// A `\` followed by a letter or digit without a special meaning, e.g. `\q`.
+    UnknownEscape,
+}
+
+/// Error returned for a malformed pattern.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub kind: ParseErrorKind,
+    /// Byte offset in the pattern where the problem was detected
+    /// (for an unclosed group, the offset of its `(`).
+    pub offset: usize,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let message = match self.kind {
+            ParseErrorKind::UnclosedGroup => "unclosed group",
+            ParseErrorKind::UnmatchedParen => "unmatched ')'",
+            ParseErrorKind::MissingOperand => "missing operand",
+            ParseErrorKind::NothingToRepeat => "nothing to repeat",
+            ParseErrorKind::UnclosedClass => "unclosed character class",
+            ParseErrorKind::InvalidRange => "invalid range in character class",
+            ParseErrorKind::InvalidRepetition => "invalid counted repetition",
+            ParseErrorKind::RepetitionTooLarge => "repetition bound too large",
+            ParseErrorKind::DanglingEscape => "dangling escape",
+            ParseErrorKind::UnknownEscape => "unknown escape sequence",
+        };
+        write!(f, "{} at offset {}", message, self.offset)
+    }
+}
+
+impl Error for ParseError {}
+
+/// Recursive-descent parser over the grammar:
+///
+/// ```text
+/// alternation = concat ('|' concat)*
+/// concat      = repeat+
+/// repeat      = atom (('*' | '+' | '?' | '{' number (',' number?)? '}') '?'?)*
+/// atom        = '(' ('?:')? alternation ')' | '[' '^'? item+ ']' | '.' | '^' | '$' | shorthand | escape | literal
+/// item        = shorthand | (escape | char) ('-' (escape | char))?
+/// shorthand   = '\' ('d' | 'D' | 'w' | 'W' | 's' | 'S')
+/// escape      = '\' ('n' | 't' | non-alphanumeric char)
+/// ```
+///
+/// Groups capture unless opened with `(?:`.
+/// Inside a class, a `]` right after the opening `[` (or `[^`) is literal, and so is a `-`
+/// at the start or the end. Escaping a char without a special meaning, e.g. `\-`, is allowed.
+/// Shorthands stand for the classes given by [`CharClass::shorthand`].
+struct Parser<'p> {
+    pattern: &'p str,
+    offset: usize,
+    /// Offsets of the currently open groups.
+    groups: Vec<usize>,
+    /// Number of capture groups opened so far.
+    num_captures: usize,
+    /// Whether shorthands use Unicode rather than ASCII classes.
+    unicode: bool,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.pattern[self.offset..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.peek() {
+            self.offset += c.len_utf8();
+        }
+    }
+
+    fn error(&self, kind: ParseErrorKind, offset: usize) -> ParseError {
+        ParseError { kind, offset }
+    }
+
+    fn alternation(&mut self) -> Result<Ast, ParseError> {
+        let mut ast = self.concat()?;
+        while self.peek() == Some('|') {
+            self.bump();
+            ast = Ast::alternation(ast, self.concat()?);
+        }
+        Ok(ast)
+    }
+
+    fn concat(&mut self) -> Result<Ast, ParseError> {
+        let mut ast = None;
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let item = self.repeat()?;
+            ast = Some(match ast {
+                Some(ast) => Ast::concat(ast, item),
+                None => item,
+            });
+        }
+        ast.ok_or_else(|| match (self.peek(), self.groups.last()) {
+            (None, Some(&open)) => self.error(ParseErrorKind::UnclosedGroup, open),
+            _ => self.error(ParseErrorKind::MissingOperand, self.offset),
+        })
+    }
+
+    fn repeat(&mut self) -> Result<Ast, ParseError> {
+        let mut ast = self.atom()?;
+        loop {
+            ast = match self.peek() {
+                Some('*') => Ast::Star(Box::new(ast)),
+                Some('+') => Ast::Plus(Box::new(ast)),
+                Some('?') => Ast::Optional(Box::new(ast)),
+                Some('{') => {
+                    let (min, max) = self.bounds()?;
+                    Ast::Repeat {
+                        inner: Box::new(ast),
+                        min,
+                        max,
+                    }
+                }
+                _ => return Ok(ast),
+            };
+            self.bump();
+            if self.peek() == Some('?') {
+                self.bump();
+                ast = Ast::Lazy(Box::new(ast));
+            }
+        }
+    }
+
+    /// Parses the bounds of a counted repetition, stopping at the closing `}`.
+    fn bounds(&mut self) -> Result<(u32, Option<u32>), ParseError> {
+        let open = self.offset;
+        self.bump();
+        let min = self
+            .number()?
+            .ok_or(self.error(ParseErrorKind::InvalidRepetition, open))?;
+        let max = if self.peek() == Some(',') {
+            self.bump();
+            self.number()?
+        } else {
+            Some(min)
+        };
+        if self.peek() != Some('}') || max.is_some_and(|max| max < min) {
+            return Err(self.error(ParseErrorKind::InvalidRepetition, open));
+        }
+        Ok((min, max))
+    }
+
+    /// Parses a decimal number, if there is one.
+    fn number(&mut self) -> Result<Option<u32>, ParseError> {
+        let offset = self.offset;
+        let mut number: Option<u32> = None;
+        while let Some(digit) = self.peek().and_then(|c| c.to_digit(10)) {
+            let value = number.unwrap_or(0) * 10 + digit;
+            if value > MAX_REPETITION {
+                return Err(self.error(ParseErrorKind::RepetitionTooLarge, offset));
+            }
+            number = Some(value);
+            self.bump();
+        }
+        Ok(number)
+    }
+
+    fn atom(&mut self) -> Result<Ast, ParseError> {
+        let offset = self.offset;
+        let c = self.peek().expect("atom is only parsed before a char");
+        self.bump();
+        match c {
+            '(' => {
+                self.groups.push(offset);
+                let index = if self.pattern[self.offset..].starts_with("?:") {
+                    self.bump();
+                    self.bump();
+                    None
+                } else {
+                    self.num_captures += 1;
+                    Some(self.num_captures)
+                };
+                let ast = self.alternation()?;
+                if self.peek() != Some(')') {
+                    return Err(self.error(ParseErrorKind::UnclosedGroup, offset));
+                }
+                self.bump();
+                self.groups.pop();
+                Ok(match index {
+                    Some(index) => Ast::Group {
+                        index,
+                        inner: Box::new(ast),
+                    },
+                    None => ast,
+                })
+            }
+            '[' => self.class(offset),
+            '*' | '+' | '?' | '{' => Err(self.error(ParseErrorKind::NothingToRepeat, offset)),
+            '.' => Ok(Ast::Any),
+            '^' => Ok(Ast::Anchor(Anchor::Start)),
+            '$' => Ok(Ast::Anchor(Anchor::End)),
+            '\\' => match self
+                .peek()
+                .and_then(|c| CharClass::shorthand(c, self.unicode))
+            {
+                Some(class) => {
+                    self.bump();
+                    Ok(Ast::Class(class))
+                }
+                None => Ok(Ast::Literal(self.escape(offset)?)),
+            },
+            c => Ok(Ast::Literal(c)),
+        }
+    }
+
+    /// Parses the rest of a class opened by the `[` at `open`.
+    fn class(&mut self, open: usize) -> Result<Ast, ParseError> {
+        let negated = self.peek() == Some('^');
+        if negated {
+            self.bump();
+        }
+        let mut ranges = Vec::new();
+        loop {
+            let offset = self.offset;
+            if let Some(class) = self.shorthand_ahead() {
+                self.bump();
+                self.bump();
+                ranges.extend(class.matched_ranges());
+                continue;
+            }
+            let lo = match self.peek() {
+                None => return Err(self.error(ParseErrorKind::UnclosedClass, open)),
+                Some(']') if !ranges.is_empty() => break,
+                Some(_) => self.class_char()?,
+            };
+            let mut hi = lo;
+            // A '-' before the closing ']' (or the end) is a literal, not a range:
+            let mut rest = self.pattern[self.offset..].chars();
+            if let (Some('-'), Some(end)) = (rest.next(), rest.next()) {
+                if end != ']' {
+                    self.bump();
+                    if self.shorthand_ahead().is_some() {
+                        return Err(self.error(ParseErrorKind::InvalidRange, offset));
+                    }
+                    hi = self.class_char()?;
+                    if hi < lo {
+                        return Err(self.error(ParseErrorKind::InvalidRange, offset));
+                    }
+                }
+            }
+            ranges.push((lo, hi));
+        }
+        self.bump();
+        Ok(Ast::Class(CharClass::new(ranges, negated)))
+    }
+
+    /// Class of the shorthand (e.g. `\d`) at the current offset, if any.
+    fn shorthand_ahead(&self) -> Option<CharClass> {
+        let mut rest = self.pattern[self.offset..].chars();
+        match (rest.next(), rest.next()) {
+            (Some('\\'), Some(letter)) => CharClass::shorthand(letter, self.unicode),
+            _ => None,
+        }
+    }
+
+    /// Parses a single (possibly escaped) char of a class.
+    fn class_char(&mut self) -> Result<char, ParseError> {
+        let offset = self.offset;
+        let c = self
+            .peek()
+            .expect("class char is only parsed before a char");
+        self.bump();
+        if c == '\\' {
+            self.escape(offset)
+        } else {
+            Ok(c)
+        }
+    }
+
+    /// Parses the rest of an escape sequence started by the `\` at `backslash`.
+    fn escape(&mut self, backslash: usize) -> Result<char, ParseError> {
+        let c = match self.peek() {
+            None => return Err(self.error(ParseErrorKind::DanglingEscape, backslash)),
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some(c) if c.is_alphanumeric() => {
+                return Err(self.error(ParseErrorKind::UnknownEscape, backslash))
+            }
+            Some(c) => c,
+        };
+        self.bump();
+        Ok(c)
+    }
+}
+
+impl Ast {
+    /// Parses `pattern` into a tree.
+    ///
+    /// Chains of the same binary operator are flattened, e.g. `a|b|c` is a single
+    /// alternation with three branches (both operators are associative).
+    /// The empty pattern parses to [`Ast::Empty`], but empty alternatives and groups are errors.
+    /// Shorthands such as `\d` use Unicode classes.
+    pub fn parse(pattern: &str) -> Result<Ast, ParseError> {
+        Self::parse_with(pattern, true)
+    }
+
+    /// Same as [`parse`](Ast::parse), with ASCII shorthand classes unless `unicode`.
+    pub(crate) fn parse_with(pattern: &str, unicode: bool) -> Result<Ast, ParseError> {
+        if pattern.is_empty() {
+            return Ok(Ast::Empty);
+        }
+        let mut parser = Parser {
+            pattern,
+            offset: 0,
+            groups: Vec::new(),
+            num_captures: 0,
+            unicode,
+        };
+        let ast = parser.alternation()?;
+        if parser.offset < pattern.len() {
+            // Only an unmatched ')' can stop the top-level alternation early.
+            return Err(parser.error(ParseErrorKind::UnmatchedParen, parser.offset));
+        }
+        Ok(ast)
+    }
+
+    pub(crate) fn concat(left: Ast, right: Ast) -> Ast {
+        match (left, right) {
+            (Ast::Concat(mut left), Ast::Concat(right)) => {
+                left.extend(right);
+                Ast::Concat(left)
+            }
+            (Ast::Concat(mut left), right) => {
+                left.push(right);
+                Ast::Concat(left)
+            }
+            (left, Ast::Concat(mut right)) => {
+                right.insert(0, left);
+                Ast::Concat(right)
+            }
+            (left, right) => Ast::Concat(vec![left, right]),
+        }
+    }
+
+    pub(crate) fn alternation(left: Ast, right: Ast) -> Ast {
+        match (left, right) {
+            (Ast::Alternation(mut left), Ast::Alternation(right)) => {
+                left.extend(right);
+                Ast::Alternation(left)
+            }
+            (Ast::Alternation(mut left), right) => {
+                left.push(right);
+                Ast::Alternation(left)
+            }
+            (left, Ast::Alternation(mut right)) => {
+                right.insert(0, left);
+                Ast::Alternation(right)
+            }
+            (left, right) => Ast::Alternation(vec![left, right]),
+        }
+    }
+
+    /// Label of this node alone, e.g. `·` for concatenation.
+    pub fn label(&self) -> String {
+        match self {
+            Ast::Empty => "ε".to_string(),
+            Ast::Literal(c) => format!("'{}'", c),
+            Ast::Any => "any".to_string(),
+            Ast::Class(class) => class.to_string(),
+            Ast::Anchor(Anchor::Start) => "^".to_string(),
+            Ast::Anchor(Anchor::End) => "$".to_string(),
+            Ast::Anchor(Anchor::LineStart) => "line ^".to_string(),
+            Ast::Anchor(Anchor::LineEnd) => "line $".to_string(),
+            Ast::Concat(_) => "·".to_string(),
+            Ast::Alternation(_) => "|".to_string(),
+            Ast::Star(_) => "*".to_string(),
+            Ast::Plus(_) => "+".to_string(),
+            Ast::Optional(_) => "?".to_string(),
+            Ast::Lazy(_) => "lazy".to_string(),
+            Ast::Group { index, .. } => format!("group {}", index),
+            Ast::Repeat { min, max, .. } => match max {
+                Some(max) if max == min => format!("{{{}}}", min),
+                Some(max) => format!("{{{},{}}}", min, max),
+                None => format!("{{{},}}", min),
+            },
+        }
+    }
+
+    pub fn children(&self) -> &[Ast] {
+        match self {
+            Ast::Empty | Ast::Literal(_) | Ast::Any | Ast::Class(_) | Ast::Anchor(_) => &[],
+            Ast::Concat(children) | Ast::Alternation(children) => children,
+            Ast::Star(child)
+            | Ast::Plus(child)
+            | Ast::Optional(child)
+            | Ast::Lazy(child)
+            | Ast::Group { inner: child, .. }
+            | Ast::Repeat { inner: child, .. } => std::slice::from_ref(child),
+        }
+    }
+
+    /// Number of capture groups, i.e. the largest group index.
+    pub fn num_captures(&self) -> usize {
+        let mut num_captures = 0;
+        self.walk(&mut |_, _, ast| {
+            if let Ast::Group { index, .. } = ast {
+                num_captures = num_captures.max(*index);
+            }
+        });
+        num_captures
+    }
+
+    /// Shifts the indices of all capture groups by `by`, e.g. when appending this tree
+    /// to another one with `by` groups.
+    pub(crate) fn shift_captures(&mut self, by: usize) {
+        if let Ast::Group { index, .. } = self {
+            *index += by;
+        }
+        match self {
+            Ast::Concat(children) | Ast::Alternation(children) => {
+                for child in children {
+                    child.shift_captures(by);
+                }
+            }
+            Ast::Star(child)
+            | Ast::Plus(child)
+            | Ast::Optional(child)
+            | Ast::Lazy(child)
+            | Ast::Group { inner: child, .. }
+            | Ast::Repeat { inner: child, .. } => child.shift_captures(by),
+            _ => {}
+        }
+    }
+
+    /// Visits all nodes in pre-order, passing each node's index and its parent's index.
+    fn walk<'a>(&'a self, visit: &mut impl FnMut(usize, Option<usize>, &'a Ast)) {
+        let mut index = 0;
+        let mut stack = vec![(self, None)];
+        while let Some((ast, parent)) = stack.pop() {
+            visit(index, parent, ast);
+            for child in ast.children().iter().rev() {
+                stack.push((child, Some(index)));
+            }
+            index += 1;
+        }
+    }
+
+    /// Renders the operator tree in the Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph AST {\n  node [shape=box];\n");
+        self.walk(&mut |index, parent, ast| {
+            let label = ast.label().replace('\\', "\\\\").replace('"', "\\\"");
+            writeln!(out, "  n{} [label=\"{}\"];", index, label).unwrap();
+            if let Some(parent) = parent {
+                writeln!(out, "  n{} -> n{};", parent, index).unwrap();
+            }
+        });
+        out.push('}');
+        out
+    }
+
+    /// Renders the operator tree as a Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("graph TD\n");
+        self.walk(&mut |index, parent, ast| {
+            let label = ast.label().replace('"', "#quot;");
+            writeln!(out, "  n{}[\"{}\"]", index, label).unwrap();
+            if let Some(parent) = parent {
+                writeln!(out, "  n{} --> n{}", parent, index).unwrap();
+            }
+        });
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        use Ast::*;
+
+        // Concatenation binds tighter than alternation:
+        assert_eq!(
+            Ast::parse("ab|c*").unwrap(),
+            Alternation(vec![
+                Concat(vec![Literal('a'), Literal('b')]),
+                Star(Box::new(Literal('c'))),
+            ])
+        );
+        assert_eq!(
+            Ast::parse("a(b|.)c").unwrap(),
+            Concat(vec![
+                Literal('a'),
+                Group {
+                    index: 1,
+                    inner: Box::new(Alternation(vec![Literal('b'), Any])),
+                },
+                Literal('c'),
+            ])
+        );
+        assert_eq!(
+            Ast::parse("a|(?:b|c)").unwrap(),
+            Alternation(vec![Literal('a'), Literal('b'), Literal('c')])
+        );
+        let ast = Ast::parse("((a)|(?:b)(c))*").unwrap();
+        assert_eq!(ast.num_captures(), 3);
+        assert_eq!(ast.children()[0].label(), "group 1");
+        assert_eq!(
+            Ast::parse("a+?b*?").unwrap(),
+            Concat(vec![
+                Lazy(Box::new(Plus(Box::new(Literal('a'))))),
+                Lazy(Box::new(Star(Box::new(Literal('b'))))),
+            ])
+        );
+        assert_eq!(
+            Ast::parse("a???").unwrap(),
+            Optional(Box::new(Lazy(Box::new(Optional(Box::new(Literal('a')))))))
+        );
+        assert_eq!(
+            Ast::parse("[^]a-c-]").unwrap(),
+            Class(CharClass::new([(']', ']'), ('a', 'c'), ('-', '-')], true))
+        );
+        assert_eq!(
+            Ast::parse("a{2,}b{3}").unwrap(),
+            Concat(vec![
+                Repeat {
+                    inner: Box::new(Literal('a')),
+                    min: 2,
+                    max: None,
+                },
+                Repeat {
+                    inner: Box::new(Literal('b')),
+                    min: 3,
+                    max: Some(3),
+                },
+            ])
+        );
+        assert_eq!(
+            Ast::parse("^a$").unwrap(),
+            Concat(vec![
+                Anchor(super::Anchor::Start),
+                Literal('a'),
+                Anchor(super::Anchor::End),
+            ])
+        );
+        assert_eq!(
+            Ast::parse("\\*\\\\\\n[\\]\\t]").unwrap(),
+            Concat(vec![
+                Literal('*'),
+                Literal('\\'),
+                Literal('\n'),
+                Class(CharClass::new([(']', ']'), ('\t', '\t')], false)),
+            ])
+        );
+        assert_eq!(Ast::parse("").unwrap(), Empty);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        use ParseErrorKind::*;
+
+        let error = |pattern| {
+            let error = Ast::parse(pattern).unwrap_err();
+            (error.kind, error.offset)
+        };
+        assert_eq!(error("a|"), (MissingOperand, 2));
+        assert_eq!(error("|a"), (MissingOperand, 0));
+        assert_eq!(error("a()"), (MissingOperand, 2));
+        assert_eq!(error("(("), (UnclosedGroup, 1));
+        assert_eq!(error("(a|b"), (UnclosedGroup, 0));
+        assert_eq!(error("é(a"), (UnclosedGroup, 2));
+        assert_eq!(error("a)b"), (UnmatchedParen, 1));
+        assert_eq!(error("*a"), (NothingToRepeat, 0));
+        assert_eq!(error("a|?"), (NothingToRepeat, 2));
+        assert_eq!(error("a[bc"), (UnclosedClass, 1));
+        assert_eq!(error("[]"), (UnclosedClass, 0));
+        assert_eq!(error("[a-"), (UnclosedClass, 0));
+        assert_eq!(error("[xz-a]"), (InvalidRange, 2));
+        assert_eq!(error("{2}"), (NothingToRepeat, 0));
+        assert_eq!(error("a{}"), (InvalidRepetition, 1));
+        assert_eq!(error("a{,2}"), (InvalidRepetition, 1));
+        assert_eq!(error("a{3,2}"), (InvalidRepetition, 1));
+        assert_eq!(error("a{2"), (InvalidRepetition, 1));
+        assert_eq!(error("a{1,99999}"), (RepetitionTooLarge, 4));
+        assert_eq!(error("ab\\"), (DanglingEscape, 2));
+        assert_eq!(error("[a\\"), (DanglingEscape, 2));
+        assert_eq!(error("a\\q"), (UnknownEscape, 1));
+        assert_eq!(
+            Ast::parse("(a").unwrap_err().to_string(),
+            "unclosed group at offset 0"
+        );
+    }
+
+    #[test]
+    fn test_compile_empty() {
+        let re = crate::Regex::new("").unwrap();
+        assert!(re.matches(""));
+        assert!(!re.matches("a"));
+    }
+
+    #[test]
+    fn test_render() {
+        let ast = Ast::parse("ab|c*").unwrap();
+        let dot = ast.to_dot();
+        assert!(dot.starts_with("digraph AST {"));
+        assert!(dot.contains("n0 [label=\"|\"];"));
+        assert!(dot.contains("n1 [label=\"·\"];"));
+        assert!(dot.contains("n4 [label=\"*\"];"));
+        assert!(dot.contains("n0 -> n4;"));
+        assert!(dot.contains("n4 -> n5;"));
+
+        let mermaid = ast.to_mermaid();
+        assert!(mermaid.starts_with("graph TD\n"));
+        assert!(mermaid.contains("n5[\"'c'\"]"));
+        assert!(mermaid.contains("n1 --> n3"));
+        assert_eq!(
+            Ast::Literal('"').to_mermaid(),
+            "graph TD\n  n0[\"'#quot;'\"]\n"
+        );
+    }
+}
@@ -0,0 +1,47 @@
+/// The pattern `a?^n a^n` with the haystack `a^n`.
+///
+/// Backtracking engines take exponential time on it, while the NFA simulation stays polynomial.
+pub fn pathological(n: usize) -> (String, String) {
+    let pattern = "a?".repeat(n) + &"a".repeat(n);
+    (pattern, "a".repeat(n))
+}
+
+/// Alternation of all `words`, e.g. a list of keywords.
+pub fn alternation(words: &[&str]) -> String {
+    words.join("|")
+}
+
+/// Deterministic log-like haystack of `num_lines` lines, where every 100th line is an error.
+pub fn log_lines(num_lines: usize) -> String {
+    let mut haystack = String::new();
+    for i in 0..num_lines {
+        let level = if i % 100 == 99 { "ERROR" } else { "INFO" };
+        haystack.push_str(&format!(
+            "2024-01-01T00:00:{:02} {} worker-{} processed request {}\n",
+            i % 60,
+            level,
+            i % 8,
+            i * 7919 % 10007
+        ));
+    }
+    haystack
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Regex;
+
+    #[test]
+    fn test_workloads() {
+        let (pattern, haystack) = pathological(10);
+        assert!(Regex::new(&pattern).unwrap().matches(&haystack));
+
+        let keywords = Regex::new(&alternation(&["if", "else", "while"])).unwrap();
+        assert!(keywords.matches("while"));
+
+        let log = log_lines(200);
+        assert_eq!(log.lines().count(), 200);
+        assert_eq!(log.matches("ERROR").count(), 2);
+    }
+}
@@ -0,0 +1,231 @@
+use crate::class::case_variants;
+use crate::{Anchor, Ast, BytesRegex, CharClass, Nfa, ParseError, Regex};
+
+/// Compiles a [`Regex`] with flags that change how the pattern is interpreted.
+///
+/// The default flags give the same regex as [`Regex::new`]:
+///
+/// ```
+/// use regex_thompson::RegexBuilder;
+///
+/// let re = RegexBuilder::new("^error: .*$")
+///     .case_insensitive(true)
+///     .dot_matches_new_line(false)
+///     .multi_line(true)
+///     .build()
+///     .unwrap();
+/// assert!(re.search("ok\nERROR: disk full\nok"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct RegexBuilder {
+    pattern: String,
+    case_insensitive: bool,
+    dot_matches_new_line: bool,
+    multi_line: bool,
+    unicode: bool,
+    simplify: bool,
+}
+
+impl RegexBuilder {
+    pub fn new(pattern: &str) -> Self {
+        Self {
+            pattern: pattern.to_string(),
+            case_insensitive: false,
+            dot_matches_new_line: true,
+            multi_line: false,
+            unicode: true,
+            simplify: true,
+        }
+    }
+
+    /// Whether literals and classes also match the other case of their chars,
+    /// e.g. `k` matches `K` and `[a-c]` matches `B`. Off by default.
+    pub fn case_insensitive(mut self, yes: bool) -> Self {
+        self.case_insensitive = yes;
+        self
+    }
+
+    /// Whether `.` matches `\n` too. On by default, as `.` matches any char.
+    pub fn dot_matches_new_line(mut self, yes: bool) -> Self {
+        self.dot_matches_new_line = yes;
+        self
+    }
+
+    /// Whether `^` and `$` also match at the start and the end of every line,
+    /// i.e. right after and right before a `\n`. Off by default.
+    pub fn multi_line(mut self, yes: bool) -> Self {
+        self.multi_line = yes;
+        self
+    }
+
+    /// Whether the shorthands `\d`, `\w` and `\s` (and their negations) match Unicode
+    /// rather than only ASCII chars, see [`CharClass::shorthand`]. On by default.
+    pub fn unicode(mut self, yes: bool) -> Self {
+        self.unicode = yes;
+        self
+    }
+
+    /// Whether the syntax tree is [simplified](Ast::simplify) before building the NFA,
+    /// which keeps the NFA of redundant (e.g. generated) patterns small. [`Regex::ast`]
+    /// still shows the pattern as written. On by default.
+    pub fn simplify(mut self, yes: bool) -> Self {
+        self.simplify = yes;
+        self
+    }
+
+    /// Compiles the pattern, failing with the byte offset of the problem if it is malformed.
+    ///
+    /// Flags are applied to the syntax tree before building the NFA, so [`Regex::ast`]
+    /// shows their effect, e.g. case-insensitive literals become classes.
+    pub fn build(&self) -> Result<Regex, ParseError> {
+        let ast = self.apply(Ast::parse_with(&self.pattern, self.unicode)?);
+        let mut states = Nfa::new();
+        let f = if self.simplify {
+            states.compile(&ast.clone().simplify())
+        } else {
+            states.compile(&ast)
+        };
+        Ok(Regex::from_fragment(ast, states, f))
+    }
+
+    /// Compiles the pattern into a [`BytesRegex`], with the same flags as [`build`](RegexBuilder::build).
+    ///
+    /// With [`unicode`](RegexBuilder::unicode) on, chars, classes and `.` match the UTF-8
+    /// encoding of chars. Otherwise, UTF-8 is skipped: `.` and classes match single bytes
+    /// (a class matching the bytes of its chars up to `U+00FF`), and chars up to `U+00FF`
+    /// match the byte of the same value, so `"\u{FF}"` matches the byte `0xFF`.
+    pub fn build_bytes(&self) -> Result<BytesRegex, ParseError> {
+        let mut ast = self.apply(Ast::parse_with(&self.pattern, self.unicode)?);
+        if self.simplify {
+            ast = ast.simplify();
+        }
+        Ok(BytesRegex::from_ast(&ast, self.unicode))
+    }
+
+    fn apply(&self, ast: Ast) -> Ast {
+        let apply = |inner: Box<Ast>| Box::new(self.apply(*inner));
+        match ast {
+            Ast::Literal(c) if self.case_insensitive => {
+                let variants = case_variants(c).collect::<Vec<_>>();
+                if variants.len() > 1 {
+                    Ast::Class(CharClass::new(variants.into_iter().map(|c| (c, c)), false))
+                } else {
+                    Ast::Literal(c)
+                }
+            }
+            Ast::Class(class) if self.case_insensitive => Ast::Class(class.fold_case()),
+            Ast::Any if !self.dot_matches_new_line => {
+                Ast::Class(CharClass::new([('\n', '\n')], true))
+            }
+            Ast::Anchor(Anchor::Start) if self.multi_line => Ast::Anchor(Anchor::LineStart),
+            Ast::Anchor(Anchor::End) if self.multi_line => Ast::Anchor(Anchor::LineEnd),
+            Ast::Empty | Ast::Literal(_) | Ast::Any | Ast::Class(_) | Ast::Anchor(_) => ast,
+            Ast::Concat(items) => {
+                Ast::Concat(items.into_iter().map(|item| self.apply(item)).collect())
+            }
+            Ast::Alternation(branches) => Ast::Alternation(
+                branches
+                    .into_iter()
+                    .map(|branch| self.apply(branch))
+                    .collect(),
+            ),
+            Ast::Star(inner) => Ast::Star(apply(inner)),
+            Ast::Plus(inner) => Ast::Plus(apply(inner)),
+            Ast::Optional(inner) => Ast::Optional(apply(inner)),
+            Ast::Lazy(inner) => Ast::Lazy(apply(inner)),
+            Ast::Group { index, inner } => Ast::Group {
+                index,
+                inner: apply(inner),
+            },
+            Ast::Repeat { inner, min, max } => Ast::Repeat {
+                inner: apply(inner),
+                min,
+                max,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_case_insensitive() {
+        let re = RegexBuilder::new("k[a-c]+é?|[^x]!")
+            .case_insensitive(true)
+            .build()
+            .unwrap();
+        assert!(re.matches("KaBc"));
+        assert!(re.matches("kcÉ"));
+        assert!(re.matches("y!"));
+        assert!(!re.matches("X!"));
+        assert!(!re.matches("kd"));
+        assert_eq!(re.find("..KAB..").unwrap().range(), 2..5);
+
+        let re = RegexBuilder::new("k").build().unwrap();
+        assert!(!re.matches("K"));
+
+        // The Kelvin sign folds to `k`, so each of them matches the others:
+        for pattern in ["k", "K", "\u{212A}"] {
+            let re = RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .unwrap();
+            assert!(re.matches("k") && re.matches("K") && re.matches("\u{212A}"));
+        }
+    }
+
+    #[test]
+    fn test_dot_matches_new_line() {
+        let re = RegexBuilder::new("a.b").build().unwrap();
+        assert!(re.matches("a\nb"));
+        let re = RegexBuilder::new("a.b")
+            .dot_matches_new_line(false)
+            .build()
+            .unwrap();
+        assert!(re.matches("a-b"));
+        assert!(!re.matches("a\nb"));
+        // Only `.` is affected:
+        let re = RegexBuilder::new("a[^x]b")
+            .dot_matches_new_line(false)
+            .build()
+            .unwrap();
+        assert!(re.matches("a\nb"));
+    }
+
+    #[test]
+    fn test_unicode() {
+        let re = RegexBuilder::new("\\d+").build().unwrap();
+        assert!(re.matches("42٣"));
+        let re = RegexBuilder::new("\\d+").unicode(false).build().unwrap();
+        assert!(!re.matches("42٣"));
+        let re = RegexBuilder::new("[^\\W\\d]+")
+            .unicode(false)
+            .build()
+            .unwrap();
+        assert!(re.matches("snake_case"));
+        assert!(!re.matches("x1"));
+        assert!(!re.matches("é"));
+    }
+
+    #[test]
+    fn test_multi_line() {
+        let haystack = "one\ntwo\nthree";
+        let re = RegexBuilder::new("^t[a-z]*$").build().unwrap();
+        assert!(re.find(haystack).is_none());
+
+        let re = RegexBuilder::new("^t[a-z]*$")
+            .multi_line(true)
+            .build()
+            .unwrap();
+        assert_eq!(re.find(haystack).unwrap().range(), 4..7);
+        assert!(re.matches("three"));
+        assert_eq!(re.count_parses::<u64>("two"), 1);
+        let re = RegexBuilder::new("o$\n^t")
+            .multi_line(true)
+            .build()
+            .unwrap();
+        assert_eq!(re.find(haystack).unwrap().range(), 6..9);
+    }
+}
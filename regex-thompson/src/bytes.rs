@@ -0,0 +1,356 @@
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use crate::{Anchor, Ast, ParseError, Position, Regex, RegexBuilder};
+
+#[derive(Debug, Default)]
+struct ByteState {
+    /// Transitions on any byte of an inclusive range.
+    ranges: Vec<(u8, u8, usize)>,
+    epsilon_transitions: Vec<usize>,
+    /// ε-transitions taken only where the anchor holds.
+    anchors: Vec<(Anchor, usize)>,
+}
+
+/// Appends the byte range sequences encoding the chars `lo..=hi` in UTF-8 to `out`,
+/// e.g. `U+0080..=U+07FF` is `[C2-DF][80-BF]`.
+fn utf8_sequences(lo: u32, hi: u32, out: &mut Vec<Vec<(u8, u8)>>) {
+    // Surrogates are not chars:
+    if lo < 0xD800 && hi > 0xDFFF {
+        utf8_sequences(lo, 0xD7FF, out);
+        utf8_sequences(0xE000, hi, out);
+        return;
+    }
+    // Both ends must have the same encoded length:
+    for max in [0x7F, 0x7FF, 0xFFFF] {
+        if lo <= max && hi > max {
+            utf8_sequences(lo, max, out);
+            utf8_sequences(max + 1, hi, out);
+            return;
+        }
+    }
+    let encode = |c: u32| {
+        let mut buffer = [0; 4];
+        let len = char::from_u32(c).unwrap().encode_utf8(&mut buffer).len();
+        buffer[..len].to_vec()
+    };
+    let len = encode(lo).len();
+    // Every continuation byte but the ones of a common prefix must span its whole range:
+    for i in 1..len {
+        let mask = (1 << (6 * i)) - 1;
+        if lo & !mask != hi & !mask {
+            if lo & mask != 0 {
+                utf8_sequences(lo, lo | mask, out);
+                utf8_sequences((lo | mask) + 1, hi, out);
+                return;
+            }
+            if hi & mask != mask {
+                utf8_sequences(lo, (hi & !mask) - 1, out);
+                utf8_sequences(hi & !mask, hi, out);
+                return;
+            }
+        }
+    }
+    out.push(encode(lo).into_iter().zip(encode(hi)).collect());
+}
+
+/// Regex matching byte strings, see [`Regex::new_bytes`].
+#[derive(Debug)]
+pub struct BytesRegex {
+    states: Vec<ByteState>,
+    start: usize,
+    end: usize,
+    /// Whether chars are matched by their UTF-8 encoding, rather than as single bytes.
+    unicode: bool,
+}
+
+impl BytesRegex {
+    fn new_state(&mut self) -> usize {
+        self.states.push(ByteState::default());
+        self.states.len() - 1
+    }
+
+    /// Adds states matching any of the byte sequences from `from` to `to`.
+    fn sequences(&mut self, from: usize, to: usize, sequences: &[Vec<(u8, u8)>]) {
+        for sequence in sequences {
+            let mut state = from;
+            for (i, &(lo, hi)) in sequence.iter().enumerate() {
+                let next = if i + 1 == sequence.len() {
+                    to
+                } else {
+                    self.new_state()
+                };
+                self.states[state].ranges.push((lo, hi, next));
+                state = next;
+            }
+        }
+    }
+
+    /// Adds states matching any char of `ranges` from `from` to `to`.
+    fn chars(&mut self, from: usize, to: usize, ranges: &[(char, char)]) {
+        let mut sequences = Vec::new();
+        for &(lo, hi) in ranges {
+            if self.unicode {
+                utf8_sequences(lo as u32, hi as u32, &mut sequences);
+            } else if lo as u32 <= 0xFF {
+                sequences.push(vec![(lo as u8, hi.min('\u{FF}') as u8)]);
+            }
+        }
+        self.sequences(from, to, &sequences);
+    }
+
+    /// Adds states matching `ast` from `from` to `to` (Thompson's construction).
+    fn compile(&mut self, ast: &Ast, from: usize, to: usize) {
+        match ast {
+            Ast::Empty => self.states[from].epsilon_transitions.push(to),
+            Ast::Literal(c) => {
+                if self.unicode || *c as u32 > 0xFF {
+                    let mut buffer = [0; 4];
+                    let bytes = c.encode_utf8(&mut buffer).bytes().map(|b| (b, b));
+                    self.sequences(from, to, &[bytes.collect()]);
+                } else {
+                    self.sequences(from, to, &[vec![(*c as u8, *c as u8)]]);
+                }
+            }
+            Ast::Any => self.chars(from, to, &[('\0', char::MAX)]),
+            Ast::Class(class) => self.chars(from, to, &class.matched_ranges()),
+            Ast::Anchor(anchor) => self.states[from].anchors.push((*anchor, to)),
+            Ast::Concat(items) => {
+                let mut state = from;
+                for (i, item) in items.iter().enumerate() {
+                    let next = if i + 1 == items.len() {
+                        to
+                    } else {
+                        self.new_state()
+                    };
+                    self.compile(item, state, next);
+                    state = next;
+                }
+            }
+            Ast::Alternation(branches) => {
+                for branch in branches {
+                    self.compile(branch, from, to);
+                }
+            }
+            Ast::Star(inner) => {
+                let middle = self.new_state();
+                self.states[from].epsilon_transitions.push(middle);
+                self.states[middle].epsilon_transitions.push(to);
+                let again = self.new_state();
+                self.compile(inner, middle, again);
+                self.states[again].epsilon_transitions.push(middle);
+            }
+            Ast::Plus(inner) => {
+                let middle = self.new_state();
+                self.compile(inner, from, middle);
+                self.compile(&Ast::Star(inner.clone()), middle, to);
+            }
+            Ast::Optional(inner) => {
+                self.states[from].epsilon_transitions.push(to);
+                self.compile(inner, from, to);
+            }
+            Ast::Lazy(inner) | Ast::Group { inner, .. } => self.compile(inner, from, to),
+            Ast::Repeat { inner, min, max } => {
+                let mut state = from;
+                for _ in 0..*min {
+                    let next = self.new_state();
+                    self.compile(inner, state, next);
+                    state = next;
+                }
+                match max {
+                    None => self.compile(&Ast::Star(inner.clone()), state, to),
+                    Some(max) => {
+                        for _ in *min..*max {
+                            self.states[state].epsilon_transitions.push(to);
+                            let next = self.new_state();
+                            self.compile(inner, state, next);
+                            state = next;
+                        }
+                        self.states[state].epsilon_transitions.push(to);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Compiles a syntax tree, with chars matched as single bytes unless `unicode`.
+    pub(crate) fn from_ast(ast: &Ast, unicode: bool) -> Self {
+        let mut regex = BytesRegex {
+            states: Vec::new(),
+            start: 0,
+            end: 0,
+            unicode,
+        };
+        regex.start = regex.new_state();
+        regex.end = regex.new_state();
+        regex.compile(ast, regex.start, regex.end);
+        regex
+    }
+
+    fn position(haystack: &[u8], offset: usize) -> Position {
+        let at_start = offset == 0;
+        let at_end = offset == haystack.len();
+        Position {
+            at_start,
+            at_end,
+            at_line_start: at_start || haystack[offset - 1] == b'\n',
+            at_line_end: at_end || haystack[offset] == b'\n',
+        }
+    }
+
+    fn epsilon_closure(&self, states: Vec<usize>, position: Position) -> BTreeSet<usize> {
+        let mut visited = BTreeSet::new();
+        let mut stack = states;
+        while let Some(state) = stack.pop() {
+            if visited.insert(state) {
+                let state = &self.states[state];
+                stack.extend(&state.epsilon_transitions);
+                for &(anchor, to) in &state.anchors {
+                    if position.holds(anchor) {
+                        stack.push(to);
+                    }
+                }
+            }
+        }
+        visited
+    }
+
+    fn advance(&self, states: &BTreeSet<usize>, byte: u8, position: Position) -> BTreeSet<usize> {
+        let next_states = states
+            .iter()
+            .flat_map(|&state| &self.states[state].ranges)
+            .filter(|&&(lo, hi, _)| lo <= byte && byte <= hi)
+            .map(|&(_, _, to)| to)
+            .collect();
+        self.epsilon_closure(next_states, position)
+    }
+
+    /// Returns `true` if the whole `haystack` matches the pattern.
+    pub fn matches(&self, haystack: &[u8]) -> bool {
+        let mut states = self.epsilon_closure(vec![self.start], Self::position(haystack, 0));
+        for (i, &byte) in haystack.iter().enumerate() {
+            states = self.advance(&states, byte, Self::position(haystack, i + 1));
+        }
+        states.contains(&self.end)
+    }
+
+    /// Length of the longest match starting at byte `offset` of `haystack`.
+    fn longest_match_at(&self, haystack: &[u8], offset: usize) -> Option<usize> {
+        let mut states = self.epsilon_closure(vec![self.start], Self::position(haystack, offset));
+        let mut longest = None;
+        for i in offset..=haystack.len() {
+            if states.contains(&self.end) {
+                longest = Some(i - offset);
+            }
+            if i == haystack.len() || states.is_empty() {
+                break;
+            }
+            states = self.advance(&states, haystack[i], Self::position(haystack, i + 1));
+        }
+        longest
+    }
+
+    /// Finds the leftmost-longest match in `haystack`, returning its byte range.
+    pub fn find(&self, haystack: &[u8]) -> Option<Range<usize>> {
+        (0..=haystack.len()).find_map(|start| {
+            let len = self.longest_match_at(haystack, start)?;
+            Some(start..start + len)
+        })
+    }
+
+    /// Returns `true` if some substring of `haystack` matches the pattern.
+    pub fn search(&self, haystack: &[u8]) -> bool {
+        self.find(haystack).is_some()
+    }
+}
+
+impl Regex {
+    /// Compiles `pattern` into a regex over bytes, matching chars by their UTF-8 encoding.
+    ///
+    /// Use [`RegexBuilder::build_bytes`] to match single bytes instead.
+    pub fn new_bytes(pattern: &str) -> Result<BytesRegex, ParseError> {
+        RegexBuilder::new(pattern).build_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf8_sequences() {
+        let mut sequences = Vec::new();
+        utf8_sequences(0, 0x10FFFF, &mut sequences);
+        for c in [
+            '\0',
+            'a',
+            '\u{7F}',
+            'é',
+            '\u{7FF}',
+            '€',
+            '\u{D7FF}',
+            '\u{E000}',
+            '🦀',
+            char::MAX,
+        ] {
+            let mut buffer = [0; 4];
+            let bytes = c.encode_utf8(&mut buffer).as_bytes();
+            let count = sequences
+                .iter()
+                .filter(|sequence| {
+                    sequence.len() == bytes.len()
+                        && sequence
+                            .iter()
+                            .zip(bytes)
+                            .all(|(&(lo, hi), b)| lo <= *b && b <= &hi)
+                })
+                .count();
+            assert_eq!(count, 1, "{:?}", c);
+        }
+        // Surrogates and overlong encodings are rejected:
+        let rejected: [&[u8]; 3] = [b"\xED\xA0\x80", b"\xC0\x80", b"\xE0\x80\x80"];
+        for bytes in rejected {
+            assert!(!sequences
+                .iter()
+                .any(|sequence| sequence.len() == bytes.len()
+                    && sequence
+                        .iter()
+                        .zip(bytes)
+                        .all(|(&(lo, hi), b)| lo <= *b && b <= &hi)));
+        }
+    }
+
+    #[test]
+    fn test_bytes_regex() {
+        let re = Regex::new_bytes("GET /[a-zé]+ HTTP/1\\.[01]").unwrap();
+        assert!(re.matches(b"GET /caf\xC3\xA9 HTTP/1.1"));
+        assert!(!re.matches(b"GET /caf\xE9 HTTP/1.1"));
+        assert_eq!(re.find(b"\x00\xFFGET /a HTTP/1.0\r\n"), Some(2..17));
+
+        // `.` matches a whole char, and no invalid UTF-8:
+        let re = Regex::new_bytes("a.b").unwrap();
+        assert!(re.matches("a€b".as_bytes()));
+        assert!(!re.matches(b"a\xFFb"));
+        assert!(Regex::new_bytes("[^a]").unwrap().matches("🦀".as_bytes()));
+
+        // Without Unicode, classes and `.` match single bytes:
+        let re = RegexBuilder::new("\u{FF}[^a]..")
+            .unicode(false)
+            .build_bytes()
+            .unwrap();
+        assert!(re.matches(b"\xFF\x00\xFF\n"));
+        assert!(!re.matches(b"\xFFa\x00\x00"));
+        assert!(!re.matches("\u{FF}xyz".as_bytes()));
+
+        let re = RegexBuilder::new("^ab$")
+            .multi_line(true)
+            .case_insensitive(true)
+            .build_bytes()
+            .unwrap();
+        assert_eq!(re.find(b"x\nAb\n"), Some(2..4));
+        assert!(Regex::new_bytes("(ab){2,3}c*")
+            .unwrap()
+            .matches(b"abababcc"));
+        assert!(!Regex::new_bytes("(ab){2,3}").unwrap().matches(b"ab"));
+    }
+}
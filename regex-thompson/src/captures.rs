@@ -0,0 +1,270 @@
+use std::collections::HashSet;
+
+use crate::{Nfa, Position, Regex, Span};
+
+/// Spans of the capture groups of a match, see [`Regex::captures`].
+///
+/// Group 0 is the whole match, groups `1..len()` are numbered in the order of their
+/// opening parentheses in the pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Captures<'h> {
+    haystack: &'h str,
+    /// Start and end byte offsets of every group, unset for groups that did not participate.
+    slots: Vec<Option<usize>>,
+}
+
+impl<'h> Captures<'h> {
+    /// Number of groups, including group 0.
+    pub fn len(&self) -> usize {
+        self.slots.len() / 2
+    }
+
+    /// Always `false`, since group 0 is always present.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    /// Span of group `index`, or `None` if it did not participate in the match
+    /// (or there is no such group).
+    pub fn get(&self, index: usize) -> Option<Span> {
+        let start = (*self.slots.get(2 * index)?)?;
+        let end = (*self.slots.get(2 * index + 1)?)?;
+        Some(Span::new(self.haystack, start, end))
+    }
+
+    /// Text of group `index`, see [`get`](Captures::get).
+    pub fn as_str(&self, index: usize) -> Option<&'h str> {
+        self.get(index)
+            .map(|span| &self.haystack[span.start..span.end])
+    }
+
+    /// Spans of all groups, in order.
+    pub fn iter(&self) -> impl Iterator<Item = Option<Span>> + '_ {
+        (0..self.len()).map(|index| self.get(index))
+    }
+}
+
+/// A thread of the simulation: a state and the capture slots recorded on the way to it.
+type Thread = (usize, Vec<Option<usize>>);
+
+impl Nfa {
+    /// Adds the threads reachable from `state` by ε-transitions to `threads`, in order of
+    /// preference, skipping states already `seen` (those were reached by a preferred thread).
+    ///
+    /// Like in backtracking engines, a loop iteration that matched the empty string ends
+    /// the loop: it continues at the loop exit, with the preference of the empty iteration.
+    fn add_thread(
+        &self,
+        threads: &mut Vec<Thread>,
+        seen: &mut HashSet<usize>,
+        (state, slots): Thread,
+        at: usize,
+        position: Position,
+    ) {
+        // Each path also carries the states it went through, i.e. entered at `at`:
+        let mut stack = vec![(state, slots, Vec::new())];
+        while let Some((mut state, mut slots, mut path)) = stack.pop() {
+            if let Some((body, exit)) = self.state(state).loop_body {
+                if path.contains(&body) {
+                    if let Some(tag) = self.state(state).tag {
+                        slots[tag] = Some(at);
+                    }
+                    state = exit;
+                }
+            }
+            if !seen.insert(state) {
+                continue;
+            }
+            path.push(state);
+            if let Some(tag) = self.state(state).tag {
+                slots[tag] = Some(at);
+            }
+            let anchors = self
+                .state(state)
+                .anchors
+                .iter()
+                .filter(|&&(anchor, _)| position.holds(anchor))
+                .map(|&(_, to)| to);
+            let next_states = self
+                .state(state)
+                .epsilon_transitions
+                .iter()
+                .copied()
+                .chain(anchors)
+                .collect::<Vec<_>>();
+            for &next_state in next_states.iter().rev() {
+                stack.push((next_state, slots.clone(), path.clone()));
+            }
+            threads.push((state, slots));
+        }
+    }
+
+    /// Finds the leftmost-first match in `haystack`, returning its capture slots:
+    /// the match starts as early as possible and follows the preferred parse, where earlier
+    /// alternatives win and greedy (lazy) repetitions prefer more (fewer) iterations.
+    pub(crate) fn captures_first(
+        &self,
+        start: usize,
+        haystack: &str,
+        num_slots: usize,
+    ) -> Option<Vec<Option<usize>>> {
+        let mut threads: Vec<Thread> = Vec::new();
+        let mut seen = HashSet::new();
+        let mut matched = None;
+        let mut at = 0;
+        loop {
+            let position = Position::of(haystack, at);
+            if matched.is_none() {
+                // A match starting here is less preferred than any started earlier:
+                let mut slots = vec![None; num_slots];
+                slots[0] = Some(at);
+                self.add_thread(&mut threads, &mut seen, (start, slots), at, position);
+            }
+            if let Some(i) = threads
+                .iter()
+                .position(|&(state, _)| self.state(state).accepting)
+            {
+                // Threads after the accepting one are less preferred, so they can't win:
+                let (_, mut slots) = threads.swap_remove(i);
+                slots[1] = Some(at);
+                matched = Some(slots);
+                threads.truncate(i);
+            }
+            let Some(c) = haystack[at..].chars().next() else {
+                break;
+            };
+            if threads.is_empty() && matched.is_some() {
+                break;
+            }
+            at += c.len_utf8();
+            let position = Position::of(haystack, at);
+            let mut next_threads = Vec::new();
+            seen.clear();
+            for (state, slots) in threads {
+                for next_state in self.step(state, c) {
+                    let thread = (next_state, slots.clone());
+                    self.add_thread(&mut next_threads, &mut seen, thread, at, position);
+                }
+            }
+            threads = next_threads;
+        }
+        matched
+    }
+}
+
+impl Regex {
+    /// Finds the leftmost-first match in `haystack` and the spans of its capture groups.
+    ///
+    /// The match starts as early as possible, like with [`find`](Regex::find), but its end
+    /// and groups follow the preferred parse, from left to right: earlier alternatives
+    /// win, greedy repetitions take as many iterations as possible and lazy ones (`*?`,
+    /// `+?`, `??`) as few. So group 0 may be shorter than the leftmost-longest match,
+    /// e.g. for `a|ab` on `"ab"` or `a+?` on `"aaa"`.
+    pub fn captures<'h>(&self, haystack: &'h str) -> Option<Captures<'h>> {
+        let num_slots = 2 * (self.ast.num_captures() + 1);
+        let slots = self
+            .states
+            .captures_first(self.start, haystack, num_slots)?;
+        Some(Captures { haystack, slots })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn groups<'h>(re: &Regex, haystack: &'h str) -> Vec<Option<&'h str>> {
+        let captures = re.captures(haystack).unwrap();
+        (0..captures.len())
+            .map(|index| captures.as_str(index))
+            .collect()
+    }
+
+    #[test]
+    fn test_captures() {
+        let re = Regex::new("([0-9]{4})-([0-9]{2})(-([0-9]{2}))?").unwrap();
+        assert_eq!(
+            groups(&re, "on 2024-06-30!"),
+            [
+                Some("2024-06-30"),
+                Some("2024"),
+                Some("06"),
+                Some("-30"),
+                Some("30")
+            ]
+        );
+        let captures = re.captures("2024-06").unwrap();
+        assert_eq!(captures.get(2).unwrap().range(), 5..7);
+        assert_eq!(captures.get(3), None);
+        assert_eq!(captures.get(5), None);
+        assert!(re.captures("June").is_none());
+
+        // Greedy repetitions, the last iteration is captured:
+        let re = Regex::new("(a*)(a*)").unwrap();
+        assert_eq!(groups(&re, "aaa"), [Some("aaa"), Some("aaa"), Some("")]);
+        let re = Regex::new("((a|b)c)+").unwrap();
+        assert_eq!(groups(&re, "acbc"), [Some("acbc"), Some("bc"), Some("b")]);
+
+        // Earlier alternatives win among parses of the same match:
+        let re = Regex::new("(a|ab)(b?)").unwrap();
+        assert_eq!(groups(&re, "ab"), [Some("ab"), Some("a"), Some("b")]);
+        let re = Regex::new("(a|ab)").unwrap();
+        assert_eq!(groups(&re, "xab"), [Some("a"), Some("a")]);
+    }
+
+    #[test]
+    fn test_lazy_captures() {
+        let re = Regex::new("\"(.*?)\"").unwrap();
+        assert_eq!(
+            groups(&re, "say \"a\" and \"b\""),
+            [Some("\"a\""), Some("a")]
+        );
+        let re = Regex::new("\"(.*)\"").unwrap();
+        assert_eq!(
+            groups(&re, "say \"a\" and \"b\""),
+            [Some("\"a\" and \"b\""), Some("a\" and \"b")]
+        );
+
+        assert_eq!(groups(&Regex::new("a+?").unwrap(), "aaa"), [Some("a")]);
+        assert_eq!(groups(&Regex::new("a*?").unwrap(), "aaa"), [Some("")]);
+        assert_eq!(
+            groups(&Regex::new("(a??)(a*)").unwrap(), "aa"),
+            [Some("aa"), Some(""), Some("aa")]
+        );
+        assert_eq!(
+            groups(&Regex::new("(a{1,3}?)(a*)").unwrap(), "aaa"),
+            [Some("aaa"), Some("a"), Some("aa")]
+        );
+        // A lazy repetition still extends as far as needed for the rest to match:
+        assert_eq!(
+            groups(&Regex::new("(a*?)b").unwrap(), "aab"),
+            [Some("aab"), Some("aa")]
+        );
+
+        // Laziness doesn't change the language:
+        let re = Regex::new("a+?b??").unwrap();
+        assert!(re.matches("aaab"));
+        assert!(re.matches("a"));
+        assert!(!re.matches("b"));
+        assert_eq!(re.find("xaab").unwrap().range(), 1..4);
+
+        // An iteration of a star that matches the empty string ends the loop, so the lazy
+        // star inside can only grow by moving the outer loop forward:
+        let re = Regex::new("(?:(?:.)*?)*[ab]{2,3}").unwrap();
+        assert_eq!(re.captures("caaba").unwrap().get(0).unwrap().range(), 0..4);
+        let re = Regex::new("(?:(.)*?)*([ab]{2,3})").unwrap();
+        assert_eq!(groups(&re, "caaba"), [Some("caab"), Some("c"), Some("aab")]);
+    }
+
+    #[test]
+    fn test_captures_combinators() {
+        let re = Regex::new("(a)")
+            .unwrap()
+            .concat(Regex::new("(b)").unwrap());
+        assert_eq!(re.ast().num_captures(), 2);
+        assert_eq!(groups(&re, "ab"), [Some("ab"), Some("a"), Some("b")]);
+
+        let re = Regex::new("(x)").unwrap().union(Regex::new("(y)").unwrap());
+        assert_eq!(groups(&re, "y"), [Some("y"), None, Some("y")]);
+    }
+}
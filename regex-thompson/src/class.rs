@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::OnceLock;
+
+/// Set of chars given by a bracket expression, e.g. `[a-z0-9_]` or `[^"]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CharClass {
+    /// Sorted, non-overlapping and non-adjacent inclusive ranges.
+    ranges: Vec<(char, char)>,
+    negated: bool,
+}
+
+impl CharClass {
+    /// Creates a class from inclusive ranges (which may overlap), matching chars outside
+    /// of all of them if `negated`.
+    pub fn new(ranges: impl IntoIterator<Item = (char, char)>, negated: bool) -> Self {
+        let mut sorted = ranges
+            .into_iter()
+            .filter(|(lo, hi)| lo <= hi)
+            .collect::<Vec<_>>();
+        sorted.sort_unstable();
+        let mut ranges: Vec<(char, char)> = Vec::with_capacity(sorted.len());
+        for (lo, hi) in sorted {
+            match ranges.last_mut() {
+                Some((_, last)) if (*last as u32).saturating_add(1) >= lo as u32 => {
+                    *last = (*last).max(hi);
+                }
+                _ => ranges.push((lo, hi)),
+            }
+        }
+        Self { ranges, negated }
+    }
+
+    /// Normalized ranges, without taking negation into account.
+    pub fn ranges(&self) -> &[(char, char)] {
+        &self.ranges
+    }
+
+    pub fn is_negated(&self) -> bool {
+        self.negated
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        let found = self
+            .ranges
+            .binary_search_by(|&(lo, hi)| {
+                if hi < c {
+                    std::cmp::Ordering::Less
+                } else if lo > c {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok();
+        found != self.negated
+    }
+
+    /// Class of a shorthand escape: `\d` (digits), `\w` (word chars) or `\s` (whitespace),
+    /// or its negation for the uppercase letter. `None` for any other letter.
+    ///
+    /// With `unicode`, these are the decimal digits of Unicode 14.0 (category Nd),
+    /// [alphanumeric](char::is_alphanumeric) chars and `_`, and [whitespace](char::is_whitespace).
+    /// Otherwise, they are `[0-9]`, `[0-9A-Za-z_]` and `[\t\n\x0B\x0C\r ]`.
+    pub fn shorthand(letter: char, unicode: bool) -> Option<CharClass> {
+        static WORD: OnceLock<Vec<(char, char)>> = OnceLock::new();
+        static SPACE: OnceLock<Vec<(char, char)>> = OnceLock::new();
+        let ranges: &[(char, char)] = match (letter.to_ascii_lowercase(), unicode) {
+            ('d', false) => &[('0', '9')],
+            ('d', true) => DECIMAL_DIGITS,
+            ('w', false) => &[('0', '9'), ('A', 'Z'), ('_', '_'), ('a', 'z')],
+            ('w', true) => WORD.get_or_init(|| ranges_of(|c| c.is_alphanumeric() || c == '_')),
+            ('s', false) => &[('\t', '\r'), (' ', ' ')],
+            ('s', true) => SPACE.get_or_init(|| ranges_of(char::is_whitespace)),
+            _ => return None,
+        };
+        Some(CharClass::new(
+            ranges.iter().copied(),
+            letter.is_ascii_uppercase(),
+        ))
+    }
+
+    /// Ranges of the chars matched by this class, taking negation into account.
+    pub fn matched_ranges(&self) -> Vec<(char, char)> {
+        if !self.negated {
+            return self.ranges.clone();
+        }
+        let mut ranges = Vec::new();
+        let mut next = Some('\0');
+        for &(lo, hi) in &self.ranges {
+            if let Some(from) = next.filter(|&from| from < lo) {
+                ranges.push((from, char_before(lo)));
+            }
+            next = char_after(hi);
+        }
+        if let Some(from) = next {
+            ranges.push((from, char::MAX));
+        }
+        ranges
+    }
+
+    /// Same class, also matching the other case of every char in it, see [`case_variants`].
+    ///
+    /// For a negated class, the folded ranges are excluded, so `[^a]` matches neither `a` nor `A`.
+    pub fn fold_case(&self) -> CharClass {
+        let mut ranges = self.ranges.clone();
+        for &(lo, hi) in &self.ranges {
+            for c in lo..=hi {
+                ranges.extend(case_variants(c).skip(1).map(|c| (c, c)));
+            }
+        }
+        CharClass::new(ranges, self.negated)
+    }
+}
+
+/// Decimal digits (general category Nd) of Unicode 14.0.
+const DECIMAL_DIGITS: &[(char, char)] = &[
+    ('\u{30}', '\u{39}'),
+    ('\u{660}', '\u{669}'),
+    ('\u{6f0}', '\u{6f9}'),
+    ('\u{7c0}', '\u{7c9}'),
+    ('\u{966}', '\u{96f}'),
+    ('\u{9e6}', '\u{9ef}'),
+    ('\u{a66}', '\u{a6f}'),
+    ('\u{ae6}', '\u{aef}'),
+    ('\u{b66}', '\u{b6f}'),
+    ('\u{be6}', '\u{bef}'),
+    ('\u{c66}', '\u{c6f}'),
+    ('\u{ce6}', '\u{cef}'),
+    ('\u{d66}', '\u{d6f}'),
+    ('\u{de6}', '\u{def}'),
+    ('\u{e50}', '\u{e59}'),
+    ('\u{ed0}', '\u{ed9}'),
+    ('\u{f20}', '\u{f29}'),
+    ('\u{1040}', '\u{1049}'),
+    ('\u{1090}', '\u{1099}'),
+    ('\u{17e0}', '\u{17e9}'),
+    ('\u{1810}', '\u{1819}'),
+    ('\u{1946}', '\u{194f}'),
+    ('\u{19d0}', '\u{19d9}'),
+    ('\u{1a80}', '\u{1a89}'),
+    ('\u{1a90}', '\u{1a99}'),
+    ('\u{1b50}', '\u{1b59}'),
+    ('\u{1bb0}', '\u{1bb9}'),
+    ('\u{1c40}', '\u{1c49}'),
+    ('\u{1c50}', '\u{1c59}'),
+    ('\u{a620}', '\u{a629}'),
+    ('\u{a8d0}', '\u{a8d9}'),
+    ('\u{a900}', '\u{a909}'),
+    ('\u{a9d0}', '\u{a9d9}'),
+    ('\u{a9f0}', '\u{a9f9}'),
+    ('\u{aa50}', '\u{aa59}'),
+    ('\u{abf0}', '\u{abf9}'),
+    ('\u{ff10}', '\u{ff19}'),
+    ('\u{104a0}', '\u{104a9}'),
+    ('\u{10d30}', '\u{10d39}'),
+    ('\u{11066}', '\u{1106f}'),
+    ('\u{110f0}', '\u{110f9}'),
+    ('\u{11136}', '\u{1113f}'),
+    ('\u{111d0}', '\u{111d9}'),
+    ('\u{112f0}', '\u{112f9}'),
+    ('\u{11450}', '\u{11459}'),
+    ('\u{114d0}', '\u{114d9}'),
+    ('\u{11650}', '\u{11659}'),
+    ('\u{116c0}', '\u{116c9}'),
+    ('\u{11730}', '\u{11739}'),
+    ('\u{118e0}', '\u{118e9}'),
+    ('\u{11950}', '\u{11959}'),
+    ('\u{11c50}', '\u{11c59}'),
+    ('\u{11d50}', '\u{11d59}'),
+    ('\u{11da0}', '\u{11da9}'),
+    ('\u{16a60}', '\u{16a69}'),
+    ('\u{16ac0}', '\u{16ac9}'),
+    ('\u{16b50}', '\u{16b59}'),
+    ('\u{1d7ce}', '\u{1d7ff}'),
+    ('\u{1e140}', '\u{1e149}'),
+    ('\u{1e2f0}', '\u{1e2f9}'),
+    ('\u{1e950}', '\u{1e959}'),
+    ('\u{1fbf0}', '\u{1fbf9}'),
+];
+
+/// Maximal ranges of the chars satisfying `predicate`.
+fn ranges_of(predicate: impl Fn(char) -> bool) -> Vec<(char, char)> {
+    let mut ranges: Vec<(char, char)> = Vec::new();
+    for c in '\0'..=char::MAX {
+        if predicate(c) {
+            match ranges.last_mut() {
+                Some((_, hi)) if char_after(*hi) == Some(c) => *hi = c,
+                _ => ranges.push((c, c)),
+            }
+        }
+    }
+    ranges
+}
+
+/// Next char, skipping the surrogates.
+fn char_after(c: char) -> Option<char> {
+    match c {
+        '\u{D7FF}' => Some('\u{E000}'),
+        c => char::from_u32(c as u32 + 1),
+    }
+}
+
+/// Previous char, skipping the surrogates (`c` must not be `'\0'`).
+fn char_before(c: char) -> char {
+    match c {
+        '\u{E000}' => '\u{D7FF}',
+        c => char::from_u32(c as u32 - 1).expect("not a surrogate"),
+    }
+}
+
+/// Representative of the simple case folding orbit of `c`: chars with the same key match
+/// each other case-insensitively. It is the lowercase of the uppercase (when both are single
+/// chars), so e.g. the Kelvin sign `K` joins `k` and `K`, except for the dotless `ı`,
+/// which Unicode only folds to `i` in Turkic languages.
+fn fold_key(c: char) -> char {
+    fn single(mut chars: impl Iterator<Item = char>) -> Option<char> {
+        let first = chars.next()?;
+        chars.next().is_none().then_some(first)
+    }
+    if c == 'ı' {
+        return c;
+    }
+    let upper = single(c.to_uppercase()).unwrap_or(c);
+    single(upper.to_lowercase()).unwrap_or(upper)
+}
+
+/// `c` followed by the other chars of its case folding orbit, see [`fold_key`].
+pub(crate) fn case_variants(c: char) -> impl Iterator<Item = char> {
+    static ORBITS: OnceLock<HashMap<char, Vec<char>>> = OnceLock::new();
+    let orbits = ORBITS.get_or_init(|| {
+        let mut orbits: HashMap<char, Vec<char>> = HashMap::new();
+        for c in (0..=char::MAX as u32).filter_map(char::from_u32) {
+            let key = fold_key(c);
+            if key != c {
+                orbits.entry(key).or_insert_with(|| vec![key]).push(c);
+            }
+        }
+        orbits
+    });
+    let orbit = orbits.get(&fold_key(c)).map_or(&[][..], Vec::as_slice);
+    std::iter::once(c).chain(orbit.iter().copied().filter(move |&other| other != c))
+}
+
+impl Display for CharClass {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        if self.negated {
+            write!(f, "^")?;
+        }
+        for &(lo, hi) in &self.ranges {
+            if lo == hi {
+                write!(f, "{}", lo)?;
+            } else {
+                write!(f, "{}-{}", lo, hi)?;
+            }
+        }
+        write!(f, "]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_char_class() {
+        let class = CharClass::new([('a', 'f'), ('0', '9'), ('d', 'z'), ('_', '_')], false);
+        assert_eq!(class.ranges(), [('0', '9'), ('_', '_'), ('a', 'z')]);
+        assert!(class.contains('q'));
+        assert!(class.contains('_'));
+        assert!(!class.contains('A'));
+        assert_eq!(class.to_string(), "[0-9_a-z]");
+
+        let negated = CharClass::new([('a', 'b'), ('c', 'c')], true);
+        assert_eq!(negated.ranges(), [('a', 'c')]);
+        assert!(!negated.contains('b'));
+        assert!(negated.contains('é'));
+        assert_eq!(negated.to_string(), "[^a-c]");
+
+        let folded = CharClass::new([('a', 'c'), ('x', 'x')], true).fold_case();
+        assert_eq!(
+            folded.ranges(),
+            [('A', 'C'), ('X', 'X'), ('a', 'c'), ('x', 'x')]
+        );
+        assert!(!folded.contains('B'));
+        assert!(folded.contains('d'));
+    }
+
+    #[test]
+    fn test_case_variants() {
+        let variants = |c: char| {
+            let mut variants = case_variants(c).collect::<Vec<_>>();
+            variants[1..].sort_unstable();
+            variants
+        };
+        // Orbits are symmetric, including chars that only fold one way:
+        assert_eq!(variants('k'), ['k', 'K', '\u{212A}']);
+        assert_eq!(variants('K'), ['K', 'k', '\u{212A}']);
+        assert_eq!(variants('\u{212A}'), ['\u{212A}', 'K', 'k']);
+        assert_eq!(variants('ſ'), ['ſ', 'S', 's']);
+        assert_eq!(variants('σ'), ['σ', 'Σ', 'ς']);
+        assert_eq!(variants('ß'), ['ß', 'ẞ']);
+        assert_eq!(variants('ı'), ['ı']);
+        assert_eq!(variants('i'), ['i', 'I']);
+        assert_eq!(variants('1'), ['1']);
+
+        let folded = CharClass::new([('j', 'k')], false).fold_case();
+        assert!(folded.contains('\u{212A}'));
+    }
+
+    #[test]
+    fn test_shorthand() {
+        let digits = CharClass::shorthand('d', false).unwrap();
+        assert!(digits.contains('7'));
+        assert!(!digits.contains('٣'));
+        assert!(CharClass::shorthand('d', true).unwrap().contains('٣'));
+        assert!(!CharClass::shorthand('D', true).unwrap().contains('٣'));
+
+        let word = CharClass::shorthand('w', true).unwrap();
+        assert!(word.contains('é') && word.contains('_') && !word.contains('-'));
+        assert!(!CharClass::shorthand('w', false).unwrap().contains('é'));
+        assert!(CharClass::shorthand('s', true).unwrap().contains('\u{A0}'));
+        assert!(CharClass::shorthand('s', false).unwrap().contains('\x0B'));
+        assert!(CharClass::shorthand('S', false).unwrap().contains('\u{A0}'));
+        assert_eq!(CharClass::shorthand('q', false), None);
+
+        let not_digits = CharClass::shorthand('D', false).unwrap().matched_ranges();
+        assert_eq!(not_digits, [('\0', '/'), (':', char::MAX)]);
+        let not_ranges = CharClass::new([('\0', 'a'), ('\u{D7FF}', '\u{E000}')], true);
+        assert_eq!(
+            not_ranges.matched_ranges(),
+            [('b', '\u{D7FE}'), ('\u{E001}', char::MAX)]
+        );
+    }
+}
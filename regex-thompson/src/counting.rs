@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+use std::ops::{Add, Mul};
+use std::rc::Rc;
+
+use crate::{Anchor, Ast, Regex};
+
+/// Numbers used for counting parses, see [`Regex::count_parses`].
+///
+/// Fixed-width integers overflow quickly for ambiguous patterns, in which case
+/// `BigUint` (with the `num-bigint` feature) is the way to go.
+pub trait Count: Clone + Add<Output = Self> + Mul<Output = Self> {
+    fn zero() -> Self;
+    fn one() -> Self;
+}
+
+macro_rules! impl_count {
+    ($($t:ty),*) => {
+        $(
+            impl Count for $t {
+                fn zero() -> Self {
+                    0
+                }
+                fn one() -> Self {
+                    1
+                }
+            }
+        )*
+    };
+}
+
+impl_count!(u32, u64, u128, usize);
+
+#[cfg(feature = "num-bigint")]
+impl Count for num_bigint::BigUint {
+    fn zero() -> Self {
+        Self::from(0u32)
+    }
+    fn one() -> Self {
+        Self::from(1u32)
+    }
+}
+
+/// Parse counts of all substrings, memoized per node.
+struct Counter<'a, W> {
+    chars: &'a [char],
+    /// `(node, i)` maps to the counts for `chars[i..j]`, for all `j` (zero for `j < i`).
+    memo: HashMap<(*const Ast, usize), Rc<Vec<W>>>,
+    /// Same as `memo`, but for repetitions of the node (see [`Counter::star_counts`]).
+    stars: HashMap<(*const Ast, usize), Rc<Vec<W>>>,
+}
+
+impl<W: Count> Counter<'_, W> {
+    fn counts(&mut self, ast: &Ast, i: usize) -> Rc<Vec<W>> {
+        if let Some(counts) = self.memo.get(&(ast as *const Ast, i)) {
+            return counts.clone();
+        }
+        let n = self.chars.len();
+        let mut counts = vec![W::zero(); n + 1];
+        match ast {
+            Ast::Empty => counts[i] = W::one(),
+            Ast::Literal(c) => {
+                if self.chars.get(i) == Some(c) {
+                    counts[i + 1] = W::one();
+                }
+            }
+            Ast::Any => {
+                if i < n {
+                    counts[i + 1] = W::one();
+                }
+            }
+            Ast::Class(class) => {
+                if self.chars.get(i).is_some_and(|&c| class.contains(c)) {
+                    counts[i + 1] = W::one();
+                }
+            }
+            Ast::Anchor(Anchor::Start) => {
+                if i == 0 {
+                    counts[i] = W::one();
+                }
+            }
+            Ast::Anchor(Anchor::End) => {
+                if i == n {
+                    counts[i] = W::one();
+                }
+            }
+            Ast::Anchor(Anchor::LineStart) => {
+                if i == 0 || self.chars[i - 1] == '\n' {
+                    counts[i] = W::one();
+                }
+            }
+            Ast::Anchor(Anchor::LineEnd) => {
+                if i == n || self.chars[i] == '\n' {
+                    counts[i] = W::one();
+                }
+            }
+            Ast::Concat(items) => {
+                counts[i] = W::one();
+                for item in items {
+                    counts = self.then(&counts, i, item, false);
+                }
+            }
+            Ast::Alternation(branches) => {
+                for branch in branches {
+                    let branch = self.counts(branch, i);
+                    for j in i..=n {
+                        counts[j] = counts[j].clone() + branch[j].clone();
+                    }
+                }
+            }
+            Ast::Group { inner, .. } | Ast::Lazy(inner) => counts = self.counts(inner, i).to_vec(),
+            Ast::Star(inner) => counts = self.star_counts(inner, i).to_vec(),
+            Ast::Plus(inner) => {
+                // As `inner inner*`, so only the first iteration may be empty.
+                let first = self.counts(inner, i);
+                for k in i..=n {
+                    let rest = self.star_counts(inner, k);
+                    for j in k..=n {
+                        counts[j] = counts[j].clone() + first[k].clone() * rest[j].clone();
+                    }
+                }
+            }
+            Ast::Optional(inner) => {
+                counts[i] = W::one();
+                let inner = self.counts(inner, i);
+                for j in i..=n {
+                    counts[j] = counts[j].clone() + inner[j].clone();
+                }
+            }
+            Ast::Repeat { inner, min, max } => {
+                // As `min` copies of `inner` (which may be empty, like the first one of `+`),
+                // followed by up to `max - min` non-empty iterations.
+                counts[i] = W::one();
+                for _ in 0..*min {
+                    counts = self.then(&counts, i, inner, false);
+                }
+                match max {
+                    None => {
+                        let mut rest = vec![W::zero(); n + 1];
+                        for (k, prefix) in counts.iter().enumerate().skip(i) {
+                            let star = self.star_counts(inner, k);
+                            for j in k..=n {
+                                rest[j] = rest[j].clone() + prefix.clone() * star[j].clone();
+                            }
+                        }
+                        counts = rest;
+                    }
+                    Some(max) => {
+                        let mut iteration = counts.clone();
+                        for _ in *min..*max {
+                            iteration = self.then(&iteration, i, inner, true);
+                            for j in i..=n {
+                                counts[j] = counts[j].clone() + iteration[j].clone();
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        let counts = Rc::new(counts);
+        self.memo.insert((ast as *const Ast, i), counts.clone());
+        counts
+    }
+
+    /// Counts for `prefix` followed by `item`, where `prefix[k]` counts the parses
+    /// of `chars[i..k]` (zero for `k < i`).
+    fn then(&mut self, prefix: &[W], i: usize, item: &Ast, nonempty: bool) -> Vec<W> {
+        let n = self.chars.len();
+        let mut counts = vec![W::zero(); n + 1];
+        for (k, prefix) in prefix.iter().enumerate().skip(i) {
+            let rest = self.counts(item, k);
+            for j in k + usize::from(nonempty)..=n {
+                counts[j] = counts[j].clone() + prefix.clone() * rest[j].clone();
+            }
+        }
+        counts
+    }
+
+    /// Counts for `inner*`, where every iteration must be non-empty,
+    /// otherwise there would be infinitely many parses.
+    fn star_counts(&mut self, inner: &Ast, i: usize) -> Rc<Vec<W>> {
+        if let Some(counts) = self.stars.get(&(inner as *const Ast, i)) {
+            return counts.clone();
+        }
+        let n = self.chars.len();
+        let mut counts = vec![W::zero(); n + 1];
+        counts[i] = W::one();
+        let first = self.counts(inner, i);
+        for k in i + 1..=n {
+            let rest = self.star_counts(inner, k);
+            for j in k..=n {
+                counts[j] = counts[j].clone() + first[k].clone() * rest[j].clone();
+            }
+        }
+        let counts = Rc::new(counts);
+        self.stars.insert((inner as *const Ast, i), counts.clone());
+        counts
+    }
+}
+
+impl Ast {
+    /// Counts the distinct parse trees of `s`, i.e. the ambiguity of the pattern on `s`.
+    ///
+    /// A star iteration must consume at least one char, so `(a*)*` has a single parse of `""`.
+    /// Zero means no match. Runs in `O(n^3)` per node for an input of `n` chars.
+    pub fn count_parses<W: Count>(&self, s: &str) -> W {
+        let chars = s.chars().collect::<Vec<_>>();
+        let mut counter = Counter::<W> {
+            chars: &chars,
+            memo: HashMap::new(),
+            stars: HashMap::new(),
+        };
+        counter.counts(self, 0)[chars.len()].clone()
+    }
+}
+
+impl Regex {
+    /// Counts the distinct parses of `s` under the pattern, see [`Ast::count_parses`].
+    pub fn count_parses<W: Count>(&self, s: &str) -> W {
+        self.ast.count_parses(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_count_parses() {
+        let re = Regex::new("(a|ab)(c|bc)").unwrap();
+        assert_eq!(re.count_parses::<u64>("abc"), 2);
+        assert_eq!(re.count_parses::<u64>("ac"), 1);
+        assert_eq!(re.count_parses::<u64>("ab"), 0);
+
+        // Compositions of n into parts 1 and 2 (Fibonacci numbers):
+        let re = Regex::new("(a|aa)*").unwrap();
+        assert_eq!(re.count_parses::<u64>(""), 1);
+        assert_eq!(re.count_parses::<u64>("aaaaa"), 8);
+        assert_eq!(re.count_parses::<u64>("aaaaaaaaaa"), 89);
+
+        assert_eq!(
+            Regex::new("(a|aa)+").unwrap().count_parses::<u64>("aaaaa"),
+            8
+        );
+        assert_eq!(Regex::new("a?a?").unwrap().count_parses::<u64>("a"), 2);
+        assert_eq!(Regex::new("[a-c]|a").unwrap().count_parses::<u64>("a"), 2);
+
+        // Empty iterations don't count:
+        let re = Regex::new("(a*)*").unwrap();
+        assert_eq!(re.count_parses::<u64>(""), 1);
+        assert_eq!(re.count_parses::<u64>("aaa"), 4);
+
+        // Agrees with the automaton on whether there is a match:
+        let re = Regex::new("(a|b)*.b(ab|.)*").unwrap();
+        for s in ["", "b", "ab", "bb", "abab", "ba", "aaa"] {
+            assert_eq!(re.count_parses::<u64>(s) > 0, re.matches(s), "{:?}", s);
+        }
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn test_count_parses_bigint() {
+        use num_bigint::BigUint;
+
+        // 2^200 parses: every 'a' is matched by either branch.
+        let re = Regex::new("(a|.)*").unwrap();
+        let count = re.count_parses::<BigUint>(&"a".repeat(200));
+        assert_eq!(count, BigUint::from(1u32) << 200);
+    }
+}
@@ -0,0 +1,261 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use fsm::dfa::Dfa;
+
+use crate::{Anchor, Ast, Position, Regex};
+
+/// Pattern kept in a normal form, so that similar derivatives (equal up to associativity,
+/// commutativity and idempotence of alternation, and the identities of `ε` and `∅`)
+/// are equal, which makes the number of distinct derivatives finite.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Term {
+    /// Matches nothing (`∅`).
+    Nothing,
+    /// Matches only the empty string (`ε`).
+    Empty,
+    /// Matches a char in one of the ranges.
+    Chars(Vec<(char, char)>),
+    Anchor(Anchor),
+    /// At least two items, none of which is a concatenation, `ε` or `∅`.
+    Concat(Vec<Term>),
+    /// At least two sorted distinct items, none of which is an alternation or `∅`.
+    Alternation(Vec<Term>),
+    Star(Box<Term>),
+}
+
+impl Term {
+    fn concat(items: impl IntoIterator<Item = Term>) -> Term {
+        let mut flat = Vec::new();
+        for item in items {
+            match item {
+                Term::Nothing => return Term::Nothing,
+                Term::Empty => {}
+                Term::Concat(inner) => flat.extend(inner),
+                item => flat.push(item),
+            }
+        }
+        match flat.len() {
+            0 => Term::Empty,
+            1 => flat.pop().unwrap(),
+            _ => Term::Concat(flat),
+        }
+    }
+
+    fn alternation(items: impl IntoIterator<Item = Term>) -> Term {
+        let mut flat = Vec::new();
+        for item in items {
+            match item {
+                Term::Nothing => {}
+                Term::Alternation(inner) => flat.extend(inner),
+                item => flat.push(item),
+            }
+        }
+        flat.sort_unstable();
+        flat.dedup();
+        match flat.len() {
+            0 => Term::Nothing,
+            1 => flat.pop().unwrap(),
+            _ => Term::Alternation(flat),
+        }
+    }
+
+    fn star(inner: Term) -> Term {
+        match inner {
+            Term::Nothing | Term::Empty => Term::Empty,
+            Term::Star(_) => inner,
+            inner => Term::Star(Box::new(inner)),
+        }
+    }
+
+    fn of(ast: &Ast) -> Term {
+        match ast {
+            Ast::Empty => Term::Empty,
+            Ast::Literal(c) => Term::Chars(vec![(*c, *c)]),
+            Ast::Any => Term::Chars(vec![('\0', char::MAX)]),
+            Ast::Class(class) => Term::Chars(class.matched_ranges()),
+            Ast::Anchor(anchor) => Term::Anchor(*anchor),
+            Ast::Concat(items) => Term::concat(items.iter().map(Term::of)),
+            Ast::Alternation(branches) => Term::alternation(branches.iter().map(Term::of)),
+            Ast::Star(inner) => Term::star(Term::of(inner)),
+            Ast::Plus(inner) => {
+                let inner = Term::of(inner);
+                Term::concat([inner.clone(), Term::star(inner)])
+            }
+            Ast::Optional(inner) => Term::alternation([Term::Empty, Term::of(inner)]),
+            Ast::Lazy(inner) | Ast::Group { inner, .. } => Term::of(inner),
+            Ast::Repeat { inner, min, max } => {
+                let inner = Term::of(inner);
+                // The optional iterations, nested as `(r(r)?)?`:
+                let rest = match max {
+                    None => Term::star(inner.clone()),
+                    Some(max) => (*min..*max).fold(Term::Empty, |rest, _| {
+                        Term::alternation([Term::Empty, Term::concat([inner.clone(), rest])])
+                    }),
+                };
+                let required = (0..*min).map(|_| inner.clone());
+                Term::concat(required.chain([rest]))
+            }
+        }
+    }
+
+    /// Whether the term matches the empty string at `position`.
+    fn nullable(&self, position: Position) -> bool {
+        match self {
+            Term::Nothing | Term::Chars(_) => false,
+            Term::Empty | Term::Star(_) => true,
+            Term::Anchor(anchor) => position.holds(*anchor),
+            Term::Concat(items) => items.iter().all(|item| item.nullable(position)),
+            Term::Alternation(items) => items.iter().any(|item| item.nullable(position)),
+        }
+    }
+
+    /// Brzozowski derivative: the term matching `w` wherever this one matches `c w`,
+    /// with `c` read at `position`.
+    fn derivative(&self, c: char, position: Position) -> Term {
+        match self {
+            Term::Nothing | Term::Empty | Term::Anchor(_) => Term::Nothing,
+            Term::Chars(ranges) => {
+                if ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) {
+                    Term::Empty
+                } else {
+                    Term::Nothing
+                }
+            }
+            Term::Concat(items) => {
+                let mut branches = Vec::new();
+                for (i, item) in items.iter().enumerate() {
+                    let rest = items[i + 1..].iter().cloned();
+                    branches.push(Term::concat(
+                        std::iter::once(item.derivative(c, position)).chain(rest),
+                    ));
+                    if !item.nullable(position) {
+                        break;
+                    }
+                }
+                Term::alternation(branches)
+            }
+            Term::Alternation(items) => {
+                Term::alternation(items.iter().map(|item| item.derivative(c, position)))
+            }
+            Term::Star(inner) => Term::concat([inner.derivative(c, position), self.clone()]),
+        }
+    }
+}
+
+/// A state of the construction: the remaining term and the anchors known to hold.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Derivative {
+    term: Term,
+    at_start: bool,
+    at_line_start: bool,
+}
+
+impl Derivative {
+    fn position(&self, at_end: bool, at_line_end: bool) -> Position {
+        Position {
+            at_start: self.at_start,
+            at_end,
+            at_line_start: self.at_line_start,
+            at_line_end,
+        }
+    }
+}
+
+impl Regex {
+    /// Same as [`to_dfa`](Regex::to_dfa), but built with Brzozowski derivatives
+    /// instead of the subset construction, see [`to_dfa_by_derivatives_over`](Regex::to_dfa_by_derivatives_over).
+    pub fn to_dfa_by_derivatives(&self) -> Dfa<char> {
+        self.to_dfa_by_derivatives_over(&self.symbols())
+    }
+
+    /// Builds a DFA over `alphabet` accepting the same strings as [`to_dfa_over`](Regex::to_dfa_over),
+    /// directly from the syntax tree.
+    ///
+    /// Every state is a derivative of the pattern by the word leading to it, and derivatives
+    /// are merged when they are similar (equal up to simple algebraic identities). This often
+    /// yields a near-minimal DFA, but the result is not minimized, so it is also a cross-check
+    /// of the Thompson construction and the minimization. The dead state is left out.
+    pub fn to_dfa_by_derivatives_over(&self, alphabet: &BTreeSet<char>) -> Dfa<char> {
+        let initial = Derivative {
+            term: Term::of(&self.ast),
+            at_start: true,
+            at_line_start: true,
+        };
+        let mut dfa = Dfa::new();
+        let mut ids = HashMap::from([(initial.clone(), dfa.add_state(false))]);
+        let mut queue = VecDeque::from([initial]);
+        while let Some(derivative) = queue.pop_front() {
+            let from = ids[&derivative];
+            dfa.state_mut(from).accepting =
+                derivative.term.nullable(derivative.position(true, true));
+            for &c in alphabet {
+                let position = derivative.position(false, c == '\n');
+                let next = Derivative {
+                    term: derivative.term.derivative(c, position),
+                    at_start: false,
+                    at_line_start: c == '\n',
+                };
+                if next.term == Term::Nothing {
+                    continue;
+                }
+                let to = match ids.get(&next) {
+                    Some(&to) => to,
+                    None => {
+                        let to = dfa.add_state(false);
+                        ids.insert(next.clone(), to);
+                        queue.push_back(next);
+                        to
+                    }
+                };
+                dfa.add_transition(from, c, to);
+            }
+        }
+        dfa
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RegexBuilder;
+
+    #[test]
+    fn test_to_dfa_by_derivatives() {
+        // Only one state more than the minimal DFA:
+        let re = Regex::new("(a|b)*abb").unwrap();
+        let dfa = re.to_dfa_by_derivatives();
+        assert_eq!(dfa.num_states(), 5);
+        assert_eq!(dfa.minimize().num_states(), 4);
+        assert!(dfa.equivalent(&re.to_dfa()));
+
+        // Minimal right away:
+        let re = Regex::new("a(b|c)*d").unwrap();
+        assert_eq!(re.to_dfa_by_derivatives().num_states(), 3);
+
+        // Agrees with the Thompson construction:
+        let alphabet = BTreeSet::from(['a', 'b', 'x', '\n']);
+        for (pattern, multi_line) in [
+            ("(a|ab)(c|bc)", false),
+            ("(a*b*)*a?", false),
+            ("[^a]+|a{2,4}", false),
+            ("(x|a+?){3,}", false),
+            ("^a|b$|^$", false),
+            ("(^|x)a+($|\n)", false),
+            ("a?$\n^b", true),
+            ("a*^b", false),
+        ] {
+            let re = RegexBuilder::new(pattern)
+                .multi_line(multi_line)
+                .build()
+                .unwrap();
+            let dfa = re.to_dfa_by_derivatives_over(&alphabet);
+            assert!(dfa.equivalent(&re.to_dfa_over(&alphabet)), "{:?}", pattern);
+            assert_eq!(
+                dfa.minimize().num_states(),
+                re.to_dfa_over(&alphabet).num_states(),
+                "{:?}",
+                pattern
+            );
+        }
+    }
+}
@@ -0,0 +1,198 @@
+use std::collections::{BTreeSet, HashMap};
+
+use fsm::dfa::Dfa;
+
+use crate::{Ast, Position, Regex};
+
+/// Chars written in the pattern: literals and the ranges of classes (negated or not).
+fn collect_symbols(ast: &Ast, symbols: &mut BTreeSet<char>) {
+    match ast {
+        Ast::Literal(c) => {
+            symbols.insert(*c);
+        }
+        Ast::Class(class) => {
+            for &(lo, hi) in class.ranges() {
+                symbols.extend(lo..=hi);
+            }
+        }
+        _ => {
+            for child in ast.children() {
+                collect_symbols(child, symbols);
+            }
+        }
+    }
+}
+
+/// A state of the subset construction: NFA states closed under the ε-transitions and
+/// the anchors known to hold, i.e. all but the ones depending on the next char.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Subset {
+    states: BTreeSet<usize>,
+    at_start: bool,
+    at_line_start: bool,
+}
+
+impl Regex {
+    /// Chars written in the pattern, the alphabet of [`to_dfa`](Regex::to_dfa).
+    ///
+    /// Every char of a class is included, so this is large for classes such as `\w`
+    /// with Unicode semantics.
+    pub fn symbols(&self) -> BTreeSet<char> {
+        let mut symbols = BTreeSet::new();
+        collect_symbols(&self.ast, &mut symbols);
+        symbols
+    }
+
+    /// Compiles the pattern into the minimal DFA over the [symbols](Regex::symbols)
+    /// of the pattern, accepting the same strings as [`matches`](Regex::matches)
+    /// (among those over this alphabet).
+    pub fn to_dfa(&self) -> Dfa<char> {
+        self.to_dfa_over(&self.symbols())
+    }
+
+    /// Compiles the pattern into the minimal DFA over `alphabet`, accepting the strings
+    /// over `alphabet` that [`matches`](Regex::matches) accepts.
+    ///
+    /// `.` and negated classes match only the chars of `alphabet`, so pass the chars
+    /// of the expected inputs for these.
+    pub fn to_dfa_over(&self, alphabet: &BTreeSet<char>) -> Dfa<char> {
+        let nfa = &self.states;
+        let position = |subset: &Subset, at_end: bool, at_line_end: bool| Position {
+            at_start: subset.at_start,
+            at_end,
+            at_line_start: subset.at_line_start,
+            at_line_end,
+        };
+        let initial = Subset {
+            states: BTreeSet::from([self.start]),
+            at_start: true,
+            at_line_start: true,
+        };
+        let initial = Subset {
+            states: nfa.epsilon_closure(self.start, position(&initial, false, false)),
+            ..initial
+        };
+
+        let mut dfa = Dfa::new();
+        let mut ids = HashMap::from([(initial.clone(), dfa.add_state(false))]);
+        let mut queue = vec![initial];
+        while let Some(subset) = queue.pop() {
+            let from = ids[&subset];
+            let end = nfa.multi_epsilon_closure(
+                subset.states.iter().copied().collect(),
+                position(&subset, true, true),
+            );
+            dfa.state_mut(from).accepting = nfa.any_accepting(&end);
+            for &c in alphabet {
+                // Anchors looking ahead at `c` hold only now:
+                let current = nfa.multi_epsilon_closure(
+                    subset.states.iter().copied().collect(),
+                    position(&subset, false, c == '\n'),
+                );
+                let next = Subset {
+                    states: BTreeSet::new(),
+                    at_start: false,
+                    at_line_start: c == '\n',
+                };
+                let next = Subset {
+                    states: nfa.advance(current, c, position(&next, false, false)),
+                    ..next
+                };
+                if next.states.is_empty() {
+                    continue;
+                }
+                let to = match ids.get(&next) {
+                    Some(&to) => to,
+                    None => {
+                        let to = dfa.add_state(false);
+                        ids.insert(next.clone(), to);
+                        queue.push(next);
+                        to
+                    }
+                };
+                dfa.add_transition(from, c, to);
+            }
+        }
+        dfa.minimize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RegexBuilder;
+
+    fn strings(symbols: &[char], max_len: usize) -> Vec<String> {
+        let mut strings = vec![String::new()];
+        let mut last = strings.clone();
+        for _ in 0..max_len {
+            last = last
+                .iter()
+                .flat_map(|s| symbols.iter().map(move |&c| format!("{}{}", s, c)))
+                .collect();
+            strings.extend(last.iter().cloned());
+        }
+        strings
+    }
+
+    #[test]
+    fn test_to_dfa() {
+        let re = Regex::new("(a|b)*abb").unwrap();
+        assert_eq!(re.symbols(), BTreeSet::from(['a', 'b']));
+        let dfa = re.to_dfa();
+        // The textbook example, with the dead state trimmed:
+        assert_eq!(dfa.num_states(), 4);
+        for s in strings(&['a', 'b'], 6) {
+            assert_eq!(dfa.accepts(s.chars()), re.matches(&s), "{:?}", s);
+        }
+
+        let alphabet = BTreeSet::from(['a', 'b', 'x', '\n']);
+        for (pattern, multi_line) in [
+            ("a.b", false),
+            ("[^a]+", false),
+            ("^a|b$", false),
+            ("(^|x)a+($|\n)", false),
+            ("a?$\n^b", true),
+            ("x{2,3}", false),
+        ] {
+            let re = RegexBuilder::new(pattern)
+                .multi_line(multi_line)
+                .build()
+                .unwrap();
+            let dfa = re.to_dfa_over(&alphabet);
+            for s in strings(&['a', 'b', 'x', '\n'], 5) {
+                assert_eq!(
+                    dfa.accepts(s.chars()),
+                    re.matches(&s),
+                    "{:?} on {:?}",
+                    pattern,
+                    s
+                );
+            }
+        }
+
+        assert_eq!(
+            Regex::new("[a-c]d|x").unwrap().to_dfa().symbols(),
+            ['a', 'b', 'c', 'd', 'x']
+        );
+    }
+
+    #[test]
+    fn test_dfa_algorithms() {
+        // Strings of a's and b's without "ab" are those matching b*a*:
+        let contains_ab = Regex::new("(a|b)*ab(a|b)*").unwrap().to_dfa();
+        let no_ab = Regex::new("b*a*").unwrap().to_dfa();
+        assert!(contains_ab.complement().equivalent(&no_ab));
+        assert!(!contains_ab.equivalent(&no_ab));
+    }
+
+    #[test]
+    fn test_to_regex_round_trip() {
+        for pattern in ["(a|b)*abb", "a(b|c)*d|x+", "(ab)*|b?", "a.c"] {
+            let dfa = Regex::new(pattern).unwrap().to_dfa();
+            let regex = dfa.to_regex().to_string();
+            let round_trip = Regex::new(&regex).unwrap().to_dfa();
+            assert!(dfa.equivalent(&round_trip), "{:?} -> {:?}", pattern, regex);
+        }
+    }
+}
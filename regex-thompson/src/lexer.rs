@@ -0,0 +1,153 @@
+use crate::{ParseError, Regex, Span};
+
+/// A token found by a [`Lexer`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Token {
+    /// Index of the matching pattern, in declaration order.
+    pub kind: usize,
+    /// Location of the token in the haystack.
+    pub span: Span,
+}
+
+/// Ordered set of patterns for splitting input into tokens.
+///
+/// At every position, the longest non-empty match among all patterns wins.
+/// When several patterns match the same longest span, the earliest-declared one wins,
+/// so keywords should be declared before the identifier pattern they overlap with.
+#[derive(Debug)]
+pub struct Lexer {
+    patterns: Vec<Regex>,
+}
+
+impl Lexer {
+    /// Creates a lexer from patterns in priority order (highest first).
+    ///
+    /// Fails on the first malformed pattern.
+    pub fn new<'p, I: IntoIterator<Item = &'p str>>(patterns: I) -> Result<Self, ParseError> {
+        Ok(Self {
+            patterns: patterns
+                .into_iter()
+                .map(Regex::new)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    pub fn num_patterns(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Finds the token starting exactly at byte `offset` of `haystack`, if any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is not on a char boundary of `haystack`.
+    pub fn token_at(&self, haystack: &str, offset: usize) -> Option<Token> {
+        let mut best: Option<(usize, usize)> = None;
+        for (kind, pattern) in self.patterns.iter().enumerate() {
+            let len = pattern
+                .states
+                .longest_prefix_match_at(pattern.start, haystack, offset);
+            if let Some(len) = len {
+                // Strictly longer only, so that ties go to the earlier pattern.
+                if len > 0 && best.is_none_or(|(_, best_len)| len > best_len) {
+                    best = Some((kind, len));
+                }
+            }
+        }
+        best.map(|(kind, len)| Token {
+            kind,
+            span: Span::new(haystack, offset, offset + len),
+        })
+    }
+
+    /// Lazily splits `haystack` into consecutive tokens.
+    ///
+    /// Iteration stops at the end of the haystack or at the first position where no pattern
+    /// matches, see [`Tokens::offset`] to tell these apart.
+    pub fn tokens<'l, 'h>(&'l self, haystack: &'h str) -> Tokens<'l, 'h> {
+        Tokens {
+            lexer: self,
+            haystack,
+            offset: 0,
+        }
+    }
+}
+
+/// Iterator over consecutive tokens, see [`Lexer::tokens`].
+#[derive(Debug)]
+pub struct Tokens<'l, 'h> {
+    lexer: &'l Lexer,
+    haystack: &'h str,
+    offset: usize,
+}
+
+impl Tokens<'_, '_> {
+    /// Byte offset right after the last yielded token.
+    ///
+    /// Once the iterator is exhausted, this is the length of the haystack
+    /// if all of it was tokenized, or the position of the offending input otherwise.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Iterator for Tokens<'_, '_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let token = self.lexer.token_at(self.haystack, self.offset)?;
+        self.offset = token.span.end;
+        Some(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEYWORD: usize = 0;
+    const IDENT: usize = 1;
+    const SPACE: usize = 2;
+
+    fn lexer() -> Lexer {
+        let letter = "(a|b|c|d|e|f|g|h|i|j|k|l|m|n|o|p|q|r|s|t|u|v|w|x|y|z)";
+        let ident = format!("{letter}{letter}*");
+        Lexer::new(["if|else", ident.as_str(), "  *"]).unwrap()
+    }
+
+    #[test]
+    fn test_priority() {
+        let lexer = lexer();
+        let haystack = "if iffy else";
+        let tokens = lexer
+            .tokens(haystack)
+            .map(|token| (token.kind, token.span.as_str(haystack)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec![
+                (KEYWORD, "if"),
+                (SPACE, " "),
+                (IDENT, "iffy"),
+                (SPACE, " "),
+                (KEYWORD, "else"),
+            ]
+        );
+
+        // Same patterns, identifier declared first: keywords are never reported.
+        let reversed = Lexer::new(["(i|f)(i|f)*", "if"]).unwrap();
+        assert_eq!(reversed.token_at("if", 0).unwrap().kind, 0);
+    }
+
+    #[test]
+    fn test_tokens_stop() {
+        let lexer = lexer();
+        let mut tokens = lexer.tokens("if x 42");
+        assert_eq!(tokens.by_ref().count(), 4);
+        assert_eq!(tokens.offset(), 5);
+
+        let mut tokens = lexer.tokens("");
+        assert_eq!(tokens.next(), None);
+        assert_eq!(tokens.offset(), 0);
+    }
+}
@@ -1,12 +1,67 @@
-use std::collections::{BTreeSet, HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap};
 use std::ops::{Index, IndexMut};
 
+use memchr::memmem;
+
+pub use ast::{Anchor, Ast, ParseError, ParseErrorKind, MAX_REPETITION};
+pub use builder::RegexBuilder;
+pub use bytes::BytesRegex;
+pub use captures::Captures;
+pub use class::CharClass;
+pub use counting::Count;
+pub use lexer::{Lexer, Token, Tokens};
+pub use matcher::Matcher;
+pub use matches::Matches;
+pub use records::{Delimiter, Records};
+pub use replace::Replacer;
+pub use set::RegexSet;
+pub use span::Span;
+
+mod ast;
+pub mod bench_support;
+mod builder;
+mod bytes;
+mod captures;
+mod class;
+mod counting;
+#[cfg(feature = "fsm")]
+mod derivatives;
+#[cfg(feature = "fsm")]
+mod dfa;
+mod lexer;
+mod matcher;
+mod matches;
+mod records;
+mod replace;
+mod set;
+mod simplify;
+mod span;
+
+/// What a transition consumes, besides a char of a class.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Symbol {
+    Char(char),
+    /// Any char, i.e. `.`.
+    Any,
+}
+
 #[derive(Debug)]
 pub struct State {
+    #[allow(dead_code)]
     id: usize,
     accepting: bool,
-    transitions: HashMap<char, usize>,
-    epsilon_transitions: HashSet<usize>,
+    transitions: HashMap<Symbol, usize>,
+    /// ε-transitions, in the order of preference used for capture groups.
+    epsilon_transitions: Vec<usize>,
+    /// Transitions on any char of a class, tried in addition to `transitions`.
+    classes: Vec<(CharClass, usize)>,
+    /// ε-transitions taken only where the anchor holds.
+    anchors: Vec<(Anchor, usize)>,
+    /// Capture slot recording the position whenever this state is entered.
+    tag: Option<usize>,
+    /// For the last state of a loop body: the first state of the body and the loop exit.
+    /// An iteration that matched the empty string goes straight to the exit.
+    loop_body: Option<(usize, usize)>,
 }
 
 impl State {
@@ -15,12 +70,65 @@ impl State {
             id,
             accepting,
             transitions: HashMap::new(),
-            epsilon_transitions: HashSet::new(),
+            epsilon_transitions: Vec::new(),
+            classes: Vec::new(),
+            anchors: Vec::new(),
+            tag: None,
+            loop_body: None,
         }
     }
 }
 
-#[derive(Debug)]
+/// Where the simulation is in the haystack, telling which anchors hold.
+#[derive(Debug, Copy, Clone)]
+struct Position {
+    at_start: bool,
+    at_end: bool,
+    at_line_start: bool,
+    at_line_end: bool,
+}
+
+impl Position {
+    /// Every anchor holds, for analyses over all possible matches.
+    const ANY: Position = Position {
+        at_start: true,
+        at_end: true,
+        at_line_start: true,
+        at_line_end: true,
+    };
+
+    fn of(haystack: &str, offset: usize) -> Self {
+        let at_start = offset == 0;
+        let at_end = offset == haystack.len();
+        Self {
+            at_start,
+            at_end,
+            at_line_start: at_start || haystack[..offset].ends_with('\n'),
+            at_line_end: at_end || haystack[offset..].starts_with('\n'),
+        }
+    }
+
+    /// Same as [`of`](Position::of), but more input may follow `haystack`,
+    /// so the end is unknown.
+    fn of_prefix(haystack: &str, offset: usize) -> Self {
+        Self {
+            at_end: false,
+            at_line_end: haystack[offset..].starts_with('\n'),
+            ..Self::of(haystack, offset)
+        }
+    }
+
+    fn holds(self, anchor: Anchor) -> bool {
+        match anchor {
+            Anchor::Start => self.at_start,
+            Anchor::End => self.at_end,
+            Anchor::LineStart => self.at_line_start,
+            Anchor::LineEnd => self.at_line_end,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
 pub struct Fragment {
     start: usize,
     end: usize,
@@ -43,6 +151,42 @@ impl Nfa {
         id
     }
 
+    /// Moves all states of `other` into this NFA, returning the offset added to their indices.
+    pub fn append(&mut self, other: Nfa) -> usize {
+        let offset = self.states.len();
+        for state in other.states {
+            self.states.push(State {
+                id: state.id + offset,
+                accepting: state.accepting,
+                transitions: state
+                    .transitions
+                    .into_iter()
+                    .map(|(c, to)| (c, to + offset))
+                    .collect(),
+                epsilon_transitions: state
+                    .epsilon_transitions
+                    .into_iter()
+                    .map(|to| to + offset)
+                    .collect(),
+                classes: state
+                    .classes
+                    .into_iter()
+                    .map(|(class, to)| (class, to + offset))
+                    .collect(),
+                anchors: state
+                    .anchors
+                    .into_iter()
+                    .map(|(anchor, to)| (anchor, to + offset))
+                    .collect(),
+                tag: state.tag,
+                loop_body: state
+                    .loop_body
+                    .map(|(body, exit)| (body + offset, exit + offset)),
+            });
+        }
+        offset
+    }
+
     pub fn state(&self, index: usize) -> &State {
         &self.states[index]
     }
@@ -51,6 +195,12 @@ impl Nfa {
     }
 }
 
+impl Default for Nfa {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Index<usize> for Nfa {
     type Output = State;
 
@@ -66,47 +216,156 @@ impl IndexMut<usize> for Nfa {
 }
 
 impl Nfa {
-    pub fn parse(&mut self, pattern: &str) -> Fragment {
-        let postfix = to_postfix(&insert_explicit_concat_operator(pattern));
-        let mut stack = Vec::new();
-        for token in postfix.chars() {
-            match token {
-                '\0' => {
-                    let f2 = stack.pop().unwrap();
-                    let f1 = stack.pop().unwrap();
-                    stack.push(self.concat(f1, f2));
-                }
-                '|' => {
-                    let f2 = stack.pop().unwrap();
-                    let f1 = stack.pop().unwrap();
-                    stack.push(self.union(f1, f2));
-                }
-                '*' => {
-                    let f = stack.pop().unwrap();
-                    stack.push(self.closure(f));
-                }
-                c => {
-                    stack.push(self.symbol(c));
+    pub fn parse(&mut self, pattern: &str) -> Result<Fragment, ParseError> {
+        Ok(self.compile(&Ast::parse(pattern)?))
+    }
+
+    /// Builds the fragment for a syntax tree (Thompson's construction).
+    pub fn compile(&mut self, ast: &Ast) -> Fragment {
+        match ast {
+            Ast::Empty => self.empty(),
+            Ast::Literal(c) => self.symbol(*c),
+            Ast::Any => self.any(),
+            Ast::Class(class) => self.class(class.clone()),
+            Ast::Anchor(anchor) => self.anchor(*anchor),
+            Ast::Concat(items) => self.compile_all(items, Nfa::concat),
+            Ast::Alternation(branches) => self.compile_all(branches, Nfa::union),
+            Ast::Star(_) | Ast::Plus(_) | Ast::Optional(_) | Ast::Repeat { .. } => {
+                self.quantifier(ast, true)
+            }
+            Ast::Lazy(inner) => self.quantifier(inner, false),
+            Ast::Group { index, inner } => {
+                let f = self.compile(inner);
+                self.group(*index, f)
+            }
+        }
+    }
+
+    /// Compiles a quantifier, preferring more repetitions if `greedy` and fewer otherwise.
+    ///
+    /// Preferences only matter for [`Regex::captures`], which follows the preferred parse.
+    fn quantifier(&mut self, ast: &Ast, greedy: bool) -> Fragment {
+        match ast {
+            Ast::Star(inner) => {
+                let f = self.compile(inner);
+                self.closure_with(f, greedy)
+            }
+            Ast::Plus(inner) => {
+                let f1 = self.compile(inner);
+                let f2 = self.compile(inner);
+                let f2 = self.closure_with(f2, greedy);
+                self.concat(f1, f2)
+            }
+            Ast::Optional(inner) => {
+                let f = self.compile(inner);
+                self.optional(f, greedy)
+            }
+            Ast::Repeat { inner, min, max } => self.repeat(inner, *min, *max, greedy),
+            _ => unreachable!("not a quantifier: {:?}", ast),
+        }
+    }
+
+    /// Expands `inner{min,max}` into `min` copies of `inner`, followed by `inner*`
+    /// when unbounded, or by nested optional copies `(inner(inner)?)?` otherwise.
+    fn repeat(&mut self, inner: &Ast, min: u32, max: Option<u32>, greedy: bool) -> Fragment {
+        let mut result = self.empty();
+        for _ in 0..min {
+            let f = self.compile(inner);
+            result = self.concat(result, f);
+        }
+        let rest = match max {
+            None => {
+                let f = self.compile(inner);
+                self.closure_with(f, greedy)
+            }
+            Some(max) => {
+                let mut rest = self.empty();
+                for _ in min..max {
+                    let f = self.compile(inner);
+                    let f = self.concat(f, rest);
+                    rest = self.optional(f, greedy);
                 }
+                rest
             }
+        };
+        self.concat(result, rest)
+    }
+
+    /// Compiles `asts` and folds their fragments with `combine` (empty when there are none).
+    fn compile_all(
+        &mut self,
+        asts: &[Ast],
+        combine: fn(&mut Nfa, Fragment, Fragment) -> Fragment,
+    ) -> Fragment {
+        let mut result: Option<Fragment> = None;
+        for ast in asts {
+            let f = self.compile(ast);
+            result = Some(match result {
+                Some(acc) => combine(self, acc, f),
+                None => f,
+            });
         }
-        stack
-            .into_iter()
-            .reduce(|f1, f2| self.concat(f1, f2))
-            .unwrap()
+        result.unwrap_or_else(|| self.empty())
+    }
+
+    /// Fragment matching only the empty string.
+    pub fn empty(&mut self) -> Fragment {
+        let start = self.new_state(false);
+        let end = self.new_state(true);
+        self.state_mut(start).epsilon_transitions.push(end);
+        Fragment { start, end }
     }
 
     pub fn symbol(&mut self, c: char) -> Fragment {
         let start = self.new_state(false);
         let end = self.new_state(true);
         // Connect the start state to the end state with the given symbol
-        self.state_mut(start).transitions.insert(c, end);
+        self.state_mut(start)
+            .transitions
+            .insert(Symbol::Char(c), end);
+        Fragment { start, end }
+    }
+
+    /// Fragment matching any single char.
+    pub fn any(&mut self) -> Fragment {
+        let start = self.new_state(false);
+        let end = self.new_state(true);
+        self.state_mut(start).transitions.insert(Symbol::Any, end);
+        Fragment { start, end }
+    }
+
+    /// Fragment matching any single char of `class`.
+    pub fn class(&mut self, class: CharClass) -> Fragment {
+        let start = self.new_state(false);
+        let end = self.new_state(true);
+        self.state_mut(start).classes.push((class, end));
+        Fragment { start, end }
+    }
+
+    /// Wraps `f` into tagged states recording the bounds of capture group `index`
+    /// in slots `2 * index` and `2 * index + 1`.
+    pub fn group(&mut self, index: usize, f: Fragment) -> Fragment {
+        let start = self.new_state(false);
+        let end = self.new_state(true);
+        self.state_mut(start).tag = Some(2 * index);
+        self.state_mut(end).tag = Some(2 * index + 1);
+        self.state_mut(start).epsilon_transitions.push(f.start);
+        self.state_mut(f.end).epsilon_transitions.push(end);
+        self.state_mut(f.end).accepting = false;
+        Fragment { start, end }
+    }
+
+    /// Fragment matching the empty string where `anchor` holds.
+    pub fn anchor(&mut self, anchor: Anchor) -> Fragment {
+        let start = self.new_state(false);
+        let end = self.new_state(true);
+        self.state_mut(start).anchors.push((anchor, end));
         Fragment { start, end }
     }
 
     pub fn concat(&mut self, f1: Fragment, f2: Fragment) -> Fragment {
         // Connect the old end state to the new start state
-        self.state_mut(f1.end).epsilon_transitions.insert(f2.start);
+        self.state_mut(f1.end).epsilon_transitions.push(f2.start);
         self.state_mut(f1.end).accepting = false;
         Fragment {
             start: f1.start,
@@ -118,11 +377,11 @@ impl Nfa {
         let start = self.new_state(false);
         let end = self.new_state(true);
         // Connect the new start state to the old start states
-        self.state_mut(start).epsilon_transitions.insert(f1.start);
-        self.state_mut(start).epsilon_transitions.insert(f2.start);
+        self.state_mut(start).epsilon_transitions.push(f1.start);
+        self.state_mut(start).epsilon_transitions.push(f2.start);
         // Connect the old end states to the new end state
-        self.state_mut(f1.end).epsilon_transitions.insert(end);
-        self.state_mut(f2.end).epsilon_transitions.insert(end);
+        self.state_mut(f1.end).epsilon_transitions.push(end);
+        self.state_mut(f2.end).epsilon_transitions.push(end);
         // Make sure the old end states are no longer accepting
         self.state_mut(f1.end).accepting = false;
         self.state_mut(f2.end).accepting = false;
@@ -130,21 +389,43 @@ impl Nfa {
     }
 
     pub fn closure(&mut self, f: Fragment) -> Fragment {
+        self.closure_with(f, true)
+    }
+
+    /// Same as [`closure`](Nfa::closure), preferring to exit the loop unless `greedy`.
+    pub fn closure_with(&mut self, f: Fragment, greedy: bool) -> Fragment {
         let start = self.new_state(false);
         let end = self.new_state(true);
-        self.state_mut(start).epsilon_transitions.insert(f.start);
-        self.state_mut(start).epsilon_transitions.insert(end);
-        self.state_mut(f.end).epsilon_transitions.insert(f.start);
-        self.state_mut(f.end).epsilon_transitions.insert(end);
+        let (first, second) = if greedy {
+            (f.start, end)
+        } else {
+            (end, f.start)
+        };
+        self.state_mut(start).epsilon_transitions.push(first);
+        self.state_mut(start).epsilon_transitions.push(second);
+        self.state_mut(f.end).epsilon_transitions.push(first);
+        self.state_mut(f.end).epsilon_transitions.push(second);
         self.state_mut(f.end).accepting = false;
+        self.state_mut(f.end).loop_body = Some((f.start, end));
         Fragment { start, end }
     }
 
-    fn epsilon_closure(&self, start: usize) -> BTreeSet<usize> {
-        self.multi_epsilon_closure(vec![start])
+    /// Fragment matching `f` or the empty string, preferring `f` if `greedy`.
+    pub fn optional(&mut self, f: Fragment, greedy: bool) -> Fragment {
+        let empty = self.empty();
+        if greedy {
+            self.union(f, empty)
+        } else {
+            self.union(empty, f)
+        }
+    }
+
+    fn epsilon_closure(&self, start: usize, position: Position) -> BTreeSet<usize> {
+        self.multi_epsilon_closure(vec![start], position)
     }
 
-    fn multi_epsilon_closure(&self, start: Vec<usize>) -> BTreeSet<usize> {
+    /// States reachable by ε-transitions and by the anchors that hold at `position`.
+    fn multi_epsilon_closure(&self, start: Vec<usize>, position: Position) -> BTreeSet<usize> {
         let mut visited = BTreeSet::new();
         let mut stack = start;
 
@@ -153,116 +434,480 @@ impl Nfa {
                 for &next_state in self.state(state).epsilon_transitions.iter() {
                     stack.push(next_state);
                 }
+                for &(anchor, next_state) in &self.state(state).anchors {
+                    if position.holds(anchor) {
+                        stack.push(next_state);
+                    }
+                }
             }
         }
 
         visited
     }
 
+    /// States reached from `state` by reading `c`, before taking ε-transitions.
+    fn step(&self, state: usize, c: char) -> impl Iterator<Item = usize> + '_ {
+        let state = self.state(state);
+        let symbol = state.transitions.get(&Symbol::Char(c));
+        let any = state.transitions.get(&Symbol::Any);
+        let classes = state
+            .classes
+            .iter()
+            .filter(move |(class, _)| class.contains(c))
+            .map(|&(_, to)| to);
+        symbol.into_iter().chain(any).copied().chain(classes)
+    }
+
+    /// Reads `c` from all `states`, ending up at `position`.
+    fn advance(&self, states: BTreeSet<usize>, c: char, position: Position) -> BTreeSet<usize> {
+        let mut next_states = BTreeSet::new();
+        for state in states {
+            for next_state in self.step(state, c) {
+                next_states.extend(self.epsilon_closure(next_state, position));
+            }
+        }
+        next_states
+    }
+
+    fn any_accepting(&self, states: &BTreeSet<usize>) -> bool {
+        states.iter().any(|&state| self.state(state).accepting)
+    }
+
     pub fn matches(&self, start: usize, s: &str) -> bool {
-        let mut current_states = self.epsilon_closure(start);
+        let mut current_states = self.epsilon_closure(start, Position::of(s, 0));
 
-        for c in s.chars() {
-            let mut next_states = BTreeSet::new();
+        for (i, c) in s.char_indices() {
+            let position = Position::of(s, i + c.len_utf8());
+            current_states = self.advance(current_states, c, position);
+        }
 
-            for state in current_states {
-                if let Some(&next_state) = self.state(state).transitions.get(&c) {
-                    next_states.extend(self.epsilon_closure(next_state));
-                } else if let Some(&next_state) = self.state(state).transitions.get(&'.') {
-                    next_states.extend(self.epsilon_closure(next_state));
-                }
-            }
+        self.any_accepting(&current_states)
+    }
+
+    /// Returns `true` if some prefix of `s` (possibly empty) is accepted.
+    pub fn matches_prefix(&self, start: usize, s: &str) -> bool {
+        self.matches_prefix_at(start, s, 0)
+    }
 
-            current_states = next_states;
+    /// Returns `true` if some prefix of `haystack[offset..]` (possibly empty) is accepted,
+    /// with anchors referring to the whole `haystack`.
+    pub fn matches_prefix_at(&self, start: usize, haystack: &str, offset: usize) -> bool {
+        let mut current_states = self.epsilon_closure(start, Position::of(haystack, offset));
+
+        for (i, c) in haystack[offset..].char_indices() {
+            if current_states.is_empty() {
+                return false;
+            }
+            if self.any_accepting(&current_states) {
+                return true;
+            }
+            let position = Position::of(haystack, offset + i + c.len_utf8());
+            current_states = self.advance(current_states, c, position);
         }
 
-        current_states
-            .into_iter()
-            .any(|state| self.state(state).accepting)
+        self.any_accepting(&current_states)
     }
-}
 
-fn insert_explicit_concat_operator(pattern: &str) -> String {
-    let mut output = String::new();
-    let mut prev_char: Option<char> = None;
+    /// Returns the length (in bytes) of the longest accepted prefix of `s`, if any.
+    pub fn longest_prefix_match(&self, start: usize, s: &str) -> Option<usize> {
+        self.longest_prefix_match_at(start, s, 0)
+    }
 
-    for token in pattern.chars() {
-        if let Some(prev) = prev_char {
-            if !(prev == '(' || prev == '|')
-                && !(token == '*' || token == '?' || token == '+' || token == '|' || token == ')')
-            {
-                output.push('\0');
+    /// Returns the length (in bytes) of the longest accepted prefix of `haystack[offset..]`,
+    /// if any, with anchors referring to the whole `haystack`.
+    pub fn longest_prefix_match_at(
+        &self,
+        start: usize,
+        haystack: &str,
+        offset: usize,
+    ) -> Option<usize> {
+        let mut current_states = self.epsilon_closure(start, Position::of(haystack, offset));
+        let mut longest = None;
+
+        for (i, c) in haystack[offset..].char_indices() {
+            if current_states.is_empty() {
+                return longest;
+            }
+            if self.any_accepting(&current_states) {
+                longest = Some(i);
             }
+            let position = Position::of(haystack, offset + i + c.len_utf8());
+            current_states = self.advance(current_states, c, position);
         }
-        output.push(token);
-        prev_char = Some(token);
+
+        if self.any_accepting(&current_states) {
+            longest = Some(haystack.len() - offset);
+        }
+        longest
     }
 
-    output
-}
+    /// Returns `true` if a match beginning at some char boundary in `0..=last_start` of `s`
+    /// could still be extended past the end of `s`.
+    ///
+    /// More input may follow `s`, so `$` never holds at its end, and a pending `$` counts as a possible
+    /// extension: the match may end differently once the actual end is known.
+    pub(crate) fn can_continue(&self, start: usize, s: &str, last_start: usize) -> bool {
+        let mut current_states = BTreeSet::new();
 
-fn to_postfix(pattern: &str) -> String {
-    let mut output = String::new();
-    let mut operator_stack = Vec::new();
-    let operator_precedence = [('|', 0), ('\0', 1), ('?', 2), ('*', 2), ('+', 2)]
-        .iter()
-        .cloned()
-        .collect::<HashMap<char, i32>>();
-
-    for token in pattern.chars() {
-        // Shunting-yard algorithm
-        if token == '(' {
-            operator_stack.push(token);
-        } else if token == ')' {
-            while operator_stack.last().map(|&c| c != '(').unwrap_or(false) {
-                output.push(operator_stack.pop().unwrap());
+        for (i, c) in s.char_indices() {
+            if i <= last_start {
+                current_states.extend(self.epsilon_closure(start, Position::of_prefix(s, i)));
             }
-            operator_stack.pop();
-        } else if token == '\0' || token == '|' || token == '*' || token == '?' || token == '+' {
-            while operator_stack.last().is_some()
-                && *operator_stack.last().unwrap() != '('
-                && operator_precedence[operator_stack.last().unwrap()]
-                    >= operator_precedence[&token]
+            let position = Position::of_prefix(s, i + c.len_utf8());
+            current_states = self.advance(current_states, c, position);
+        }
+
+        if s.len() <= last_start {
+            let position = Position::of_prefix(s, s.len());
+            current_states.extend(self.epsilon_closure(start, position));
+        }
+        current_states.into_iter().any(|state| {
+            let state = self.state(state);
+            !state.transitions.is_empty()
+                || !state.classes.is_empty()
+                || state
+                    .anchors
+                    .iter()
+                    .any(|&(anchor, _)| matches!(anchor, Anchor::End | Anchor::LineEnd))
+        })
+    }
+
+    /// Literal string that every word accepted from `start` begins with.
+    pub fn literal_prefix(&self, start: usize) -> String {
+        let mut prefix = String::new();
+        let mut current_states = self.epsilon_closure(start, Position::ANY);
+
+        // Every step consumes a character, so the prefix can't be longer than the number of states.
+        while prefix.len() < self.states.len() {
+            if current_states
+                .iter()
+                .any(|&state| self.state(state).accepting)
             {
-                output.push(operator_stack.pop().unwrap());
+                break;
             }
-            operator_stack.push(token);
-        } else {
-            output.push(token);
+            let mut symbol = None;
+            let mut next_states = Vec::new();
+            for &state in &current_states {
+                if !self.state(state).classes.is_empty() {
+                    return prefix;
+                }
+                for (&transition, &next_state) in &self.state(state).transitions {
+                    let Symbol::Char(c) = transition else {
+                        return prefix;
+                    };
+                    if symbol.is_some_and(|symbol| symbol != c) {
+                        return prefix;
+                    }
+                    symbol = Some(c);
+                    next_states.push(next_state);
+                }
+            }
+            match symbol {
+                Some(c) => prefix.push(c),
+                None => break,
+            }
+            current_states = self.multi_epsilon_closure(next_states, Position::ANY);
         }
-    }
 
-    while !operator_stack.is_empty() {
-        output.push(operator_stack.pop().unwrap());
+        prefix
     }
 
-    output
+    /// Literal string that every word accepted from `start` ends with.
+    pub fn literal_suffix(&self, start: usize) -> String {
+        // Reverse transitions, restricted to the states reachable from `start`:
+        let mut reachable = BTreeSet::new();
+        let mut stack = vec![start];
+        // Predecessors on a wildcard or a class are stored with `None`.
+        let mut symbol_predecessors: HashMap<usize, Vec<(Option<char>, usize)>> = HashMap::new();
+        let mut epsilon_predecessors: HashMap<usize, Vec<usize>> = HashMap::new();
+        while let Some(state) = stack.pop() {
+            if reachable.insert(state) {
+                let transitions = self.state(state).transitions.iter();
+                let classes = self.state(state).classes.iter();
+                for (c, next_state) in transitions
+                    .map(|(&symbol, &to)| match symbol {
+                        Symbol::Char(c) => (Some(c), to),
+                        Symbol::Any => (None, to),
+                    })
+                    .chain(classes.map(|&(_, to)| (None, to)))
+                {
+                    symbol_predecessors
+                        .entry(next_state)
+                        .or_default()
+                        .push((c, state));
+                    stack.push(next_state);
+                }
+                let anchors = self.state(state).anchors.iter().map(|(_, to)| to);
+                for &next_state in self.state(state).epsilon_transitions.iter().chain(anchors) {
+                    epsilon_predecessors
+                        .entry(next_state)
+                        .or_default()
+                        .push(state);
+                    stack.push(next_state);
+                }
+            }
+        }
+        let reverse_closure = |start: Vec<usize>| {
+            let mut visited = BTreeSet::new();
+            let mut stack = start;
+            while let Some(state) = stack.pop() {
+                if visited.insert(state) {
+                    if let Some(predecessors) = epsilon_predecessors.get(&state) {
+                        stack.extend(predecessors);
+                    }
+                }
+            }
+            visited
+        };
+
+        let mut suffix = Vec::new();
+        let mut current_states = reverse_closure(
+            reachable
+                .iter()
+                .copied()
+                .filter(|&state| self.state(state).accepting)
+                .collect(),
+        );
+
+        while suffix.len() < self.states.len() {
+            if current_states.contains(&start) {
+                break;
+            }
+            let mut symbol = None;
+            let mut previous_states = Vec::new();
+            for state in &current_states {
+                for &(c, previous_state) in symbol_predecessors.get(state).into_iter().flatten() {
+                    if c.is_none() || symbol.is_some_and(|symbol| Some(symbol) != c) {
+                        return suffix.into_iter().rev().collect();
+                    }
+                    symbol = c;
+                    previous_states.push(previous_state);
+                }
+            }
+            match symbol {
+                Some(c) => suffix.push(c),
+                None => break,
+            }
+            current_states = reverse_closure(previous_states);
+        }
+
+        suffix.into_iter().rev().collect()
+    }
 }
 
 #[derive(Debug)]
-struct Regex {
+pub struct Regex {
+    ast: Ast,
     states: Nfa,
     start: usize,
+    end: usize,
+    prefix: memmem::Finder<'static>,
+    suffix: memmem::Finder<'static>,
 }
 
 impl Regex {
-    pub fn new(pattern: &str) -> Self {
-        let mut states = Nfa::new();
-        let f = states.parse(pattern);
+    /// Compiles `pattern`, failing with the byte offset of the problem if it is malformed.
+    ///
+    /// See [`RegexBuilder`] for compiling with flags.
+    pub fn new(pattern: &str) -> Result<Self, ParseError> {
+        RegexBuilder::new(pattern).build()
+    }
+
+    pub(crate) fn from_fragment(ast: Ast, states: Nfa, f: Fragment) -> Self {
+        let prefix = states.literal_prefix(f.start);
+        let suffix = states.literal_suffix(f.start);
         Self {
+            ast,
             states,
             start: f.start,
+            end: f.end,
+            prefix: memmem::Finder::new(prefix.as_bytes()).into_owned(),
+            suffix: memmem::Finder::new(suffix.as_bytes()).into_owned(),
+        }
+    }
+
+    fn fragment(&self) -> Fragment {
+        Fragment {
+            start: self.start,
+            end: self.end,
+        }
+    }
+
+    /// Splices the NFA of `other` into the NFA of `self`, returning both fragments and syntax trees.
+    ///
+    /// Capture groups of `other` are renumbered to follow those of `self`.
+    fn splice(self, other: Regex) -> (Nfa, Fragment, Fragment, Ast, Ast) {
+        let f1 = self.fragment();
+        let f2 = other.fragment();
+        let shift = self.ast.num_captures();
+        let mut other_ast = other.ast;
+        other_ast.shift_captures(shift);
+        let mut states = self.states;
+        let offset = states.append(other.states);
+        for state in &mut states.states[offset..] {
+            if let Some(tag) = &mut state.tag {
+                *tag += 2 * shift;
+            }
         }
+        let f2 = Fragment {
+            start: f2.start + offset,
+            end: f2.end + offset,
+        };
+        (states, f1, f2, self.ast, other_ast)
+    }
+
+    /// Pattern matching what either `self` or `other` matches, i.e. `self|other`.
+    pub fn union(self, other: Regex) -> Regex {
+        let (mut states, f1, f2, a1, a2) = self.splice(other);
+        let f = states.union(f1, f2);
+        Self::from_fragment(Ast::alternation(a1, a2), states, f)
+    }
+
+    /// Pattern matching what `self` matches followed by what `other` matches, i.e. `(self)(other)`.
+    pub fn concat(self, other: Regex) -> Regex {
+        let (mut states, f1, f2, a1, a2) = self.splice(other);
+        let f = states.concat(f1, f2);
+        Self::from_fragment(Ast::concat(a1, a2), states, f)
+    }
+
+    /// Pattern matching zero or more repetitions of `self`, i.e. `(self)*`.
+    pub fn star(self) -> Regex {
+        let f = self.fragment();
+        let mut states = self.states;
+        let f = states.closure(f);
+        Self::from_fragment(Ast::Star(Box::new(self.ast)), states, f)
+    }
+
+    /// Syntax tree of the pattern.
+    pub fn ast(&self) -> &Ast {
+        &self.ast
+    }
+
+    /// Literal string that every match begins with.
+    pub fn literal_prefix(&self) -> &str {
+        std::str::from_utf8(self.prefix.needle()).unwrap()
+    }
+
+    /// Literal string that every match ends with.
+    pub fn literal_suffix(&self) -> &str {
+        std::str::from_utf8(self.suffix.needle()).unwrap()
     }
 
     pub fn matches(&self, s: &str) -> bool {
         self.states.matches(self.start, s)
     }
+
+    /// Returns `true` if a match starts exactly at byte `offset` of `haystack`.
+    ///
+    /// The match may end anywhere, so this is suitable for resuming a scan (e.g., in a lexer)
+    /// without slicing the input.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `offset` is not on a char boundary of `haystack`.
+    pub fn is_match_at(&self, haystack: &str, offset: usize) -> bool {
+        self.states.matches_prefix_at(self.start, haystack, offset)
+    }
+
+    /// Finds the leftmost-longest match in `haystack`.
+    ///
+    /// Unlike [`matches`](Regex::matches), the match may be any substring,
+    /// unless the pattern is anchored with `^` and `$`.
+    pub fn find(&self, haystack: &str) -> Option<Span> {
+        self.find_at(haystack, 0)
+    }
+
+    /// Finds the leftmost-longest match starting at byte `from` of `haystack` or later.
+    ///
+    /// Unlike searching in `&haystack[from..]`, anchors and spans are relative to the whole
+    /// haystack, e.g. `^` does not match at `from > 0`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `from` is not on a char boundary of `haystack`.
+    pub fn find_at(&self, haystack: &str, from: usize) -> Option<Span> {
+        let bytes = haystack.as_bytes();
+        self.suffix.find(&bytes[from..])?;
+        let mut at = from;
+        loop {
+            let start = at + self.prefix.find(&bytes[at..])?;
+            if let Some(len) = self
+                .states
+                .longest_prefix_match_at(self.start, haystack, start)
+            {
+                return Some(Span::new(haystack, start, start + len));
+            }
+            at = start + haystack[start..].chars().next()?.len_utf8();
+        }
+    }
+
+    /// Returns `true` if some substring of `haystack` matches the pattern.
+    ///
+    /// Candidate match starts are found with a substring search for the literal prefix
+    /// of the pattern, so the automaton only runs where a match can actually begin.
+    pub fn search(&self, haystack: &str) -> bool {
+        if self.suffix.find(haystack.as_bytes()).is_none() {
+            return false;
+        }
+        self.search_starts(haystack, 0, haystack.len() + 1)
+    }
+
+    /// Like [`search`](Regex::search), but scans the haystack on multiple threads.
+    ///
+    /// The haystack is split into chunks of candidate match starts, one task per chunk.
+    /// Matches are still run against the whole haystack, so a match crossing a chunk
+    /// boundary is found by the chunk containing its start, and no overlap is needed.
+    #[cfg(feature = "rayon")]
+    pub fn par_search(&self, haystack: &str) -> bool {
+        use rayon::prelude::*;
+
+        const CHUNK_SIZE: usize = 1 << 20;
+
+        if self.suffix.find(haystack.as_bytes()).is_none() {
+            return false;
+        }
+        let mut bounds = vec![0];
+        let mut at = CHUNK_SIZE;
+        while at < haystack.len() {
+            while !haystack.is_char_boundary(at) {
+                at += 1;
+            }
+            bounds.push(at);
+            at += CHUNK_SIZE;
+        }
+        // The end of the haystack is a candidate start, too (for an empty match).
+        bounds.push(haystack.len() + 1);
+        bounds
+            .par_windows(2)
+            .any(|w| self.search_starts(haystack, w[0], w[1]))
+    }
+
+    /// Checks for a match starting at some char boundary in `from..to`.
+    ///
+    /// Note that `haystack.len()` itself is a valid start, so `to` may be `haystack.len() + 1`.
+    fn search_starts(&self, haystack: &str, from: usize, to: usize) -> bool {
+        let bytes = haystack.as_bytes();
+        let mut at = from;
+        while at < to {
+            let start = match self.prefix.find(&bytes[at..]) {
+                Some(offset) if at + offset < to => at + offset,
+                _ => return false,
+            };
+            if self.states.matches_prefix_at(self.start, haystack, start) {
+                return true;
+            }
+            match haystack[start..].chars().next() {
+                Some(c) => at = start + c.len_utf8(),
+                None => return false,
+            }
+        }
+        false
+    }
 }
 
-pub fn is_match(pattern: &str, input: &str) -> bool {
-    let re = Regex::new(pattern);
-    re.matches(input)
+pub fn is_match(pattern: &str, input: &str) -> Result<bool, ParseError> {
+    let re = Regex::new(pattern)?;
+    Ok(re.matches(input))
 }
 
 #[cfg(test)]
@@ -271,7 +916,7 @@ mod tests {
 
     #[test]
     fn test_pattern_a() {
-        let re = Regex::new("a");
+        let re = Regex::new("a").unwrap();
         assert!(re.matches("a"));
         assert!(!re.matches("ab"));
         assert!(!re.matches("aa"));
@@ -280,7 +925,7 @@ mod tests {
 
     #[test]
     fn test_pattern_a_or_b() {
-        let re = Regex::new("a|b");
+        let re = Regex::new("a|b").unwrap();
         assert!(re.matches("a"));
         assert!(re.matches("b"));
         assert!(!re.matches("ab"));
@@ -290,7 +935,7 @@ mod tests {
 
     #[test]
     fn test_pattern_debug() {
-        let re = Regex::new("(a|b)*");
+        let re = Regex::new("(a|b)*").unwrap();
         assert!(re.matches("a"));
         assert!(re.matches("b"));
         assert!(re.matches("aa"));
@@ -303,7 +948,7 @@ mod tests {
 
     #[test]
     fn test_pattern_complex() {
-        let re = Regex::new("a(b|c)*d");
+        let re = Regex::new("a(b|c)*d").unwrap();
         assert!(re.matches("ad"));
         assert!(re.matches("abd"));
         assert!(re.matches("acd"));
@@ -322,6 +967,250 @@ mod tests {
         assert!(!re.matches("aad"));
     }
 
+    #[test]
+    fn test_quantifiers() {
+        let re = Regex::new("ab+c?").unwrap();
+        assert!(re.matches("ab"));
+        assert!(re.matches("abbbc"));
+        assert!(!re.matches("ac"));
+        assert!(!re.matches("abcc"));
+        assert_eq!(is_match("(ab)?", ""), Ok(true));
+    }
+
+    #[test]
+    fn test_counted_repetition() {
+        let re = Regex::new("[0-9]{4}-[0-9]{2}").unwrap();
+        assert!(re.matches("2024-06"));
+        assert!(!re.matches("202-06"));
+        assert!(!re.matches("2024-6"));
+
+        let re = Regex::new("(ab){2,3}").unwrap();
+        for (s, expected) in [
+            ("ab", false),
+            ("abab", true),
+            ("ababab", true),
+            ("abababab", false),
+        ] {
+            assert_eq!(re.matches(s), expected, "{:?}", s);
+        }
+
+        let re = Regex::new("a{2,}").unwrap();
+        assert!(!re.matches("a"));
+        assert!(re.matches("aaaaa"));
+        assert!(Regex::new("a{0}").unwrap().matches(""));
+    }
+
+    #[test]
+    fn test_anchors() {
+        let re = Regex::new("^ab").unwrap();
+        assert_eq!(re.find("abab").unwrap().range(), 0..2);
+        assert_eq!(re.find("xab"), None);
+        assert!(!re.is_match_at("abab", 2));
+        assert!(re.matches("ab"));
+
+        let re = Regex::new("ab$").unwrap();
+        assert_eq!(re.find("abab").unwrap().range(), 2..4);
+        assert!(!re.search("aba"));
+
+        let re = Regex::new("^(a|b)*$").unwrap();
+        assert!(re.search("abba"));
+        assert!(!re.search("abxba"));
+        assert_eq!(re.find("").unwrap().range(), 0..0);
+
+        // Anchors inside alternations and repetitions:
+        let re = Regex::new("(^|,)x").unwrap();
+        assert_eq!(re.find("x").unwrap().range(), 0..1);
+        assert_eq!(re.find("yx,x").unwrap().range(), 2..4);
+        assert!(!Regex::new("a^b").unwrap().search("ab"));
+        assert_eq!(Regex::new("^a").unwrap().literal_prefix(), "a");
+        assert_eq!(re.count_parses::<u64>("x"), 1);
+    }
+
+    #[test]
+    fn test_escapes() {
+        let re = Regex::new("f\\(x\\) = \\*p\\.").unwrap();
+        assert!(re.matches("f(x) = *p."));
+        assert!(!re.matches("f(x) = *p!"));
+        assert_eq!(re.literal_prefix(), "f(x) = *p.");
+
+        // A literal dot is not a wildcard, and a wildcard matches a dot:
+        let re = Regex::new("a\\.b|c.d").unwrap();
+        assert!(re.matches("a.b"));
+        assert!(!re.matches("axb"));
+        assert!(re.matches("c.d"));
+        assert!(re.matches("cxd"));
+        let mut nfa = Nfa::new();
+        let f = nfa.symbol('.');
+        assert!(nfa.matches(f.start, "."));
+        assert!(!nfa.matches(f.start, "x"));
+
+        let re = Regex::new("[^\\t\\n]+\\\\").unwrap();
+        assert!(re.matches("C:\\"));
+        assert!(!re.matches("a\tb\\"));
+        assert!(Regex::new("\\[\\]").unwrap().search("x[]"));
+    }
+
+    #[test]
+    fn test_shorthands() {
+        let re = Regex::new("\\w+@\\w+\\.\\w+").unwrap();
+        let haystack = "mail: jo_1@example.org!";
+        assert_eq!(
+            re.find(haystack).unwrap().as_str(haystack),
+            "jo_1@example.org"
+        );
+        let re = Regex::new("\\d+\\s*\\D").unwrap();
+        assert!(re.matches("12 x"));
+        assert!(re.matches("٣٤\u{A0}€"));
+        assert!(!re.matches("12 3"));
+        let re = Regex::new("[\\s,]+").unwrap();
+        assert!(re.matches(" ,\t"));
+        assert!(Regex::new("\\S\\W").unwrap().matches("a-"));
+
+        assert_eq!(
+            Regex::new("[a-\\d]").unwrap_err().kind,
+            ParseErrorKind::InvalidRange
+        );
+        assert_eq!(
+            Regex::new("\\q").unwrap_err().kind,
+            ParseErrorKind::UnknownEscape
+        );
+    }
+
+    #[test]
+    fn test_char_classes() {
+        let re = Regex::new("[a-z0-9_]+").unwrap();
+        assert!(re.matches("snake_case42"));
+        assert!(!re.matches("Camel"));
+        assert!(!re.matches(""));
+
+        let re = Regex::new("\"[^\"]*\"").unwrap();
+        assert!(re.matches("\"\""));
+        assert!(re.matches("\"a b\""));
+        assert!(!re.matches("\"a\"b\""));
+        assert_eq!(re.literal_prefix(), "\"");
+        assert_eq!(re.literal_suffix(), "\"");
+
+        // Overlapping class and literal transitions are both followed:
+        let re = Regex::new("[ab]c|ad").unwrap();
+        assert!(re.matches("ac"));
+        assert!(re.matches("ad"));
+        assert_eq!(re.literal_prefix(), "");
+        assert!(re.search("xxbc"));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = Regex::new("a|").unwrap_err();
+        assert_eq!(error.kind, ParseErrorKind::MissingOperand);
+        assert_eq!(error.offset, 2);
+        assert!(is_match("((", "").is_err());
+    }
+
+    #[test]
+    fn test_combinators() {
+        // (a|b)*c, built programmatically:
+        let re = Regex::new("a")
+            .unwrap()
+            .union(Regex::new("b").unwrap())
+            .star()
+            .concat(Regex::new("c").unwrap());
+        let expected = Regex::new("(a|b)*c").unwrap();
+        for s in ["c", "ac", "abbac", "", "ab", "ca", "acc"] {
+            assert_eq!(re.matches(s), expected.matches(s), "{:?}", s);
+        }
+        assert_eq!(re.literal_suffix(), "c");
+
+        // Combining with itself-shaped patterns keeps states separate:
+        let re = Regex::new("ab").unwrap().concat(Regex::new("ab").unwrap());
+        assert!(re.matches("abab"));
+        assert!(!re.matches("ab"));
+        assert_eq!(re.literal_prefix(), "abab");
+    }
+
+    #[test]
+    fn test_literal_prefix_suffix() {
+        let re = Regex::new("ab(c|d)*ef").unwrap();
+        assert_eq!(re.literal_prefix(), "ab");
+        assert_eq!(re.literal_suffix(), "ef");
+
+        let re = Regex::new("x(ab|ac)y").unwrap();
+        assert_eq!(re.literal_prefix(), "xa");
+        assert_eq!(re.literal_suffix(), "y");
+
+        let re = Regex::new("a*b").unwrap();
+        assert_eq!(re.literal_prefix(), "");
+        assert_eq!(re.literal_suffix(), "b");
+
+        let re = Regex::new("a.c").unwrap();
+        assert_eq!(re.literal_prefix(), "a");
+        assert_eq!(re.literal_suffix(), "c");
+    }
+
+    #[test]
+    fn test_search() {
+        let re = Regex::new("ab(c|d)*e").unwrap();
+        assert!(re.search("abe"));
+        assert!(re.search("xxabcdcex"));
+        assert!(re.search("ab abde"));
+        assert!(!re.search("abcd"));
+        assert!(!re.search("xxaxbcex"));
+        assert!(!re.search(""));
+
+        let re = Regex::new("a*").unwrap();
+        assert!(re.search(""));
+        assert!(re.search("bbb"));
+
+        let re = Regex::new("é(b|c)").unwrap();
+        assert!(re.search("ééc"));
+        assert!(!re.search("ééé"));
+    }
+
+    #[test]
+    fn test_is_match_at() {
+        let re = Regex::new("ab*").unwrap();
+        assert!(re.is_match_at("xxabbbx", 2));
+        assert!(!re.is_match_at("xxabbbx", 1));
+        assert!(!re.is_match_at("xxabbbx", 7));
+
+        let re = Regex::new("b*").unwrap();
+        assert!(re.is_match_at("aaa", 3));
+    }
+
+    #[test]
+    fn test_find() {
+        let re = Regex::new("ab*").unwrap();
+        let span = re.find("xxabbbxab").unwrap();
+        assert_eq!(span.range(), 2..6);
+        assert_eq!(span.as_str("xxabbbxab"), "abbb");
+        assert_eq!(re.find("xyz"), None);
+
+        // Byte offsets vs char indices:
+        let re = Regex::new("é(b|c)*").unwrap();
+        let haystack = "ñ€ébbx";
+        let span = re.find(haystack).unwrap();
+        assert_eq!(span.range(), 5..9);
+        assert_eq!(span.char_range(), 2..5);
+        assert_eq!(span.as_str(haystack), "ébb");
+
+        let re = Regex::new("a*").unwrap();
+        assert_eq!(re.find("bbb").unwrap().range(), 0..0);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_search() {
+        let re = Regex::new("ab(c|d)*e").unwrap();
+        let mut haystack = "x".repeat(3 << 20);
+        assert!(!re.par_search(&haystack));
+        // Match crossing the chunk boundary:
+        haystack.replace_range((1 << 20) - 2..(1 << 20) + 2, "abce");
+        assert!(re.par_search(&haystack));
+        assert_eq!(re.par_search(""), re.search(""));
+
+        let re = Regex::new("a*").unwrap();
+        assert!(re.par_search(""));
+    }
+
     #[test]
     fn test_fragment_concat_ab() {
         let mut nfa = Nfa::new();
@@ -0,0 +1,133 @@
+use std::collections::BTreeSet;
+
+use crate::{Position, Regex};
+
+/// Incremental [`Regex::matches`] over an input arriving in chunks, see [`Regex::matcher`].
+///
+/// Only the set of NFA states is kept between chunks, so memory does not grow with the input.
+#[derive(Debug, Clone)]
+pub struct Matcher<'r> {
+    regex: &'r Regex,
+    /// States reached by the input so far, closed under the ε-transitions and the anchors
+    /// not depending on the next char.
+    states: BTreeSet<usize>,
+    at_start: bool,
+    at_line_start: bool,
+}
+
+impl Matcher<'_> {
+    fn position(&self, at_end: bool, at_line_end: bool) -> Position {
+        Position {
+            at_start: self.at_start,
+            at_end,
+            at_line_start: self.at_line_start,
+            at_line_end,
+        }
+    }
+
+    /// Reads the next chunk of the input.
+    pub fn feed(&mut self, chunk: &str) {
+        let nfa = &self.regex.states;
+        for c in chunk.chars() {
+            if self.states.is_empty() {
+                return;
+            }
+            // Anchors looking ahead at `c` hold only now:
+            let states = std::mem::take(&mut self.states).into_iter().collect();
+            let current = nfa.multi_epsilon_closure(states, self.position(false, c == '\n'));
+            self.at_start = false;
+            self.at_line_start = c == '\n';
+            self.states = nfa.advance(current, c, self.position(false, false));
+        }
+    }
+
+    /// Returns `true` once the simulation has no states left, so no continuation of the input
+    /// can match and the rest of it need not be fed.
+    pub fn is_dead(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// Returns `true` if the whole input matches the pattern.
+    pub fn finish(self) -> bool {
+        let states = self.states.iter().copied().collect();
+        let states = self
+            .regex
+            .states
+            .multi_epsilon_closure(states, self.position(true, true));
+        self.regex.states.any_accepting(&states)
+    }
+}
+
+impl Regex {
+    /// Starts matching an input that is [fed](Matcher::feed) in chunks, e.g. as it arrives
+    /// from a socket, without buffering it.
+    ///
+    /// ```
+    /// # use regex_thompson::Regex;
+    /// let re = Regex::new("GET /[a-z]*\n").unwrap();
+    /// let mut matcher = re.matcher();
+    /// for chunk in ["GE", "T /ind", "ex\n"] {
+    ///     matcher.feed(chunk);
+    /// }
+    /// assert!(matcher.finish());
+    /// ```
+    pub fn matcher(&self) -> Matcher<'_> {
+        let position = Position {
+            at_start: true,
+            at_end: false,
+            at_line_start: true,
+            at_line_end: false,
+        };
+        Matcher {
+            regex: self,
+            states: self.states.epsilon_closure(self.start, position),
+            at_start: true,
+            at_line_start: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RegexBuilder;
+
+    #[test]
+    fn test_matcher() {
+        let re = Regex::new("(ab|c)*d").unwrap();
+        let mut matcher = re.matcher();
+        matcher.feed("a");
+        matcher.feed("");
+        matcher.feed("bc");
+        assert!(!matcher.is_dead());
+        let mut done = matcher.clone();
+        done.feed("d");
+        assert!(done.finish());
+        assert!(!matcher.clone().finish());
+        matcher.feed("x");
+        assert!(matcher.is_dead());
+        assert!(!matcher.finish());
+
+        // Agrees with matching the whole input, whatever the split:
+        for (pattern, multi_line) in [("^a|b$", false), ("a$\n^é", true), ("a.*é", false)] {
+            let re = RegexBuilder::new(pattern)
+                .multi_line(multi_line)
+                .build()
+                .unwrap();
+            for input in ["a", "b", "a\né", "aé", "ba\né", "a\n"] {
+                for (i, _) in input.char_indices() {
+                    let mut matcher = re.matcher();
+                    matcher.feed(&input[..i]);
+                    matcher.feed(&input[i..]);
+                    assert_eq!(
+                        matcher.finish(),
+                        re.matches(input),
+                        "{:?} {:?}",
+                        pattern,
+                        input
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{Regex, Span};
+
+/// Iterator over the successive non-overlapping matches found by [`Regex::find_iter`].
+#[derive(Debug)]
+pub struct Matches<'r, 'h> {
+    regex: &'r Regex,
+    haystack: &'h str,
+    /// Byte offset to search from, past the end of the haystack once it is exhausted.
+    at: usize,
+    /// End of the last match, where an empty match would overlap it.
+    last_end: Option<usize>,
+}
+
+impl Iterator for Matches<'_, '_> {
+    type Item = Span;
+
+    fn next(&mut self) -> Option<Span> {
+        loop {
+            if self.at > self.haystack.len() {
+                return None;
+            }
+            let span = self.regex.find_at(self.haystack, self.at)?;
+            self.at = if span.is_empty() {
+                // Step over a char, so that the next search makes progress:
+                let next = self.haystack[span.end..].chars().next();
+                span.end + next.map_or(1, char::len_utf8)
+            } else {
+                span.end
+            };
+            if span.is_empty() && self.last_end == Some(span.end) {
+                continue;
+            }
+            self.last_end = Some(span.end);
+            return Some(span);
+        }
+    }
+}
+
+impl Regex {
+    /// Iterates over the successive non-overlapping leftmost-longest matches in `haystack`.
+    ///
+    /// Each search starts where the previous match ended. An empty match right
+    /// at the end of the previous match is skipped, e.g. `a*` on `"baab"` yields
+    /// `0..0`, `1..3` and `4..4`.
+    pub fn find_iter<'r, 'h>(&'r self, haystack: &'h str) -> Matches<'r, 'h> {
+        Matches {
+            regex: self,
+            haystack,
+            at: 0,
+            last_end: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(pattern: &str, haystack: &str) -> Vec<(usize, usize)> {
+        let re = Regex::new(pattern).unwrap();
+        re.find_iter(haystack)
+            .map(|span| (span.start, span.end))
+            .collect()
+    }
+
+    #[test]
+    fn test_find_iter() {
+        let line = "GET /a 200 12ms; GET /b 404 3ms";
+        let re = Regex::new("[0-9]+ms").unwrap();
+        let durations = re
+            .find_iter(line)
+            .map(|span| span.as_str(line))
+            .collect::<Vec<_>>();
+        assert_eq!(durations, ["12ms", "3ms"]);
+
+        assert_eq!(ranges("aa", "aaaaa"), [(0, 2), (2, 4)]);
+        assert_eq!(ranges("a*", "baab"), [(0, 0), (1, 3), (4, 4)]);
+        assert_eq!(ranges("", "é!"), [(0, 0), (2, 2), (3, 3)]);
+        assert_eq!(ranges("x", "abc"), []);
+        assert_eq!(ranges("^a", "aaa"), [(0, 1)]);
+        assert_eq!(ranges("a$", "aaa"), [(2, 3)]);
+
+        let spans = Regex::new("é+")
+            .unwrap()
+            .find_iter("éaéé")
+            .collect::<Vec<_>>();
+        assert_eq!(spans[1].char_range(), 2..4);
+    }
+}
@@ -0,0 +1,172 @@
+use std::io::{self, BufRead};
+
+use crate::{Regex, Span};
+
+/// How a [`Records`] scanner splits its input.
+#[derive(Debug)]
+pub enum Delimiter {
+    /// Records end at every occurrence of the given byte, e.g. `b'\n'` for lines.
+    Byte(u8),
+    /// Records end at every non-empty leftmost-longest match of the given pattern.
+    Pattern(Box<Regex>),
+}
+
+/// Lazy iterator over the records of a reader which contain a match, see [`Regex::records`].
+///
+/// Records are yielded without their delimiters.
+#[derive(Debug)]
+pub struct Records<'r, R> {
+    regex: &'r Regex,
+    reader: R,
+    delimiter: Delimiter,
+    pending: String,
+    eof: bool,
+}
+
+impl<'r, R: BufRead> Records<'r, R> {
+    fn new(regex: &'r Regex, reader: R, delimiter: Delimiter) -> Self {
+        Self {
+            regex,
+            reader,
+            delimiter,
+            pending: String::new(),
+            eof: false,
+        }
+    }
+
+    /// Reads the next record, regardless of whether it matches.
+    fn next_record(&mut self) -> io::Result<Option<String>> {
+        match &self.delimiter {
+            Delimiter::Byte(byte) => {
+                let mut record = Vec::new();
+                if self.reader.read_until(*byte, &mut record)? == 0 {
+                    return Ok(None);
+                }
+                if record.last() == Some(byte) {
+                    record.pop();
+                }
+                String::from_utf8(record)
+                    .map(Some)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+            }
+            Delimiter::Pattern(pattern) => loop {
+                match find_non_empty(pattern, &self.pending) {
+                    // A match can only be trusted when neither it nor an earlier match
+                    // may change after reading more input.
+                    Some(span)
+                        if self.eof
+                            || !pattern.states.can_continue(
+                                pattern.start,
+                                &self.pending,
+                                span.start,
+                            ) =>
+                    {
+                        let rest = self.pending.split_off(span.end);
+                        self.pending.truncate(span.start);
+                        return Ok(Some(std::mem::replace(&mut self.pending, rest)));
+                    }
+                    None if self.eof => {
+                        if self.pending.is_empty() {
+                            return Ok(None);
+                        }
+                        return Ok(Some(std::mem::take(&mut self.pending)));
+                    }
+                    _ => {
+                        if self.reader.read_line(&mut self.pending)? == 0 {
+                            self.eof = true;
+                        }
+                    }
+                }
+            },
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Records<'_, R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.next_record() {
+                Ok(Some(record)) if self.regex.search(&record) => return Some(Ok(record)),
+                Ok(Some(_)) => continue,
+                Ok(None) => return None,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
+
+/// Finds the leftmost-longest match of `pattern` in `haystack`, skipping empty matches.
+fn find_non_empty(pattern: &Regex, haystack: &str) -> Option<Span> {
+    let mut at = 0;
+    loop {
+        let span = pattern.find(&haystack[at..])?;
+        let start = at + span.start;
+        if !span.is_empty() {
+            return Some(Span::new(haystack, start, at + span.end));
+        }
+        at = start + haystack[start..].chars().next()?.len_utf8();
+    }
+}
+
+impl Regex {
+    /// Splits `reader` into records and lazily yields those containing a match.
+    ///
+    /// This is the `grep` use case: with `Delimiter::Byte(b'\n')`, matching lines are yielded.
+    /// Input must be valid UTF-8, otherwise an error of kind [`io::ErrorKind::InvalidData`] is yielded.
+    pub fn records<R: BufRead>(&self, reader: R, delimiter: Delimiter) -> Records<'_, R> {
+        Records::new(self, reader, delimiter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect<R: BufRead>(records: Records<'_, R>) -> Vec<String> {
+        records.collect::<io::Result<Vec<_>>>().unwrap()
+    }
+
+    #[test]
+    fn test_records_lines() {
+        let re = Regex::new("ab*c").unwrap();
+        let input = "xac\nnope\nabbbc\n\nac";
+        let lines = collect(re.records(input.as_bytes(), Delimiter::Byte(b'\n')));
+        assert_eq!(lines, vec!["xac", "abbbc", "ac"]);
+
+        let re = Regex::new("a*").unwrap();
+        let lines = collect(re.records("x\n\ny\n".as_bytes(), Delimiter::Byte(b'\n')));
+        assert_eq!(lines, vec!["x", "", "y"]);
+    }
+
+    #[test]
+    fn test_records_pattern() {
+        // Records separated by blank lines, i.e. by two or more newlines:
+        let re = Regex::new("b").unwrap();
+        let delimiter = Delimiter::Pattern(Box::new(Regex::new("\n\n\n*").unwrap()));
+        let input = "a\nb\n\n\nc\n\nb\nd\n";
+        let records = collect(re.records(input.as_bytes(), delimiter));
+        assert_eq!(records, vec!["a\nb", "b\nd\n"]);
+    }
+
+    #[test]
+    fn test_records_pattern_across_reads() {
+        // The first delimiter spans a line break, so it must not be cut short at the first "c".
+        let re = Regex::new("x").unwrap();
+        let delimiter = Delimiter::Pattern(Box::new(Regex::new("a(c|\n)*d|c").unwrap()));
+        let input = "xac\ncdxcx";
+        let records = collect(re.records(input.as_bytes(), delimiter));
+        assert_eq!(records, vec!["x", "x", "x"]);
+    }
+
+    #[test]
+    fn test_records_invalid_utf8() {
+        let re = Regex::new("a").unwrap();
+        let input: &[u8] = b"a\n\xff\n";
+        let mut records = re.records(input, Delimiter::Byte(b'\n'));
+        assert_eq!(records.next().unwrap().unwrap(), "a");
+        let error = records.next().unwrap().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{Regex, Span};
+
+/// Replacement for a match, see [`Regex::replace`] and [`Regex::replace_all`].
+///
+/// Implemented for literal replacements (`&str` and `String`, inserted as is) and for
+/// closures computing the replacement from the matched text.
+pub trait Replacer {
+    /// Appends the replacement for the match `span` of `haystack` to `dst`.
+    fn replace_append(&mut self, haystack: &str, span: Span, dst: &mut String);
+}
+
+impl Replacer for &str {
+    fn replace_append(&mut self, _haystack: &str, _span: Span, dst: &mut String) {
+        dst.push_str(self);
+    }
+}
+
+impl Replacer for String {
+    fn replace_append(&mut self, _haystack: &str, _span: Span, dst: &mut String) {
+        dst.push_str(self);
+    }
+}
+
+impl<F, T> Replacer for F
+where
+    F: FnMut(&str) -> T,
+    T: AsRef<str>,
+{
+    fn replace_append(&mut self, haystack: &str, span: Span, dst: &mut String) {
+        dst.push_str(self(span.as_str(haystack)).as_ref());
+    }
+}
+
+/// Copies `haystack` to a new string with the `spans` replaced.
+fn replace_spans(
+    haystack: &str,
+    spans: impl IntoIterator<Item = Span>,
+    mut replacer: impl Replacer,
+) -> String {
+    let mut result = String::with_capacity(haystack.len());
+    let mut copied = 0;
+    for span in spans {
+        result.push_str(&haystack[copied..span.start]);
+        replacer.replace_append(haystack, span, &mut result);
+        copied = span.end;
+    }
+    result.push_str(&haystack[copied..]);
+    result
+}
+
+impl Regex {
+    /// Replaces the leftmost-longest match in `haystack`, see [`find`](Regex::find).
+    pub fn replace(&self, haystack: &str, replacer: impl Replacer) -> String {
+        replace_spans(haystack, self.find(haystack), replacer)
+    }
+
+    /// Replaces all the non-overlapping matches in `haystack`, see [`find_iter`](Regex::find_iter).
+    ///
+    /// ```
+    /// # use regex_thompson::Regex;
+    /// let re = Regex::new("[0-9]+").unwrap();
+    /// assert_eq!(re.replace_all("3 apples, 12 pears", "N"), "N apples, N pears");
+    /// let doubled = re.replace_all("3 apples", |n: &str| (2 * n.parse::<u32>().unwrap()).to_string());
+    /// assert_eq!(doubled, "6 apples");
+    /// ```
+    pub fn replace_all(&self, haystack: &str, replacer: impl Replacer) -> String {
+        replace_spans(haystack, self.find_iter(haystack), replacer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replace() {
+        let re = Regex::new("a+").unwrap();
+        assert_eq!(re.replace("baaca", "-"), "b-ca");
+        assert_eq!(re.replace_all("baaca", "-"), "b-c-");
+        assert_eq!(re.replace_all("bbb", "-"), "bbb");
+        assert_eq!(re.replace_all("baaca", String::from("$1")), "b$1c$1");
+        assert_eq!(
+            re.replace_all("baaca", |s: &str| s.len().to_string()),
+            "b2c1"
+        );
+
+        // Empty matches are replaced too, but not right after another match:
+        let re = Regex::new("a*").unwrap();
+        assert_eq!(re.replace_all("baab", "-"), "-b-b-");
+        assert_eq!(Regex::new("").unwrap().replace_all("é!", "/"), "/é/!/");
+
+        let mut count = 0;
+        let numbered = Regex::new("x").unwrap().replace_all("xyx", |_: &str| {
+            count += 1;
+            format!("<{}>", count)
+        });
+        assert_eq!(numbered, "<1>y<2>");
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::{Nfa, ParseError, Position, Regex};
+
+/// Set of patterns compiled into a single NFA, telling which of them match
+/// in a single pass over the input.
+#[derive(Debug)]
+pub struct RegexSet {
+    states: Nfa,
+    start: usize,
+    /// Index of the pattern of every final state.
+    patterns: HashMap<usize, usize>,
+}
+
+impl RegexSet {
+    /// Compiles the patterns, indexed in iteration order.
+    ///
+    /// Fails on the first malformed pattern.
+    pub fn new<'p, I: IntoIterator<Item = &'p str>>(patterns: I) -> Result<Self, ParseError> {
+        let mut states = Nfa::new();
+        let start = states.new_state(false);
+        let mut ends = HashMap::new();
+        for (index, pattern) in patterns.into_iter().enumerate() {
+            let regex = Regex::new(pattern)?;
+            let offset = states.append(regex.states);
+            states
+                .state_mut(start)
+                .epsilon_transitions
+                .push(regex.start + offset);
+            ends.insert(regex.end + offset, index);
+        }
+        Ok(Self {
+            states,
+            start,
+            patterns: ends,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Marks the patterns whose final state is among `states`.
+    fn record(&self, states: &BTreeSet<usize>, matched: &mut [bool]) {
+        for state in states {
+            if let Some(&index) = self.patterns.get(state) {
+                matched[index] = true;
+            }
+        }
+    }
+
+    fn indices(matched: Vec<bool>) -> Vec<usize> {
+        (0..matched.len()).filter(|&index| matched[index]).collect()
+    }
+
+    /// Indices of the patterns matching the whole `s` (as [`Regex::matches`]), in increasing order.
+    pub fn matches(&self, s: &str) -> Vec<usize> {
+        let mut current_states = self.states.epsilon_closure(self.start, Position::of(s, 0));
+        for (i, c) in s.char_indices() {
+            let position = Position::of(s, i + c.len_utf8());
+            current_states = self.states.advance(current_states, c, position);
+        }
+        let mut matched = vec![false; self.len()];
+        self.record(&current_states, &mut matched);
+        Self::indices(matched)
+    }
+
+    /// Indices of the patterns matching some substring of `haystack` (as [`Regex::search`]),
+    /// in increasing order.
+    ///
+    /// A match may start at every position, so all of them are simulated at once,
+    /// stopping early once every pattern has matched.
+    pub fn search(&self, haystack: &str) -> Vec<usize> {
+        let mut matched = vec![false; self.len()];
+        let mut current_states = BTreeSet::new();
+        for (i, c) in haystack.char_indices() {
+            let position = Position::of(haystack, i);
+            current_states.extend(self.states.epsilon_closure(self.start, position));
+            self.record(&current_states, &mut matched);
+            if matched.iter().all(|&matched| matched) {
+                return Self::indices(matched);
+            }
+            let position = Position::of(haystack, i + c.len_utf8());
+            current_states = self.states.advance(current_states, c, position);
+        }
+        let position = Position::of(haystack, haystack.len());
+        current_states.extend(self.states.epsilon_closure(self.start, position));
+        self.record(&current_states, &mut matched);
+        Self::indices(matched)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regex_set() {
+        let patterns = ["[0-9]+", "[a-z]+", "a.c", "^x", "c$", ""];
+        let set = RegexSet::new(patterns).unwrap();
+        assert_eq!(set.len(), 6);
+        assert_eq!(set.matches("abc"), [1, 2]);
+        assert_eq!(set.matches("42"), [0]);
+        assert_eq!(set.matches(""), [5]);
+        assert_eq!(set.search("x = abc"), [1, 2, 3, 4, 5]);
+        assert_eq!(set.search("42 "), [0, 5]);
+
+        // Agrees with the individual patterns:
+        let regexes = patterns.map(|pattern| Regex::new(pattern).unwrap());
+        for haystack in ["", "a1c", "xyz", "9 a-c", "abc\nx", "é"] {
+            let expected = (0..regexes.len())
+                .filter(|&i| regexes[i].search(haystack))
+                .collect::<Vec<_>>();
+            assert_eq!(set.search(haystack), expected, "{:?}", haystack);
+            let expected = (0..regexes.len())
+                .filter(|&i| regexes[i].matches(haystack))
+                .collect::<Vec<_>>();
+            assert_eq!(set.matches(haystack), expected, "{:?}", haystack);
+        }
+
+        assert!(RegexSet::new(["a", "("]).is_err());
+        assert!(RegexSet::new([]).unwrap().search("a").is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+use crate::{Ast, CharClass};
+
+/// Ranges of the chars matched by `ast`, if it always matches a single char.
+fn single_char(ast: &Ast) -> Option<Vec<(char, char)>> {
+    match ast {
+        Ast::Literal(c) => Some(vec![(*c, *c)]),
+        Ast::Any => Some(vec![('\0', char::MAX)]),
+        Ast::Class(class) => Some(class.matched_ranges()),
+        _ => None,
+    }
+}
+
+/// First item of a branch and the items after it.
+fn split_first(ast: &Ast) -> Option<(&Ast, &[Ast])> {
+    match ast {
+        Ast::Empty => None,
+        Ast::Concat(items) => items.split_first(),
+        ast => Some((ast, &[])),
+    }
+}
+
+fn concat(items: Vec<Ast>) -> Ast {
+    Ast::Concat(items).simplify()
+}
+
+/// Simplifies an alternation of already simplified branches, keeping their order,
+/// so that the preferred branch of every match stays the same.
+fn alternation(branches: Vec<Ast>) -> Ast {
+    let mut unique: Vec<Ast> = Vec::new();
+    for branch in branches {
+        let flat = match branch {
+            Ast::Alternation(inner) => inner,
+            branch => vec![branch],
+        };
+        for branch in flat {
+            // A later copy of a branch never matches where the first one doesn't:
+            if !unique.contains(&branch) {
+                unique.push(branch);
+            }
+        }
+    }
+
+    // Adjacent single chars become a class, e.g. `a|b|[x-z]` is `[abx-z]`:
+    let mut merged: Vec<Ast> = Vec::new();
+    for branch in unique {
+        match (merged.last_mut(), single_char(&branch)) {
+            (Some(last), Some(ranges)) if single_char(last).is_some() => {
+                let mut all = single_char(last).unwrap();
+                all.extend(ranges);
+                *last = Ast::Class(CharClass::new(all, false));
+            }
+            _ => merged.push(branch),
+        }
+    }
+
+    // Adjacent branches starting with the same item share it, e.g. `ab|ac` is `a(?:b|c)`:
+    let mut factored = Vec::new();
+    let mut i = 0;
+    while i < merged.len() {
+        let Some((first, _)) = split_first(&merged[i]) else {
+            factored.push(merged[i].clone());
+            i += 1;
+            continue;
+        };
+        let mut j = i + 1;
+        while j < merged.len() && split_first(&merged[j]).is_some_and(|(other, _)| other == first) {
+            j += 1;
+        }
+        if j - i == 1 {
+            factored.push(merged[i].clone());
+        } else {
+            let rests = merged[i..j]
+                .iter()
+                .map(|branch| concat(split_first(branch).unwrap().1.to_vec()))
+                .collect();
+            factored.push(concat(vec![first.clone(), alternation(rests)]));
+        }
+        i = j;
+    }
+
+    match factored.len() {
+        1 => factored.pop().unwrap(),
+        _ => Ast::Alternation(factored),
+    }
+}
+
+impl Ast {
+    /// Rewrites the tree into a smaller one matching the same strings, with the same
+    /// capture groups and the same preferred parse for [`Regex::captures`](crate::Regex::captures).
+    ///
+    /// Nested concatenations and alternations are flattened, `ε` items are dropped,
+    /// duplicate branches are removed, adjacent single-char branches are merged into a class,
+    /// adjacent branches with a common first item are factored (e.g. `ab|ac` becomes
+    /// `a(?:b|c)`), and nested or trivial quantifiers are collapsed (e.g. `(?:a*)+` is `a*`
+    /// and `a{1}` is `a`). Used by [`RegexBuilder`](crate::RegexBuilder) before building the NFA.
+    pub fn simplify(self) -> Ast {
+        match self {
+            Ast::Empty | Ast::Literal(_) | Ast::Any | Ast::Class(_) | Ast::Anchor(_) => self,
+            Ast::Concat(items) => {
+                let mut flat = Vec::new();
+                for item in items {
+                    match item.simplify() {
+                        Ast::Empty => {}
+                        Ast::Concat(inner) => flat.extend(inner),
+                        item => flat.push(item),
+                    }
+                }
+                match flat.len() {
+                    0 => Ast::Empty,
+                    1 => flat.pop().unwrap(),
+                    _ => Ast::Concat(flat),
+                }
+            }
+            Ast::Alternation(branches) => {
+                alternation(branches.into_iter().map(Ast::simplify).collect())
+            }
+            // Only greedy quantifiers are collapsed, lazy ones are wrapped in `Ast::Lazy`:
+            Ast::Star(inner) => match inner.simplify() {
+                Ast::Empty => Ast::Empty,
+                Ast::Star(inner) | Ast::Plus(inner) | Ast::Optional(inner) => Ast::Star(inner),
+                inner => Ast::Star(Box::new(inner)),
+            },
+            Ast::Plus(inner) => match inner.simplify() {
+                Ast::Empty => Ast::Empty,
+                Ast::Star(inner) | Ast::Optional(inner) => Ast::Star(inner),
+                Ast::Plus(inner) => Ast::Plus(inner),
+                inner => Ast::Plus(Box::new(inner)),
+            },
+            Ast::Optional(inner) => match inner.simplify() {
+                Ast::Empty => Ast::Empty,
+                Ast::Star(inner) | Ast::Plus(inner) => Ast::Star(inner),
+                Ast::Optional(inner) => Ast::Optional(inner),
+                inner => Ast::Optional(Box::new(inner)),
+            },
+            Ast::Repeat { inner, min, max } => match (min, max) {
+                (_, Some(0)) => Ast::Empty,
+                (0, None) => Ast::Star(inner).simplify(),
+                (1, None) => Ast::Plus(inner).simplify(),
+                (0, Some(1)) => Ast::Optional(inner).simplify(),
+                (1, Some(1)) => inner.simplify(),
+                _ => match inner.simplify() {
+                    Ast::Empty => Ast::Empty,
+                    inner => Ast::Repeat {
+                        inner: Box::new(inner),
+                        min,
+                        max,
+                    },
+                },
+            },
+            Ast::Lazy(quantifier) => {
+                let simplify = |inner: Box<Ast>| Box::new(inner.simplify());
+                Ast::Lazy(Box::new(match *quantifier {
+                    Ast::Star(inner) => Ast::Star(simplify(inner)),
+                    Ast::Plus(inner) => Ast::Plus(simplify(inner)),
+                    Ast::Optional(inner) => Ast::Optional(simplify(inner)),
+                    Ast::Repeat { inner, min, max } => Ast::Repeat {
+                        inner: simplify(inner),
+                        min,
+                        max,
+                    },
+                    quantifier => quantifier.simplify(),
+                }))
+            }
+            Ast::Group { index, inner } => Ast::Group {
+                index,
+                inner: Box::new(inner.simplify()),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Regex, RegexBuilder};
+
+    fn simplified(pattern: &str) -> Ast {
+        Ast::parse(pattern).unwrap().simplify()
+    }
+
+    #[test]
+    fn test_simplify() {
+        assert_eq!(simplified("a|a"), Ast::Literal('a'));
+        assert_eq!(simplified("(?:a)(?:b(?:c))"), Ast::parse("abc").unwrap());
+        assert_eq!(simplified("a|b|[x-z]"), Ast::parse("[abx-z]").unwrap());
+        assert_eq!(
+            simplified("abc|abd|ax"),
+            Ast::parse("a(?:b[cd]|x)").unwrap()
+        );
+        assert_eq!(
+            simplified("ab|a"),
+            Ast::Concat(vec![
+                Ast::Literal('a'),
+                Ast::Alternation(vec![Ast::Literal('b'), Ast::Empty]),
+            ])
+        );
+        assert_eq!(
+            simplified("(?:a*)+|b{1}|c{0,1}"),
+            Ast::parse("a*|b|c?").unwrap()
+        );
+        assert_eq!(simplified("a{0}b"), Ast::Literal('b'));
+        // Groups and lazy quantifiers are kept:
+        assert_eq!(simplified("(a)|(a)"), Ast::parse("(a)|(a)").unwrap());
+        assert_eq!(simplified("(?:a*)*?"), Ast::parse("(?:a*)*?").unwrap());
+    }
+
+    #[test]
+    fn test_simplify_regex() {
+        let pattern = "(?:foo|foo|fob|fa)+|(?:x|y|z)*|foo";
+        let re = Regex::new(pattern).unwrap();
+        let plain = RegexBuilder::new(pattern).simplify(false).build().unwrap();
+        assert!(re.states.states.len() * 2 < plain.states.states.len());
+        // The pattern as written is kept:
+        assert_eq!(re.ast(), plain.ast());
+        for s in ["", "foo", "fofa", "fobfoofa", "xzy", "fox", "fooz"] {
+            assert_eq!(re.matches(s), plain.matches(s), "{:?}", s);
+        }
+
+        let pattern = "(a|ab)(c|bcd|b)(d*)";
+        let re = Regex::new(pattern).unwrap();
+        let plain = RegexBuilder::new(pattern).simplify(false).build().unwrap();
+        for haystack in ["abcd", "abd", "xabbcdd"] {
+            assert_eq!(re.captures(haystack), plain.captures(haystack));
+        }
+    }
+}
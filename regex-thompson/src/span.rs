@@ -0,0 +1,82 @@
+use std::ops::Range;
+
+/// Location of a match inside a haystack.
+///
+/// Positions are tracked both as byte offsets (suitable for slicing the haystack)
+/// and as char indices (suitable for reporting to users), so multi-byte UTF-8
+/// characters are handled consistently.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// Byte offset of the first byte of the match.
+    pub start: usize,
+    /// Byte offset right after the last byte of the match.
+    pub end: usize,
+    /// Index of the first char of the match.
+    pub char_start: usize,
+    /// Index right after the last char of the match.
+    pub char_end: usize,
+}
+
+impl Span {
+    /// Creates a span for the byte range `start..end` of `haystack`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` or `end` is not on a char boundary of `haystack`.
+    pub fn new(haystack: &str, start: usize, end: usize) -> Self {
+        let char_start = haystack[..start].chars().count();
+        let char_end = char_start + haystack[start..end].chars().count();
+        Self {
+            start,
+            end,
+            char_start,
+            char_end,
+        }
+    }
+
+    /// Byte range of the match.
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+
+    /// Char range of the match.
+    pub fn char_range(&self) -> Range<usize> {
+        self.char_start..self.char_end
+    }
+
+    /// Length of the match in bytes.
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Matched substring of `haystack`.
+    pub fn as_str<'h>(&self, haystack: &'h str) -> &'h str {
+        &haystack[self.range()]
+    }
+}
+
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Self {
+        span.range()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_span_multibyte() {
+        let haystack = "añb€c";
+        // 'ñ' is 2 bytes, '€' is 3 bytes:
+        let span = Span::new(haystack, 1, 7);
+        assert_eq!(span.range(), 1..7);
+        assert_eq!(span.char_range(), 1..4);
+        assert_eq!(span.len(), 6);
+        assert_eq!(span.as_str(haystack), "ñb€");
+    }
+}